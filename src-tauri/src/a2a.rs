@@ -0,0 +1,275 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Mutex;
+
+use crate::api::ask_webview;
+use crate::events;
+
+pub const PROTOCOL_VERSION: &str = "0.2.5";
+// 완료된 작업은 이 개수까지만 메모리에 보관합니다
+const MAX_TASKS: usize = 1000;
+
+// JSON-RPC / A2A 오류 코드
+const INVALID_PARAMS: i64 = -32602;
+const METHOD_NOT_FOUND: i64 = -32601;
+const TASK_NOT_FOUND: i64 = -32001;
+const TASK_NOT_CANCELABLE: i64 = -32002;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskState {
+    Working,
+    Completed,
+    Canceled,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskStatus {
+    pub state: TaskState,
+    pub timestamp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    pub id: String,
+    pub context_id: String,
+    pub status: TaskStatus,
+    pub artifacts: Vec<serde_json::Value>,
+    pub history: Vec<serde_json::Value>,
+    pub kind: &'static str,
+    #[serde(skip)]
+    agent_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id: serde_json::Value,
+    method: String,
+    #[serde(default)]
+    params: serde_json::Value,
+}
+
+static TASKS: Mutex<Vec<Task>> = Mutex::new(Vec::new());
+
+fn status(state: TaskState, message: Option<serde_json::Value>) -> TaskStatus {
+    TaskStatus {
+        state,
+        timestamp: chrono::Local::now().to_rfc3339(),
+        message,
+    }
+}
+
+fn text_message(role: &str, text: &str, context_id: &str, task_id: &str) -> serde_json::Value {
+    json!({
+        "kind": "message",
+        "role": role,
+        "messageId": uuid::Uuid::new_v4().to_string(),
+        "contextId": context_id,
+        "taskId": task_id,
+        "parts": [{ "kind": "text", "text": text }],
+    })
+}
+
+fn message_text(message: &serde_json::Value) -> String {
+    message
+        .get("parts")
+        .and_then(|parts| parts.as_array())
+        .map(|parts| {
+            parts
+                .iter()
+                .filter_map(|part| part.get("text").and_then(|t| t.as_str()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default()
+}
+
+fn store(task: Task) {
+    let mut tasks = TASKS.lock().unwrap_or_else(|e| e.into_inner());
+    tasks.retain(|existing| existing.id != task.id);
+    if tasks.len() >= MAX_TASKS {
+        tasks.remove(0);
+    }
+    tasks.push(task);
+}
+
+fn find(id: &str) -> Option<Task> {
+    TASKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|task| task.id == id)
+        .cloned()
+}
+
+/// Agent card advertising one saved assistant
+pub fn agent_card(agent: &serde_json::Value, base_url: &str) -> serde_json::Value {
+    let id = agent.get("id").and_then(|v| v.as_str()).unwrap_or_default();
+    let name = agent.get("name").and_then(|v| v.as_str()).unwrap_or(id);
+    json!({
+        "protocolVersion": PROTOCOL_VERSION,
+        "name": name,
+        "description": format!("Local agent '{}' with access to its configured tools", name),
+        "url": format!("{}/a2a/agents/{}", base_url, id),
+        "preferredTransport": "JSONRPC",
+        "version": env!("CARGO_PKG_VERSION"),
+        "capabilities": { "streaming": false, "pushNotifications": false },
+        "securitySchemes": { "bearer": { "type": "http", "scheme": "bearer" } },
+        "security": [{ "bearer": [] }],
+        "defaultInputModes": ["text/plain"],
+        "defaultOutputModes": ["text/plain"],
+        "skills": [{
+            "id": id,
+            "name": name,
+            "description": format!("Send a text task to '{}'", name),
+            "tags": ["chat", "tools"],
+        }],
+    })
+}
+
+/// Saved assistants, read from the webview's store
+pub async fn agents() -> Result<Vec<serde_json::Value>, String> {
+    let agents = ask_webview("agents", serde_json::Value::Null).await?;
+    Ok(agents.as_array().cloned().unwrap_or_default())
+}
+
+async fn run_task(mut task: Task, text: String) -> Task {
+    let request = json!({
+        "agent_id": task.agent_id,
+        "context_id": task.context_id,
+        "prompt": text,
+    });
+    let result = ask_webview("agent-run", request).await;
+    // 실행 중에 취소되었다면 결과를 덮어쓰지 않습니다
+    if find(&task.id).is_some_and(|current| current.status.state == TaskState::Canceled) {
+        return find(&task.id).unwrap_or(task);
+    }
+    match result {
+        Ok(reply) => {
+            let content = reply
+                .get("content")
+                .and_then(|c| c.as_str())
+                .unwrap_or_default()
+                .to_string();
+            let message = text_message("agent", &content, &task.context_id, &task.id);
+            task.artifacts.push(json!({
+                "artifactId": uuid::Uuid::new_v4().to_string(),
+                "name": "response",
+                "parts": [{ "kind": "text", "text": content }],
+            }));
+            task.history.push(message.clone());
+            task.status = status(TaskState::Completed, Some(message));
+        }
+        Err(e) => {
+            let message = text_message("agent", &e, &task.context_id, &task.id);
+            task.status = status(TaskState::Failed, Some(message));
+        }
+    }
+    events::record(
+        "a2a-task-finished",
+        None,
+        json!({ "task_id": task.id, "agent_id": task.agent_id, "state": task.status.state }),
+    );
+    store(task.clone());
+    task
+}
+
+async fn send_message(agent_id: &str, params: &serde_json::Value) -> Result<Task, (i64, String)> {
+    let message = params
+        .get("message")
+        .ok_or((INVALID_PARAMS, "Missing 'message'".to_string()))?;
+    let text = message_text(message);
+    if text.is_empty() {
+        return Err((INVALID_PARAMS, "Message has no text parts".to_string()));
+    }
+    let context_id = message
+        .get("contextId")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let id = uuid::Uuid::new_v4().to_string();
+    let mut user_message = message.clone();
+    user_message["taskId"] = json!(id);
+    user_message["contextId"] = json!(context_id);
+
+    let task = Task {
+        id,
+        context_id,
+        status: status(TaskState::Working, None),
+        artifacts: Vec::new(),
+        history: vec![user_message],
+        kind: "task",
+        agent_id: agent_id.to_string(),
+    };
+    store(task.clone());
+    events::record(
+        "a2a-task-started",
+        None,
+        json!({ "task_id": task.id, "agent_id": agent_id }),
+    );
+
+    let blocking = params
+        .pointer("/configuration/blocking")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    if blocking {
+        Ok(run_task(task, text).await)
+    } else {
+        tokio::spawn(run_task(task.clone(), text));
+        Ok(task)
+    }
+}
+
+fn task_id(params: &serde_json::Value) -> Result<String, (i64, String)> {
+    params
+        .get("id")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .ok_or((INVALID_PARAMS, "Missing task 'id'".to_string()))
+}
+
+fn cancel_task(params: &serde_json::Value) -> Result<Task, (i64, String)> {
+    let id = task_id(params)?;
+    let mut task = find(&id).ok_or((TASK_NOT_FOUND, "Task not found".to_string()))?;
+    if task.status.state != TaskState::Working {
+        return Err((TASK_NOT_CANCELABLE, "Task is not running".to_string()));
+    }
+    task.status = status(TaskState::Canceled, None);
+    store(task.clone());
+    Ok(task)
+}
+
+/// A2A JSON-RPC 요청을 처리합니다 (`message/send`, `tasks/get`, `tasks/cancel`)
+pub async fn handle_rpc(agent_id: &str, body: serde_json::Value) -> serde_json::Value {
+    let request: RpcRequest = match serde_json::from_value(body) {
+        Ok(request) => request,
+        Err(e) => {
+            return json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": { "code": -32600, "message": format!("Invalid request: {}", e) },
+            })
+        }
+    };
+    let result = match request.method.as_str() {
+        "message/send" => send_message(agent_id, &request.params).await,
+        "tasks/get" => task_id(&request.params)
+            .and_then(|id| find(&id).ok_or((TASK_NOT_FOUND, "Task not found".to_string()))),
+        "tasks/cancel" => cancel_task(&request.params),
+        other => Err((METHOD_NOT_FOUND, format!("Method '{}' not found", other))),
+    };
+    match result {
+        Ok(task) => json!({ "jsonrpc": "2.0", "id": request.id, "result": task }),
+        Err((code, message)) => json!({
+            "jsonrpc": "2.0",
+            "id": request.id,
+            "error": { "code": code, "message": message },
+        }),
+    }
+}
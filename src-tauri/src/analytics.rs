@@ -0,0 +1,261 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+// 일별 집계를 보관하는 기간
+const MAX_DAYS: i64 = 90;
+const FLUSH_INTERVAL_SECS: u64 = 60;
+
+/// Period covered by [`ToolAnalyticsStore::report`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalyticsRange {
+    Day,
+    #[default]
+    Week,
+    Month,
+    /// Everything kept, up to 90 days
+    All,
+}
+
+impl AnalyticsRange {
+    fn days(self) -> i64 {
+        match self {
+            Self::Day => 1,
+            Self::Week => 7,
+            Self::Month => 30,
+            Self::All => MAX_DAYS,
+        }
+    }
+}
+
+/// Counters for one tool on one day
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DayStats {
+    calls: u64,
+    successes: u64,
+    total_latency_ms: u64,
+    sessions: BTreeSet<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AnalyticsData {
+    /// `YYYY-MM-DD` → `server__tool` → counters
+    days: BTreeMap<String, BTreeMap<String, DayStats>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolUsage {
+    pub server_name: String,
+    pub tool_name: String,
+    pub calls: u64,
+    /// Between 0 and 1
+    pub success_rate: f64,
+    pub avg_latency_ms: f64,
+    /// Distinct chat sessions that called the tool
+    pub sessions: usize,
+}
+
+/// A connected server with no tool calls in the range
+#[derive(Debug, Clone, Serialize)]
+pub struct UnusedServer {
+    pub server_name: String,
+    /// Tools it adds to every prompt
+    pub tool_count: usize,
+    /// Memory of its process tree, when it runs locally
+    pub memory_bytes: Option<u64>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolAnalytics {
+    pub range: AnalyticsRange,
+    /// First day with data inside the range
+    pub since: Option<String>,
+    /// Most called first
+    pub tools: Vec<ToolUsage>,
+    /// Candidates to disable to shrink prompts and memory use
+    pub unused_servers: Vec<UnusedServer>,
+}
+
+/// A running server as the report needs it
+pub struct ServerUsageInput {
+    pub server_name: String,
+    pub tool_count: usize,
+    pub pid: Option<u32>,
+}
+
+/// Per-tool call statistics aggregated by day and persisted across restarts
+pub struct ToolAnalyticsStore {
+    path: PathBuf,
+    data: Mutex<Option<AnalyticsData>>,
+    dirty: AtomicBool,
+}
+
+impl ToolAnalyticsStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            data: Mutex::new(None),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    async fn with_data<T>(&self, f: impl FnOnce(&mut AnalyticsData) -> T) -> T {
+        let mut guard = self.data.lock().await;
+        if guard.is_none() {
+            let loaded = tokio::fs::read_to_string(&self.path)
+                .await
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+                .unwrap_or_default();
+            *guard = Some(loaded);
+        }
+        f(guard.as_mut().expect("loaded above"))
+    }
+
+    pub async fn record(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        success: bool,
+        latency: Duration,
+        session_id: Option<&str>,
+    ) {
+        let day = chrono::Local::now().format("%Y-%m-%d").to_string();
+        self.with_data(|data| {
+            let stats = data
+                .days
+                .entry(day)
+                .or_default()
+                .entry(format!("{}__{}", server_name, tool_name))
+                .or_default();
+            stats.calls += 1;
+            if success {
+                stats.successes += 1;
+            }
+            stats.total_latency_ms += latency.as_millis() as u64;
+            if let Some(session_id) = session_id {
+                stats.sessions.insert(session_id.to_string());
+            }
+        })
+        .await;
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// Write pending counters and drop days older than the retention window
+    pub async fn flush(&self) -> Result<()> {
+        if !self.dirty.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+        let cutoff = (chrono::Local::now() - chrono::Duration::days(MAX_DAYS))
+            .format("%Y-%m-%d")
+            .to_string();
+        let content = self
+            .with_data(|data| {
+                data.days.retain(|day, _| *day > cutoff);
+                serde_json::to_string(data)
+            })
+            .await?;
+        // 사용 통계는 설정이 아니므로 버전 관리 없이 저장합니다
+        tokio::fs::write(&self.path, content).await?;
+        Ok(())
+    }
+
+    /// Flush periodically for as long as the app runs
+    pub async fn run_background(&self) {
+        loop {
+            tokio::time::sleep(Duration::from_secs(FLUSH_INTERVAL_SECS)).await;
+            if let Err(e) = self.flush().await {
+                eprintln!("❌ Failed to save tool analytics: {}", e);
+            }
+        }
+    }
+
+    /// Usage per tool over `range`, and the connected servers nobody called
+    pub async fn report(
+        &self,
+        range: AnalyticsRange,
+        servers: &[ServerUsageInput],
+    ) -> ToolAnalytics {
+        let first_day = (chrono::Local::now() - chrono::Duration::days(range.days() - 1))
+            .format("%Y-%m-%d")
+            .to_string();
+        let (since, totals) = self
+            .with_data(|data| {
+                let mut totals: BTreeMap<String, DayStats> = BTreeMap::new();
+                let mut since = None;
+                for (day, tools) in data.days.range(first_day..) {
+                    since.get_or_insert_with(|| day.clone());
+                    for (name, stats) in tools {
+                        let total = totals.entry(name.clone()).or_default();
+                        total.calls += stats.calls;
+                        total.successes += stats.successes;
+                        total.total_latency_ms += stats.total_latency_ms;
+                        total.sessions.extend(stats.sessions.iter().cloned());
+                    }
+                }
+                (since, totals)
+            })
+            .await;
+
+        let mut tools: Vec<ToolUsage> = totals
+            .into_iter()
+            .filter_map(|(name, stats)| {
+                let (server_name, tool_name) = name.split_once("__")?;
+                Some(ToolUsage {
+                    server_name: server_name.to_string(),
+                    tool_name: tool_name.to_string(),
+                    calls: stats.calls,
+                    success_rate: stats.successes as f64 / stats.calls.max(1) as f64,
+                    avg_latency_ms: stats.total_latency_ms as f64 / stats.calls.max(1) as f64,
+                    sessions: stats.sessions.len(),
+                })
+            })
+            .collect();
+        tools.sort_by_key(|tool| std::cmp::Reverse(tool.calls));
+
+        let unused: Vec<&ServerUsageInput> = servers
+            .iter()
+            .filter(|server| !tools.iter().any(|t| t.server_name == server.server_name))
+            .collect();
+        let pids: Vec<u32> = unused.iter().filter_map(|s| s.pid).collect();
+        let memory = if pids.is_empty() {
+            HashMap::new()
+        } else {
+            tokio::task::spawn_blocking(move || crate::limits::tree_memory_bytes(&pids))
+                .await
+                .unwrap_or_default()
+        };
+        let unused_servers = unused
+            .into_iter()
+            .map(|server| {
+                let memory_bytes = server.pid.and_then(|pid| memory.get(&pid).copied());
+                let mut message = format!(
+                    "No calls in this period; disabling it removes {} tools from every prompt",
+                    server.tool_count
+                );
+                if let Some(bytes) = memory_bytes {
+                    message.push_str(&format!(" and frees {} MB", bytes / (1024 * 1024)));
+                }
+                UnusedServer {
+                    server_name: server.server_name.clone(),
+                    tool_count: server.tool_count,
+                    memory_bytes,
+                    message,
+                }
+            })
+            .collect();
+
+        ToolAnalytics {
+            range,
+            since,
+            tools,
+            unused_servers,
+        }
+    }
+}
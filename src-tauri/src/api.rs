@@ -0,0 +1,550 @@
+use anyhow::Result;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex as StdMutex;
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, oneshot, Mutex};
+use tokio_util::sync::CancellationToken;
+
+use crate::config_versions;
+use crate::mcp::ToolCallContext;
+use crate::webhooks::constant_time_eq;
+use crate::{
+    a2a, all_connected_tools, call_mcp_tool, events, get_app_data_dir, webhooks, websocket,
+};
+
+// 웹뷰(에이전트 루프)의 응답을 기다리는 최대 시간
+const WEBVIEW_TIMEOUT_SECS: u64 = 300;
+const MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+/// Optional localhost HTTP API; off by default
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Bearer token every request must present; generated on first use
+    #[serde(default)]
+    pub token: String,
+}
+
+impl Default for ApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 7891,
+            token: String::new(),
+        }
+    }
+}
+
+/// A request the backend cannot answer itself (chat, sessions), forwarded to the webview
+#[derive(Debug, Clone, Serialize)]
+pub struct WebviewRequest {
+    pub id: String,
+    pub kind: String,
+    pub payload: serde_json::Value,
+}
+
+type PendingReply = oneshot::Sender<Result<serde_json::Value, String>>;
+
+static RUNNING: Mutex<Option<CancellationToken>> = Mutex::const_new(None);
+static PENDING: StdMutex<Option<HashMap<String, PendingReply>>> = StdMutex::new(None);
+
+fn settings_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("api_settings.json")
+}
+
+fn generate_token() -> String {
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+/// Load the settings, creating and persisting a token if there is none yet
+pub fn load_settings(data_dir: &Path) -> Result<ApiSettings> {
+    let mut settings: ApiSettings = match std::fs::read_to_string(settings_path(data_dir)) {
+        Ok(content) => serde_json::from_str(&content)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => ApiSettings::default(),
+        Err(e) => return Err(e.into()),
+    };
+    if settings.token.is_empty() {
+        settings.token = generate_token();
+        save_settings(data_dir, &settings)?;
+    }
+    Ok(settings)
+}
+
+pub fn save_settings(data_dir: &Path, settings: &ApiSettings) -> Result<()> {
+    config_versions::write_blocking(
+        &settings_path(data_dir),
+        &serde_json::to_string_pretty(settings)?,
+    )?;
+    Ok(())
+}
+
+pub fn regenerate_token(data_dir: &Path) -> Result<ApiSettings> {
+    let mut settings = load_settings(data_dir)?;
+    settings.token = generate_token();
+    save_settings(data_dir, &settings)?;
+    Ok(settings)
+}
+
+/// Forward a request to one window and wait for `resolve_webview_request`
+pub(crate) async fn ask_webview(
+    kind: &str,
+    payload: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let (sender, receiver) = oneshot::channel();
+    PENDING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(HashMap::new)
+        .insert(id.clone(), sender);
+    let request = WebviewRequest {
+        id: id.clone(),
+        kind: kind.to_string(),
+        payload,
+    };
+    // 여러 창이 같은 요청에 답하지 않도록 호출한 창(없으면 포커스된 창) 하나에만 보냅니다
+    let window = ToolCallContext::current()
+        .and_then(|context| context.window)
+        .filter(|label| events::window_exists(label))
+        .or_else(events::request_window);
+    match window {
+        Some(window) => events::emit_to(&window, "api-request", request),
+        None => events::emit("api-request", request),
+    }
+
+    let result = tokio::time::timeout(
+        tokio::time::Duration::from_secs(WEBVIEW_TIMEOUT_SECS),
+        receiver,
+    )
+    .await;
+    if let Some(pending) = PENDING.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        pending.remove(&id);
+    }
+    match result {
+        Ok(Ok(reply)) => reply,
+        Ok(Err(_)) => Err("The app dropped the request".to_string()),
+        Err(_) => Err("Timed out waiting for the app window".to_string()),
+    }
+}
+
+/// Ask the user to allow a sensitive action; headless mode always refuses
+pub(crate) async fn ask_approval(action: &str, message: String) -> Result<(), String> {
+    if !events::app_attached() {
+        return Err(format!(
+            "'{}' needs the desktop app for user approval",
+            action
+        ));
+    }
+    // 어떤 채팅이나 에이전트가 요청했는지 함께 보여줍니다
+    let context = ToolCallContext::current();
+    let message = match context.as_ref().and_then(ToolCallContext::describe) {
+        Some(who) => format!("{}\n\nRequested by {}", message, who),
+        None => message,
+    };
+    let reply = ask_webview(
+        "approval",
+        json!({ "action": action, "message": message, "context": context }),
+    )
+    .await?;
+    if reply.as_bool() != Some(true) {
+        return Err(format!("'{}' was declined", action));
+    }
+    Ok(())
+}
+
+/// Deliver the webview's answer to a forwarded request
+pub fn resolve_webview_request(id: &str, result: Result<serde_json::Value, String>) -> bool {
+    let sender = PENDING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_mut()
+        .and_then(|pending| pending.remove(id));
+    match sender {
+        Some(sender) => sender.send(result).is_ok(),
+        None => false,
+    }
+}
+
+fn json_response(status: StatusCode, body: serde_json::Value) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(body.to_string())))
+        .unwrap_or_default()
+}
+
+fn error_response(status: StatusCode, message: impl Into<String>) -> Response<Full<Bytes>> {
+    json_response(status, json!({ "error": message.into() }))
+}
+
+fn authorized(request: &Request<Incoming>, token: &str) -> bool {
+    request
+        .headers()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|presented| constant_time_eq(presented.as_bytes(), token.as_bytes()))
+        // 브라우저 WebSocket은 헤더를 설정할 수 없으므로 쿼리 파라미터도 허용합니다
+        || (request.uri().path() == "/events"
+            && query_param(request, "token")
+                .is_some_and(|presented| constant_time_eq(presented.as_bytes(), token.as_bytes())))
+}
+
+fn query_param(request: &Request<Incoming>, name: &str) -> Option<String> {
+    request.uri().query()?.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key == name).then(|| value.to_string())
+    })
+}
+
+async fn read_body(request: Request<Incoming>) -> Result<Bytes, String> {
+    Ok(Limited::new(request.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+        .map_err(|e| format!("Failed to read request body: {}", e))?
+        .to_bytes())
+}
+
+async fn read_json(request: Request<Incoming>) -> Result<serde_json::Value, String> {
+    let body = read_body(request).await?;
+    serde_json::from_slice(&body).map_err(|e| format!("Invalid JSON body: {}", e))
+}
+
+/// 서명된 웹훅을 받아 해당 에이전트 실행을 웹뷰에 요청합니다
+///
+/// Authenticated by the per-webhook HMAC instead of the API token, and
+/// answered with 202 right away since agent runs can take minutes.
+async fn handle_webhook(request: Request<Incoming>, id: &str) -> Response<Full<Bytes>> {
+    let Some(webhook) = webhooks::load(&get_app_data_dir())
+        .into_iter()
+        .find(|webhook| webhook.id == id)
+    else {
+        return error_response(StatusCode::NOT_FOUND, "Unknown webhook");
+    };
+    let signature = request
+        .headers()
+        .get("x-hub-signature-256")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let event_type = request
+        .headers()
+        .get("x-github-event")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = match read_body(request).await {
+        Ok(body) => body,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+    if !webhooks::verify_signature(&webhook.secret, &body, &signature) {
+        return error_response(StatusCode::UNAUTHORIZED, "Invalid webhook signature");
+    }
+
+    let payload = serde_json::from_slice(&body)
+        .unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(&body).into()));
+    events::record(
+        "webhook-received",
+        None,
+        json!({ "webhook_id": webhook.id, "agent_id": webhook.agent_id, "event_type": event_type }),
+    );
+    tokio::spawn(async move {
+        let request = json!({
+            "webhook_id": webhook.id,
+            "agent_id": webhook.agent_id,
+            "event_type": event_type,
+            "payload": payload,
+        });
+        if let Err(e) = ask_webview("webhook", request).await {
+            eprintln!(
+                "❌ Webhook run for agent {} failed: {}",
+                webhook.agent_id, e
+            );
+        }
+    });
+    json_response(StatusCode::ACCEPTED, json!({ "accepted": true }))
+}
+
+#[derive(Deserialize)]
+struct CallRequest {
+    server_name: String,
+    tool_name: String,
+    #[serde(default)]
+    arguments: serde_json::Value,
+    #[serde(default)]
+    session_id: Option<String>,
+    #[serde(default)]
+    agent_id: Option<String>,
+    #[serde(default)]
+    bypass_cache: Option<bool>,
+}
+
+/// Stream events to one WebSocket client until it closes or the server stops
+async fn push_events(upgraded: hyper::upgrade::Upgraded, cancel: CancellationToken) {
+    let (mut reader, mut writer) = tokio::io::split(TokioIo::new(upgraded));
+    let mut events = events::subscribe();
+    let (pong_sender, mut pong_receiver) = tokio::sync::mpsc::channel::<Vec<u8>>(8);
+
+    // 클라이언트 프레임은 ping/close 처리에만 사용합니다
+    let reader_task = tokio::spawn(async move {
+        loop {
+            match websocket::read_frame(&mut reader).await {
+                Ok((websocket::OP_PING, payload)) => {
+                    if pong_sender.send(payload).await.is_err() {
+                        break;
+                    }
+                }
+                Ok((websocket::OP_CLOSE, _)) | Err(_) => break,
+                Ok(_) => {}
+            }
+        }
+    });
+
+    loop {
+        let result = tokio::select! {
+            _ = cancel.cancelled() => break,
+            pong = pong_receiver.recv() => match pong {
+                Some(payload) => websocket::write_frame(&mut writer, websocket::OP_PONG, &payload).await,
+                None => break,
+            },
+            event = events.recv() => match event {
+                Ok(message) => websocket::write_frame(&mut writer, websocket::OP_TEXT, message.as_bytes()).await,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    let notice = json!({ "event": "lagged", "payload": { "skipped": skipped } });
+                    websocket::write_frame(&mut writer, websocket::OP_TEXT, notice.to_string().as_bytes()).await
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+        };
+        if result.is_err() {
+            break;
+        }
+    }
+    let _ = websocket::write_frame(&mut writer, websocket::OP_CLOSE, &[]).await;
+    reader_task.abort();
+}
+
+fn upgrade_to_websocket(
+    mut request: Request<Incoming>,
+    cancel: CancellationToken,
+) -> Response<Full<Bytes>> {
+    let key = request
+        .headers()
+        .get("sec-websocket-key")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let is_upgrade = request
+        .headers()
+        .get("upgrade")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+    let Some(key) = key.filter(|_| is_upgrade) else {
+        return error_response(StatusCode::BAD_REQUEST, "Expected a WebSocket upgrade");
+    };
+
+    let on_upgrade = hyper::upgrade::on(&mut request);
+    tokio::spawn(async move {
+        match on_upgrade.await {
+            Ok(upgraded) => push_events(upgraded, cancel).await,
+            Err(e) => eprintln!("❌ WebSocket upgrade failed: {}", e),
+        }
+    });
+    Response::builder()
+        .status(StatusCode::SWITCHING_PROTOCOLS)
+        .header("upgrade", "websocket")
+        .header("connection", "Upgrade")
+        .header("sec-websocket-accept", websocket::accept_key(&key))
+        .body(Full::new(Bytes::new()))
+        .unwrap_or_default()
+}
+
+async fn route(
+    request: Request<Incoming>,
+    token: String,
+    cancel: CancellationToken,
+) -> Response<Full<Bytes>> {
+    if request.method() == Method::POST {
+        if let Some(id) = request.uri().path().strip_prefix("/webhooks/") {
+            let id = id.to_string();
+            return handle_webhook(request, &id).await;
+        }
+    }
+    if !authorized(&request, &token) {
+        return error_response(StatusCode::UNAUTHORIZED, "Missing or invalid bearer token");
+    }
+    match (request.method().clone(), request.uri().path()) {
+        (Method::GET, "/events") => upgrade_to_websocket(request, cancel),
+        (Method::GET, "/tools") => match all_connected_tools().await {
+            Ok(tools) => json_response(
+                StatusCode::OK,
+                serde_json::to_value(tools).unwrap_or_default(),
+            ),
+            Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        },
+        (Method::POST, "/call") => {
+            let call: CallRequest = match read_json(request)
+                .await
+                .and_then(|body| serde_json::from_value(body).map_err(|e| e.to_string()))
+            {
+                Ok(call) => call,
+                Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+            };
+            let result = call_mcp_tool(
+                call.server_name,
+                call.tool_name,
+                call.arguments,
+                Some(ToolCallContext {
+                    session_id: call.session_id,
+                    agent_id: call.agent_id,
+                    window: None,
+                }),
+                call.bypass_cache,
+            )
+            .await;
+            json_response(
+                StatusCode::OK,
+                serde_json::to_value(result).unwrap_or_default(),
+            )
+        }
+        (Method::GET, "/sessions") => {
+            let payload = json!({
+                "page": query_param(&request, "page").and_then(|p| p.parse::<u32>().ok()).unwrap_or(1),
+                "page_size": query_param(&request, "page_size").and_then(|p| p.parse::<i32>().ok()).unwrap_or(20),
+            });
+            match ask_webview("sessions", payload).await {
+                Ok(page) => json_response(StatusCode::OK, page),
+                Err(e) => error_response(StatusCode::SERVICE_UNAVAILABLE, e),
+            }
+        }
+        (Method::POST, "/chat") => {
+            let body = match read_json(request).await {
+                Ok(body) => body,
+                Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+            };
+            match ask_webview("chat", body).await {
+                Ok(message) => json_response(StatusCode::OK, message),
+                Err(e) => error_response(StatusCode::SERVICE_UNAVAILABLE, e),
+            }
+        }
+        (method, path) if path == "/a2a/agents" || path.starts_with("/a2a/agents/") => {
+            let path = path.to_string();
+            route_a2a(request, method, &path).await
+        }
+        _ => error_response(StatusCode::NOT_FOUND, "Not found"),
+    }
+}
+
+/// A2A facade: agent cards and one JSON-RPC endpoint per saved agent
+async fn route_a2a(
+    request: Request<Incoming>,
+    method: Method,
+    path: &str,
+) -> Response<Full<Bytes>> {
+    let base_url = format!(
+        "http://{}",
+        request
+            .headers()
+            .get("host")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("127.0.0.1")
+    );
+    let rest = path.trim_start_matches("/a2a/agents").trim_matches('/');
+    let (agent_id, card) = match rest.strip_suffix("/.well-known/agent.json") {
+        Some(agent_id) => (agent_id, true),
+        None => (rest, false),
+    };
+
+    if method == Method::POST && !card && !agent_id.is_empty() {
+        let body = match read_json(request).await {
+            Ok(body) => body,
+            Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+        };
+        return json_response(StatusCode::OK, a2a::handle_rpc(agent_id, body).await);
+    }
+    if method != Method::GET {
+        return error_response(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed");
+    }
+    let agents = match a2a::agents().await {
+        Ok(agents) => agents,
+        Err(e) => return error_response(StatusCode::SERVICE_UNAVAILABLE, e),
+    };
+    if agent_id.is_empty() {
+        let cards: Vec<serde_json::Value> = agents
+            .iter()
+            .map(|agent| a2a::agent_card(agent, &base_url))
+            .collect();
+        return json_response(StatusCode::OK, json!(cards));
+    }
+    match agents
+        .iter()
+        .find(|agent| agent.get("id").and_then(|id| id.as_str()) == Some(agent_id))
+    {
+        Some(agent) => json_response(StatusCode::OK, a2a::agent_card(agent, &base_url)),
+        None => error_response(StatusCode::NOT_FOUND, "Unknown agent"),
+    }
+}
+
+async fn serve(listener: TcpListener, token: String, cancel: CancellationToken) {
+    loop {
+        let (stream, _) = tokio::select! {
+            _ = cancel.cancelled() => break,
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    eprintln!("❌ API server accept failed: {}", e);
+                    continue;
+                }
+            },
+        };
+        let token = token.clone();
+        let cancel = cancel.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |request| {
+                let token = token.clone();
+                let cancel = cancel.clone();
+                async move { Ok::<_, Infallible>(route(request, token, cancel).await) }
+            });
+            if let Err(e) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .with_upgrades()
+                .await
+            {
+                eprintln!("❌ API connection error: {}", e);
+            }
+        });
+    }
+}
+
+/// (재)시작하거나 비활성화되어 있으면 중지합니다. 항상 127.0.0.1에만 바인딩합니다
+pub async fn apply(settings: &ApiSettings) -> Result<()> {
+    let mut running = RUNNING.lock().await;
+    if let Some(cancel) = running.take() {
+        cancel.cancel();
+    }
+    if !settings.enabled {
+        return Ok(());
+    }
+    let listener =
+        TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, settings.port))).await?;
+    println!("Local API listening on {}", listener.local_addr()?);
+    let cancel = CancellationToken::new();
+    tokio::spawn(serve(listener, settings.token.clone(), cancel.clone()));
+    *running = Some(cancel);
+    Ok(())
+}
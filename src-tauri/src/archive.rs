@@ -0,0 +1,502 @@
+use anyhow::Result;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use zip::write::SimpleFileOptions;
+
+// 압축 폭탄을 막기 위한 한도
+const MAX_TOTAL_BYTES: u64 = 1024 * 1024 * 1024;
+const MAX_ENTRIES: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+    /// A single gzip-compressed file
+    Gz,
+}
+
+impl ArchiveFormat {
+    pub fn parse(format: Option<&str>, path: &Path) -> Result<Self> {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        let format = format.map(str::to_ascii_lowercase).unwrap_or_else(|| {
+            if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+                "tar.gz".to_string()
+            } else {
+                name.rsplit('.').next().unwrap_or_default().to_string()
+            }
+        });
+        match format.as_str() {
+            "zip" => Ok(Self::Zip),
+            "tar" => Ok(Self::Tar),
+            "tar.gz" | "tgz" => Ok(Self::TarGz),
+            "gz" | "gzip" => Ok(Self::Gz),
+            other => Err(anyhow::anyhow!(
+                "Unsupported archive format '{}' (zip, tar, tar.gz or gz)",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ArchiveSummary {
+    pub entries: usize,
+    pub bytes: u64,
+    /// Symlinks, hardlinks and special files that were left out
+    pub skipped: Vec<String>,
+}
+
+struct Budget {
+    summary: ArchiveSummary,
+}
+
+impl Budget {
+    fn new() -> Self {
+        Self {
+            summary: ArchiveSummary::default(),
+        }
+    }
+
+    fn entry(&mut self) -> Result<()> {
+        self.summary.entries += 1;
+        if self.summary.entries > MAX_ENTRIES {
+            return Err(anyhow::anyhow!(
+                "Archive has more than {} entries",
+                MAX_ENTRIES
+            ));
+        }
+        Ok(())
+    }
+
+    fn remaining(&self) -> u64 {
+        MAX_TOTAL_BYTES.saturating_sub(self.summary.bytes)
+    }
+
+    /// 한도를 넘으면 중단하도록 최대 `remaining + 1` 바이트만 복사합니다
+    fn copy(&mut self, reader: &mut impl Read, writer: &mut impl Write) -> Result<()> {
+        let copied = io::copy(&mut reader.take(self.remaining() + 1), writer)?;
+        self.summary.bytes += copied;
+        if self.summary.bytes > MAX_TOTAL_BYTES {
+            return Err(anyhow::anyhow!(
+                "Archive contents exceed the {} MiB limit",
+                MAX_TOTAL_BYTES / 1024 / 1024
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 항목 경로에서 절대 경로, `..`, 드라이브 접두사를 거부합니다
+fn sanitize(entry: &Path) -> Result<PathBuf> {
+    let mut clean = PathBuf::new();
+    for component in entry.components() {
+        match component {
+            Component::Normal(part) => clean.push(part),
+            Component::CurDir => {}
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Refusing unsafe entry path '{}'",
+                    entry.display()
+                ))
+            }
+        }
+    }
+    if clean.as_os_str().is_empty() {
+        return Err(anyhow::anyhow!("Archive entry has an empty path"));
+    }
+    Ok(clean)
+}
+
+/// Resolve an entry below `destination` without passing through existing symlinks
+fn target(destination: &Path, relative: &Path) -> Result<PathBuf> {
+    let mut path = destination.to_path_buf();
+    for component in relative.components() {
+        path.push(component);
+        if std::fs::symlink_metadata(&path).is_ok_and(|m| m.file_type().is_symlink()) {
+            return Err(anyhow::anyhow!(
+                "Refusing to write through symlink '{}'",
+                path.display()
+            ));
+        }
+    }
+    Ok(path)
+}
+
+/// Called with each file path right before extraction creates or overwrites it
+pub type BeforeWrite<'a> = &'a mut dyn FnMut(&Path) -> Result<()>;
+
+fn write_entry(
+    budget: &mut Budget,
+    destination: &Path,
+    relative: &Path,
+    reader: &mut impl Read,
+    before_write: BeforeWrite,
+) -> Result<()> {
+    let path = target(destination, relative)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    before_write(&path)?;
+    let mut file = File::create(&path)?;
+    if let Err(e) = budget.copy(reader, &mut file) {
+        drop(file);
+        let _ = std::fs::remove_file(&path);
+        return Err(e);
+    }
+    Ok(())
+}
+
+fn extract_zip(
+    archive: &Path,
+    destination: &Path,
+    budget: &mut Budget,
+    before_write: BeforeWrite,
+) -> Result<()> {
+    let mut zip = zip::ZipArchive::new(File::open(archive)?)?;
+    for index in 0..zip.len() {
+        budget.entry()?;
+        let mut entry = zip.by_index(index)?;
+        let Some(relative) = entry.enclosed_name() else {
+            return Err(anyhow::anyhow!(
+                "Refusing unsafe entry path '{}'",
+                entry.name()
+            ));
+        };
+        let relative = sanitize(&relative)?;
+        if entry.is_dir() {
+            std::fs::create_dir_all(target(destination, &relative)?)?;
+        } else if entry.is_symlink() {
+            budget.summary.skipped.push(entry.name().to_string());
+        } else {
+            write_entry(budget, destination, &relative, &mut entry, before_write)?;
+        }
+    }
+    Ok(())
+}
+
+fn extract_tar(
+    reader: impl Read,
+    destination: &Path,
+    budget: &mut Budget,
+    before_write: BeforeWrite,
+) -> Result<()> {
+    let mut tar = tar::Archive::new(reader);
+    for entry in tar.entries()? {
+        budget.entry()?;
+        let mut entry = entry?;
+        let name = entry.path()?.to_path_buf();
+        let relative = sanitize(&name)?;
+        match entry.header().entry_type() {
+            tar::EntryType::Directory => {
+                std::fs::create_dir_all(target(destination, &relative)?)?;
+            }
+            tar::EntryType::Regular | tar::EntryType::Continuous => {
+                write_entry(budget, destination, &relative, &mut entry, before_write)?;
+            }
+            // 심볼릭/하드 링크와 장치 파일은 루트 밖을 가리킬 수 있으므로 건너뜁니다
+            _ => budget.summary.skipped.push(name.display().to_string()),
+        }
+    }
+    Ok(())
+}
+
+/// Extract into `destination`; `gz` archives become one file named after the archive
+pub fn extract(
+    archive: &Path,
+    destination: &Path,
+    format: ArchiveFormat,
+    before_write: BeforeWrite,
+) -> Result<ArchiveSummary> {
+    std::fs::create_dir_all(destination)?;
+    let mut budget = Budget::new();
+    match format {
+        ArchiveFormat::Zip => extract_zip(archive, destination, &mut budget, before_write)?,
+        ArchiveFormat::Tar => {
+            extract_tar(File::open(archive)?, destination, &mut budget, before_write)?
+        }
+        ArchiveFormat::TarGz => extract_tar(
+            GzDecoder::new(File::open(archive)?),
+            destination,
+            &mut budget,
+            before_write,
+        )?,
+        ArchiveFormat::Gz => {
+            budget.entry()?;
+            let name = archive
+                .file_stem()
+                .ok_or_else(|| anyhow::anyhow!("Cannot name the decompressed file"))?;
+            write_entry(
+                &mut budget,
+                destination,
+                Path::new(name),
+                &mut GzDecoder::new(File::open(archive)?),
+                before_write,
+            )?;
+        }
+    }
+    Ok(budget.summary)
+}
+
+/// 디렉토리를 재귀적으로 훑되 심볼릭 링크는 따라가지 않습니다
+fn collect(
+    path: &Path,
+    name: PathBuf,
+    files: &mut Vec<(PathBuf, PathBuf, bool)>,
+    skipped: &mut Vec<String>,
+) -> Result<()> {
+    let metadata = std::fs::symlink_metadata(path)?;
+    if metadata.is_dir() {
+        files.push((path.to_path_buf(), name.clone(), true));
+        let mut children: Vec<_> = std::fs::read_dir(path)?.collect::<io::Result<_>>()?;
+        children.sort_by_key(|entry| entry.file_name());
+        for child in children {
+            collect(&child.path(), name.join(child.file_name()), files, skipped)?;
+        }
+    } else if metadata.is_file() {
+        files.push((path.to_path_buf(), name, false));
+    } else {
+        skipped.push(path.display().to_string());
+    }
+    Ok(())
+}
+
+fn append_tar<W: Write>(
+    writer: W,
+    files: &[(PathBuf, PathBuf, bool)],
+    budget: &mut Budget,
+) -> Result<W> {
+    let mut tar = tar::Builder::new(writer);
+    tar.follow_symlinks(false);
+    for (path, name, is_dir) in files {
+        budget.entry()?;
+        if *is_dir {
+            tar.append_dir(name, path)?;
+            continue;
+        }
+        let size = std::fs::metadata(path)?.len();
+        if size > budget.remaining() {
+            return Err(anyhow::anyhow!(
+                "Archive contents exceed the {} MiB limit",
+                MAX_TOTAL_BYTES / 1024 / 1024
+            ));
+        }
+        budget.summary.bytes += size;
+        tar.append_path_with_name(path, name)?;
+    }
+    Ok(tar.into_inner()?)
+}
+
+/// Bundle `sources` (files or directories) into `output`
+pub fn create(sources: &[PathBuf], output: &Path, format: ArchiveFormat) -> Result<ArchiveSummary> {
+    let mut budget = Budget::new();
+    let mut files = Vec::new();
+    for source in sources {
+        let name = source
+            .file_name()
+            .map(PathBuf::from)
+            .ok_or_else(|| anyhow::anyhow!("Cannot archive '{}'", source.display()))?;
+        collect(source, name, &mut files, &mut budget.summary.skipped)?;
+    }
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let result = (|| -> Result<()> {
+        match format {
+            ArchiveFormat::Zip => {
+                let mut zip = zip::ZipWriter::new(File::create(output)?);
+                let options = SimpleFileOptions::default()
+                    .compression_method(zip::CompressionMethod::Deflated);
+                for (path, name, is_dir) in &files {
+                    budget.entry()?;
+                    // zip 항목 이름은 항상 `/` 구분자를 사용합니다
+                    let name = name
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    if *is_dir {
+                        zip.add_directory(name, options)?;
+                    } else {
+                        zip.start_file(name, options)?;
+                        budget.copy(&mut File::open(path)?, &mut zip)?;
+                    }
+                }
+                zip.finish()?;
+            }
+            ArchiveFormat::Tar => {
+                append_tar(File::create(output)?, &files, &mut budget)?.flush()?;
+            }
+            ArchiveFormat::TarGz => {
+                let encoder = GzEncoder::new(File::create(output)?, Compression::default());
+                append_tar(encoder, &files, &mut budget)?.finish()?;
+            }
+            ArchiveFormat::Gz => {
+                let [(path, _, false)] = files.as_slice() else {
+                    return Err(anyhow::anyhow!("gz compresses exactly one file"));
+                };
+                budget.entry()?;
+                let mut encoder = GzEncoder::new(File::create(output)?, Compression::default());
+                budget.copy(&mut File::open(path)?, &mut encoder)?;
+                encoder.finish()?;
+            }
+        }
+        Ok(())
+    })();
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(output);
+        return Err(e);
+    }
+    Ok(budget.summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("archive-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Tar with raw entry names, so unsafe paths the builder would refuse can be written
+    fn write_tar(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut builder = tar::Builder::new(File::create(path).unwrap());
+        for (name, data) in entries {
+            let mut header = tar::Header::new_old();
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_size(data.len() as u64);
+            header.set_entry_type(tar::EntryType::Regular);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append(&header, *data).unwrap();
+        }
+        builder.finish().unwrap();
+    }
+
+    fn no_hook() -> impl FnMut(&Path) -> Result<()> {
+        |_| Ok(())
+    }
+
+    #[test]
+    fn sanitize_rejects_escaping_paths() {
+        for path in ["../x", "a/../../x", "a/..", "/etc/passwd", "", "."] {
+            assert!(sanitize(Path::new(path)).is_err(), "{}", path);
+        }
+        assert_eq!(
+            sanitize(Path::new("./a/./b.txt")).unwrap(),
+            PathBuf::from("a/b.txt")
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn sanitize_rejects_windows_prefixes() {
+        for path in [r"C:\x", r"C:x", r"\\server\share\x", r"\x"] {
+            assert!(sanitize(Path::new(path)).is_err(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn extract_refuses_parent_entries() {
+        let dir = temp_dir();
+        let archive = dir.join("evil.tar");
+        write_tar(&archive, &[("../escaped.txt", b"x")]);
+        let destination = dir.join("out");
+
+        let result = extract(&archive, &destination, ArchiveFormat::Tar, &mut no_hook());
+        let escaped = dir.join("escaped.txt").exists();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(result.is_err());
+        assert!(!escaped);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn extract_refuses_existing_symlinks() {
+        let dir = temp_dir();
+        let outside = dir.join("outside");
+        let destination = dir.join("out");
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::create_dir_all(&destination).unwrap();
+        std::os::unix::fs::symlink(&outside, destination.join("link")).unwrap();
+        let archive = dir.join("link.tar");
+        write_tar(&archive, &[("link/file.txt", b"x")]);
+
+        let direct = target(&destination, Path::new("link/file.txt"));
+        let result = extract(&archive, &destination, ArchiveFormat::Tar, &mut no_hook());
+        let written = outside.join("file.txt").exists();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(direct.is_err());
+        assert!(result.is_err());
+        assert!(!written);
+    }
+
+    #[test]
+    fn extract_writes_safe_entries() {
+        let dir = temp_dir();
+        let archive = dir.join("ok.tar");
+        write_tar(&archive, &[("a/b.txt", b"hello"), ("c.txt", b"!")]);
+        let destination = dir.join("out");
+
+        let mut written = Vec::new();
+        let mut hook = |path: &Path| {
+            written.push(path.to_path_buf());
+            Ok(())
+        };
+        let summary = extract(&archive, &destination, ArchiveFormat::Tar, &mut hook);
+        let content = std::fs::read_to_string(destination.join("a/b.txt"));
+        let _ = std::fs::remove_dir_all(&dir);
+        let summary = summary.unwrap();
+        assert_eq!((summary.entries, summary.bytes), (2, 6));
+        assert_eq!(written.len(), 2);
+        assert_eq!(content.unwrap(), "hello");
+    }
+
+    #[test]
+    fn budget_caps_entries() {
+        let mut budget = Budget::new();
+        budget.summary.entries = MAX_ENTRIES - 1;
+        assert!(budget.entry().is_ok());
+        assert!(budget.entry().is_err());
+    }
+
+    #[test]
+    fn budget_caps_total_bytes() {
+        let mut budget = Budget::new();
+        budget.summary.bytes = MAX_TOTAL_BYTES - 8;
+        let mut sink = Vec::new();
+        assert!(budget.copy(&mut &[0u8; 8][..], &mut sink).is_ok());
+        assert!(budget.copy(&mut &[0u8; 1][..], &mut sink).is_err());
+        // 한도를 넘는 만큼만 읽고 멈춥니다
+        assert_eq!(sink.len(), 9);
+    }
+
+    #[test]
+    fn oversized_entries_are_removed() {
+        let dir = temp_dir();
+        let mut budget = Budget::new();
+        budget.summary.bytes = MAX_TOTAL_BYTES - 2;
+        let result = write_entry(
+            &mut budget,
+            &dir,
+            Path::new("big.bin"),
+            &mut &[0u8; 16][..],
+            &mut no_hook(),
+        );
+        let left = dir.join("big.bin").exists();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(result.is_err());
+        assert!(!left);
+    }
+}
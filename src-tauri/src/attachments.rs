@@ -0,0 +1,320 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentInfo {
+    pub hash: String,
+    pub size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    pub ref_count: u32,
+    pub created_at: u64,
+}
+
+/// Content-addressed blob store for message attachments.
+///
+/// Blobs live under `<root>/<hash[..2]>/<hash>` and are shared between every
+/// message that references the same content; `index.json` tracks reference counts.
+pub struct AttachmentStore {
+    root: PathBuf,
+    index: Mutex<Option<HashMap<String, AttachmentInfo>>>,
+}
+
+impl AttachmentStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            index: Mutex::new(None),
+        }
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.root.join("index.json")
+    }
+
+    fn blob_path(&self, hash: &str) -> Result<PathBuf> {
+        if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(anyhow::anyhow!("Invalid attachment hash: {}", hash));
+        }
+        Ok(self.root.join(&hash[..2]).join(hash))
+    }
+
+    async fn loaded<'a>(
+        &self,
+        cache: &'a mut Option<HashMap<String, AttachmentInfo>>,
+    ) -> Result<&'a mut HashMap<String, AttachmentInfo>> {
+        if cache.is_none() {
+            let index = match tokio::fs::read_to_string(self.index_path()).await {
+                Ok(content) => serde_json::from_str(&content)?,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+                Err(e) => return Err(e.into()),
+            };
+            *cache = Some(index);
+        }
+        Ok(cache.get_or_insert_with(HashMap::new))
+    }
+
+    async fn persist(&self, index: &HashMap<String, AttachmentInfo>) -> Result<()> {
+        tokio::fs::create_dir_all(&self.root).await?;
+        tokio::fs::write(self.index_path(), serde_json::to_string_pretty(index)?).await?;
+        Ok(())
+    }
+
+    /// 바이트를 저장하고 참조 횟수를 증가시킵니다. 이미 있는 내용이면 파일을 다시 쓰지 않습니다
+    pub async fn store_bytes(
+        &self,
+        bytes: &[u8],
+        mime_type: Option<String>,
+    ) -> Result<AttachmentInfo> {
+        let hash = format!("{:x}", Sha256::digest(bytes));
+        let blob_path = self.blob_path(&hash)?;
+
+        let mut guard = self.index.lock().await;
+        let index = self.loaded(&mut guard).await?;
+
+        if let Some(info) = index.get_mut(&hash) {
+            if tokio::fs::try_exists(&blob_path).await.unwrap_or(false) {
+                info.ref_count += 1;
+                let info = info.clone();
+                self.persist(index).await?;
+                println!(
+                    "Deduplicated attachment {} (refs: {})",
+                    info.hash, info.ref_count
+                );
+                return Ok(info);
+            }
+        }
+
+        if let Some(parent) = blob_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        // 부분적으로 쓰여진 blob이 남지 않도록 임시 파일에 쓴 뒤 이동합니다
+        let tmp_path = blob_path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, bytes).await?;
+        tokio::fs::rename(&tmp_path, &blob_path).await?;
+
+        let info = AttachmentInfo {
+            hash: hash.clone(),
+            size: bytes.len() as u64,
+            mime_type,
+            ref_count: 1,
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        };
+        index.insert(hash, info.clone());
+        self.persist(index).await?;
+        println!("Stored attachment {} ({} bytes)", info.hash, info.size);
+        Ok(info)
+    }
+
+    /// Store the contents of a file on disk
+    pub async fn store_file(
+        &self,
+        path: &Path,
+        mime_type: Option<String>,
+    ) -> Result<AttachmentInfo> {
+        let bytes = tokio::fs::read(path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+        self.store_bytes(&bytes, mime_type).await
+    }
+
+    pub async fn info(&self, hash: &str) -> Result<AttachmentInfo> {
+        let mut guard = self.index.lock().await;
+        self.loaded(&mut guard)
+            .await?
+            .get(hash)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Attachment '{}' not found", hash))
+    }
+
+    /// Add a reference to an existing attachment (e.g. a copied message)
+    pub async fn retain(&self, hash: &str) -> Result<AttachmentInfo> {
+        let mut guard = self.index.lock().await;
+        let index = self.loaded(&mut guard).await?;
+        let info = index
+            .get_mut(hash)
+            .ok_or_else(|| anyhow::anyhow!("Attachment '{}' not found", hash))?;
+        info.ref_count += 1;
+        let info = info.clone();
+        self.persist(index).await?;
+        Ok(info)
+    }
+
+    /// Drop a reference; the blob is deleted once nothing references it
+    pub async fn release(&self, hash: &str) -> Result<()> {
+        let blob_path = self.blob_path(hash)?;
+        let mut guard = self.index.lock().await;
+        let index = self.loaded(&mut guard).await?;
+        let info = index
+            .get_mut(hash)
+            .ok_or_else(|| anyhow::anyhow!("Attachment '{}' not found", hash))?;
+
+        info.ref_count = info.ref_count.saturating_sub(1);
+        if info.ref_count == 0 {
+            index.remove(hash);
+            if let Err(e) = tokio::fs::remove_file(&blob_path).await {
+                println!("Warning: Failed to remove attachment blob {}: {}", hash, e);
+            }
+            println!("Removed unreferenced attachment {}", hash);
+        }
+        self.persist(index).await
+    }
+
+    /// Open a blob for streaming reads
+    pub async fn open(&self, hash: &str) -> Result<tokio::fs::File> {
+        let blob_path = self.blob_path(hash)?;
+        tokio::fs::File::open(&blob_path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Attachment '{}' is not available: {}", hash, e))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AttachmentCompaction {
+    pub removed_blobs: usize,
+    pub removed_index_entries: usize,
+    pub reclaimed_bytes: u64,
+    /// Bytes still above the size cap because the remaining blobs are referenced
+    pub over_cap_bytes: u64,
+}
+
+impl AttachmentStore {
+    pub async fn list(&self) -> Result<Vec<AttachmentInfo>> {
+        let mut guard = self.index.lock().await;
+        Ok(self.loaded(&mut guard).await?.values().cloned().collect())
+    }
+
+    /// 인덱스와 디스크 상태를 맞춥니다: 고아 blob, 남은 임시 파일, 파일이 없는 항목을 정리합니다
+    pub async fn compact(&self) -> Result<AttachmentCompaction> {
+        let mut report = AttachmentCompaction::default();
+        let mut guard = self.index.lock().await;
+        let index = self.loaded(&mut guard).await?;
+
+        let mut shards = match tokio::fs::read_dir(&self.root).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(report),
+            Err(e) => return Err(e.into()),
+        };
+        while let Some(shard) = shards.next_entry().await? {
+            if !shard.file_type().await?.is_dir() {
+                continue;
+            }
+            let mut blobs = tokio::fs::read_dir(shard.path()).await?;
+            while let Some(blob) = blobs.next_entry().await? {
+                let name = blob.file_name().to_string_lossy().to_string();
+                if index.contains_key(&name) {
+                    continue;
+                }
+                let size = blob.metadata().await.map(|m| m.len()).unwrap_or(0);
+                if tokio::fs::remove_file(blob.path()).await.is_ok() {
+                    report.removed_blobs += 1;
+                    report.reclaimed_bytes += size;
+                }
+            }
+        }
+
+        let mut missing = Vec::new();
+        for hash in index.keys() {
+            let exists = match self.blob_path(hash) {
+                Ok(path) => tokio::fs::try_exists(path).await.unwrap_or(false),
+                Err(_) => false,
+            };
+            if !exists {
+                missing.push(hash.clone());
+            }
+        }
+        for hash in missing {
+            index.remove(&hash);
+            report.removed_index_entries += 1;
+        }
+
+        self.persist(index).await?;
+        Ok(report)
+    }
+
+    /// Evict the oldest unreferenced blobs until the store fits in `max_bytes`; blobs a
+    /// message still references are kept and counted in `over_cap_bytes`
+    pub async fn evict_to_size(&self, max_bytes: u64) -> Result<AttachmentCompaction> {
+        let mut report = AttachmentCompaction::default();
+        let mut guard = self.index.lock().await;
+        let index = self.loaded(&mut guard).await?;
+
+        let mut total: u64 = index.values().map(|info| info.size).sum();
+        if total <= max_bytes {
+            return Ok(report);
+        }
+
+        let mut by_age: Vec<AttachmentInfo> = index
+            .values()
+            .filter(|info| info.ref_count == 0)
+            .cloned()
+            .collect();
+        by_age.sort_by_key(|info| info.created_at);
+        for info in by_age {
+            if total <= max_bytes {
+                break;
+            }
+            if let Ok(path) = self.blob_path(&info.hash) {
+                let _ = tokio::fs::remove_file(path).await;
+            }
+            index.remove(&info.hash);
+            total = total.saturating_sub(info.size);
+            report.removed_blobs += 1;
+            report.removed_index_entries += 1;
+            report.reclaimed_bytes += info.size;
+        }
+
+        self.persist(index).await?;
+        report.over_cap_bytes = total.saturating_sub(max_bytes);
+        println!(
+            "Evicted {} attachments ({} bytes) to fit the {} byte cap",
+            report.removed_blobs, report.reclaimed_bytes, max_bytes
+        );
+        if report.over_cap_bytes > 0 {
+            println!(
+                "Warning: Referenced attachments keep the store {} bytes over its cap",
+                report.over_cap_bytes
+            );
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn eviction_skips_referenced_blobs_and_reports_the_shortfall() {
+        let root = std::env::temp_dir().join(format!("attachments-{}", uuid::Uuid::new_v4()));
+        let store = AttachmentStore::new(root.clone());
+        let kept = store.store_bytes(&[1; 100], None).await.unwrap();
+        let orphan = store.store_bytes(&[2; 50], None).await.unwrap();
+        drop(store);
+
+        // 참조가 없는 항목은 이전 버전이 남긴 인덱스에서만 생깁니다
+        let index_path = root.join("index.json");
+        let mut index: HashMap<String, AttachmentInfo> =
+            serde_json::from_str(&std::fs::read_to_string(&index_path).unwrap()).unwrap();
+        index.get_mut(&orphan.hash).unwrap().ref_count = 0;
+        std::fs::write(&index_path, serde_json::to_string(&index).unwrap()).unwrap();
+
+        let store = AttachmentStore::new(root.clone());
+        let report = store.evict_to_size(40).await.unwrap();
+        assert_eq!(report.removed_blobs, 1);
+        assert_eq!(report.reclaimed_bytes, 50);
+        assert_eq!(report.over_cap_bytes, 60);
+        assert!(store.info(&orphan.hash).await.is_err());
+        assert_eq!(store.info(&kept.hash).await.unwrap().ref_count, 1);
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
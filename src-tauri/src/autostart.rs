@@ -0,0 +1,219 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::{config_versions, get_mcp_manager, load_connection_snapshot, prepare_server_config};
+
+/// Passed by the login entry: start hidden in the tray and warm up servers
+pub const BACKGROUND_FLAG: &str = "--background";
+const SETTINGS_FILE: &str = "autostart.json";
+// tauri.conf.json의 productName과 같아야 합니다
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+const APP_NAME: &str = "SynapticFlow";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AutostartSettings {
+    /// Launch the app when the user logs in
+    #[serde(default)]
+    pub enabled: bool,
+    /// Start login launches in background mode: hidden in the tray, with servers flagged
+    /// `autostart` warmed up
+    #[serde(default)]
+    pub background: bool,
+}
+
+/// `--background` was given
+pub fn requested(args: &[String]) -> bool {
+    args.iter().any(|arg| arg == BACKGROUND_FLAG)
+}
+
+fn settings_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(SETTINGS_FILE)
+}
+
+pub fn load_settings(data_dir: &Path) -> Result<AutostartSettings> {
+    match std::fs::read_to_string(settings_path(data_dir)) {
+        Ok(content) => Ok(serde_json::from_str(&content)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(AutostartSettings::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn save_settings(data_dir: &Path, settings: &AutostartSettings) -> Result<()> {
+    config_versions::write_blocking(
+        &settings_path(data_dir),
+        &serde_json::to_string_pretty(settings)?,
+    )?;
+    Ok(())
+}
+
+/// The file the OS should run to start the app
+pub(crate) fn executable() -> Result<PathBuf> {
+    // AppImage는 실행할 때마다 다른 경로에 마운트되므로 이미지 파일 경로를 사용합니다
+    match std::env::var_os("APPIMAGE") {
+        Some(image) => Ok(PathBuf::from(image)),
+        None => Ok(std::env::current_exe()?),
+    }
+}
+
+/// The command line the OS runs at login
+fn launch_command(settings: &AutostartSettings) -> Result<(PathBuf, Vec<String>)> {
+    let executable = executable()?;
+    let args = if settings.background {
+        vec![BACKGROUND_FLAG.to_string()]
+    } else {
+        Vec::new()
+    };
+    Ok((executable, args))
+}
+
+/// Register or remove the login entry to match `settings`
+#[cfg(target_os = "linux")]
+pub fn apply(settings: &AutostartSettings) -> Result<()> {
+    let path = dirs::config_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine the config directory"))?
+        .join("autostart")
+        .join(format!("{}.desktop", crate::headless::APP_IDENTIFIER));
+    if !settings.enabled {
+        return match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        };
+    }
+    let (executable, args) = launch_command(settings)?;
+    let exec = std::iter::once(executable.to_string_lossy().to_string())
+        .chain(args)
+        .map(|arg| format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(
+        &path,
+        format!(
+            "[Desktop Entry]\nType=Application\nName={}\nExec={}\nX-GNOME-Autostart-enabled=true\n",
+            APP_NAME, exec
+        ),
+    )?;
+    Ok(())
+}
+
+/// Register or remove the login entry to match `settings`
+#[cfg(target_os = "macos")]
+pub fn apply(settings: &AutostartSettings) -> Result<()> {
+    let identifier = crate::headless::APP_IDENTIFIER;
+    let path = dirs::home_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine the home directory"))?
+        .join("Library/LaunchAgents")
+        .join(format!("{}.plist", identifier));
+    if !settings.enabled {
+        return match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        };
+    }
+    let (executable, args) = launch_command(settings)?;
+    let escape = |value: &str| {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    let program_arguments = std::iter::once(executable.to_string_lossy().to_string())
+        .chain(args)
+        .map(|arg| format!("        <string>{}</string>\n", escape(&arg)))
+        .collect::<String>();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(
+        &path,
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+{}    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+            identifier, program_arguments
+        ),
+    )?;
+    Ok(())
+}
+
+/// Register or remove the login entry to match `settings`
+#[cfg(target_os = "windows")]
+pub fn apply(settings: &AutostartSettings) -> Result<()> {
+    const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+    let mut command = std::process::Command::new("reg");
+    if settings.enabled {
+        let (executable, args) = launch_command(settings)?;
+        let mut value = format!("\"{}\"", executable.display());
+        for arg in args {
+            value.push(' ');
+            value.push_str(&arg);
+        }
+        command.args([
+            "add", RUN_KEY, "/v", APP_NAME, "/t", "REG_SZ", "/d", &value, "/f",
+        ]);
+    } else {
+        command.args(["delete", RUN_KEY, "/v", APP_NAME, "/f"]);
+    }
+    let output = command.output()?;
+    // 등록되지 않은 값을 지우는 것은 실패로 보지 않습니다
+    if !output.status.success() && settings.enabled {
+        return Err(anyhow::anyhow!(
+            "Failed to update the Run key: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Register or remove the login entry to match `settings`
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn apply(settings: &AutostartSettings) -> Result<()> {
+    if settings.enabled {
+        return Err(anyhow::anyhow!(
+            "Autostart is not supported on this platform"
+        ));
+    }
+    Ok(())
+}
+
+/// Start the servers flagged `autostart` from the last session, so their packages are
+/// installed and running before the first prompt
+pub async fn warm_up() -> Result<()> {
+    let Some(snapshot) = load_connection_snapshot()
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?
+    else {
+        return Ok(());
+    };
+    let mut configs = Vec::new();
+    for config in snapshot
+        .servers
+        .into_iter()
+        .filter(|config| config.autostart)
+    {
+        configs.push(prepare_server_config(config).await);
+    }
+    if configs.is_empty() {
+        return Ok(());
+    }
+    let report = get_mcp_manager().start_servers(configs).await?;
+    println!("Warmed up {} servers", report.started.len());
+    for (name, error) in report.failed.iter().chain(report.skipped.iter()) {
+        eprintln!("❌ Failed to warm up server {}: {}", name, error);
+    }
+    Ok(())
+}
@@ -0,0 +1,345 @@
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::sync::{Mutex, RwLock};
+
+use crate::api::ask_webview;
+use crate::archive::{self, ArchiveFormat};
+use crate::config_versions;
+use crate::events;
+use crate::power;
+
+const ID_PREFIX: &str = "backup-";
+const ID_TIME_FORMAT: &str = "%Y%m%d-%H%M%S";
+/// Archive entry holding the webview's session database
+const SESSION_DB_ENTRY: &str = "session_db.json";
+// 백그라운드 루프가 예약된 백업을 확인하는 주기
+const CHECK_INTERVAL_SECS: u64 = 300;
+
+fn default_interval_hours() -> u64 {
+    24
+}
+
+fn default_keep() -> usize {
+    10
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupSettings {
+    /// Create backups on a schedule; manual backups work either way
+    #[serde(default)]
+    pub enabled: bool,
+    /// Directory the archives are written to
+    #[serde(default)]
+    pub directory: Option<String>,
+    #[serde(default = "default_interval_hours")]
+    pub interval_hours: u64,
+    /// Archives to keep; older ones are deleted after each backup
+    #[serde(default = "default_keep")]
+    pub keep: usize,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: None,
+            interval_hours: default_interval_hours(),
+            keep: default_keep(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupInfo {
+    pub id: String,
+    pub path: PathBuf,
+    pub created_at: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupRestoreReport {
+    pub id: String,
+    /// Config files written back; the versions they replaced can be rolled back
+    pub restored: Vec<String>,
+    /// Sessions and messages were merged back into the session database
+    pub session_db: bool,
+    /// Restored files that only apply after a restart
+    pub restart_required: Vec<String>,
+}
+
+fn created_at(id: &str) -> Option<DateTime<Local>> {
+    let stamp = id.strip_prefix(ID_PREFIX)?;
+    NaiveDateTime::parse_from_str(stamp, ID_TIME_FORMAT)
+        .ok()?
+        .and_local_timezone(Local)
+        .earliest()
+}
+
+/// Config store files: every JSON file at the top of the app data directory.
+/// The memory server's `memory.json` lives there too.
+fn config_files(app_data_dir: &Path, settings_path: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(app_data_dir)? {
+        let path = entry?.path();
+        // 백업 설정 자체는 이 기기의 경로이므로 포함하지 않습니다
+        if path.is_file()
+            && path.extension().is_some_and(|ext| ext == "json")
+            && path != settings_path
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn list_dir(directory: &Path) -> Result<Vec<BackupInfo>> {
+    let mut backups = Vec::new();
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(backups),
+        Err(e) => return Err(e.into()),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(id) = name.strip_suffix(".zip") else {
+            continue;
+        };
+        let Some(created) = created_at(id) else {
+            continue;
+        };
+        backups.push(BackupInfo {
+            id: id.to_string(),
+            path: entry.path(),
+            created_at: created.to_rfc3339(),
+            size_bytes: entry.metadata().map(|m| m.len()).unwrap_or_default(),
+        });
+    }
+    // id에 시각이 들어 있으므로 이름순 역정렬이 최신순입니다
+    backups.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(backups)
+}
+
+/// Periodic archives of the config store, memory DB and session DB
+pub struct BackupService {
+    app_data_dir: PathBuf,
+    settings_path: PathBuf,
+    settings: RwLock<Option<BackupSettings>>,
+    // 예약 백업과 수동 백업이 겹치지 않도록 합니다
+    running: Mutex<()>,
+}
+
+impl BackupService {
+    pub fn new(app_data_dir: PathBuf, settings_path: PathBuf) -> Self {
+        Self {
+            app_data_dir,
+            settings_path,
+            settings: RwLock::new(None),
+            running: Mutex::new(()),
+        }
+    }
+
+    /// Forget the cached copy so the next read picks up the file on disk
+    pub async fn reload(&self) {
+        *self.settings.write().await = None;
+    }
+
+    pub async fn settings(&self) -> BackupSettings {
+        if let Some(settings) = self.settings.read().await.as_ref() {
+            return settings.clone();
+        }
+        let settings: BackupSettings = tokio::fs::read_to_string(&self.settings_path)
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        *self.settings.write().await = Some(settings.clone());
+        settings
+    }
+
+    pub async fn save_settings(&self, settings: BackupSettings) -> Result<()> {
+        if settings.interval_hours == 0 {
+            return Err(anyhow::anyhow!("interval_hours must be greater than 0"));
+        }
+        if settings.keep == 0 {
+            return Err(anyhow::anyhow!("keep must be greater than 0"));
+        }
+        if let Some(directory) = &settings.directory {
+            tokio::fs::create_dir_all(directory)
+                .await
+                .map_err(|e| anyhow::anyhow!("Cannot use backup directory {}: {}", directory, e))?;
+        }
+        config_versions::write(
+            &self.settings_path,
+            serde_json::to_string_pretty(&settings)?,
+        )
+        .await?;
+        *self.settings.write().await = Some(settings);
+        Ok(())
+    }
+
+    async fn directory(&self) -> Result<PathBuf> {
+        self.settings()
+            .await
+            .directory
+            .map(PathBuf::from)
+            .ok_or_else(|| anyhow::anyhow!("No backup directory has been chosen"))
+    }
+
+    /// Backups in the chosen directory, newest first
+    pub async fn list(&self) -> Result<Vec<BackupInfo>> {
+        let directory = self.directory().await?;
+        tokio::task::spawn_blocking(move || list_dir(&directory)).await?
+    }
+
+    /// Write a timestamped archive now and prune the oldest beyond `keep`.
+    /// The session DB lives in the webview, so it is only included while the app window is up.
+    pub async fn create(&self) -> Result<BackupInfo> {
+        let _running = self.running.lock().await;
+        let settings = self.settings().await;
+        let directory = self.directory().await?;
+        let id = format!("{}{}", ID_PREFIX, Local::now().format(ID_TIME_FORMAT));
+        let output = directory.join(format!("{}.zip", id));
+        if tokio::fs::try_exists(&output).await.unwrap_or(false) {
+            return Err(anyhow::anyhow!("Backup '{}' already exists", id));
+        }
+
+        let staging =
+            std::env::temp_dir().join(format!("backup-{}", uuid::Uuid::new_v4().simple()));
+        tokio::fs::create_dir_all(&staging).await?;
+        let result = async {
+            let mut sources = config_files(&self.app_data_dir, &self.settings_path)?;
+            if events::app_attached() {
+                match ask_webview("backup-export", serde_json::json!({})).await {
+                    Ok(export) => {
+                        let path = staging.join(SESSION_DB_ENTRY);
+                        tokio::fs::write(&path, serde_json::to_vec(&export)?).await?;
+                        sources.push(path);
+                    }
+                    Err(e) => eprintln!("⚠️ Backup {} is missing the session DB: {}", id, e),
+                }
+            }
+
+            // 쓰는 도중의 파일이 목록에 보이지 않도록 임시 이름으로 만든 뒤 옮깁니다
+            let partial = directory.join(format!("{}.zip.part", id));
+            let archive_path = partial.clone();
+            tokio::task::spawn_blocking(move || {
+                archive::create(&sources, &archive_path, ArchiveFormat::Zip)
+            })
+            .await??;
+            tokio::fs::rename(&partial, &output).await?;
+            Ok::<_, anyhow::Error>(())
+        }
+        .await;
+        let _ = tokio::fs::remove_dir_all(&staging).await;
+        result?;
+
+        let backups = {
+            let directory = directory.clone();
+            tokio::task::spawn_blocking(move || list_dir(&directory)).await??
+        };
+        for old in backups.iter().skip(settings.keep) {
+            if let Err(e) = tokio::fs::remove_file(&old.path).await {
+                eprintln!("❌ Failed to delete old backup {}: {}", old.id, e);
+            }
+        }
+        backups
+            .into_iter()
+            .find(|backup| backup.id == id)
+            .ok_or_else(|| anyhow::anyhow!("Backup '{}' was not written", id))
+    }
+
+    /// Put a backup's config files back and merge its sessions into the session DB.
+    /// Existing sessions are kept, and each replaced config file gets a version first.
+    pub async fn restore(&self, id: &str) -> Result<BackupRestoreReport> {
+        if created_at(id).is_none() {
+            return Err(anyhow::anyhow!("Invalid backup id '{}'", id));
+        }
+        let _running = self.running.lock().await;
+        let archive_path = self.directory().await?.join(format!("{}.zip", id));
+        if !tokio::fs::try_exists(&archive_path).await.unwrap_or(false) {
+            return Err(anyhow::anyhow!("Backup '{}' not found", id));
+        }
+
+        let staging =
+            std::env::temp_dir().join(format!("restore-{}", uuid::Uuid::new_v4().simple()));
+        let result = async {
+            let destination = staging.clone();
+            tokio::task::spawn_blocking(move || {
+                archive::extract(&archive_path, &destination, ArchiveFormat::Zip, &mut |_| {
+                    Ok(())
+                })
+            })
+            .await??;
+
+            let mut restored = Vec::new();
+            let mut session_db = false;
+            let settings_file = self.settings_path.file_name();
+            let mut entries = tokio::fs::read_dir(&staging).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let name = entry.file_name();
+                let file = name.to_string_lossy().to_string();
+                if !file.ends_with(".json") || Some(name.as_os_str()) == settings_file {
+                    continue;
+                }
+                let content = tokio::fs::read_to_string(entry.path()).await?;
+                if file == SESSION_DB_ENTRY {
+                    if !events::app_attached() {
+                        eprintln!("⚠️ The session DB in backup {} needs the app window", id);
+                        continue;
+                    }
+                    let export: serde_json::Value = serde_json::from_str(&content)?;
+                    ask_webview("backup-restore", export)
+                        .await
+                        .map_err(|e| anyhow::anyhow!("Failed to restore the session DB: {}", e))?;
+                    session_db = true;
+                } else {
+                    config_versions::write(&self.app_data_dir.join(&file), content).await?;
+                    restored.push(file);
+                }
+            }
+            restored.sort();
+            Ok::<_, anyhow::Error>(BackupRestoreReport {
+                id: id.to_string(),
+                restored,
+                session_db,
+                restart_required: Vec::new(),
+            })
+        }
+        .await;
+        let _ = tokio::fs::remove_dir_all(&staging).await;
+        result
+    }
+
+    /// Create a backup whenever the newest one is older than the interval, for as long as
+    /// the app runs
+    pub async fn run_background(&self) {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+            let settings = self.settings().await;
+            if !settings.enabled || settings.directory.is_none() || power::paused() {
+                continue;
+            }
+            let newest = match self.list().await {
+                Ok(backups) => backups.first().and_then(|backup| created_at(&backup.id)),
+                Err(e) => {
+                    eprintln!("❌ Failed to list backups: {}", e);
+                    continue;
+                }
+            };
+            let due = newest.is_none_or(|created| {
+                Local::now() - created >= chrono::Duration::hours(settings.interval_hours as i64)
+            });
+            if due {
+                match self.create().await {
+                    Ok(backup) => println!("Created scheduled backup {}", backup.id),
+                    Err(e) => eprintln!("❌ Scheduled backup failed: {}", e),
+                }
+            }
+        }
+    }
+}
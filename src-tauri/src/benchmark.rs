@@ -0,0 +1,86 @@
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use std::future::Future;
+use std::time::Instant;
+
+use crate::conformance::LatencyStats;
+use crate::mcp::ToolCallResult;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkReport {
+    pub server_name: String,
+    pub tool_name: String,
+    pub iterations: usize,
+    pub concurrency: usize,
+    /// Latency of the first call, which includes starting the server if it wasn't running
+    pub cold_start_ms: f64,
+    /// Latency of the remaining calls
+    pub latency: LatencyStats,
+    pub errors: usize,
+    pub error_rate: f64,
+    pub calls_per_second: f64,
+    pub first_error: Option<String>,
+}
+
+/// 도구 호출의 지연 시간과 처리량을 측정합니다
+///
+/// The first call runs alone so cold-start cost doesn't skew the percentiles;
+/// the rest run with up to `concurrency` calls in flight.
+pub async fn run<F, Fut>(
+    server_name: &str,
+    tool_name: &str,
+    iterations: usize,
+    concurrency: usize,
+    call: F,
+) -> BenchmarkReport
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = ToolCallResult>,
+{
+    let iterations = iterations.max(1);
+    let concurrency = concurrency.max(1);
+
+    let timed = |started: Instant, result: ToolCallResult| {
+        (started.elapsed().as_secs_f64() * 1000.0, result)
+    };
+
+    let started = Instant::now();
+    let (cold_start_ms, first) = timed(started, call().await);
+
+    let wall_clock = Instant::now();
+    let results: Vec<(f64, ToolCallResult)> = stream::iter(1..iterations)
+        .map(|_| {
+            let future = call();
+            async move {
+                let started = Instant::now();
+                timed(started, future.await)
+            }
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+    let elapsed = wall_clock.elapsed().as_secs_f64();
+
+    let samples: Vec<f64> = results.iter().map(|(ms, _)| *ms).collect();
+    let failures: Vec<&ToolCallResult> = std::iter::once(&first)
+        .chain(results.iter().map(|(_, result)| result))
+        .filter(|result| !result.success)
+        .collect();
+
+    BenchmarkReport {
+        server_name: server_name.to_string(),
+        tool_name: tool_name.to_string(),
+        iterations,
+        concurrency,
+        cold_start_ms,
+        latency: LatencyStats::from_samples(&samples),
+        errors: failures.len(),
+        error_rate: failures.len() as f64 / iterations as f64,
+        calls_per_second: if elapsed > 0.0 {
+            samples.len() as f64 / elapsed
+        } else {
+            0.0
+        },
+        first_error: failures.first().and_then(|result| result.error.clone()),
+    }
+}
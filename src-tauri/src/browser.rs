@@ -0,0 +1,297 @@
+use anyhow::Result;
+use chromiumoxide::page::ScreenshotParams;
+use chromiumoxide::{Browser, BrowserConfig, Page};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::PathBuf;
+use tokio::sync::Mutex;
+
+use crate::attachments::AttachmentStore;
+use crate::builtin::{error_result, text_result, tool};
+use crate::config_versions;
+use crate::mcp::{MCPTool, ToolCallResult};
+
+/// Virtual server name used to route browser tool calls
+pub const SERVER_NAME: &str = "browser";
+
+// 에이전트 컨텍스트를 넘치지 않도록 추출한 텍스트를 자릅니다
+const MAX_TEXT_CHARS: usize = 50_000;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BrowserSettings {
+    /// Hosts the browser may visit; subdomains are included. Empty blocks everything
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+    /// Show the browser window instead of running headless
+    #[serde(default)]
+    pub show_window: bool,
+}
+
+struct Session {
+    browser: Browser,
+    page: Page,
+}
+
+/// A single Chromium instance with a persistent profile, started on first use
+pub struct BrowserTools {
+    settings_path: PathBuf,
+    profile_dir: PathBuf,
+    session: Mutex<Option<Session>>,
+}
+
+pub fn list_tools() -> Vec<MCPTool> {
+    let mut tools = vec![
+        tool(
+            "navigate",
+            "Open a URL on an allowed domain and return the page title",
+            json!({
+                "type": "object",
+                "properties": { "url": { "type": "string" } },
+                "required": ["url"]
+            }),
+            true,
+        ),
+        tool(
+            "extract_text",
+            "Return the visible text of the page, or of the first element matching a CSS selector",
+            json!({
+                "type": "object",
+                "properties": { "selector": { "type": "string" } }
+            }),
+            true,
+        ),
+        tool(
+            "screenshot",
+            "Capture the current page; returns an image attachment handle",
+            json!({
+                "type": "object",
+                "properties": { "full_page": { "type": "boolean" } }
+            }),
+            true,
+        ),
+        tool(
+            "click",
+            "Click the first element matching a CSS selector",
+            json!({
+                "type": "object",
+                "properties": { "selector": { "type": "string" } },
+                "required": ["selector"]
+            }),
+            false,
+        ),
+        tool(
+            "fill",
+            "Type a value into the first input matching a CSS selector",
+            json!({
+                "type": "object",
+                "properties": {
+                    "selector": { "type": "string" },
+                    "value": { "type": "string" }
+                },
+                "required": ["selector", "value"]
+            }),
+            false,
+        ),
+    ];
+    for tool in &mut tools {
+        if let Some(annotations) = tool.annotations.as_mut() {
+            annotations.open_world_hint = Some(true);
+        }
+    }
+    tools
+}
+
+fn host_allowed(url: &str, allowed_domains: &[String]) -> bool {
+    let Ok(url) = url::Url::parse(url) else {
+        return false;
+    };
+    if url.scheme() == "about" {
+        return true;
+    }
+    if !matches!(url.scheme(), "http" | "https") {
+        return false;
+    }
+    let Some(host) = url.host_str().map(str::to_ascii_lowercase) else {
+        return false;
+    };
+    allowed_domains.iter().any(|domain| {
+        let domain = domain.trim().trim_start_matches("*.").to_ascii_lowercase();
+        !domain.is_empty() && (host == domain || host.ends_with(&format!(".{}", domain)))
+    })
+}
+
+fn string_arg<'a>(arguments: &'a serde_json::Value, name: &str) -> Result<&'a str> {
+    arguments
+        .get(name)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing string argument '{}'", name))
+}
+
+impl BrowserTools {
+    pub fn new(settings_path: PathBuf, profile_dir: PathBuf) -> Self {
+        Self {
+            settings_path,
+            profile_dir,
+            session: Mutex::new(None),
+        }
+    }
+
+    pub async fn settings(&self) -> BrowserSettings {
+        tokio::fs::read_to_string(&self.settings_path)
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub async fn save_settings(&self, settings: &BrowserSettings) -> Result<()> {
+        config_versions::write(&self.settings_path, serde_json::to_string_pretty(settings)?)
+            .await?;
+        // 창 표시 여부가 바뀌었을 수 있으므로 다음 호출에서 다시 시작합니다
+        self.shutdown().await;
+        Ok(())
+    }
+
+    async fn launch(&self) -> Result<Session> {
+        let mut config = BrowserConfig::builder().user_data_dir(&self.profile_dir);
+        if self.settings().await.show_window {
+            config = config.with_head();
+        }
+        let config = config.build().map_err(|e| {
+            anyhow::anyhow!("Chrome or Chromium is required for browser tools: {}", e)
+        })?;
+        let (browser, mut handler) = Browser::launch(config).await?;
+        // CDP 이벤트 루프; 브라우저가 닫히면 끝납니다
+        tokio::spawn(async move { while handler.next().await.is_some() {} });
+        let page = browser.new_page("about:blank").await?;
+        println!(
+            "Started browser with profile {}",
+            self.profile_dir.display()
+        );
+        Ok(Session { browser, page })
+    }
+
+    /// Close the browser; the profile on disk is kept
+    pub async fn shutdown(&self) {
+        if let Some(mut session) = self.session.lock().await.take() {
+            let _ = session.browser.close().await;
+            let _ = session.browser.wait().await;
+        }
+    }
+
+    /// 이동 후 주소가 허용 목록을 벗어나면 빈 페이지로 되돌립니다
+    async fn ensure_allowed(&self, page: &Page, allowed_domains: &[String]) -> Result<String> {
+        let url = page.url().await?.unwrap_or_default();
+        if !host_allowed(&url, allowed_domains) {
+            page.goto("about:blank").await?;
+            return Err(anyhow::anyhow!(
+                "The page moved to '{}', which is not on the allowed domain list",
+                url
+            ));
+        }
+        Ok(url)
+    }
+
+    pub async fn call_tool(
+        &self,
+        store: &AttachmentStore,
+        tool_name: &str,
+        arguments: serde_json::Value,
+    ) -> ToolCallResult {
+        self.run(store, tool_name, &arguments)
+            .await
+            .unwrap_or_else(|e| error_result(e.to_string()))
+    }
+
+    async fn run(
+        &self,
+        store: &AttachmentStore,
+        tool_name: &str,
+        arguments: &serde_json::Value,
+    ) -> Result<ToolCallResult> {
+        let allowed_domains = self.settings().await.allowed_domains;
+        let mut session = self.session.lock().await;
+        if session.is_none() {
+            *session = Some(self.launch().await?);
+        }
+        let page = &session.as_ref().expect("session was just started").page;
+
+        match tool_name {
+            "navigate" => {
+                let url = string_arg(arguments, "url")?;
+                if !host_allowed(url, &allowed_domains) {
+                    return Err(anyhow::anyhow!(
+                        "'{}' is not on the allowed domain list",
+                        url
+                    ));
+                }
+                page.goto(url).await?;
+                let url = self.ensure_allowed(page, &allowed_domains).await?;
+                let title = page.get_title().await?.unwrap_or_default();
+                Ok(text_result(format!("{}\n{}", title, url)))
+            }
+            "extract_text" => {
+                let text = match arguments.get("selector").and_then(|v| v.as_str()) {
+                    Some(selector) => page
+                        .find_element(selector)
+                        .await?
+                        .inner_text()
+                        .await?
+                        .unwrap_or_default(),
+                    None => page
+                        .evaluate("document.body ? document.body.innerText : ''")
+                        .await?
+                        .into_value::<String>()?,
+                };
+                let truncated = text.chars().count() > MAX_TEXT_CHARS;
+                let mut text: String = text.chars().take(MAX_TEXT_CHARS).collect();
+                if truncated {
+                    text.push_str("\n… text truncated");
+                }
+                Ok(text_result(text))
+            }
+            "screenshot" => {
+                let full_page = arguments
+                    .get("full_page")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                let png = page
+                    .screenshot(ScreenshotParams::builder().full_page(full_page).build())
+                    .await?;
+                let info = store
+                    .store_bytes(&png, Some("image/png".to_string()))
+                    .await?;
+                Ok(text_result(
+                    json!({
+                        "attachment": info.hash,
+                        "mime_type": info.mime_type,
+                        "size": info.size,
+                    })
+                    .to_string(),
+                ))
+            }
+            "click" => {
+                let selector = string_arg(arguments, "selector")?;
+                page.find_element(selector).await?.click().await?;
+                let url = self.ensure_allowed(page, &allowed_domains).await?;
+                Ok(text_result(format!(
+                    "Clicked '{}' (now at {})",
+                    selector, url
+                )))
+            }
+            "fill" => {
+                let selector = string_arg(arguments, "selector")?;
+                let value = string_arg(arguments, "value")?;
+                page.find_element(selector)
+                    .await?
+                    .click()
+                    .await?
+                    .type_str(value)
+                    .await?;
+                Ok(text_result(format!("Filled '{}'", selector)))
+            }
+            _ => Err(anyhow::anyhow!("Unknown browser tool '{}'", tool_name)),
+        }
+    }
+}
@@ -0,0 +1,806 @@
+use anyhow::Result;
+use serde_json::json;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncReadExt;
+
+use crate::archive::{self, ArchiveFormat};
+use crate::attachments::AttachmentStore;
+use crate::clipboard::{self, ClipboardContent};
+use crate::code_index;
+use crate::git_tools;
+use crate::mcp::{MCPTool, MCPToolAnnotations, ToolCallContext, ToolCallResult};
+use crate::ocr::{self, OcrInput};
+use crate::sandbox::{AccessKind, PathPolicy};
+use crate::screen::{self, CaptureTarget};
+use crate::system_info::{self, ProcessSort};
+
+/// Virtual server name used to route builtin tool calls
+pub const SERVER_NAME: &str = "builtin";
+
+pub(crate) fn tool(
+    name: &str,
+    description: &str,
+    schema: serde_json::Value,
+    read_only: bool,
+) -> MCPTool {
+    MCPTool {
+        name: name.to_string(),
+        description: description.to_string(),
+        input_schema: serde_json::from_value(schema).unwrap_or_default(),
+        annotations: Some(MCPToolAnnotations {
+            read_only_hint: Some(read_only),
+            destructive_hint: Some(!read_only),
+            open_world_hint: Some(false),
+            ..Default::default()
+        }),
+    }
+}
+
+/// Wrap text the same way MCP servers return tool content
+pub fn text_result(text: impl Into<String>) -> ToolCallResult {
+    ToolCallResult {
+        success: true,
+        result: Some(json!({
+            "content": [{ "type": "text", "text": text.into() }],
+            "isError": false
+        })),
+        error: None,
+    }
+}
+
+pub fn error_result(error: impl Into<String>) -> ToolCallResult {
+    ToolCallResult {
+        success: false,
+        result: None,
+        error: Some(error.into()),
+    }
+}
+
+fn string_arg<'a>(arguments: &'a serde_json::Value, name: &str) -> Result<&'a str> {
+    arguments
+        .get(name)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing string argument '{}'", name))
+}
+
+pub fn list_tools() -> Vec<MCPTool> {
+    vec![
+        tool(
+            "read_file",
+            "Read a UTF-8 text file inside the workspace",
+            json!({
+                "type": "object",
+                "properties": { "path": { "type": "string" } },
+                "required": ["path"]
+            }),
+            true,
+        ),
+        tool(
+            "write_file",
+            "Create or overwrite a text file inside the workspace",
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string" },
+                    "content": { "type": "string" }
+                },
+                "required": ["path", "content"]
+            }),
+            false,
+        ),
+        tool(
+            "list_directory",
+            "List the entries of a directory inside the workspace",
+            json!({
+                "type": "object",
+                "properties": { "path": { "type": "string" } },
+                "required": ["path"]
+            }),
+            true,
+        ),
+        tool(
+            "capture_screen",
+            "Capture the whole screen after the user approves; returns an image attachment handle",
+            json!({ "type": "object", "properties": {} }),
+            true,
+        ),
+        tool(
+            "capture_window",
+            "Let the user pick a window to capture; returns an image attachment handle",
+            json!({ "type": "object", "properties": {} }),
+            true,
+        ),
+        tool(
+            "clipboard_read",
+            "Read the clipboard after the user approves; images come back as an attachment handle",
+            json!({
+                "type": "object",
+                "properties": {
+                    "format": { "type": "string", "enum": ["text", "image"] }
+                }
+            }),
+            true,
+        ),
+        tool(
+            "clipboard_write",
+            "Copy text or an image attachment to the clipboard after the user approves",
+            json!({
+                "type": "object",
+                "properties": {
+                    "text": { "type": "string" },
+                    "attachment": {
+                        "type": "string",
+                        "description": "Hash of a PNG attachment"
+                    }
+                }
+            }),
+            false,
+        ),
+        tool(
+            "ocr_image",
+            "Extract text with line bounding boxes from a workspace image or an image attachment",
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string" },
+                    "attachment": {
+                        "type": "string",
+                        "description": "Hash of an image attachment, e.g. from capture_screen"
+                    },
+                    "lang": {
+                        "type": "string",
+                        "description": "Tesseract language codes such as eng or kor+eng (default eng)"
+                    }
+                }
+            }),
+            true,
+        ),
+        tool(
+            "web_search",
+            "Search the web with the configured engine; returns title, url and snippet per result",
+            json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "k": { "type": "integer", "description": "Number of results (default 5, max 20)" }
+                },
+                "required": ["query"]
+            }),
+            true,
+        ),
+        tool(
+            "archive_create",
+            "Bundle workspace files or directories into a zip, tar, tar.gz or gz archive",
+            json!({
+                "type": "object",
+                "properties": {
+                    "output": { "type": "string" },
+                    "sources": { "type": "array", "items": { "type": "string" } },
+                    "format": {
+                        "type": "string",
+                        "enum": ["zip", "tar", "tar.gz", "gz"],
+                        "description": "Defaults to the output file extension"
+                    }
+                },
+                "required": ["output", "sources"]
+            }),
+            false,
+        ),
+        tool(
+            "archive_extract",
+            "Extract a zip, tar, tar.gz or gz archive into a workspace directory",
+            json!({
+                "type": "object",
+                "properties": {
+                    "archive": { "type": "string" },
+                    "destination": { "type": "string" },
+                    "format": {
+                        "type": "string",
+                        "enum": ["zip", "tar", "tar.gz", "gz"],
+                        "description": "Defaults to the archive file extension"
+                    }
+                },
+                "required": ["archive", "destination"]
+            }),
+            false,
+        ),
+        tool(
+            "system_info",
+            "Report OS version, uptime, CPU, memory and disk usage after the user approves",
+            json!({ "type": "object", "properties": {} }),
+            true,
+        ),
+        tool(
+            "process_list",
+            "List the busiest processes by CPU or memory after the user approves",
+            json!({
+                "type": "object",
+                "properties": {
+                    "filter": { "type": "string", "description": "Case-insensitive name substring" },
+                    "sort_by": { "type": "string", "enum": ["cpu", "memory"] },
+                    "limit": { "type": "integer", "description": "Number of processes (default 20, max 100)" }
+                }
+            }),
+            true,
+        ),
+        tool(
+            "code_search",
+            "Case-insensitive text search over the indexed source files under a workspace directory",
+            json!({
+                "type": "object",
+                "properties": {
+                    "root": { "type": "string" },
+                    "query": { "type": "string" },
+                    "limit": { "type": "integer", "description": "Maximum matching lines (default 50, max 200)" }
+                },
+                "required": ["root", "query"]
+            }),
+            true,
+        ),
+        tool(
+            "find_symbol",
+            "Find function, type and class definitions by name under a workspace directory, optionally with references",
+            json!({
+                "type": "object",
+                "properties": {
+                    "root": { "type": "string" },
+                    "name": { "type": "string" },
+                    "kind": {
+                        "type": "string",
+                        "description": "function, method, struct, class, enum, trait, interface, type, impl, module, constant or macro"
+                    },
+                    "include_references": { "type": "boolean" },
+                    "limit": { "type": "integer", "description": "Maximum results (default 50, max 200)" }
+                },
+                "required": ["root", "name"]
+            }),
+            true,
+        ),
+        tool(
+            "get_file_outline",
+            "List the symbols defined in a source file with their line ranges",
+            json!({
+                "type": "object",
+                "properties": { "path": { "type": "string" } },
+                "required": ["path"]
+            }),
+            true,
+        ),
+        tool(
+            "propose_patch",
+            "Validate a unified diff against a workspace file and store it for review; nothing is written yet",
+            json!({
+                "type": "object",
+                "properties": {
+                    "file": { "type": "string" },
+                    "unified_diff": {
+                        "type": "string",
+                        "description": "Unified diff for this one file, with @@ hunk headers"
+                    }
+                },
+                "required": ["file", "unified_diff"]
+            }),
+            true,
+        ),
+        tool(
+            "apply_patch",
+            "Apply a proposed patch after the user approves; a backup is kept for rollback",
+            json!({
+                "type": "object",
+                "properties": { "patch_id": { "type": "string" } },
+                "required": ["patch_id"]
+            }),
+            false,
+        ),
+        tool(
+            "rollback_patch",
+            "Restore the file changed by an applied patch",
+            json!({
+                "type": "object",
+                "properties": { "patch_id": { "type": "string" } },
+                "required": ["patch_id"]
+            }),
+            false,
+        ),
+    ]
+    .into_iter()
+    .chain(git_tools::list_tools())
+    .collect()
+}
+
+/// 빌트인 도구를 호출합니다. 모든 경로는 샌드박스 정책을 통과해야 합니다
+pub async fn call_tool(
+    policy: &PathPolicy,
+    attachments: &AttachmentStore,
+    context: &ToolCallContext,
+    tool_name: &str,
+    arguments: serde_json::Value,
+) -> ToolCallResult {
+    let session_id = context.session_id.as_deref();
+    let result = match tool_name {
+        "read_file" => read_file(policy, &arguments).await,
+        "write_file" => write_file(policy, session_id, &arguments).await,
+        "list_directory" => list_directory(policy, &arguments).await,
+        "capture_screen" => capture(attachments, CaptureTarget::Screen).await,
+        "capture_window" => capture(attachments, CaptureTarget::Window).await,
+        "clipboard_read" => clipboard_read(attachments, &arguments).await,
+        "clipboard_write" => clipboard_write(attachments, &arguments).await,
+        "ocr_image" => ocr_image(policy, attachments, &arguments).await,
+        "web_search" => web_search(&arguments).await,
+        "archive_create" => archive_create(policy, session_id, &arguments).await,
+        "archive_extract" => archive_extract(policy, session_id, &arguments).await,
+        "system_info" => system_overview().await,
+        "code_search" => code_search(policy, &arguments).await,
+        "find_symbol" => find_symbol(policy, &arguments).await,
+        "get_file_outline" => get_file_outline(policy, &arguments).await,
+        "propose_patch" => propose_patch(policy, &arguments).await,
+        "apply_patch" => apply_patch(policy, session_id, &arguments).await,
+        "rollback_patch" => rollback_patch(policy, session_id, &arguments).await,
+        "process_list" => process_list(&arguments).await,
+        name if name.starts_with("git_") => git_tools::call_tool(policy, name, &arguments)
+            .await
+            .map(text_result),
+        _ => Err(anyhow::anyhow!("Unknown builtin tool '{}'", tool_name)),
+    };
+    result.unwrap_or_else(|e| error_result(e.to_string()))
+}
+
+async fn read_file(policy: &PathPolicy, arguments: &serde_json::Value) -> Result<ToolCallResult> {
+    let path = policy
+        .check(Path::new(string_arg(arguments, "path")?), AccessKind::Read)
+        .await?;
+    let content = tokio::fs::read_to_string(&path).await?;
+    Ok(text_result(content))
+}
+
+/// 에이전트 세션이 워크스페이스를 처음 바꾸기 전에 전체 스냅샷을 남깁니다
+async fn snapshot_before_change(policy: &PathPolicy, session_id: Option<&str>, path: &Path) {
+    let Some(session_id) = session_id else {
+        return;
+    };
+    let Some(root) = policy
+        .roots()
+        .await
+        .into_iter()
+        .filter(|root| path.starts_with(root))
+        .max_by_key(|root| root.components().count())
+    else {
+        return;
+    };
+    let session_id = session_id.to_string();
+    let snapshot = tokio::task::spawn_blocking(move || {
+        crate::get_snapshot_store().ensure_session_snapshot(&session_id, &root)
+    })
+    .await;
+    // 스냅샷을 못 만들어도 (너무 큰 워크스페이스 등) 도구 실행은 막지 않습니다
+    match snapshot {
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => eprintln!("⚠️ Skipping automatic workspace snapshot: {}", e),
+        Err(e) => eprintln!("⚠️ Skipping automatic workspace snapshot: {}", e),
+    }
+}
+
+/// 파일을 바꾸기 전후로 되돌리기 기록을 남깁니다
+async fn journaled<T>(
+    policy: &PathPolicy,
+    session_id: Option<&str>,
+    tool: &str,
+    path: &Path,
+    write: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    snapshot_before_change(policy, session_id, path).await;
+    let journal = crate::get_fs_journal();
+    let session_id = session_id.map(str::to_string);
+    let tool = tool.to_string();
+    let target = path.to_path_buf();
+    let pending =
+        tokio::task::spawn_blocking(move || journal.begin(session_id.as_deref(), &tool, &target))
+            .await??;
+    let result = write.await;
+    tokio::task::spawn_blocking(move || journal.finish(pending)).await??;
+    result
+}
+
+async fn write_file(
+    policy: &PathPolicy,
+    session_id: Option<&str>,
+    arguments: &serde_json::Value,
+) -> Result<ToolCallResult> {
+    let path = policy
+        .check(Path::new(string_arg(arguments, "path")?), AccessKind::Write)
+        .await?;
+    let content = string_arg(arguments, "content")?;
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    journaled(policy, session_id, "write_file", &path, async {
+        Ok(tokio::fs::write(&path, content).await?)
+    })
+    .await?;
+    Ok(text_result(format!(
+        "Wrote {} bytes to {}",
+        content.len(),
+        path.display()
+    )))
+}
+
+async fn list_directory(
+    policy: &PathPolicy,
+    arguments: &serde_json::Value,
+) -> Result<ToolCallResult> {
+    let path = policy
+        .check(Path::new(string_arg(arguments, "path")?), AccessKind::Read)
+        .await?;
+    let mut entries = tokio::fs::read_dir(&path).await?;
+    let mut lines = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let marker = if entry.file_type().await?.is_dir() {
+            "[DIR]"
+        } else {
+            "[FILE]"
+        };
+        lines.push(format!(
+            "{} {}",
+            marker,
+            entry.file_name().to_string_lossy()
+        ));
+    }
+    lines.sort();
+    Ok(text_result(lines.join("\n")))
+}
+
+async fn capture(attachments: &AttachmentStore, target: CaptureTarget) -> Result<ToolCallResult> {
+    let info = screen::capture(attachments, target).await?;
+    Ok(text_result(
+        json!({
+            "attachment": info.hash,
+            "mime_type": info.mime_type,
+            "size": info.size,
+        })
+        .to_string(),
+    ))
+}
+
+async fn clipboard_read(
+    attachments: &AttachmentStore,
+    arguments: &serde_json::Value,
+) -> Result<ToolCallResult> {
+    let image = arguments.get("format").and_then(|v| v.as_str()) == Some("image");
+    Ok(match clipboard::read(attachments, image).await? {
+        ClipboardContent::Text(text) => text_result(text),
+        ClipboardContent::Image(info) => text_result(
+            json!({
+                "attachment": info.hash,
+                "mime_type": info.mime_type,
+                "size": info.size,
+            })
+            .to_string(),
+        ),
+    })
+}
+
+async fn clipboard_write(
+    attachments: &AttachmentStore,
+    arguments: &serde_json::Value,
+) -> Result<ToolCallResult> {
+    let content = if let Some(hash) = arguments.get("attachment").and_then(|v| v.as_str()) {
+        ClipboardContent::Image(attachments.info(hash).await?)
+    } else {
+        ClipboardContent::Text(string_arg(arguments, "text")?.to_string())
+    };
+    clipboard::write(attachments, content).await?;
+    Ok(text_result("Copied to the clipboard"))
+}
+
+async fn ocr_image(
+    policy: &PathPolicy,
+    attachments: &AttachmentStore,
+    arguments: &serde_json::Value,
+) -> Result<ToolCallResult> {
+    let input = if let Some(hash) = arguments.get("attachment").and_then(|v| v.as_str()) {
+        let mut bytes = Vec::new();
+        attachments
+            .open(hash)
+            .await?
+            .read_to_end(&mut bytes)
+            .await?;
+        OcrInput::Bytes(bytes)
+    } else {
+        OcrInput::Path(
+            policy
+                .check(Path::new(string_arg(arguments, "path")?), AccessKind::Read)
+                .await?,
+        )
+    };
+    let result = ocr::recognize(input, arguments.get("lang").and_then(|v| v.as_str())).await?;
+    Ok(text_result(serde_json::to_string_pretty(&result)?))
+}
+
+async fn web_search(arguments: &serde_json::Value) -> Result<ToolCallResult> {
+    let query = string_arg(arguments, "query")?;
+    let k = arguments
+        .get("k")
+        .and_then(|v| v.as_u64())
+        .map(|k| k as usize);
+    let results = crate::get_web_search().search(query, k).await?;
+    Ok(text_result(serde_json::to_string_pretty(&results)?))
+}
+
+async fn archive_create(
+    policy: &PathPolicy,
+    session_id: Option<&str>,
+    arguments: &serde_json::Value,
+) -> Result<ToolCallResult> {
+    let output = policy
+        .check(
+            Path::new(string_arg(arguments, "output")?),
+            AccessKind::Write,
+        )
+        .await?;
+    let format = ArchiveFormat::parse(arguments.get("format").and_then(|v| v.as_str()), &output)?;
+    let mut sources = Vec::new();
+    for source in arguments
+        .get("sources")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow::anyhow!("Missing array argument 'sources'"))?
+    {
+        let source = source
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("'sources' must contain paths"))?;
+        sources.push(policy.check(Path::new(source), AccessKind::Read).await?);
+    }
+    let target = output.clone();
+    let summary = journaled(policy, session_id, "archive_create", &output, async {
+        tokio::task::spawn_blocking(move || archive::create(&sources, &target, format)).await?
+    })
+    .await?;
+    Ok(text_result(format!(
+        "Created {} with {} entries ({} bytes){}",
+        output.display(),
+        summary.entries,
+        summary.bytes,
+        skipped_note(&summary.skipped)
+    )))
+}
+
+async fn archive_extract(
+    policy: &PathPolicy,
+    session_id: Option<&str>,
+    arguments: &serde_json::Value,
+) -> Result<ToolCallResult> {
+    let archive_path = policy
+        .check(
+            Path::new(string_arg(arguments, "archive")?),
+            AccessKind::Read,
+        )
+        .await?;
+    let destination = policy
+        .check(
+            Path::new(string_arg(arguments, "destination")?),
+            AccessKind::Write,
+        )
+        .await?;
+    let format = ArchiveFormat::parse(
+        arguments.get("format").and_then(|v| v.as_str()),
+        &archive_path,
+    )?;
+    snapshot_before_change(policy, session_id, &destination).await;
+    let target = destination.clone();
+    let session_id = session_id.map(str::to_string);
+    let summary = tokio::task::spawn_blocking(move || {
+        let journal = crate::get_fs_journal();
+        let mut pending = Vec::new();
+        let result = archive::extract(&archive_path, &target, format, &mut |path| {
+            pending.push(journal.begin(session_id.as_deref(), "archive_extract", path)?);
+            Ok(())
+        });
+        // 실패해도 이미 쓴 파일은 되돌릴 수 있도록 기록합니다
+        for operation in pending {
+            journal.finish(operation)?;
+        }
+        result
+    })
+    .await??;
+    Ok(text_result(format!(
+        "Extracted {} entries ({} bytes) into {}{}",
+        summary.entries,
+        summary.bytes,
+        destination.display(),
+        skipped_note(&summary.skipped)
+    )))
+}
+
+fn skipped_note(skipped: &[String]) -> String {
+    if skipped.is_empty() {
+        String::new()
+    } else {
+        format!("\nSkipped links and special files: {}", skipped.join(", "))
+    }
+}
+
+async fn system_overview() -> Result<ToolCallResult> {
+    let overview = system_info::overview().await?;
+    Ok(text_result(serde_json::to_string_pretty(&overview)?))
+}
+
+async fn process_list(arguments: &serde_json::Value) -> Result<ToolCallResult> {
+    let sort = ProcessSort::parse(arguments.get("sort_by").and_then(|v| v.as_str()))?;
+    let processes = system_info::processes(
+        arguments
+            .get("filter")
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+        sort,
+        arguments
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize),
+    )
+    .await?;
+    Ok(text_result(serde_json::to_string_pretty(&processes)?))
+}
+
+fn limit_arg(arguments: &serde_json::Value) -> usize {
+    arguments
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map_or(code_index::DEFAULT_LIMIT, |v| v as usize)
+        .clamp(1, code_index::MAX_LIMIT)
+}
+
+/// 검색 전에 변경된 파일만 다시 색인합니다
+async fn indexed_root(policy: &PathPolicy, arguments: &serde_json::Value) -> Result<PathBuf> {
+    let root = policy
+        .check(Path::new(string_arg(arguments, "root")?), AccessKind::Read)
+        .await?;
+    let target = root.clone();
+    tokio::task::spawn_blocking(move || crate::get_code_index().refresh(&target)).await??;
+    Ok(root)
+}
+
+async fn code_search(policy: &PathPolicy, arguments: &serde_json::Value) -> Result<ToolCallResult> {
+    let root = indexed_root(policy, arguments).await?;
+    let query = string_arg(arguments, "query")?.to_string();
+    let limit = limit_arg(arguments);
+    let matches =
+        tokio::task::spawn_blocking(move || crate::get_code_index().search(&root, &query, limit))
+            .await??;
+    if matches.is_empty() {
+        return Ok(text_result("No matches"));
+    }
+    Ok(text_result(
+        matches
+            .iter()
+            .map(|m| format!("{}:{}: {}", m.path, m.line, m.text))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    ))
+}
+
+async fn find_symbol(policy: &PathPolicy, arguments: &serde_json::Value) -> Result<ToolCallResult> {
+    let root = indexed_root(policy, arguments).await?;
+    let name = string_arg(arguments, "name")?.to_string();
+    let kind = arguments
+        .get("kind")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let include_references = arguments
+        .get("include_references")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let limit = limit_arg(arguments);
+    let found = tokio::task::spawn_blocking(move || {
+        crate::get_code_index().find_symbol(
+            &root,
+            &name,
+            kind.as_deref(),
+            include_references,
+            limit,
+        )
+    })
+    .await??;
+    Ok(text_result(serde_json::to_string_pretty(&found)?))
+}
+
+async fn get_file_outline(
+    policy: &PathPolicy,
+    arguments: &serde_json::Value,
+) -> Result<ToolCallResult> {
+    let path = policy
+        .check(Path::new(string_arg(arguments, "path")?), AccessKind::Read)
+        .await?;
+    let symbols =
+        tokio::task::spawn_blocking(move || crate::get_code_index().outline(&path)).await??;
+    if symbols.is_empty() {
+        return Ok(text_result("No symbols found"));
+    }
+    Ok(text_result(
+        symbols
+            .iter()
+            .map(|s| {
+                format!(
+                    "{}{} {} (lines {}-{})",
+                    "  ".repeat(s.depth),
+                    s.kind,
+                    s.name,
+                    s.start_line,
+                    s.end_line
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    ))
+}
+
+async fn propose_patch(
+    policy: &PathPolicy,
+    arguments: &serde_json::Value,
+) -> Result<ToolCallResult> {
+    let file = policy
+        .check(Path::new(string_arg(arguments, "file")?), AccessKind::Write)
+        .await?;
+    let diff = string_arg(arguments, "unified_diff")?.to_string();
+    let record = crate::get_patch_store().propose(file, diff).await?;
+    Ok(text_result(format!(
+        "Patch {} for {} is valid ({} hunks, +{} -{}). Call apply_patch to write it.",
+        record.id,
+        record.file.display(),
+        record.hunks,
+        record.added,
+        record.removed
+    )))
+}
+
+/// 제안 이후 작업 공간 설정이 바뀌었을 수 있으므로 대상 파일을 다시 검사합니다
+async fn checked_patch<'a>(
+    policy: &PathPolicy,
+    arguments: &'a serde_json::Value,
+) -> Result<(&'a str, PathBuf)> {
+    let id = string_arg(arguments, "patch_id")?;
+    let record = crate::get_patch_store().get(id).await?;
+    policy.check(&record.file, AccessKind::Write).await?;
+    Ok((id, record.file))
+}
+
+async fn apply_patch(
+    policy: &PathPolicy,
+    session_id: Option<&str>,
+    arguments: &serde_json::Value,
+) -> Result<ToolCallResult> {
+    let (id, file) = checked_patch(policy, arguments).await?;
+    let record = journaled(
+        policy,
+        session_id,
+        "apply_patch",
+        &file,
+        crate::get_patch_store().apply(id),
+    )
+    .await?;
+    Ok(text_result(format!(
+        "Applied patch {} to {}",
+        record.id,
+        record.file.display()
+    )))
+}
+
+async fn rollback_patch(
+    policy: &PathPolicy,
+    session_id: Option<&str>,
+    arguments: &serde_json::Value,
+) -> Result<ToolCallResult> {
+    let (id, file) = checked_patch(policy, arguments).await?;
+    let record = journaled(
+        policy,
+        session_id,
+        "rollback_patch",
+        &file,
+        crate::get_patch_store().rollback(id),
+    )
+    .await?;
+    Ok(text_result(format!(
+        "Rolled back patch {} on {}",
+        record.id,
+        record.file.display()
+    )))
+}
@@ -0,0 +1,496 @@
+use anyhow::{Context, Result};
+use async_tungstenite::tungstenite::Message;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+use crate::api::ask_webview;
+use crate::config_versions;
+use crate::redact;
+
+const DISCORD_API: &str = "https://discord.com/api/v10";
+const DISCORD_GATEWAY: &str = "wss://gateway.discord.gg/?v=10&encoding=json";
+// GUILD_MESSAGES | DIRECT_MESSAGES | MESSAGE_CONTENT
+const DISCORD_INTENTS: u64 = (1 << 9) | (1 << 12) | (1 << 15);
+const SLACK_API: &str = "https://slack.com/api";
+const PLACEHOLDER: &str = "⏳ Working on it…";
+const MAX_BACKOFF_SECS: u64 = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Platform {
+    Slack,
+    Discord,
+}
+
+/// Relays one Slack or Discord channel to a saved assistant. Answers run through
+/// the same agent loop as the app, so tool approval prompts still appear there.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatBridgeSettings {
+    pub enabled: bool,
+    pub platform: Platform,
+    /// Bot token: `xoxb-…` for Slack; for Discord the bot needs the Message Content intent
+    #[serde(default)]
+    pub bot_token: String,
+    /// Slack app-level token (`xapp-…`) that opens the Socket Mode connection
+    #[serde(default)]
+    pub app_token: Option<String>,
+    /// Only messages posted in this channel reach the agent
+    #[serde(default)]
+    pub channel_id: String,
+    /// Saved assistant that answers
+    #[serde(default)]
+    pub agent_id: String,
+}
+
+impl Default for ChatBridgeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            platform: Platform::Discord,
+            bot_token: String::new(),
+            app_token: None,
+            channel_id: String::new(),
+            agent_id: String::new(),
+        }
+    }
+}
+
+impl ChatBridgeSettings {
+    fn validate(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        for (field, value) in [
+            ("bot_token", &self.bot_token),
+            ("channel_id", &self.channel_id),
+            ("agent_id", &self.agent_id),
+        ] {
+            if value.trim().is_empty() {
+                anyhow::bail!("\"{}\" is required to enable the chat bridge", field);
+            }
+        }
+        if self.platform == Platform::Slack
+            && self
+                .app_token
+                .as_deref()
+                .is_none_or(|t| t.trim().is_empty())
+        {
+            anyhow::bail!("Slack needs an app-level token (\"app_token\") for Socket Mode");
+        }
+        Ok(())
+    }
+}
+
+static RUNNING: Mutex<Option<CancellationToken>> = Mutex::const_new(None);
+
+fn settings_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("chat_bridge.json")
+}
+
+pub fn load_settings(data_dir: &Path) -> Result<ChatBridgeSettings> {
+    match std::fs::read_to_string(settings_path(data_dir)) {
+        Ok(content) => Ok(serde_json::from_str(&content)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ChatBridgeSettings::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn save_settings(data_dir: &Path, settings: &ChatBridgeSettings) -> Result<()> {
+    settings.validate()?;
+    config_versions::write_blocking(
+        &settings_path(data_dir),
+        &serde_json::to_string_pretty(settings)?,
+    )?;
+    Ok(())
+}
+
+/// Stop the running connection and start a new one if the bridge is enabled
+pub async fn apply(settings: &ChatBridgeSettings) -> Result<()> {
+    settings.validate()?;
+    let mut running = RUNNING.lock().await;
+    if let Some(cancel) = running.take() {
+        cancel.cancel();
+    }
+    if !settings.enabled {
+        return Ok(());
+    }
+    let secrets: Vec<String> = std::iter::once(settings.bot_token.clone())
+        .chain(settings.app_token.clone())
+        .collect();
+    redact::register_secrets(&secrets);
+
+    let cancel = CancellationToken::new();
+    tokio::spawn(run(settings.clone(), cancel.clone()));
+    *running = Some(cancel);
+    Ok(())
+}
+
+/// Keep the connection up, reconnecting with backoff, until cancelled
+async fn run(settings: ChatBridgeSettings, cancel: CancellationToken) {
+    let channel = Arc::new(Channel {
+        platform: settings.platform,
+        client: reqwest::Client::new(),
+        token: settings.bot_token.clone(),
+        channel_id: settings.channel_id.clone(),
+    });
+    let mut backoff = 1;
+    loop {
+        let connected = Instant::now();
+        let result = tokio::select! {
+            _ = cancel.cancelled() => return,
+            result = connect(&settings, &channel) => result,
+        };
+        match result {
+            Ok(()) => println!("Chat bridge connection closed; reconnecting"),
+            Err(e) => eprintln!(
+                "❌ [BRIDGE] {:?} connection failed: {}",
+                settings.platform,
+                redact::redact(&format!("{:#}", e))
+            ),
+        }
+        // 한동안 잘 유지된 연결이었다면 대기 시간을 처음부터 다시 늘립니다
+        if connected.elapsed() > Duration::from_secs(MAX_BACKOFF_SECS) {
+            backoff = 1;
+        }
+        tokio::select! {
+            _ = cancel.cancelled() => return,
+            _ = tokio::time::sleep(Duration::from_secs(backoff)) => {}
+        }
+        backoff = (backoff * 2).min(MAX_BACKOFF_SECS);
+    }
+}
+
+async fn connect(settings: &ChatBridgeSettings, channel: &Arc<Channel>) -> Result<()> {
+    match settings.platform {
+        Platform::Discord => run_discord(settings, channel).await,
+        Platform::Slack => run_slack(settings, channel).await,
+    }
+}
+
+/// Discord gateway session: heartbeat, identify, then relay MESSAGE_CREATE events
+async fn run_discord(settings: &ChatBridgeSettings, channel: &Arc<Channel>) -> Result<()> {
+    let (socket, _) = async_tungstenite::tokio::connect_async(DISCORD_GATEWAY)
+        .await
+        .context("Could not reach the Discord gateway")?;
+    let (mut sink, mut stream) = socket.split();
+    // Hello가 오기 전에는 하트비트를 보내지 않습니다
+    let mut heartbeat = tokio::time::interval_at(
+        Instant::now() + Duration::from_secs(3600),
+        Duration::from_secs(3600),
+    );
+    let mut sequence: Option<u64> = None;
+    loop {
+        let frame = tokio::select! {
+            _ = heartbeat.tick() => {
+                sink.send(Message::text(json!({ "op": 1, "d": sequence }).to_string()))
+                    .await?;
+                continue;
+            }
+            frame = stream.next() => frame,
+        };
+        let text = match frame {
+            Some(Ok(Message::Text(text))) => text,
+            Some(Ok(Message::Close(close))) => {
+                anyhow::bail!("Discord closed the connection: {:?}", close)
+            }
+            Some(Ok(_)) => continue,
+            Some(Err(e)) => return Err(e.into()),
+            None => return Ok(()),
+        };
+        let event: serde_json::Value = serde_json::from_str(&text)?;
+        if let Some(seq) = event.get("s").and_then(|s| s.as_u64()) {
+            sequence = Some(seq);
+        }
+        match event.get("op").and_then(|op| op.as_u64()) {
+            Some(10) => {
+                let period = event["d"]["heartbeat_interval"].as_u64().unwrap_or(41_250);
+                let period = Duration::from_millis(period);
+                heartbeat = tokio::time::interval_at(Instant::now() + period, period);
+                let identify = json!({
+                    "op": 2,
+                    "d": {
+                        "token": settings.bot_token,
+                        "intents": DISCORD_INTENTS,
+                        "properties": { "os": std::env::consts::OS, "browser": "tauri-mcp-agent", "device": "tauri-mcp-agent" },
+                    },
+                });
+                sink.send(Message::text(identify.to_string())).await?;
+            }
+            // 서버가 즉시 하트비트를 요청했습니다
+            Some(1) => {
+                sink.send(Message::text(json!({ "op": 1, "d": sequence }).to_string()))
+                    .await?;
+            }
+            Some(7) => return Ok(()),
+            Some(9) => {
+                anyhow::bail!("Discord rejected the session; check the bot token and intents")
+            }
+            Some(0) if event["t"] == "MESSAGE_CREATE" => {
+                let message = &event["d"];
+                let from_bot = message["author"]["bot"].as_bool().unwrap_or(false);
+                if from_bot || message["channel_id"].as_str() != Some(&settings.channel_id) {
+                    continue;
+                }
+                if let Some(text) = message["content"].as_str().filter(|t| !t.trim().is_empty()) {
+                    relay(channel, &settings.agent_id, text.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Slack Socket Mode session: acknowledge every envelope and relay channel messages
+async fn run_slack(settings: &ChatBridgeSettings, channel: &Arc<Channel>) -> Result<()> {
+    let app_token = settings.app_token.as_deref().unwrap_or_default();
+    let opened: serde_json::Value = channel
+        .client
+        .post(format!("{}/apps.connections.open", SLACK_API))
+        .bearer_auth(app_token)
+        .send()
+        .await?
+        .json()
+        .await?;
+    let url = slack_result(&opened, "apps.connections.open")?["url"]
+        .as_str()
+        .context("Slack returned no Socket Mode URL")?
+        .to_string();
+    let (socket, _) = async_tungstenite::tokio::connect_async(url.as_str())
+        .await
+        .context("Could not open the Slack Socket Mode connection")?;
+    let (mut sink, mut stream) = socket.split();
+    while let Some(frame) = stream.next().await {
+        let text = match frame? {
+            Message::Text(text) => text,
+            Message::Close(close) => anyhow::bail!("Slack closed the connection: {:?}", close),
+            _ => continue,
+        };
+        let envelope: serde_json::Value = serde_json::from_str(&text)?;
+        // 확인 응답이 없으면 Slack이 같은 이벤트를 다시 보냅니다
+        if let Some(id) = envelope["envelope_id"].as_str() {
+            sink.send(Message::text(json!({ "envelope_id": id }).to_string()))
+                .await?;
+        }
+        match envelope["type"].as_str() {
+            Some("disconnect") => return Ok(()),
+            Some("events_api") => {
+                let event = &envelope["payload"]["event"];
+                let is_user_message = event["type"] == "message"
+                    && event.get("subtype").is_none()
+                    && event.get("bot_id").is_none();
+                if !is_user_message || event["channel"].as_str() != Some(&settings.channel_id) {
+                    continue;
+                }
+                if let Some(text) = event["text"].as_str().filter(|t| !t.trim().is_empty()) {
+                    relay(channel, &settings.agent_id, text.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn slack_result<'a>(
+    response: &'a serde_json::Value,
+    method: &str,
+) -> Result<&'a serde_json::Value> {
+    if response["ok"].as_bool() == Some(true) {
+        Ok(response)
+    } else {
+        anyhow::bail!(
+            "Slack {} failed: {}",
+            method,
+            response["error"].as_str().unwrap_or("unknown error")
+        )
+    }
+}
+
+/// The channel answers are posted to
+struct Channel {
+    platform: Platform,
+    client: reqwest::Client,
+    token: String,
+    channel_id: String,
+}
+
+impl Channel {
+    /// Longest message the platform accepts
+    fn limit(&self) -> usize {
+        match self.platform {
+            Platform::Discord => 2000,
+            Platform::Slack => 4000,
+        }
+    }
+
+    /// Post a message and return its id (the `ts` on Slack)
+    async fn post(&self, text: &str) -> Result<String> {
+        match self.platform {
+            Platform::Discord => {
+                let message: serde_json::Value = self
+                    .client
+                    .post(format!(
+                        "{}/channels/{}/messages",
+                        DISCORD_API, self.channel_id
+                    ))
+                    .header("Authorization", format!("Bot {}", self.token))
+                    .json(&json!({ "content": text }))
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+                Ok(message["id"].as_str().unwrap_or_default().to_string())
+            }
+            Platform::Slack => {
+                let response: serde_json::Value = self
+                    .client
+                    .post(format!("{}/chat.postMessage", SLACK_API))
+                    .bearer_auth(&self.token)
+                    .json(&json!({ "channel": self.channel_id, "text": text }))
+                    .send()
+                    .await?
+                    .json()
+                    .await?;
+                Ok(slack_result(&response, "chat.postMessage")?["ts"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string())
+            }
+        }
+    }
+
+    async fn edit(&self, id: &str, text: &str) -> Result<()> {
+        match self.platform {
+            Platform::Discord => {
+                self.client
+                    .patch(format!(
+                        "{}/channels/{}/messages/{}",
+                        DISCORD_API, self.channel_id, id
+                    ))
+                    .header("Authorization", format!("Bot {}", self.token))
+                    .json(&json!({ "content": text }))
+                    .send()
+                    .await?
+                    .error_for_status()?;
+            }
+            Platform::Slack => {
+                let response: serde_json::Value = self
+                    .client
+                    .post(format!("{}/chat.update", SLACK_API))
+                    .bearer_auth(&self.token)
+                    .json(&json!({ "channel": self.channel_id, "ts": id, "text": text }))
+                    .send()
+                    .await?
+                    .json()
+                    .await?;
+                slack_result(&response, "chat.update")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run one agent turn for a channel message and post the answer over a placeholder
+fn relay(channel: &Arc<Channel>, agent_id: &str, prompt: String) {
+    let channel = Arc::clone(channel);
+    let request = json!({
+        "agent_id": agent_id,
+        "context_id": format!("{:?}-{}", channel.platform, channel.channel_id).to_lowercase(),
+        "prompt": prompt,
+    });
+    tokio::spawn(async move {
+        let placeholder = channel.post(PLACEHOLDER).await;
+        let answer = match ask_webview("agent-run", request).await {
+            Ok(reply) => reply
+                .get("content")
+                .and_then(|c| c.as_str())
+                .filter(|c| !c.trim().is_empty())
+                .unwrap_or("(no answer)")
+                .to_string(),
+            Err(e) => format!("⚠️ The agent could not answer: {}", e),
+        };
+        // 원격 채널로 나가는 답변에는 등록된 비밀 값이 남지 않게 합니다
+        let answer = redact::redact(&answer);
+        for (index, chunk) in split_message(&answer, channel.limit()).iter().enumerate() {
+            let sent = match (&placeholder, index) {
+                (Ok(id), 0) if !id.is_empty() => channel.edit(id, chunk).await,
+                _ => channel.post(chunk).await.map(|_| ()),
+            };
+            if let Err(e) = sent {
+                eprintln!("❌ [BRIDGE] Failed to post the answer: {:#}", e);
+                break;
+            }
+        }
+    });
+}
+
+/// Split text into messages of at most `limit` characters, preferring line breaks
+fn split_message(text: &str, limit: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while rest.chars().count() > limit {
+        let end = rest
+            .char_indices()
+            .nth(limit)
+            .map(|(i, _)| i)
+            .unwrap_or(rest.len());
+        let cut = match rest[..end].rfind('\n') {
+            Some(newline) if newline > 0 => newline,
+            _ => end,
+        };
+        chunks.push(rest[..cut].to_string());
+        rest = rest[cut..].strip_prefix('\n').unwrap_or(&rest[cut..]);
+    }
+    chunks.push(rest.to_string());
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_message_prefers_line_breaks_and_respects_the_limit() {
+        assert_eq!(split_message("short", 10), vec!["short"]);
+        assert_eq!(
+            split_message("first line\nsecond", 12),
+            vec!["first line", "second"]
+        );
+        let chunks = split_message(&"가".repeat(25), 10);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 10));
+    }
+
+    #[test]
+    fn enabling_requires_tokens_channel_and_agent() {
+        let mut settings = ChatBridgeSettings {
+            enabled: true,
+            bot_token: "token".to_string(),
+            channel_id: "C1".to_string(),
+            agent_id: "agent".to_string(),
+            ..Default::default()
+        };
+        assert!(settings.validate().is_ok());
+
+        settings.platform = Platform::Slack;
+        assert!(settings
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("app_token"));
+        settings.app_token = Some("xapp-1".to_string());
+        assert!(settings.validate().is_ok());
+
+        settings.channel_id = " ".to_string();
+        assert!(settings.validate().is_err());
+        settings.enabled = false;
+        assert!(settings.validate().is_ok());
+    }
+}
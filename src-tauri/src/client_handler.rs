@@ -0,0 +1,118 @@
+use rmcp::{
+    model::{
+        LoggingLevel, LoggingMessageNotificationParam, ProgressNotificationParam,
+        ResourceUpdatedNotificationParam,
+    },
+    service::{NotificationContext, RoleClient},
+    ClientHandler,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::events;
+use crate::mcp::MCPTool;
+
+/// Event payload for a server notification, tagged with the server it came from
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerNotification<T: Serialize> {
+    pub server_name: String,
+    #[serde(flatten)]
+    pub params: T,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Empty {}
+
+/// rmcp client handler for one connection.
+///
+/// Every server-initiated notification lands here and is dispatched to the
+/// subsystem that owns it. Sampling requests are not handled yet and get the
+/// default method-not-found error.
+pub struct MCPClientHandler {
+    server_name: String,
+    tool_cache: Arc<Mutex<HashMap<String, Vec<MCPTool>>>>,
+}
+
+impl MCPClientHandler {
+    pub fn new(server_name: String, tool_cache: Arc<Mutex<HashMap<String, Vec<MCPTool>>>>) -> Self {
+        Self {
+            server_name,
+            tool_cache,
+        }
+    }
+
+    fn emit<T: Serialize + Clone>(&self, event: &str, params: T) {
+        events::emit(
+            event,
+            ServerNotification {
+                server_name: self.server_name.clone(),
+                params,
+            },
+        );
+    }
+}
+
+fn log_level(level: LoggingLevel) -> log::Level {
+    match level {
+        LoggingLevel::Debug => log::Level::Debug,
+        LoggingLevel::Info | LoggingLevel::Notice => log::Level::Info,
+        LoggingLevel::Warning => log::Level::Warn,
+        LoggingLevel::Error
+        | LoggingLevel::Critical
+        | LoggingLevel::Alert
+        | LoggingLevel::Emergency => log::Level::Error,
+    }
+}
+
+impl ClientHandler for MCPClientHandler {
+    async fn on_progress(
+        &self,
+        params: ProgressNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        self.emit("mcp-progress", params);
+    }
+
+    async fn on_logging_message(
+        &self,
+        params: LoggingMessageNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        // 서버 로그는 `mcp::<서버 이름>` 타깃으로 로그 플러그인에 기록됩니다
+        let target = format!("mcp::{}", self.server_name);
+        let message = match &params.data {
+            serde_json::Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        match &params.logger {
+            Some(logger) => {
+                log::log!(target: &target, log_level(params.level), "[{}] {}", logger, message)
+            }
+            None => log::log!(target: &target, log_level(params.level), "{}", message),
+        }
+    }
+
+    async fn on_resource_updated(
+        &self,
+        params: ResourceUpdatedNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        self.emit("mcp-resource-updated", params);
+    }
+
+    async fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        // 캐시된 목록은 더 이상 유효하지 않으므로 다음 조회 때 다시 가져옵니다
+        self.tool_cache.lock().await.remove(&self.server_name);
+        self.emit("mcp-tools-changed", Empty {});
+    }
+
+    async fn on_prompt_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        self.emit("mcp-prompts-changed", Empty {});
+    }
+
+    async fn on_resource_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        self.emit("mcp-resources-changed", Empty {});
+    }
+}
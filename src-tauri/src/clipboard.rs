@@ -0,0 +1,154 @@
+use anyhow::Result;
+#[cfg(desktop)]
+use arboard::{Clipboard, ImageData};
+#[cfg(desktop)]
+use std::borrow::Cow;
+#[cfg(desktop)]
+use std::io::Cursor;
+#[cfg(desktop)]
+use std::sync::Mutex;
+use tokio::io::AsyncReadExt;
+
+use crate::api::ask_approval;
+use crate::attachments::{AttachmentInfo, AttachmentStore};
+
+// X11/Wayland에서는 클립보드 소유자가 살아 있어야 내용이 유지되므로 계속 보관합니다
+#[cfg(desktop)]
+static CLIPBOARD: Mutex<Option<Clipboard>> = Mutex::new(None);
+
+pub enum ClipboardContent {
+    Text(String),
+    Image(AttachmentInfo),
+}
+
+#[cfg(desktop)]
+fn with_clipboard<T>(f: impl FnOnce(&mut Clipboard) -> Result<T, arboard::Error>) -> Result<T> {
+    let mut guard = CLIPBOARD.lock().unwrap_or_else(|e| e.into_inner());
+    if guard.is_none() {
+        *guard = Some(Clipboard::new()?);
+    }
+    Ok(f(guard.as_mut().expect("clipboard was just created"))?)
+}
+
+#[cfg(desktop)]
+fn read_text() -> Result<String> {
+    with_clipboard(|c| c.get_text())
+}
+
+/// Clipboard image encoded as PNG
+#[cfg(desktop)]
+fn read_png() -> Result<Vec<u8>> {
+    let data = with_clipboard(|c| c.get_image())?;
+    let buffer = image::RgbaImage::from_raw(
+        data.width as u32,
+        data.height as u32,
+        data.bytes.into_owned(),
+    )
+    .ok_or_else(|| anyhow::anyhow!("Clipboard image has an unexpected size"))?;
+    let mut png = Vec::new();
+    buffer.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)?;
+    Ok(png)
+}
+
+#[cfg(desktop)]
+fn write_text(text: String) -> Result<()> {
+    with_clipboard(|c| c.set_text(text))
+}
+
+#[cfg(desktop)]
+fn write_image(bytes: Vec<u8>) -> Result<()> {
+    let image = image::load_from_memory(&bytes)?.into_rgba8();
+    let data = ImageData {
+        width: image.width() as usize,
+        height: image.height() as usize,
+        bytes: Cow::Owned(image.into_raw()),
+    };
+    with_clipboard(|c| c.set_image(data))
+}
+
+// 모바일 플랫폼의 클립보드는 아직 지원하지 않습니다
+#[cfg(mobile)]
+fn unsupported<T>() -> Result<T> {
+    Err(anyhow::anyhow!(
+        "Clipboard access is not supported on this platform"
+    ))
+}
+
+#[cfg(mobile)]
+fn read_text() -> Result<String> {
+    unsupported()
+}
+
+#[cfg(mobile)]
+fn read_png() -> Result<Vec<u8>> {
+    unsupported()
+}
+
+#[cfg(mobile)]
+fn write_text(_text: String) -> Result<()> {
+    unsupported()
+}
+
+#[cfg(mobile)]
+fn write_image(_bytes: Vec<u8>) -> Result<()> {
+    unsupported()
+}
+
+async fn approve(message: String) -> Result<()> {
+    ask_approval("clipboard", message)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+}
+
+/// Read text, or an image stored as a PNG attachment, after the user approves
+pub async fn read(store: &AttachmentStore, image: bool) -> Result<ClipboardContent> {
+    let kind = if image { "image" } else { "text" };
+    approve(format!(
+        "The assistant wants to read the {} on your clipboard. Allow?",
+        kind
+    ))
+    .await?;
+
+    if !image {
+        let text = tokio::task::spawn_blocking(read_text).await??;
+        return Ok(ClipboardContent::Text(text));
+    }
+
+    let png = tokio::task::spawn_blocking(read_png).await??;
+    let info = store
+        .store_bytes(&png, Some("image/png".to_string()))
+        .await?;
+    Ok(ClipboardContent::Image(info))
+}
+
+/// Put text, or an image attachment, on the clipboard after the user approves
+pub async fn write(store: &AttachmentStore, content: ClipboardContent) -> Result<()> {
+    match content {
+        ClipboardContent::Text(text) => {
+            let preview: String = text.chars().take(200).collect();
+            let ellipsis = if preview.len() < text.len() {
+                "…"
+            } else {
+                ""
+            };
+            approve(format!(
+                "The assistant wants to copy this text to your clipboard:\n\n{}{}",
+                preview, ellipsis
+            ))
+            .await?;
+            tokio::task::spawn_blocking(move || write_text(text)).await??;
+        }
+        ClipboardContent::Image(info) => {
+            approve("The assistant wants to copy an image to your clipboard. Allow?".to_string())
+                .await?;
+            let mut bytes = Vec::new();
+            store
+                .open(&info.hash)
+                .await?
+                .read_to_end(&mut bytes)
+                .await?;
+            tokio::task::spawn_blocking(move || write_image(bytes)).await??;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,642 @@
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf, MAIN_SEPARATOR};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+use tree_sitter::{Language, Node, Parser};
+
+// 거대한 생성 파일이나 번들은 색인하지 않습니다
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+const MAX_FILES: usize = 50_000;
+const MAX_REFERENCES_PER_FILE: usize = 20_000;
+const MAX_SIGNATURE_CHARS: usize = 200;
+const MAX_LINE_CHARS: usize = 300;
+pub const DEFAULT_LIMIT: usize = 50;
+pub const MAX_LIMIT: usize = 200;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS files (
+    id INTEGER PRIMARY KEY,
+    path TEXT NOT NULL UNIQUE,
+    language TEXT NOT NULL,
+    modified INTEGER NOT NULL,
+    size INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS symbols (
+    file_id INTEGER NOT NULL REFERENCES files(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    parent TEXT,
+    depth INTEGER NOT NULL,
+    start_line INTEGER NOT NULL,
+    end_line INTEGER NOT NULL,
+    signature TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS symbols_name ON symbols(name);
+CREATE INDEX IF NOT EXISTS symbols_file ON symbols(file_id);
+CREATE TABLE IF NOT EXISTS refs (
+    file_id INTEGER NOT NULL REFERENCES files(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    line INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS refs_name ON refs(name);
+CREATE INDEX IF NOT EXISTS refs_file ON refs(file_id);
+CREATE VIRTUAL TABLE IF NOT EXISTS contents USING fts5(body, tokenize = 'trigram');
+";
+
+struct LanguageSpec {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    language: fn() -> Language,
+    /// tree-sitter node kind -> symbol kind
+    definitions: &'static [(&'static str, &'static str)],
+}
+
+const JS_DEFINITIONS: &[(&str, &str)] = &[
+    ("function_declaration", "function"),
+    ("generator_function_declaration", "function"),
+    ("class_declaration", "class"),
+    ("method_definition", "method"),
+    ("variable_declarator", "function"),
+];
+
+const TS_DEFINITIONS: &[(&str, &str)] = &[
+    ("function_declaration", "function"),
+    ("generator_function_declaration", "function"),
+    ("class_declaration", "class"),
+    ("abstract_class_declaration", "class"),
+    ("method_definition", "method"),
+    ("method_signature", "method"),
+    ("variable_declarator", "function"),
+    ("interface_declaration", "interface"),
+    ("type_alias_declaration", "type"),
+    ("enum_declaration", "enum"),
+];
+
+const LANGUAGES: &[LanguageSpec] = &[
+    LanguageSpec {
+        name: "rust",
+        extensions: &["rs"],
+        language: || tree_sitter_rust::LANGUAGE.into(),
+        definitions: &[
+            ("function_item", "function"),
+            ("function_signature_item", "function"),
+            ("struct_item", "struct"),
+            ("enum_item", "enum"),
+            ("union_item", "union"),
+            ("trait_item", "trait"),
+            ("impl_item", "impl"),
+            ("mod_item", "module"),
+            ("const_item", "constant"),
+            ("static_item", "constant"),
+            ("type_item", "type"),
+            ("macro_definition", "macro"),
+        ],
+    },
+    LanguageSpec {
+        name: "python",
+        extensions: &["py", "pyi"],
+        language: || tree_sitter_python::LANGUAGE.into(),
+        definitions: &[
+            ("function_definition", "function"),
+            ("class_definition", "class"),
+        ],
+    },
+    LanguageSpec {
+        name: "javascript",
+        extensions: &["js", "jsx", "mjs", "cjs"],
+        language: || tree_sitter_javascript::LANGUAGE.into(),
+        definitions: JS_DEFINITIONS,
+    },
+    LanguageSpec {
+        name: "typescript",
+        extensions: &["ts", "mts", "cts"],
+        language: || tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        definitions: TS_DEFINITIONS,
+    },
+    LanguageSpec {
+        name: "tsx",
+        extensions: &["tsx"],
+        language: || tree_sitter_typescript::LANGUAGE_TSX.into(),
+        definitions: TS_DEFINITIONS,
+    },
+    LanguageSpec {
+        name: "go",
+        extensions: &["go"],
+        language: || tree_sitter_go::LANGUAGE.into(),
+        definitions: &[
+            ("function_declaration", "function"),
+            ("method_declaration", "method"),
+            ("type_spec", "type"),
+        ],
+    },
+];
+
+// 참조로 기록할 식별자 노드 종류
+const IDENTIFIER_KINDS: &[&str] = &[
+    "identifier",
+    "type_identifier",
+    "field_identifier",
+    "property_identifier",
+];
+
+fn language_for(path: &Path) -> Option<&'static LanguageSpec> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|spec| spec.extensions.contains(&extension.as_str()))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: String,
+    pub parent: Option<String>,
+    pub depth: usize,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolMatch {
+    pub path: String,
+    #[serde(flatten)]
+    pub symbol: Symbol,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LineMatch {
+    pub path: String,
+    pub line: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolSearch {
+    pub definitions: Vec<SymbolMatch>,
+    /// Only filled when references were requested
+    pub references: Vec<LineMatch>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IndexStats {
+    pub files: usize,
+    pub updated: usize,
+    pub removed: usize,
+    /// True when the workspace had more files than the index accepts
+    pub truncated: bool,
+}
+
+struct ParsedFile {
+    symbols: Vec<Symbol>,
+    references: Vec<(String, usize)>,
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(max) {
+        Some((index, _)) => format!("{}…", &text[..index]),
+        None => text.to_string(),
+    }
+}
+
+/// 정의 노드의 이름을 찾습니다. 이름이 없거나 함수가 아닌 변수는 `None`
+fn definition_name(node: Node, source: &[u8]) -> Option<String> {
+    let name_node = match node.kind() {
+        "impl_item" => node.child_by_field_name("type"),
+        // `const foo = () => {}` 처럼 함수가 대입된 변수만 심볼로 취급합니다
+        "variable_declarator" => {
+            let value = node.child_by_field_name("value")?;
+            if !matches!(
+                value.kind(),
+                "arrow_function" | "function_expression" | "function" | "generator_function"
+            ) {
+                return None;
+            }
+            node.child_by_field_name("name")
+        }
+        _ => node.child_by_field_name("name"),
+    }?;
+    let name = name_node.utf8_text(source).ok()?;
+    Some(match (node.kind(), node.child_by_field_name("trait")) {
+        ("impl_item", Some(trait_node)) => {
+            format!("{} for {}", trait_node.utf8_text(source).ok()?, name)
+        }
+        _ => name.to_string(),
+    })
+}
+
+fn parse_file(parser: &mut Parser, spec: &LanguageSpec, source: &str) -> Result<ParsedFile> {
+    parser.set_language(&(spec.language)())?;
+    let tree = parser
+        .parse(source, None)
+        .ok_or_else(|| anyhow::anyhow!("tree-sitter could not parse the file"))?;
+    let bytes = source.as_bytes();
+
+    let mut symbols: Vec<Symbol> = Vec::new();
+    let mut references = Vec::new();
+    let mut seen_references = HashSet::new();
+    let mut definition_names = HashSet::new();
+    // (노드, 가장 가까운 상위 심볼 인덱스)
+    let mut stack: Vec<(Node, Option<usize>)> = vec![(tree.root_node(), None)];
+    while let Some((node, parent)) = stack.pop() {
+        let mut scope = parent;
+        if let Some((_, kind)) = spec.definitions.iter().find(|(k, _)| *k == node.kind()) {
+            if let Some(name) = definition_name(node, bytes) {
+                if let Some(name_node) = node.child_by_field_name("name") {
+                    definition_names.insert(name_node.id());
+                }
+                let signature = node
+                    .utf8_text(bytes)
+                    .unwrap_or_default()
+                    .lines()
+                    .next()
+                    .unwrap_or_default();
+                let enclosing = parent.map(|index| &symbols[index]);
+                symbols.push(Symbol {
+                    name,
+                    kind: kind.to_string(),
+                    parent: enclosing.map(|symbol| symbol.name.clone()),
+                    depth: enclosing.map_or(0, |symbol| symbol.depth + 1),
+                    start_line: node.start_position().row + 1,
+                    end_line: node.end_position().row + 1,
+                    signature: truncate_chars(signature, MAX_SIGNATURE_CHARS),
+                });
+                scope = Some(symbols.len() - 1);
+            }
+        }
+
+        if IDENTIFIER_KINDS.contains(&node.kind())
+            && !definition_names.contains(&node.id())
+            && references.len() < MAX_REFERENCES_PER_FILE
+        {
+            if let Ok(name) = node.utf8_text(bytes) {
+                let line = node.start_position().row + 1;
+                if seen_references.insert((name, line)) {
+                    references.push((name.to_string(), line));
+                }
+            }
+        }
+
+        // 스택이므로 역순으로 넣어야 소스 순서대로 방문합니다
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev().map(|child| (child, scope)));
+    }
+    Ok(ParsedFile {
+        symbols,
+        references,
+    })
+}
+
+fn modified_secs(metadata: &std::fs::Metadata) -> i64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_secs() as i64)
+}
+
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// `root` 아래의 경로만 고르기 위한 접두사 (`/repo`가 `/repo2`와 겹치지 않도록)
+fn root_prefix(root: &Path) -> String {
+    let root = root.display().to_string();
+    if root.ends_with(MAIN_SEPARATOR) {
+        root
+    } else {
+        format!("{}{}", root, MAIN_SEPARATOR)
+    }
+}
+
+/// Tree-sitter symbol index kept in SQLite, refreshed incrementally on every query
+pub struct CodeIndex {
+    db_path: PathBuf,
+    connection: Mutex<Option<Connection>>,
+}
+
+impl CodeIndex {
+    pub fn new(db_path: PathBuf) -> Self {
+        Self {
+            db_path,
+            connection: Mutex::new(None),
+        }
+    }
+
+    fn with_connection<T>(&self, f: impl FnOnce(&mut Connection) -> Result<T>) -> Result<T> {
+        let mut guard = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        if guard.is_none() {
+            if let Some(parent) = self.db_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let connection = Connection::open(&self.db_path)?;
+            connection.pragma_update(None, "journal_mode", "WAL")?;
+            connection.pragma_update(None, "foreign_keys", true)?;
+            connection.execute_batch(SCHEMA)?;
+            *guard = Some(connection);
+        }
+        f(guard.as_mut().expect("connection was just opened"))
+    }
+
+    fn store_file(
+        connection: &Connection,
+        path: &str,
+        spec: &LanguageSpec,
+        metadata: &std::fs::Metadata,
+        source: &str,
+        parsed: &ParsedFile,
+    ) -> Result<()> {
+        Self::remove_file(connection, path)?;
+        connection.execute(
+            "INSERT INTO files (path, language, modified, size) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                path,
+                spec.name,
+                modified_secs(metadata),
+                metadata.len() as i64
+            ],
+        )?;
+        let file_id = connection.last_insert_rowid();
+        connection.execute(
+            "INSERT INTO contents (rowid, body) VALUES (?1, ?2)",
+            params![file_id, source],
+        )?;
+        let mut insert_symbol = connection.prepare_cached(
+            "INSERT INTO symbols (file_id, name, kind, parent, depth, start_line, end_line, signature)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+        for symbol in &parsed.symbols {
+            insert_symbol.execute(params![
+                file_id,
+                symbol.name,
+                symbol.kind,
+                symbol.parent,
+                symbol.depth as i64,
+                symbol.start_line as i64,
+                symbol.end_line as i64,
+                symbol.signature,
+            ])?;
+        }
+        let mut insert_reference = connection
+            .prepare_cached("INSERT INTO refs (file_id, name, line) VALUES (?1, ?2, ?3)")?;
+        for (name, line) in &parsed.references {
+            insert_reference.execute(params![file_id, name, *line as i64])?;
+        }
+        Ok(())
+    }
+
+    fn remove_file(connection: &Connection, path: &str) -> Result<()> {
+        let id: Option<i64> = connection
+            .query_row("SELECT id FROM files WHERE path = ?1", [path], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        if let Some(id) = id {
+            connection.execute("DELETE FROM contents WHERE rowid = ?1", [id])?;
+            connection.execute("DELETE FROM files WHERE id = ?1", [id])?;
+        }
+        Ok(())
+    }
+
+    /// Re-parse files under `root` whose size or mtime changed and drop deleted ones.
+    /// `.gitignore` rules and hidden directories are respected.
+    pub fn refresh(&self, root: &Path) -> Result<IndexStats> {
+        let mut stats = IndexStats::default();
+        let mut found = Vec::new();
+        for entry in ignore::WalkBuilder::new(root).build() {
+            let Ok(entry) = entry else { continue };
+            if !entry.file_type().is_some_and(|t| t.is_file()) {
+                continue;
+            }
+            let Some(spec) = language_for(entry.path()) else {
+                continue;
+            };
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.len() > MAX_FILE_BYTES {
+                continue;
+            }
+            if found.len() >= MAX_FILES {
+                stats.truncated = true;
+                break;
+            }
+            found.push((entry.into_path(), spec, metadata));
+        }
+        stats.files = found.len();
+
+        let prefix = root_prefix(root);
+        self.with_connection(|connection| {
+            let mut indexed: HashMap<String, (i64, i64)> = HashMap::new();
+            {
+                let mut statement = connection.prepare(
+                    "SELECT path, modified, size FROM files WHERE substr(path, 1, ?1) = ?2",
+                )?;
+                let rows = statement
+                    .query_map(params![prefix.chars().count() as i64, prefix], |row| {
+                        Ok((row.get::<_, String>(0)?, (row.get(1)?, row.get(2)?)))
+                    })?;
+                for row in rows {
+                    let (path, state) = row?;
+                    indexed.insert(path, state);
+                }
+            }
+
+            let transaction = connection.transaction()?;
+            let mut parser = Parser::new();
+            for (path, spec, metadata) in &found {
+                let key = path.display().to_string();
+                let state = (modified_secs(metadata), metadata.len() as i64);
+                if indexed.remove(&key) == Some(state) {
+                    continue;
+                }
+                // 바이너리나 UTF-8이 아닌 파일은 건너뜁니다
+                let Ok(source) = std::fs::read_to_string(path) else {
+                    continue;
+                };
+                match parse_file(&mut parser, spec, &source) {
+                    Ok(parsed) => {
+                        Self::store_file(&transaction, &key, spec, metadata, &source, &parsed)?;
+                        stats.updated += 1;
+                    }
+                    Err(e) => println!("Warning: Failed to index {}: {}", key, e),
+                }
+            }
+            // 남은 항목은 삭제되었거나 더 이상 색인 대상이 아닌 파일입니다
+            for path in indexed.keys() {
+                Self::remove_file(&transaction, path)?;
+                stats.removed += 1;
+            }
+            transaction.commit()?;
+            Ok(())
+        })?;
+        if stats.updated > 0 || stats.removed > 0 {
+            println!(
+                "Indexed {}: {} updated, {} removed, {} files total",
+                root.display(),
+                stats.updated,
+                stats.removed,
+                stats.files
+            );
+        }
+        Ok(stats)
+    }
+
+    /// Case-insensitive substring search over indexed file contents
+    pub fn search(&self, root: &Path, query: &str, limit: usize) -> Result<Vec<LineMatch>> {
+        if query.trim().is_empty() {
+            return Err(anyhow::anyhow!("Search query is empty"));
+        }
+        let prefix = root_prefix(root);
+        let needle = query.to_lowercase();
+        self.with_connection(|connection| {
+            // trigram 토크나이저는 3글자 이상의 LIKE 패턴을 색인으로 처리합니다
+            let mut statement = connection.prepare(
+                "SELECT f.path, c.body FROM contents c JOIN files f ON f.id = c.rowid
+                 WHERE c.body LIKE ?1 ESCAPE '\\' AND substr(f.path, 1, ?2) = ?3
+                 ORDER BY f.path",
+            )?;
+            let mut rows = statement.query(params![
+                format!("%{}%", escape_like(query)),
+                prefix.chars().count() as i64,
+                prefix
+            ])?;
+            let mut matches = Vec::new();
+            while let Some(row) = rows.next()? {
+                let path: String = row.get(0)?;
+                let body: String = row.get(1)?;
+                for (index, line) in body.lines().enumerate() {
+                    if !line.to_lowercase().contains(&needle) {
+                        continue;
+                    }
+                    matches.push(LineMatch {
+                        path: path.clone(),
+                        line: index + 1,
+                        text: truncate_chars(line, MAX_LINE_CHARS),
+                    });
+                    if matches.len() >= limit {
+                        return Ok(matches);
+                    }
+                }
+            }
+            Ok(matches)
+        })
+    }
+
+    /// Definitions whose name contains `name` (exact matches first), plus optional references
+    pub fn find_symbol(
+        &self,
+        root: &Path,
+        name: &str,
+        kind: Option<&str>,
+        include_references: bool,
+        limit: usize,
+    ) -> Result<SymbolSearch> {
+        let prefix = root_prefix(root);
+        self.with_connection(|connection| {
+            let mut statement = connection.prepare(
+                "SELECT f.path, s.name, s.kind, s.parent, s.depth, s.start_line, s.end_line, s.signature
+                 FROM symbols s JOIN files f ON f.id = s.file_id
+                 WHERE s.name LIKE ?1 ESCAPE '\\' AND substr(f.path, 1, ?2) = ?3
+                   AND (?4 IS NULL OR s.kind = ?4)
+                 ORDER BY s.name = ?5 DESC, length(s.name), f.path, s.start_line
+                 LIMIT ?6",
+            )?;
+            let definitions = statement
+                .query_map(
+                    params![
+                        format!("%{}%", escape_like(name)),
+                        prefix.chars().count() as i64,
+                        prefix,
+                        kind,
+                        name,
+                        limit as i64
+                    ],
+                    |row| {
+                        Ok(SymbolMatch {
+                            path: row.get(0)?,
+                            symbol: Symbol {
+                                name: row.get(1)?,
+                                kind: row.get(2)?,
+                                parent: row.get(3)?,
+                                depth: row.get::<_, i64>(4)? as usize,
+                                start_line: row.get::<_, i64>(5)? as usize,
+                                end_line: row.get::<_, i64>(6)? as usize,
+                                signature: row.get(7)?,
+                            },
+                        })
+                    },
+                )?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let mut references = Vec::new();
+            if include_references {
+                let mut statement = connection.prepare(
+                    "SELECT f.path, r.line, c.body FROM refs r
+                     JOIN files f ON f.id = r.file_id
+                     JOIN contents c ON c.rowid = f.id
+                     WHERE r.name = ?1 AND substr(f.path, 1, ?2) = ?3
+                     ORDER BY f.path, r.line
+                     LIMIT ?4",
+                )?;
+                let mut rows = statement.query(params![
+                    name,
+                    prefix.chars().count() as i64,
+                    prefix,
+                    limit as i64
+                ])?;
+                while let Some(row) = rows.next()? {
+                    let line = row.get::<_, i64>(1)? as usize;
+                    let body: String = row.get(2)?;
+                    references.push(LineMatch {
+                        path: row.get(0)?,
+                        line,
+                        text: truncate_chars(
+                            body.lines().nth(line - 1).unwrap_or_default(),
+                            MAX_LINE_CHARS,
+                        ),
+                    });
+                }
+            }
+            Ok(SymbolSearch {
+                definitions,
+                references,
+            })
+        })
+    }
+
+    /// Parse one file (updating the index) and return its symbols in source order
+    pub fn outline(&self, path: &Path) -> Result<Vec<Symbol>> {
+        let spec = language_for(path).ok_or_else(|| {
+            anyhow::anyhow!(
+                "No outline support for '{}' (Rust, Python, JavaScript, TypeScript or Go)",
+                path.display()
+            )
+        })?;
+        let metadata = std::fs::metadata(path)?;
+        if metadata.len() > MAX_FILE_BYTES {
+            return Err(anyhow::anyhow!(
+                "'{}' is larger than {} KiB",
+                path.display(),
+                MAX_FILE_BYTES / 1024
+            ));
+        }
+        let source = std::fs::read_to_string(path)?;
+        let parsed = parse_file(&mut Parser::new(), spec, &source)?;
+        let key = path.display().to_string();
+        self.with_connection(|connection| {
+            let transaction = connection.transaction()?;
+            Self::store_file(&transaction, &key, spec, &metadata, &source, &parsed)?;
+            transaction.commit()?;
+            Ok(())
+        })?;
+        Ok(parsed.symbols)
+    }
+}
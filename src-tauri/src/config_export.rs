@@ -0,0 +1,132 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::mcp::MCPServerConfig;
+use crate::redact;
+use crate::runtime;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ClaudeExportReport {
+    pub exported: Vec<String>,
+    /// Servers Claude Desktop cannot run, with the reason
+    pub skipped: BTreeMap<String, String>,
+}
+
+/// Env values become `${NAME}` placeholders so secrets never leave the app
+fn env_placeholders(config: &MCPServerConfig) -> serde_json::Map<String, serde_json::Value> {
+    config
+        .env
+        .iter()
+        .flatten()
+        .map(|(key, _)| (key.clone(), json!(format!("${{{}}}", key))))
+        .collect()
+}
+
+/// Arguments may embed a token too; mask anything the redactor recognizes
+fn redacted_args(args: &[String]) -> Vec<String> {
+    args.iter().map(|arg| redact::redact(arg)).collect()
+}
+
+/// `docker run` invocation equivalent to the `docker` transport, minus the per-run container name
+fn container_args(config: &MCPServerConfig) -> Option<(String, Vec<String>)> {
+    let container = runtime::container(config).ok()?;
+    let engine = container
+        .engine
+        .clone()
+        .unwrap_or_else(|| "docker".to_string());
+    let mut args: Vec<String> = vec!["run".into(), "-i".into(), "--rm".into()];
+    if let Some(pull) = &container.pull {
+        args.push(format!("--pull={}", pull));
+    }
+    if let Some(memory) = container
+        .memory
+        .clone()
+        .or(config.max_memory_mb.map(|mb| format!("{}m", mb)))
+    {
+        args.push(format!("--memory={}", memory));
+    }
+    if let Some(cpus) = container
+        .cpus
+        .clone()
+        .or(config.cpu_limit.map(|cpus| cpus.to_string()))
+    {
+        args.push(format!("--cpus={}", cpus));
+    }
+    if let Some(network) = &container.network {
+        args.push(format!("--network={}", network));
+    }
+    for volume in &container.volumes {
+        args.push("-v".into());
+        args.push(volume.clone());
+    }
+    let mut keys: Vec<&String> = config.env.iter().flat_map(|env| env.keys()).collect();
+    keys.sort();
+    for key in keys {
+        args.push("-e".into());
+        args.push(key.clone());
+    }
+    args.push(container.image.clone());
+    args.extend(config.command.clone());
+    args.extend(config.args.clone().unwrap_or_default());
+    Some((engine, args))
+}
+
+/// One `mcpServers` entry, or why the server cannot be expressed in Claude Desktop's format
+fn claude_entry(config: &MCPServerConfig) -> Result<serde_json::Value, String> {
+    let (command, args) = match runtime::transport(config) {
+        "stdio" => runtime::command_line(config).map_err(|e| e.to_string())?,
+        "docker" => container_args(config).ok_or_else(|| "No container configured".to_string())?,
+        // Claude Desktop은 stdio만 실행하므로 원격 서버는 mcp-remote로 연결합니다
+        "http" => {
+            let url = config
+                .url
+                .clone()
+                .ok_or_else(|| "No URL configured".to_string())?;
+            (
+                "npx".to_string(),
+                vec!["-y".to_string(), "mcp-remote".to_string(), url],
+            )
+        }
+        other => return Err(format!("The {} transport is not supported", other)),
+    };
+
+    let mut entry = json!({
+        "command": command,
+        "args": redacted_args(&args),
+    });
+    let env = env_placeholders(config);
+    if !env.is_empty() {
+        entry["env"] = serde_json::Value::Object(env);
+    }
+    Ok(entry)
+}
+
+/// Build a Claude Desktop `{"mcpServers": {...}}` document
+pub fn convert(configs: &[MCPServerConfig]) -> (serde_json::Value, ClaudeExportReport) {
+    let mut servers = serde_json::Map::new();
+    let mut report = ClaudeExportReport::default();
+    for config in configs {
+        match claude_entry(config) {
+            Ok(entry) => {
+                servers.insert(config.name.clone(), entry);
+                report.exported.push(config.name.clone());
+            }
+            Err(reason) => {
+                report.skipped.insert(config.name.clone(), reason);
+            }
+        }
+    }
+    (json!({ "mcpServers": servers }), report)
+}
+
+pub async fn export(configs: &[MCPServerConfig], path: &Path) -> Result<ClaudeExportReport> {
+    let (document, report) = convert(configs);
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(path, serde_json::to_string_pretty(&document)?).await?;
+    Ok(report)
+}
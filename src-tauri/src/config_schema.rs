@@ -0,0 +1,616 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+
+use crate::runtime::{self, Runtime, TRANSPORTS};
+
+// 오타 추천에 쓰는 최대 편집 거리
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+/// One problem in a server config, located precisely enough to render inline
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigDiagnostic {
+    pub severity: DiagnosticSeverity,
+    /// e.g. `mcpServers.github.args[1]`
+    pub path: String,
+    /// JSON pointer to the same location, e.g. `/mcpServers/github/args/1`
+    pub pointer: String,
+    pub expected: Option<String>,
+    pub found: Option<String>,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+/// JSON Schema of one server entry
+pub fn server_schema() -> &'static Value {
+    static SCHEMA: OnceLock<Value> = OnceLock::new();
+    SCHEMA.get_or_init(|| {
+        let string_list = json!({ "type": "array", "items": { "type": "string" } });
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "MCP server",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "name": { "type": "string", "minLength": 1 },
+                "command": { "type": "string", "minLength": 1 },
+                "args": string_list,
+                "env": { "type": "object", "additionalProperties": { "type": "string" } },
+                "path": string_list,
+                "runtime": {
+                    "type": "string",
+                    "enum": Runtime::ALL.map(Runtime::name)
+                },
+                "package": { "type": "string", "minLength": 1 },
+                "distro": { "type": "string", "minLength": 1 },
+                "transport": { "type": "string", "enum": TRANSPORTS },
+                "url": { "type": "string", "minLength": 1 },
+                "port": { "type": "integer", "minimum": 0, "maximum": 65535 },
+                "env_policy": {
+                    "type": "string",
+                    "enum": ["inherit-all", "inherit-list", "clean"]
+                },
+                "inherit_env": string_list,
+                "container": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "required": ["image"],
+                    "properties": {
+                        "image": { "type": "string", "minLength": 1 },
+                        "engine": { "type": "string", "enum": ["docker", "podman"] },
+                        "pull": { "type": "string", "enum": ["missing", "always", "never"] },
+                        "volumes": string_list,
+                        "mount_workspace_roots": { "type": "boolean" },
+                        "memory": { "type": "string" },
+                        "cpus": { "type": "string" },
+                        "network": { "type": "string" }
+                    }
+                },
+                "max_memory_mb": { "type": "integer", "minimum": 1 },
+                "cpu_limit": { "type": "number", "exclusiveMinimum": 0 },
+                "sha256": { "type": "string", "pattern": "^[0-9a-fA-F]{64}$" },
+                "max_concurrent_calls": { "type": "integer", "minimum": 1 },
+                "requests_per_minute": { "type": "integer", "minimum": 1 },
+                "idle_timeout_secs": { "type": "integer", "minimum": 1 },
+                "depends_on": string_list,
+                "call_timeout_secs": { "type": "integer", "minimum": 1 },
+                "group": { "type": "string", "minLength": 1 },
+                "autostart": { "type": "boolean" }
+            }
+        })
+    })
+}
+
+fn type_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_u64() || n.is_i64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn matches_type(value: &Value, expected: &str) -> bool {
+    let actual = type_of(value);
+    actual == expected || (expected == "number" && actual == "integer")
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(current)
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+fn closest<'a>(word: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let word = word.to_lowercase();
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&word, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn short(value: &Value) -> String {
+    let mut text = value.to_string();
+    if text.len() > 40 {
+        let end = (0..=37)
+            .rev()
+            .find(|&i| text.is_char_boundary(i))
+            .unwrap_or(0);
+        text.truncate(end);
+        text.push_str("...");
+    }
+    text
+}
+
+/// Fix for a value of the wrong type, when an obvious one exists
+fn type_fix(value: &Value, expected: &str) -> Option<String> {
+    match (value, expected) {
+        (Value::String(s), "array") => {
+            let parts: Vec<Value> = s.split_whitespace().map(|p| json!(p)).collect();
+            Some(format!("Use a list of strings: {}", Value::Array(parts)))
+        }
+        (Value::String(s), "integer" | "number") if s.trim().parse::<f64>().is_ok() => {
+            Some(format!("Remove the quotes: {}", s.trim()))
+        }
+        (Value::String(s), "boolean") if matches!(s.as_str(), "true" | "false") => {
+            Some(format!("Remove the quotes: {}", s))
+        }
+        (Value::Number(_) | Value::Bool(_), "string") => {
+            Some(format!("Quote the value: \"{}\"", value))
+        }
+        (Value::Array(items), "string") if items.len() == 1 && items[0].is_string() => {
+            Some(format!("Use the single string: {}", items[0]))
+        }
+        _ => None,
+    }
+}
+
+struct Location {
+    path: String,
+    pointer: String,
+}
+
+impl Location {
+    fn root() -> Self {
+        Self {
+            path: String::new(),
+            pointer: String::new(),
+        }
+    }
+
+    fn key(&self, key: &str) -> Self {
+        let path = if self.path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", self.path, key)
+        };
+        Self {
+            path,
+            pointer: format!(
+                "{}/{}",
+                self.pointer,
+                key.replace('~', "~0").replace('/', "~1")
+            ),
+        }
+    }
+
+    fn index(&self, index: usize) -> Self {
+        Self {
+            path: format!("{}[{}]", self.path, index),
+            pointer: format!("{}/{}", self.pointer, index),
+        }
+    }
+}
+
+struct Validator {
+    diagnostics: Vec<ConfigDiagnostic>,
+}
+
+impl Validator {
+    fn push(
+        &mut self,
+        severity: DiagnosticSeverity,
+        at: &Location,
+        expected: Option<String>,
+        found: Option<String>,
+        message: String,
+        suggestion: Option<String>,
+    ) {
+        self.diagnostics.push(ConfigDiagnostic {
+            severity,
+            path: at.path.clone(),
+            pointer: at.pointer.clone(),
+            expected,
+            found,
+            message,
+            suggestion,
+        });
+    }
+
+    fn error(&mut self, at: &Location, expected: String, value: &Value, message: String) {
+        self.push(
+            DiagnosticSeverity::Error,
+            at,
+            Some(expected),
+            Some(short(value)),
+            message,
+            None,
+        );
+    }
+
+    /// Check `value` against the subset of JSON Schema used by [`server_schema`]
+    fn check(&mut self, schema: &Value, value: &Value, at: &Location) {
+        if let Some(expected) = schema.get("type").and_then(|t| t.as_str()) {
+            if !matches_type(value, expected) {
+                self.push(
+                    DiagnosticSeverity::Error,
+                    at,
+                    Some(expected.to_string()),
+                    Some(format!("{} {}", type_of(value), short(value))),
+                    format!("Expected {}, found {}", expected, type_of(value)),
+                    type_fix(value, expected),
+                );
+                return;
+            }
+        }
+
+        if let Some(allowed) = schema.get("enum").and_then(|e| e.as_array()) {
+            if !allowed.contains(value) {
+                let names: Vec<&str> = allowed.iter().filter_map(|v| v.as_str()).collect();
+                let suggestion = value
+                    .as_str()
+                    .and_then(|s| closest(s, names.iter().copied()))
+                    .map(|name| format!("Did you mean \"{}\"?", name))
+                    .or_else(|| Some(format!("Use one of: {}", names.join(", "))));
+                self.push(
+                    DiagnosticSeverity::Error,
+                    at,
+                    Some(names.join(" | ")),
+                    Some(short(value)),
+                    format!("{} is not an allowed value", short(value)),
+                    suggestion,
+                );
+            }
+        }
+
+        match value {
+            Value::String(s) => self.check_string(schema, s, value, at),
+            Value::Number(n) => {
+                self.check_number(schema, n.as_f64().unwrap_or_default(), value, at)
+            }
+            Value::Array(items) => {
+                if let Some(item_schema) = schema.get("items") {
+                    for (index, item) in items.iter().enumerate() {
+                        self.check(item_schema, item, &at.index(index));
+                    }
+                }
+            }
+            Value::Object(map) => self.check_object(schema, map, at),
+            _ => {}
+        }
+    }
+
+    fn check_string(&mut self, schema: &Value, s: &str, value: &Value, at: &Location) {
+        if let Some(min) = schema.get("minLength").and_then(|m| m.as_u64()) {
+            if (s.chars().count() as u64) < min {
+                self.error(
+                    at,
+                    "non-empty string".to_string(),
+                    value,
+                    "Value is empty".to_string(),
+                );
+            }
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(|p| p.as_str()) {
+            if regex::Regex::new(pattern).is_ok_and(|re| !re.is_match(s)) {
+                self.error(
+                    at,
+                    format!("string matching {}", pattern),
+                    value,
+                    "Value does not have the expected format".to_string(),
+                );
+            }
+        }
+    }
+
+    fn check_number(&mut self, schema: &Value, n: f64, value: &Value, at: &Location) {
+        if let Some(min) = schema.get("minimum").and_then(|m| m.as_f64()) {
+            if n < min {
+                self.error(
+                    at,
+                    format!(">= {}", min),
+                    value,
+                    format!("Must be at least {}", min),
+                );
+            }
+        }
+        if let Some(min) = schema.get("exclusiveMinimum").and_then(|m| m.as_f64()) {
+            if n <= min {
+                self.error(
+                    at,
+                    format!("> {}", min),
+                    value,
+                    format!("Must be greater than {}", min),
+                );
+            }
+        }
+        if let Some(max) = schema.get("maximum").and_then(|m| m.as_f64()) {
+            if n > max {
+                self.error(
+                    at,
+                    format!("<= {}", max),
+                    value,
+                    format!("Must be at most {}", max),
+                );
+            }
+        }
+    }
+
+    fn check_object(
+        &mut self,
+        schema: &Value,
+        map: &serde_json::Map<String, Value>,
+        at: &Location,
+    ) {
+        let properties = schema.get("properties").and_then(|p| p.as_object());
+        for required in schema
+            .get("required")
+            .and_then(|r| r.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|r| r.as_str())
+        {
+            if map.get(required).is_none_or(|value| value.is_null()) {
+                self.push(
+                    DiagnosticSeverity::Error,
+                    &at.key(required),
+                    Some("present".to_string()),
+                    None,
+                    format!("Missing required field \"{}\"", required),
+                    Some(format!("Add \"{}\"", required)),
+                );
+            }
+        }
+        for (key, item) in map {
+            // serde는 Option 필드의 null을 값이 없는 것으로 받아들입니다
+            if item.is_null() {
+                continue;
+            }
+            let location = at.key(key);
+            if let Some(property) = properties.and_then(|p| p.get(key)) {
+                self.check(property, item, &location);
+                continue;
+            }
+            match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    // 지금까지 모르는 필드는 무시했으므로 경고로만 알립니다
+                    let suggestion = properties
+                        .and_then(|p| closest(key, p.keys().map(String::as_str)))
+                        .map(|name| format!("Did you mean \"{}\"?", name))
+                        .or_else(|| Some("Remove the field".to_string()));
+                    self.push(
+                        DiagnosticSeverity::Warning,
+                        &location,
+                        None,
+                        None,
+                        format!("Unknown field \"{}\" is ignored", key),
+                        suggestion,
+                    );
+                }
+                Some(additional @ Value::Object(_)) => self.check(additional, item, &location),
+                _ => {}
+            }
+        }
+    }
+
+    /// Rules that depend on the transport and cannot be expressed per field
+    fn check_transport(&mut self, entry: &serde_json::Map<String, Value>, at: &Location) {
+        let present = |field: &str| entry.get(field).is_some_and(|value| !value.is_null());
+        let runtime = entry.get("runtime").and_then(|r| r.as_str());
+        if runtime == Some("wsl") {
+            // wsl은 패키지 대신 command를 WSL 안에서 실행합니다
+            if !present("command") {
+                self.push(
+                    DiagnosticSeverity::Error,
+                    &at.key("command"),
+                    Some("present".to_string()),
+                    None,
+                    "The wsl runtime requires \"command\"".to_string(),
+                    Some("Add the Linux executable to run inside WSL".to_string()),
+                );
+            }
+            if present("package") {
+                self.push(
+                    DiagnosticSeverity::Warning,
+                    &at.key("package"),
+                    None,
+                    None,
+                    "\"package\" is ignored by the wsl runtime".to_string(),
+                    Some("Remove \"package\"".to_string()),
+                );
+            }
+        } else if runtime.is_some() {
+            if !present("package") {
+                self.push(
+                    DiagnosticSeverity::Error,
+                    &at.key("package"),
+                    Some("present".to_string()),
+                    None,
+                    "A runtime requires \"package\"".to_string(),
+                    Some("Add the package to run, e.g. \"mcp-server-git\"".to_string()),
+                );
+            }
+            if present("command") {
+                self.push(
+                    DiagnosticSeverity::Warning,
+                    &at.key("command"),
+                    None,
+                    None,
+                    "\"command\" is ignored when a runtime is set".to_string(),
+                    Some("Remove \"command\"".to_string()),
+                );
+            }
+        }
+        let transport = match runtime {
+            Some("docker") => "docker",
+            _ => entry
+                .get("transport")
+                .and_then(|t| t.as_str())
+                .unwrap_or_else(|| {
+                    runtime::implied_transport(entry.get("url").and_then(|u| u.as_str()))
+                }),
+        };
+        if !runtime::transport_available(transport) {
+            self.push(
+                DiagnosticSeverity::Error,
+                &at.key("transport"),
+                Some("http or websocket".to_string()),
+                Some(transport.to_string()),
+                runtime::unavailable_transport(transport).to_string(),
+                Some("Connect to the server over http or websocket instead".to_string()),
+            );
+            return;
+        }
+        // args 없이 한 문자열로 적힌 명령은 실행할 때 단어로 나눕니다
+        if matches!(runtime, None | Some("wsl")) && !present("args") {
+            if let Some(Err(e)) = entry
+                .get("command")
+                .and_then(|command| command.as_str())
+                .map(runtime::split_words)
+            {
+                self.push(
+                    DiagnosticSeverity::Error,
+                    &at.key("command"),
+                    Some("balanced quotes".to_string()),
+                    None,
+                    format!("Could not parse \"command\": {}", e),
+                    Some("Close the quote, or move the arguments to \"args\"".to_string()),
+                );
+            }
+        }
+        if present("distro") && runtime != Some("wsl") {
+            self.push(
+                DiagnosticSeverity::Warning,
+                &at.key("distro"),
+                None,
+                None,
+                "\"distro\" is only used by the wsl runtime".to_string(),
+                Some("Set \"runtime\": \"wsl\" or remove \"distro\"".to_string()),
+            );
+        }
+        let (field, hint) = match transport {
+            "stdio" if runtime.is_some() => return,
+            "docker" if runtime.is_some() => return,
+            "stdio" => (
+                "command",
+                "Add the executable to run, or a \"runtime\" and \"package\"",
+            ),
+            "http" | "websocket" => ("url", "Add the server's URL"),
+            "docker" => ("container", "Add a container with at least an \"image\""),
+            _ => return,
+        };
+        if !present(field) {
+            self.push(
+                DiagnosticSeverity::Error,
+                &at.key(field),
+                Some("present".to_string()),
+                None,
+                format!("The {} transport requires \"{}\"", transport, field),
+                Some(hint.to_string()),
+            );
+        }
+    }
+
+    fn check_server(&mut self, entry: &Value, at: &Location, named: bool) {
+        let Some(map) = entry.as_object() else {
+            self.error(
+                at,
+                "object".to_string(),
+                entry,
+                format!("Expected a server object, found {}", type_of(entry)),
+            );
+            return;
+        };
+        self.check(server_schema(), entry, at);
+        if !named && map.get("name").is_none_or(|value| value.is_null()) {
+            self.push(
+                DiagnosticSeverity::Error,
+                &at.key("name"),
+                Some("string".to_string()),
+                None,
+                "Missing required field \"name\"".to_string(),
+                Some("Add a unique \"name\"".to_string()),
+            );
+        }
+        self.check_transport(map, at);
+    }
+}
+
+/// Validate a `{"mcpServers": {...}}` or `{"servers": [...]}` document
+pub fn validate(config: &Value) -> Vec<ConfigDiagnostic> {
+    let mut validator = Validator {
+        diagnostics: Vec::new(),
+    };
+    let root = Location::root();
+    if let Some(servers) = config.get("mcpServers") {
+        let at = root.key("mcpServers");
+        match servers.as_object() {
+            Some(servers) => {
+                for (name, entry) in servers {
+                    validator.check_server(entry, &at.key(name), true);
+                }
+            }
+            None => validator.error(
+                &at,
+                "object".to_string(),
+                servers,
+                "mcpServers must map server names to configs".to_string(),
+            ),
+        }
+    } else if let Some(servers) = config.get("servers") {
+        let at = root.key("servers");
+        match servers.as_array() {
+            Some(servers) => {
+                for (index, entry) in servers.iter().enumerate() {
+                    validator.check_server(entry, &at.index(index), false);
+                }
+            }
+            None => validator.error(
+                &at,
+                "array".to_string(),
+                servers,
+                "servers must be a list of server configs".to_string(),
+            ),
+        }
+    } else {
+        validator.push(
+            DiagnosticSeverity::Error,
+            &root,
+            Some("mcpServers object or servers array".to_string()),
+            Some(type_of(config).to_string()),
+            "Missing mcpServers object or servers array".to_string(),
+            Some("Wrap the servers in {\"mcpServers\": {...}}".to_string()),
+        );
+    }
+    validator.diagnostics
+}
+
+/// One line per diagnostic, for places that can only show text
+pub fn describe(diagnostics: &[ConfigDiagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|d| {
+            let path = if d.path.is_empty() { "(root)" } else { &d.path };
+            match &d.suggestion {
+                Some(suggestion) => format!("{}: {} ({})", path, d.message, suggestion),
+                None => format!("{}: {}", path, d.message),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
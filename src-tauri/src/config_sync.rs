@@ -0,0 +1,307 @@
+use anyhow::Result;
+use git2::{IndexAddOption, Repository, RepositoryInitOptions, Signature};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::process::Command;
+
+use crate::config_versions;
+
+const SETTINGS_FILE: &str = "config_sync.json";
+
+/// Config files that follow the user between machines.
+/// Files holding secrets, machine-specific paths or runtime state stay local.
+pub(crate) const SYNCED_FILES: &[&str] = &[
+    "prompt_templates.json",
+    "retention.json",
+    "redaction_rules.json",
+    "disabled_instructions.json",
+    "telemetry.json",
+    "postprocess.json",
+    "result_cache.json",
+    "tool_aliases.json",
+    "tool_conflicts.json",
+    "tool_priority.json",
+    "script_tools.json",
+    "openapi_servers.json",
+    "browser.json",
+    "remote_config_sources.json",
+];
+
+fn default_branch() -> String {
+    "main".to_string()
+}
+
+fn default_auto_push() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigSyncSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Remote URL; without one, changes are only committed locally
+    #[serde(default)]
+    pub remote: Option<String>,
+    #[serde(default = "default_branch")]
+    pub branch: String,
+    /// Push after every committed change
+    #[serde(default = "default_auto_push")]
+    pub auto_push: bool,
+}
+
+impl Default for ConfigSyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            remote: None,
+            branch: default_branch(),
+            auto_push: default_auto_push(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigSyncStatus {
+    pub settings: ConfigSyncSettings,
+    pub initialized: bool,
+    /// Short ID and summary of the last commit
+    pub head: Option<String>,
+    pub synced_files: Vec<String>,
+}
+
+/// Files changed by a pull, so the caller can reload them
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncReport {
+    pub committed: Option<String>,
+    pub pulled: Vec<String>,
+    pub pushed: bool,
+}
+
+pub fn load_settings(dir: &Path) -> ConfigSyncSettings {
+    std::fs::read_to_string(dir.join(SETTINGS_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn gitignore() -> String {
+    let mut lines = vec![
+        "# Managed by the app: only shareable config files are tracked".to_string(),
+        "*".to_string(),
+        "!.gitignore".to_string(),
+    ];
+    lines.extend(SYNCED_FILES.iter().map(|file| format!("!{}", file)));
+    lines.join("\n") + "\n"
+}
+
+fn signature(repo: &Repository) -> Result<Signature<'static>> {
+    // git 사용자 정보가 없는 기기에서도 커밋할 수 있도록 기본값을 씁니다
+    Ok(repo
+        .signature()
+        .or_else(|_| Signature::now("tauri-agent", "tauri-agent@localhost"))?)
+}
+
+/// Commit the tracked config files if they changed; returns the short commit ID
+fn commit(repo: &Repository, message: &str) -> Result<Option<String>> {
+    let mut index = repo.index()?;
+    index.update_all(["*"], None)?;
+    index.add_all(["*"], IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+    if parent.as_ref().is_some_and(|p| p.tree_id() == tree.id()) {
+        return Ok(None);
+    }
+    let signature = signature(repo)?;
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    let oid = repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parents,
+    )?;
+    Ok(Some(oid.to_string()[..7].to_string()))
+}
+
+fn open_or_init(dir: &Path, settings: &ConfigSyncSettings) -> Result<Repository> {
+    let repo = match Repository::open(dir) {
+        Ok(repo) => repo,
+        Err(_) => Repository::init_opts(
+            dir,
+            RepositoryInitOptions::new().initial_head(&settings.branch),
+        )?,
+    };
+    std::fs::write(dir.join(".gitignore"), gitignore())?;
+    match (&settings.remote, repo.find_remote("origin")) {
+        (Some(url), Ok(remote)) if remote.url().ok() != Some(url.as_str()) => {
+            repo.remote_set_url("origin", url)?
+        }
+        (Some(url), Err(_)) => {
+            repo.remote("origin", url)?;
+        }
+        (None, Ok(_)) => repo.remote_delete("origin")?,
+        _ => {}
+    }
+    Ok(repo)
+}
+
+pub fn status(dir: &Path) -> ConfigSyncStatus {
+    let repo = Repository::open(dir).ok();
+    let head = repo
+        .as_ref()
+        .and_then(|repo| repo.head().ok())
+        .and_then(|head| head.peel_to_commit().ok())
+        .map(|commit| {
+            format!(
+                "{} {}",
+                &commit.id().to_string()[..7],
+                commit.summary().ok().flatten().unwrap_or_default()
+            )
+        });
+    ConfigSyncStatus {
+        settings: load_settings(dir),
+        initialized: repo.is_some(),
+        head,
+        synced_files: SYNCED_FILES.iter().map(|file| file.to_string()).collect(),
+    }
+}
+
+/// Commit a change written through [`config_versions`], and push it in the background
+pub fn record_change(dir: &Path, file: &str) {
+    let settings = load_settings(dir);
+    if !settings.enabled || !SYNCED_FILES.contains(&file) {
+        return;
+    }
+    let committed = Repository::open(dir)
+        .map_err(anyhow::Error::from)
+        .and_then(|repo| commit(&repo, &format!("Update {}", file)));
+    match committed {
+        Ok(Some(_)) if settings.auto_push && settings.remote.is_some() => {
+            let dir = dir.to_path_buf();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = push(&dir, &settings).await {
+                    eprintln!("❌ Failed to push config change: {}", e);
+                }
+            });
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("❌ Failed to commit config change to {}: {}", file, e),
+    }
+}
+
+/// Run the git CLI in `dir`; it handles credentials the way the user configured them
+async fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+async fn push(dir: &Path, settings: &ConfigSyncSettings) -> Result<()> {
+    let refspec = format!("HEAD:refs/heads/{}", settings.branch);
+    git(dir, &["push", "origin", &refspec]).await?;
+    Ok(())
+}
+
+/// Merge the remote branch, preferring the remote side of conflicting edits.
+/// Returns the config files the merge changed.
+async fn pull(dir: &Path, settings: &ConfigSyncSettings) -> Result<Vec<String>> {
+    let branch = settings.branch.as_str();
+    // 원격 브랜치가 아직 없으면 (처음 동기화하는 기기) 가져올 것이 없습니다
+    if git(
+        dir,
+        &["ls-remote", "--exit-code", "--heads", "origin", branch],
+    )
+    .await
+    .is_err()
+    {
+        return Ok(Vec::new());
+    }
+    let before = git(dir, &["rev-parse", "HEAD"]).await?;
+    let (name, email) = {
+        let signature = signature(&Repository::open(dir)?)?;
+        (
+            format!("user.name={}", signature.name().unwrap_or_default()),
+            format!("user.email={}", signature.email().unwrap_or_default()),
+        )
+    };
+    git(
+        dir,
+        &[
+            "-c",
+            &name,
+            "-c",
+            &email,
+            "pull",
+            "--no-rebase",
+            "--no-edit",
+            "--allow-unrelated-histories",
+            "-X",
+            "theirs",
+            "origin",
+            branch,
+        ],
+    )
+    .await?;
+    let changed = git(dir, &["diff", "--name-only", before.trim(), "HEAD"]).await?;
+    Ok(changed
+        .lines()
+        .filter(|file| SYNCED_FILES.contains(file))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Save the settings and, when enabled, set up the repository and sync once
+pub async fn configure(dir: &Path, settings: ConfigSyncSettings) -> Result<SyncReport> {
+    config_versions::write(
+        &dir.join(SETTINGS_FILE),
+        serde_json::to_string_pretty(&settings)?,
+    )
+    .await?;
+    if !settings.enabled {
+        return Ok(SyncReport::default());
+    }
+    let dir_owned = dir.to_path_buf();
+    let init_settings = settings.clone();
+    tokio::task::spawn_blocking(move || open_or_init(&dir_owned, &init_settings)).await??;
+    sync(dir).await
+}
+
+/// Commit local changes, pull the remote and push the result
+pub async fn sync(dir: &Path) -> Result<SyncReport> {
+    let settings = load_settings(dir);
+    if !settings.enabled {
+        return Err(anyhow::anyhow!("Config sync is not enabled"));
+    }
+    let dir_owned = dir.to_path_buf();
+    let committed = tokio::task::spawn_blocking(move || {
+        let repo = Repository::open(&dir_owned)?;
+        commit(&repo, "Sync config")
+    })
+    .await??;
+
+    let mut report = SyncReport {
+        committed,
+        ..Default::default()
+    };
+    if settings.remote.is_some() {
+        report.pulled = pull(dir, &settings).await?;
+        push(dir, &settings).await?;
+        report.pushed = true;
+    }
+    Ok(report)
+}
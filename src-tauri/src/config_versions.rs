@@ -0,0 +1,135 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::config_sync;
+use crate::get_app_data_dir;
+
+// 파일마다 보관하는 백업 개수
+const MAX_VERSIONS_PER_FILE: usize = 50;
+
+// 같은 밀리초에 두 번 저장해도 ID가 겹치지 않도록 직렬화합니다
+static LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigVersion {
+    /// `<unix millis>-<file name>`
+    pub id: String,
+    pub file: String,
+    pub created_at: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RollbackReport {
+    pub file: String,
+    /// Backup of the content that was replaced, so the rollback can itself be undone
+    pub backup_id: Option<String>,
+    /// False when the restored setting only takes effect after a restart
+    pub reloaded: bool,
+}
+
+fn versions_dir() -> PathBuf {
+    get_app_data_dir().join("config_versions")
+}
+
+fn parse_id(id: &str) -> Option<(u64, &str)> {
+    let (millis, file) = id.split_once('-')?;
+    Some((millis.parse().ok()?, file))
+}
+
+fn version_of(path: &Path) -> Option<ConfigVersion> {
+    let id = path.file_name()?.to_str()?.to_string();
+    let (millis, file) = parse_id(&id)?;
+    let created_at = chrono::DateTime::from_timestamp_millis(millis as i64)?
+        .with_timezone(&chrono::Local)
+        .to_rfc3339();
+    Some(ConfigVersion {
+        file: file.to_string(),
+        created_at,
+        size: path.metadata().ok()?.len(),
+        id,
+    })
+}
+
+/// Every backup, newest first, optionally only those of one file
+pub fn list(file: Option<&str>) -> Result<Vec<ConfigVersion>> {
+    let entries = match std::fs::read_dir(versions_dir()) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut versions: Vec<ConfigVersion> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| version_of(&entry.path()))
+        .filter(|version| file.is_none_or(|file| version.file == file))
+        .collect();
+    versions.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(versions)
+}
+
+/// Copy the current content of `path` into the versions folder
+fn backup(path: &Path, file: &str, current: &[u8]) -> Result<String> {
+    let dir = versions_dir();
+    std::fs::create_dir_all(&dir)?;
+    let mut millis = chrono::Utc::now().timestamp_millis();
+    let mut id = format!("{:013}-{}", millis, file);
+    while dir.join(&id).exists() {
+        millis += 1;
+        id = format!("{:013}-{}", millis, file);
+    }
+    std::fs::write(dir.join(&id), current)?;
+
+    for stale in list(Some(file))?.into_iter().skip(MAX_VERSIONS_PER_FILE) {
+        let _ = std::fs::remove_file(dir.join(&stale.id));
+    }
+    println!("Backed up {} as version {}", path.display(), id);
+    Ok(id)
+}
+
+/// Write a config file, first backing up the content it replaces.
+/// Returns the backup's ID, or `None` when there was nothing to back up.
+pub fn write_blocking(path: &Path, content: &str) -> Result<Option<String>> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow::anyhow!("Invalid config path {}", path.display()))?;
+    let backup_id = match std::fs::read(path) {
+        Ok(current) if current != content.as_bytes() => Some(backup(path, file, &current)?),
+        Ok(_) => None,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+    std::fs::write(path, content)?;
+    if let Some(dir) = path.parent().filter(|dir| *dir == get_app_data_dir()) {
+        config_sync::record_change(dir, file);
+    }
+    Ok(backup_id)
+}
+
+/// Async form of [`write_blocking`]
+pub async fn write(path: &Path, content: String) -> Result<Option<String>> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || write_blocking(&path, &content)).await?
+}
+
+/// Put a backed-up version back in place; the content it replaces is backed up first
+pub async fn rollback(id: &str) -> Result<RollbackReport> {
+    let (_, file) = parse_id(id)
+        .filter(|(_, file)| !file.contains(['/', '\\']) && !file.starts_with('.'))
+        .ok_or_else(|| anyhow::anyhow!("Invalid config version '{}'", id))?;
+    let content = tokio::fs::read_to_string(versions_dir().join(id))
+        .await
+        .map_err(|e| anyhow::anyhow!("Config version '{}' not found: {}", id, e))?;
+    let backup_id = write(&get_app_data_dir().join(file), content).await?;
+    Ok(RollbackReport {
+        file: file.to_string(),
+        backup_id,
+        reloaded: false,
+    })
+}
@@ -0,0 +1,280 @@
+use anyhow::Result;
+use rmcp::model::PaginatedRequestParam;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashSet;
+use std::time::Instant;
+
+use crate::mcp::{MCPServerInfo, MCPServerManager, MCPTool, ToolCallContext};
+
+// 잘못된 커서를 반복하는 서버에서 멈추지 않도록 제한합니다
+const MAX_PAGES: usize = 100;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConformanceCheck {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolCallCheck {
+    pub tool_name: String,
+    pub arguments: serde_json::Value,
+    pub success: bool,
+    pub latency_ms: f64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LatencyStats {
+    pub samples: usize,
+    pub min_ms: f64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencyStats {
+    pub fn from_samples(samples: &[f64]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        Self {
+            samples: sorted.len(),
+            min_ms: sorted[0],
+            mean_ms: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            p50_ms: percentile(&sorted, 50.0),
+            p95_ms: percentile(&sorted, 95.0),
+            p99_ms: percentile(&sorted, 99.0),
+            max_ms: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+/// Nearest-rank percentile of an ascending, non-empty slice
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Self-contained report meant to be attached to bug reports against server authors
+#[derive(Debug, Clone, Serialize)]
+pub struct ConformanceReport {
+    pub server_name: String,
+    pub checked_at: String,
+    pub server_info: Option<MCPServerInfo>,
+    pub passed: bool,
+    pub checks: Vec<ConformanceCheck>,
+    pub tool_calls: Vec<ToolCallCheck>,
+    pub list_latency: LatencyStats,
+    pub call_latency: LatencyStats,
+}
+
+fn check(checks: &mut Vec<ConformanceCheck>, name: &str, passed: bool, detail: impl Into<String>) {
+    checks.push(ConformanceCheck {
+        name: name.to_string(),
+        passed,
+        detail: detail.into(),
+    });
+}
+
+/// Placeholder value for a JSON schema property
+fn sample_value(schema: &serde_json::Value) -> serde_json::Value {
+    if let Some(value) = schema.get("default") {
+        return value.clone();
+    }
+    if let Some(first) = schema.get("enum").and_then(|e| e.get(0)) {
+        return first.clone();
+    }
+    match schema.get("type").and_then(|t| t.as_str()) {
+        Some("string") => json!("test"),
+        Some("integer") | Some("number") => json!(0),
+        Some("boolean") => json!(false),
+        Some("array") => json!([]),
+        Some("object") => json!({}),
+        _ => serde_json::Value::Null,
+    }
+}
+
+/// Arguments that fill only the required properties
+pub fn sample_arguments(tool: &MCPTool) -> serde_json::Value {
+    let mut arguments = serde_json::Map::new();
+    for name in tool.input_schema.required.iter().flatten() {
+        let schema = tool
+            .input_schema
+            .properties
+            .get(name)
+            .cloned()
+            .unwrap_or_default();
+        arguments.insert(name.clone(), sample_value(&schema));
+    }
+    serde_json::Value::Object(arguments)
+}
+
+/// 연결된 서버에 대해 프로토콜 적합성 검사를 실행합니다
+///
+/// Only tools annotated `readOnlyHint: true` are called, since the sample
+/// arguments are placeholders and other tools may have side effects.
+pub async fn run(manager: &MCPServerManager, server_name: &str) -> Result<ConformanceReport> {
+    let mut checks = Vec::new();
+
+    // Initialize handshake
+    let server_info = manager.get_server_info(server_name).await;
+    match &server_info {
+        Some(info) => {
+            check(
+                &mut checks,
+                "initialize",
+                !info.implementation_name.is_empty(),
+                format!(
+                    "{} {} (protocol {})",
+                    info.implementation_name, info.implementation_version, info.protocol_version
+                ),
+            );
+            check(
+                &mut checks,
+                "protocol-version",
+                info.protocol_supported,
+                format!("negotiated '{}'", info.protocol_version),
+            );
+        }
+        None => return Err(anyhow::anyhow!("Server '{}' is not connected", server_name)),
+    }
+
+    // tools/list pagination
+    let peer = manager.peer(server_name).await?;
+    let mut tools = Vec::new();
+    let mut list_samples = Vec::new();
+    let mut cursor: Option<String> = None;
+    let mut seen_cursors = HashSet::new();
+    let mut pages = 0;
+    let mut pagination_error = None;
+    loop {
+        let started = Instant::now();
+        let result = peer
+            .list_tools(Some(PaginatedRequestParam {
+                cursor: cursor.clone(),
+            }))
+            .await;
+        list_samples.push(started.elapsed().as_secs_f64() * 1000.0);
+        pages += 1;
+
+        match result {
+            Ok(result) => {
+                tools.extend(result.tools.into_iter().map(MCPServerManager::convert_tool));
+                cursor = result.next_cursor;
+            }
+            Err(e) => {
+                pagination_error = Some(format!("tools/list failed on page {}: {}", pages, e));
+                break;
+            }
+        }
+        match &cursor {
+            None => break,
+            Some(next) if !seen_cursors.insert(next.clone()) => {
+                pagination_error = Some(format!("cursor '{}' was returned twice", next));
+                break;
+            }
+            Some(_) if pages >= MAX_PAGES => {
+                pagination_error = Some(format!("more than {} pages", MAX_PAGES));
+                break;
+            }
+            Some(_) => {}
+        }
+    }
+    check(
+        &mut checks,
+        "tools-list-pagination",
+        pagination_error.is_none(),
+        pagination_error.unwrap_or_else(|| format!("{} tools in {} page(s)", tools.len(), pages)),
+    );
+
+    let mut names = HashSet::new();
+    let duplicates: Vec<&str> = tools
+        .iter()
+        .filter(|tool| !names.insert(tool.name.as_str()))
+        .map(|tool| tool.name.as_str())
+        .collect();
+    check(
+        &mut checks,
+        "unique-tool-names",
+        duplicates.is_empty(),
+        if duplicates.is_empty() {
+            "all tool names are unique".to_string()
+        } else {
+            format!("duplicate names: {}", duplicates.join(", "))
+        },
+    );
+
+    let declares_tools = server_info
+        .as_ref()
+        .is_some_and(|info| info.capabilities.tools);
+    check(
+        &mut checks,
+        "tools-capability",
+        declares_tools || tools.is_empty(),
+        if declares_tools {
+            "tools capability declared"
+        } else {
+            "tools capability not declared"
+        },
+    );
+
+    // Schema validity
+    for tool in &tools {
+        let result = MCPServerManager::validate_tool_schema(tool);
+        check(
+            &mut checks,
+            &format!("schema:{}", tool.name),
+            result.is_ok(),
+            result
+                .err()
+                .map(|e| e.to_string())
+                .unwrap_or_else(|| "valid".to_string()),
+        );
+    }
+
+    // Read-only tool calls with sample arguments
+    let mut tool_calls = Vec::new();
+    for tool in tools.iter().filter(|tool| {
+        tool.annotations
+            .as_ref()
+            .and_then(|a| a.read_only_hint)
+            .unwrap_or(false)
+    }) {
+        let arguments = sample_arguments(tool);
+        let started = Instant::now();
+        let result = manager
+            .call_tool(
+                server_name,
+                &tool.name,
+                arguments.clone(),
+                &ToolCallContext::default(),
+            )
+            .await;
+        tool_calls.push(ToolCallCheck {
+            tool_name: tool.name.clone(),
+            arguments,
+            success: result.success,
+            latency_ms: started.elapsed().as_secs_f64() * 1000.0,
+            error: result.error,
+        });
+    }
+    let call_samples: Vec<f64> = tool_calls.iter().map(|c| c.latency_ms).collect();
+
+    Ok(ConformanceReport {
+        server_name: server_name.to_string(),
+        checked_at: chrono::Local::now().to_rfc3339(),
+        server_info,
+        passed: checks.iter().all(|c| c.passed),
+        checks,
+        tool_calls,
+        list_latency: LatencyStats::from_samples(&list_samples),
+        call_latency: LatencyStats::from_samples(&call_samples),
+    })
+}
@@ -0,0 +1,162 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::config_versions;
+use crate::events;
+use crate::redact;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub id: String,
+    pub created_at: String,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub thread: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+    #[serde(default)]
+    pub submitted: bool,
+}
+
+/// Where opt-in crash reports are sent; nothing is uploaded until this is set
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CrashSettings {
+    pub submit_url: Option<String>,
+}
+
+fn reports_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("crash_reports")
+}
+
+fn settings_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("crash_settings.json")
+}
+
+/// 패닉 발생 시 백트레이스를 포함한 크래시 리포트를 남깁니다
+///
+/// The previous hook still runs afterwards, so the panic is printed as before.
+pub fn install_panic_hook(data_dir: impl Fn() -> PathBuf + Send + Sync + 'static) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic payload".to_string());
+        let report = CrashReport {
+            id: format!(
+                "{}-{}",
+                chrono::Local::now().format("%Y%m%d-%H%M%S"),
+                &uuid::Uuid::new_v4().simple().to_string()[..8]
+            ),
+            created_at: chrono::Local::now().to_rfc3339(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            thread: std::thread::current()
+                .name()
+                .unwrap_or("<unnamed>")
+                .to_string(),
+            // 리포트가 외부로 전송될 수 있으므로 비밀 값을 마스킹합니다
+            message: redact::redact(&message),
+            location: info
+                .location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+            backtrace: redact::redact(&std::backtrace::Backtrace::force_capture().to_string()),
+            submitted: false,
+        };
+        events::record(
+            "crash",
+            None,
+            serde_json::json!({
+                "id": report.id,
+                "message": report.message,
+                "location": report.location,
+            }),
+        );
+        if let Err(e) = write_report(&data_dir(), &report) {
+            eprintln!("❌ Failed to write crash report: {}", e);
+        }
+        previous(info);
+    }));
+}
+
+fn write_report(data_dir: &Path, report: &CrashReport) -> Result<()> {
+    let dir = reports_dir(data_dir);
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(
+        dir.join(format!("{}.json", report.id)),
+        serde_json::to_string_pretty(report)?,
+    )?;
+    Ok(())
+}
+
+/// Newest first
+pub fn list(data_dir: &Path) -> Result<Vec<CrashReport>> {
+    let entries = match std::fs::read_dir(reports_dir(data_dir)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut reports: Vec<CrashReport> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+    reports.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(reports)
+}
+
+pub fn load_settings(data_dir: &Path) -> CrashSettings {
+    std::fs::read_to_string(settings_path(data_dir))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(data_dir: &Path, settings: &CrashSettings) -> Result<()> {
+    config_versions::write_blocking(
+        &settings_path(data_dir),
+        &serde_json::to_string_pretty(settings)?,
+    )?;
+    Ok(())
+}
+
+/// Upload one report to the configured endpoint and mark it as submitted
+pub async fn submit(data_dir: &Path, id: &str) -> Result<()> {
+    let url = load_settings(data_dir)
+        .submit_url
+        .filter(|url| !url.trim().is_empty())
+        .ok_or_else(|| anyhow::anyhow!("No crash report endpoint is configured"))?;
+    // id는 파일 이름으로 쓰이므로 경로 조작을 막습니다
+    if id.contains(['/', '\\']) || id.contains("..") {
+        return Err(anyhow::anyhow!("Invalid crash report id '{}'", id));
+    }
+    let path = reports_dir(data_dir).join(format!("{}.json", id));
+    let mut report: CrashReport = serde_json::from_str(
+        &tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|_| anyhow::anyhow!("Crash report '{}' not found", id))?,
+    )?;
+
+    let response = reqwest::Client::new()
+        .post(&url)
+        .json(&report)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!(
+            "Crash report endpoint returned {}",
+            response.status()
+        ));
+    }
+
+    report.submitted = true;
+    tokio::fs::write(&path, serde_json::to_string_pretty(&report)?).await?;
+    Ok(())
+}
@@ -0,0 +1,1195 @@
+use anyhow::Result;
+use base64::Engine;
+use reqwest::{Method, StatusCode, Url};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::future::Future;
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+
+use crate::api::ask_webview;
+use crate::config_sync::SYNCED_FILES;
+use crate::config_versions;
+use crate::events;
+use crate::power;
+
+const SETTINGS_FILE: &str = "device_sync.json";
+const STATE_FILE: &str = "device_sync_state.json";
+const KEY_FILE: &str = "device_sync.key";
+
+// 원격 저장소에 놓이는 객체 이름
+const KEY_CHECK_OBJECT: &str = "keycheck.json";
+const INDEX_OBJECT: &str = "index.bin";
+
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+const KDF_ITERATIONS: u32 = 600_000;
+const KEY_CHECK: &[u8] = b"tauri-agent device sync";
+const ENVELOPE_MAGIC: &[u8] = b"TAS1";
+
+const REQUEST_TIMEOUT_SECS: u64 = 60;
+const MIN_INTERVAL_SECS: u64 = 60;
+// 백그라운드 루프가 예약된 동기화를 확인하는 주기
+const CHECK_INTERVAL_SECS: u64 = 60;
+
+const CONFIG_PREFIX: &str = "config/";
+const SESSION_PREFIX: &str = "session/";
+
+/// Storage the encrypted records are kept in. It only ever sees ciphertext and hashed names.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SyncBackend {
+    /// S3-compatible bucket (AWS, MinIO, R2, ...), addressed path-style
+    S3 {
+        endpoint: String,
+        bucket: String,
+        region: String,
+        access_key_id: String,
+        secret_access_key: String,
+        /// Folder inside the bucket
+        #[serde(default)]
+        prefix: String,
+    },
+    /// Folder on a WebDAV server such as Nextcloud
+    Webdav {
+        url: String,
+        username: String,
+        password: String,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeviceSyncSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub backend: Option<SyncBackend>,
+    /// Sync in the background this often; only on demand when unset
+    #[serde(default)]
+    pub interval_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceSyncStatus {
+    pub settings: DeviceSyncSettings,
+    /// A passphrase has been set on this device
+    pub has_key: bool,
+    pub device_id: Option<String>,
+    pub last_sync: Option<String>,
+    /// Configs and sessions tracked by sync
+    pub records: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConflictResolution {
+    /// Sessions edited on both sides keep the messages of both
+    Merged,
+    KeptLocal,
+    TookRemote,
+}
+
+/// A record changed on two devices without either seeing the other's change
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncConflict {
+    pub record: String,
+    pub resolution: ConflictResolution,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DeviceSyncReport {
+    pub uploaded: usize,
+    pub downloaded: usize,
+    pub conflicts: Vec<SyncConflict>,
+    /// Config files changed by the sync, so the caller can reload them
+    pub configs: Vec<String>,
+    /// Sessions were included; they need the app window
+    pub sessions: bool,
+}
+
+/// Per-device counters; one clock dominates another only if it saw every change the
+/// other did
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct VectorClock(BTreeMap<String, u64>);
+
+impl VectorClock {
+    fn tick(&mut self, device: &str) {
+        *self.0.entry(device.to_string()).or_insert(0) += 1;
+    }
+
+    fn merge(&mut self, other: &Self) {
+        for (device, counter) in &other.0 {
+            let entry = self.0.entry(device.clone()).or_insert(0);
+            *entry = (*entry).max(*counter);
+        }
+    }
+
+    /// `None` when the clocks are concurrent
+    fn compare(&self, other: &Self) -> Option<Ordering> {
+        let (mut behind, mut ahead) = (false, false);
+        for device in self.0.keys().chain(other.0.keys()) {
+            let ours = self.0.get(device).copied().unwrap_or(0);
+            let theirs = other.0.get(device).copied().unwrap_or(0);
+            behind |= ours < theirs;
+            ahead |= ours > theirs;
+        }
+        match (behind, ahead) {
+            (false, false) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (true, true) => None,
+        }
+    }
+}
+
+/// Version of one record, kept locally and in the remote index
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct RecordState {
+    clock: VectorClock,
+    /// Hash of the content this version holds; empty once deleted
+    hash: String,
+    #[serde(default)]
+    deleted: bool,
+    /// Wall-clock time of the change, used to pick a side in config conflicts
+    modified_at: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncState {
+    device_id: String,
+    last_sync: Option<String>,
+    records: BTreeMap<String, RecordState>,
+}
+
+/// Encrypted content of `records/<hashed name>.bin`
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordPayload {
+    record: String,
+    #[serde(flatten)]
+    state: RecordState,
+    device: String,
+    content: Value,
+}
+
+/// Stored in the clear so another device can derive the same key from the passphrase
+#[derive(Debug, Serialize, Deserialize)]
+struct KeyCheck {
+    salt: String,
+    iterations: u32,
+    /// `KEY_CHECK` sealed with the derived key
+    check: String,
+}
+
+/// Key derived on this device, with the parameters needed to describe it to a new backend
+#[derive(Debug, Serialize, Deserialize)]
+struct KeyFile {
+    salt: String,
+    iterations: u32,
+    key: String,
+}
+
+/// Sessions and messages handed to the webview to store
+#[derive(Debug, Default, Serialize)]
+struct SessionImport {
+    sessions: Vec<Value>,
+    messages: Vec<Value>,
+    deleted: Vec<String>,
+}
+
+impl SessionImport {
+    fn is_empty(&self) -> bool {
+        self.sessions.is_empty() && self.deleted.is_empty()
+    }
+}
+
+fn base64() -> base64::engine::GeneralPurpose {
+    base64::engine::general_purpose::STANDARD
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), data)
+        .as_ref()
+        .to_vec()
+}
+
+/// serde_json keeps insertion order here, so objects are sorted before hashing
+fn canonical(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.clone(), canonical(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonical).collect()),
+        other => other.clone(),
+    }
+}
+
+fn content_hash(content: &Value) -> String {
+    let bytes = serde_json::to_vec(&canonical(content)).unwrap_or_default();
+    format!("{:x}", Sha256::digest(&bytes))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<Vec<u8>> {
+    let iterations = NonZeroU32::new(iterations)
+        .ok_or_else(|| anyhow::anyhow!("Invalid key derivation parameters"))?;
+    let mut key = vec![0u8; KEY_LEN * 2];
+    ring::pbkdf2::derive(
+        ring::pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    Ok(key)
+}
+
+/// Keys derived from the passphrase: the first half encrypts, the second hashes record names
+struct Cipher {
+    aead: LessSafeKey,
+    names: hmac::Key,
+    rng: SystemRandom,
+}
+
+impl Cipher {
+    fn new(key: &[u8]) -> Result<Self> {
+        if key.len() != KEY_LEN * 2 {
+            return Err(anyhow::anyhow!("Invalid sync key"));
+        }
+        let aead = UnboundKey::new(&CHACHA20_POLY1305, &key[..KEY_LEN])
+            .map_err(|_| anyhow::anyhow!("Invalid sync key"))?;
+        Ok(Self {
+            aead: LessSafeKey::new(aead),
+            names: hmac::Key::new(hmac::HMAC_SHA256, &key[KEY_LEN..]),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    /// `magic || nonce || ciphertext || tag`; the object name is authenticated too, so an
+    /// envelope cannot be swapped for another record's
+    fn seal(&self, name: &str, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| anyhow::anyhow!("No randomness available"))?;
+        let mut in_out = plaintext.to_vec();
+        self.aead
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(name.as_bytes()),
+                &mut in_out,
+            )
+            .map_err(|_| anyhow::anyhow!("Failed to encrypt {}", name))?;
+        let mut envelope = ENVELOPE_MAGIC.to_vec();
+        envelope.extend_from_slice(&nonce);
+        envelope.extend(in_out);
+        Ok(envelope)
+    }
+
+    fn open(&self, name: &str, envelope: &[u8]) -> Result<Vec<u8>> {
+        let body = envelope
+            .strip_prefix(ENVELOPE_MAGIC)
+            .filter(|body| body.len() >= NONCE_LEN)
+            .ok_or_else(|| anyhow::anyhow!("{} is not an encrypted sync object", name))?;
+        let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| anyhow::anyhow!("{} has an invalid nonce", name))?;
+        let mut in_out = ciphertext.to_vec();
+        let plaintext = self
+            .aead
+            .open_in_place(nonce, Aad::from(name.as_bytes()), &mut in_out)
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Cannot decrypt {}: wrong passphrase or corrupted data",
+                    name
+                )
+            })?;
+        Ok(plaintext.to_vec())
+    }
+
+    /// Record keys carry file names and session IDs, so the backend only sees a keyed hash
+    fn object_name(&self, record: &str) -> String {
+        format!(
+            "records/{}.bin",
+            hex(hmac::sign(&self.names, record.as_bytes()).as_ref())
+        )
+    }
+}
+
+/// AWS Signature Version 4 headers for one request
+fn sign_s3(
+    method: &Method,
+    url: &Url,
+    body: &[u8],
+    region: &str,
+    access_key_id: &str,
+    secret_access_key: &str,
+) -> Result<Vec<(&'static str, String)>> {
+    let host = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        (None, _) => return Err(anyhow::anyhow!("Invalid S3 endpoint {}", url)),
+    };
+    let now = chrono::Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let payload_hash = format!("{:x}", Sha256::digest(body));
+
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        method.as_str(),
+        url.path(),
+        host,
+        payload_hash,
+        amz_date,
+        signed_headers,
+        payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{:x}",
+        amz_date,
+        scope,
+        Sha256::digest(canonical_request.as_bytes())
+    );
+    let mut key = hmac_sha256(
+        format!("AWS4{}", secret_access_key).as_bytes(),
+        date.as_bytes(),
+    );
+    for part in [region, "s3", "aws4_request"] {
+        key = hmac_sha256(&key, part.as_bytes());
+    }
+    let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+
+    Ok(vec![
+        ("x-amz-date", amz_date),
+        ("x-amz-content-sha256", payload_hash),
+        (
+            "authorization",
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                access_key_id, scope, signed_headers, signature
+            ),
+        ),
+    ])
+}
+
+/// Plain object reads and writes on the configured backend
+struct Remote {
+    client: reqwest::Client,
+    backend: SyncBackend,
+}
+
+impl Remote {
+    fn new(backend: SyncBackend) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()?;
+        let remote = Self { client, backend };
+        remote.url(INDEX_OBJECT)?;
+        Ok(remote)
+    }
+
+    fn url(&self, name: &str) -> Result<Url> {
+        match &self.backend {
+            SyncBackend::S3 {
+                endpoint,
+                bucket,
+                prefix,
+                ..
+            } => {
+                let mut url = Url::parse(endpoint)?;
+                url.path_segments_mut()
+                    .map_err(|_| anyhow::anyhow!("Invalid S3 endpoint {}", endpoint))?
+                    .pop_if_empty()
+                    .push(bucket)
+                    .extend(prefix.split('/').filter(|part| !part.is_empty()))
+                    .extend(name.split('/').filter(|part| !part.is_empty()));
+                Ok(url)
+            }
+            SyncBackend::Webdav { url, .. } => {
+                let base = if url.ends_with('/') {
+                    Url::parse(url)?
+                } else {
+                    Url::parse(&format!("{}/", url))?
+                };
+                Ok(base.join(name)?)
+            }
+        }
+    }
+
+    fn request(
+        &self,
+        method: Method,
+        name: &str,
+        body: Vec<u8>,
+    ) -> Result<reqwest::RequestBuilder> {
+        let url = self.url(name)?;
+        let mut request = self.client.request(method.clone(), url.clone());
+        match &self.backend {
+            SyncBackend::S3 {
+                region,
+                access_key_id,
+                secret_access_key,
+                ..
+            } => {
+                for (name, value) in sign_s3(
+                    &method,
+                    &url,
+                    &body,
+                    region,
+                    access_key_id,
+                    secret_access_key,
+                )? {
+                    request = request.header(name, value);
+                }
+            }
+            SyncBackend::Webdav {
+                username, password, ..
+            } => request = request.basic_auth(username, Some(password)),
+        }
+        Ok(request.body(body))
+    }
+
+    /// WebDAV folders have to exist before files are written into them
+    async fn prepare(&self) -> Result<()> {
+        if let SyncBackend::Webdav { .. } = &self.backend {
+            let mkcol = Method::from_bytes(b"MKCOL")?;
+            for folder in ["", "records/"] {
+                let status = self
+                    .request(mkcol.clone(), folder, Vec::new())?
+                    .send()
+                    .await?
+                    .status();
+                // 이미 있는 폴더에는 405가 돌아옵니다
+                if !status.is_success() && status != StatusCode::METHOD_NOT_ALLOWED {
+                    return Err(anyhow::anyhow!(
+                        "Cannot create WebDAV folder '{}': HTTP {}",
+                        folder,
+                        status
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn get(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        let response = self.request(Method::GET, name, Vec::new())?.send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = response.error_for_status()?;
+        Ok(Some(response.bytes().await?.to_vec()))
+    }
+
+    async fn put(&self, name: &str, body: Vec<u8>) -> Result<()> {
+        self.request(Method::PUT, name, body)?
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// One entry per session with its messages sorted by ID, so the content hashes the same on
+/// every device
+fn session_records(export: &Value) -> BTreeMap<String, Value> {
+    let mut messages: HashMap<&str, Vec<&Value>> = HashMap::new();
+    for message in export["messages"].as_array().into_iter().flatten() {
+        if let Some(session_id) = message["sessionId"].as_str() {
+            messages.entry(session_id).or_default().push(message);
+        }
+    }
+    let mut records = BTreeMap::new();
+    for session in export["sessions"].as_array().into_iter().flatten() {
+        let Some(id) = session["id"].as_str() else {
+            continue;
+        };
+        let mut session_messages: Vec<Value> = messages
+            .remove(id)
+            .unwrap_or_default()
+            .into_iter()
+            .cloned()
+            .collect();
+        session_messages.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+        records.insert(
+            format!("{}{}", SESSION_PREFIX, id),
+            json!({ "session": session, "messages": session_messages }),
+        );
+    }
+    records
+}
+
+/// Union of both sides' messages; the session row comes from the side updated last
+fn merge_sessions(ours: &Value, theirs: &Value) -> Value {
+    let updated_at = |record: &Value| {
+        record["session"]["updatedAt"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string()
+    };
+    let session = if updated_at(theirs) > updated_at(ours) {
+        &theirs["session"]
+    } else {
+        &ours["session"]
+    };
+    let mut messages = BTreeMap::new();
+    // 같은 메시지가 양쪽에 있으면 로컬 쪽을 씁니다
+    for message in theirs["messages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .chain(ours["messages"].as_array().into_iter().flatten())
+    {
+        if let Some(id) = message["id"].as_str() {
+            messages.insert(id.to_string(), message.clone());
+        }
+    }
+    json!({ "session": session, "messages": messages.into_values().collect::<Vec<_>>() })
+}
+
+/// Client-side encrypted sync of configs and sessions through a user-provided bucket or
+/// WebDAV folder. Concurrent edits are detected with vector clocks.
+pub struct DeviceSync {
+    app_data_dir: PathBuf,
+    settings: RwLock<Option<DeviceSyncSettings>>,
+    // 예약 동기화와 수동 동기화가 겹치지 않도록 합니다
+    running: Mutex<()>,
+}
+
+impl DeviceSync {
+    pub fn new(app_data_dir: PathBuf) -> Self {
+        Self {
+            app_data_dir,
+            settings: RwLock::new(None),
+            running: Mutex::new(()),
+        }
+    }
+
+    /// Forget the cached copy so the next read picks up the file on disk
+    pub async fn reload(&self) {
+        *self.settings.write().await = None;
+    }
+
+    pub async fn settings(&self) -> DeviceSyncSettings {
+        if let Some(settings) = self.settings.read().await.as_ref() {
+            return settings.clone();
+        }
+        let settings: DeviceSyncSettings =
+            tokio::fs::read_to_string(self.app_data_dir.join(SETTINGS_FILE))
+                .await
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+                .unwrap_or_default();
+        *self.settings.write().await = Some(settings.clone());
+        settings
+    }
+
+    /// Save the settings and, with a passphrase, derive this device's key. On a backend
+    /// another device already set up, the passphrase must match the one used there.
+    pub async fn save_settings(
+        &self,
+        settings: DeviceSyncSettings,
+        passphrase: Option<String>,
+    ) -> Result<()> {
+        if settings
+            .interval_secs
+            .is_some_and(|secs| secs < MIN_INTERVAL_SECS)
+        {
+            return Err(anyhow::anyhow!(
+                "interval_secs must be at least {}",
+                MIN_INTERVAL_SECS
+            ));
+        }
+        if settings.enabled && settings.backend.is_none() {
+            return Err(anyhow::anyhow!("Choose a sync backend first"));
+        }
+        if let Some(passphrase) = passphrase {
+            let backend = settings
+                .backend
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("Choose a sync backend first"))?;
+            self.set_passphrase(Remote::new(backend)?, passphrase)
+                .await?;
+        }
+        config_versions::write(
+            &self.app_data_dir.join(SETTINGS_FILE),
+            serde_json::to_string_pretty(&settings)?,
+        )
+        .await?;
+        *self.settings.write().await = Some(settings);
+        Ok(())
+    }
+
+    async fn set_passphrase(&self, remote: Remote, passphrase: String) -> Result<()> {
+        if passphrase.is_empty() {
+            return Err(anyhow::anyhow!("The passphrase cannot be empty"));
+        }
+        remote.prepare().await?;
+        let existing: Option<KeyCheck> = match remote.get(KEY_CHECK_OBJECT).await? {
+            Some(bytes) => Some(serde_json::from_slice(&bytes)?),
+            None => None,
+        };
+        let (salt, iterations) = match &existing {
+            Some(check) => (base64().decode(&check.salt)?, check.iterations),
+            None => {
+                let mut salt = vec![0u8; SALT_LEN];
+                SystemRandom::new()
+                    .fill(&mut salt)
+                    .map_err(|_| anyhow::anyhow!("No randomness available"))?;
+                (salt, KDF_ITERATIONS)
+            }
+        };
+        // 키 유도는 의도적으로 느리므로 블로킹 스레드에서 합니다
+        let derive_salt = salt.clone();
+        let key =
+            tokio::task::spawn_blocking(move || derive_key(&passphrase, &derive_salt, iterations))
+                .await??;
+        let key_file = KeyFile {
+            salt: base64().encode(&salt),
+            iterations,
+            key: base64().encode(&key),
+        };
+        let cipher = Cipher::new(&key)?;
+        match existing {
+            Some(check) => {
+                let sealed = base64().decode(&check.check)?;
+                if cipher.open(KEY_CHECK_OBJECT, &sealed).ok().as_deref() != Some(KEY_CHECK) {
+                    return Err(anyhow::anyhow!(
+                        "The passphrase does not match the one this sync folder was set up with"
+                    ));
+                }
+            }
+            None => upload_key_check(&remote, &cipher, &key_file).await?,
+        }
+
+        let path = self.app_data_dir.join(KEY_FILE);
+        // 키는 설정이 아니므로 버전 관리 없이 저장합니다
+        tokio::fs::write(&path, serde_json::to_string(&key_file)?).await?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            tokio::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).await?;
+        }
+        Ok(())
+    }
+
+    async fn key_file(&self) -> Option<KeyFile> {
+        let content = tokio::fs::read_to_string(self.app_data_dir.join(KEY_FILE))
+            .await
+            .ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    async fn load_state(&self) -> SyncState {
+        let mut state: SyncState = tokio::fs::read_to_string(self.app_data_dir.join(STATE_FILE))
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        if state.device_id.is_empty() {
+            state.device_id = uuid::Uuid::new_v4().simple().to_string();
+        }
+        state
+    }
+
+    async fn save_state(&self, state: &SyncState) -> Result<()> {
+        // 동기화 상태는 이 기기의 런타임 정보이므로 버전 관리 없이 저장합니다
+        tokio::fs::write(
+            self.app_data_dir.join(STATE_FILE),
+            serde_json::to_string(state)?,
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn status(&self) -> DeviceSyncStatus {
+        let state = tokio::fs::read_to_string(self.app_data_dir.join(STATE_FILE))
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_str::<SyncState>(&content).ok());
+        DeviceSyncStatus {
+            settings: self.settings().await,
+            has_key: self.key_file().await.is_some(),
+            device_id: state.as_ref().map(|state| state.device_id.clone()),
+            last_sync: state.as_ref().and_then(|state| state.last_sync.clone()),
+            records: state.map_or(0, |state| state.records.len()),
+        }
+    }
+
+    /// Current content of every syncable record. Sessions are missing when the app window
+    /// is not up, which the second value reports.
+    async fn collect_local(&self) -> (BTreeMap<String, Value>, bool) {
+        let mut contents = BTreeMap::new();
+        for file in SYNCED_FILES {
+            if let Ok(text) = tokio::fs::read_to_string(self.app_data_dir.join(file)).await {
+                contents.insert(format!("{}{}", CONFIG_PREFIX, file), Value::String(text));
+            }
+        }
+        if !events::app_attached() {
+            return (contents, false);
+        }
+        match ask_webview("sync-export", json!({})).await {
+            Ok(export) => {
+                contents.extend(session_records(&export));
+                (contents, true)
+            }
+            Err(e) => {
+                eprintln!(
+                    "⚠️ Syncing configs only; the session DB is unavailable: {}",
+                    e
+                );
+                (contents, false)
+            }
+        }
+    }
+
+    async fn download(
+        &self,
+        remote: &Remote,
+        cipher: &Cipher,
+        record: &str,
+    ) -> Result<RecordPayload> {
+        let name = cipher.object_name(record);
+        let bytes = remote
+            .get(&name)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Sync record {} is missing", record))?;
+        let payload: RecordPayload = serde_json::from_slice(&cipher.open(&name, &bytes)?)?;
+        if payload.record != record {
+            return Err(anyhow::anyhow!(
+                "Sync record {} holds {}",
+                record,
+                payload.record
+            ));
+        }
+        Ok(payload)
+    }
+
+    async fn upload(
+        &self,
+        remote: &Remote,
+        cipher: &Cipher,
+        record: &str,
+        state: &RecordState,
+        device: &str,
+        content: Value,
+    ) -> Result<()> {
+        let payload = RecordPayload {
+            record: record.to_string(),
+            state: state.clone(),
+            device: device.to_string(),
+            content,
+        };
+        let name = cipher.object_name(record);
+        remote
+            .put(&name, cipher.seal(&name, &serde_json::to_vec(&payload)?)?)
+            .await
+    }
+
+    /// Write a record's content into this device's configs or session import
+    async fn apply(
+        &self,
+        record: &str,
+        content: &Value,
+        deleted: bool,
+        import: &mut SessionImport,
+        report: &mut DeviceSyncReport,
+    ) -> Result<()> {
+        if let Some(file) = record.strip_prefix(CONFIG_PREFIX) {
+            // 원격 레코드가 임의 경로에 쓰지 못하도록 동기화 대상만 받습니다
+            let (Some(text), false) = (content.as_str(), deleted) else {
+                return Ok(());
+            };
+            if !SYNCED_FILES.contains(&file) {
+                return Ok(());
+            }
+            config_versions::write(&self.app_data_dir.join(file), text.to_string()).await?;
+            report.configs.push(file.to_string());
+        } else if let Some(id) = record.strip_prefix(SESSION_PREFIX) {
+            if deleted {
+                import.deleted.push(id.to_string());
+            } else {
+                import.sessions.push(content["session"].clone());
+                import.messages.extend(
+                    content["messages"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .cloned(),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Exchange changes with the backend. Records changed on one side are copied to the
+    /// other; records changed on both are merged (sessions) or settled by the later edit
+    /// (configs). On a device's first sync the backend's configs win.
+    pub async fn sync(&self) -> Result<DeviceSyncReport> {
+        let _running = self.running.lock().await;
+        let settings = self.settings().await;
+        if !settings.enabled {
+            return Err(anyhow::anyhow!("Device sync is not enabled"));
+        }
+        let backend = settings
+            .backend
+            .ok_or_else(|| anyhow::anyhow!("Choose a sync backend first"))?;
+        let key_file = self
+            .key_file()
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Set the sync passphrase first"))?;
+        let cipher = Cipher::new(&base64().decode(&key_file.key)?)?;
+        let remote = Remote::new(backend)?;
+        remote.prepare().await?;
+        check_key(&remote, &cipher, &key_file).await?;
+
+        let mut state = self.load_state().await;
+        let first_sync = state.last_sync.is_none();
+        let device = state.device_id.clone();
+        let now = chrono::Utc::now().to_rfc3339();
+        let (local, sessions) = self.collect_local().await;
+
+        // 지난 동기화 이후 이 기기에서 바뀐 레코드의 시계를 올립니다
+        for (record, content) in &local {
+            let hash = content_hash(content);
+            let entry = state.records.entry(record.clone()).or_default();
+            if entry.deleted || entry.hash != hash {
+                entry.clock.tick(&device);
+                entry.hash = hash;
+                entry.deleted = false;
+                entry.modified_at = now.clone();
+            }
+        }
+        if sessions {
+            for (record, entry) in state.records.iter_mut() {
+                if record.starts_with(SESSION_PREFIX)
+                    && !entry.deleted
+                    && !local.contains_key(record)
+                {
+                    entry.clock.tick(&device);
+                    entry.hash.clear();
+                    entry.deleted = true;
+                    entry.modified_at = now.clone();
+                }
+            }
+        }
+
+        let mut index = read_index(&remote, &cipher).await?;
+        let mut report = DeviceSyncReport {
+            sessions,
+            ..Default::default()
+        };
+        let mut import = SessionImport::default();
+        let records: BTreeSet<String> = state.records.keys().chain(index.keys()).cloned().collect();
+        for record in records {
+            // 세션 DB를 읽지 못했으면 세션 레코드는 다음 동기화로 미룹니다
+            if record.starts_with(SESSION_PREFIX) && !sessions {
+                continue;
+            }
+            let ours = state.records.get(&record).cloned();
+            let theirs = index.get(&record).cloned();
+            let order = match (&ours, &theirs) {
+                (Some(ours), Some(theirs)) => ours.clock.compare(&theirs.clock),
+                (Some(_), None) => Some(Ordering::Greater),
+                (None, Some(_)) => Some(Ordering::Less),
+                (None, None) => continue,
+            };
+            let local_content = local.get(&record).cloned();
+
+            match order {
+                Some(Ordering::Equal) => {}
+                Some(Ordering::Greater) => {
+                    let entry = ours.expect("compared above");
+                    let content = match (&local_content, entry.deleted) {
+                        (_, true) => Value::Null,
+                        (Some(content), false) => content.clone(),
+                        // 로컬에서 사라진 설정 파일은 올리지 않습니다
+                        (None, false) => continue,
+                    };
+                    self.upload(&remote, &cipher, &record, &entry, &device, content)
+                        .await?;
+                    index.insert(record, entry);
+                    report.uploaded += 1;
+                }
+                Some(Ordering::Less) => {
+                    let payload = self.download(&remote, &cipher, &record).await?;
+                    if ours.is_none_or(|ours| ours.hash != payload.state.hash) {
+                        self.apply(
+                            &record,
+                            &payload.content,
+                            payload.state.deleted,
+                            &mut import,
+                            &mut report,
+                        )
+                        .await?;
+                    }
+                    state.records.insert(record, payload.state);
+                    report.downloaded += 1;
+                }
+                None => {
+                    let ours = ours.expect("compared above");
+                    let payload = self.download(&remote, &cipher, &record).await?;
+                    let (content, deleted, resolution) = if record.starts_with(SESSION_PREFIX) {
+                        match (local_content.clone(), payload.state.deleted) {
+                            (Some(content), false) => (
+                                merge_sessions(&content, &payload.content),
+                                false,
+                                ConflictResolution::Merged,
+                            ),
+                            // 한쪽에서 고치고 다른 쪽에서 지웠다면 고친 쪽을 남깁니다
+                            (Some(content), true) => {
+                                (content, false, ConflictResolution::KeptLocal)
+                            }
+                            (None, deleted) => {
+                                (payload.content, deleted, ConflictResolution::TookRemote)
+                            }
+                        }
+                    } else {
+                        let remote_wins = first_sync
+                            || local_content.is_none()
+                            || (payload.state.modified_at.as_str(), payload.device.as_str())
+                                > (ours.modified_at.as_str(), device.as_str());
+                        match local_content.clone() {
+                            Some(content) if !remote_wins => {
+                                (content, false, ConflictResolution::KeptLocal)
+                            }
+                            _ => (
+                                payload.content,
+                                payload.state.deleted,
+                                ConflictResolution::TookRemote,
+                            ),
+                        }
+                    };
+
+                    let mut clock = ours.clock.clone();
+                    clock.merge(&payload.state.clock);
+                    clock.tick(&device);
+                    let entry = RecordState {
+                        clock,
+                        hash: if deleted {
+                            String::new()
+                        } else {
+                            content_hash(&content)
+                        },
+                        deleted,
+                        modified_at: now.clone(),
+                    };
+                    if entry.hash != ours.hash || entry.deleted != ours.deleted {
+                        self.apply(&record, &content, deleted, &mut import, &mut report)
+                            .await?;
+                    }
+                    self.upload(&remote, &cipher, &record, &entry, &device, content)
+                        .await?;
+                    state.records.insert(record.clone(), entry.clone());
+                    index.insert(record.clone(), entry);
+                    report.uploaded += 1;
+                    report.conflicts.push(SyncConflict { record, resolution });
+                }
+            }
+        }
+
+        if !import.is_empty() {
+            ask_webview("sync-import", serde_json::to_value(&import)?)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to store synced sessions: {}", e))?;
+        }
+        if report.uploaded > 0 {
+            // 다른 기기가 그사이 올린 항목을 잃지 않도록 인덱스를 다시 읽어 합칩니다
+            let mut latest = read_index(&remote, &cipher).await?;
+            for (record, entry) in index {
+                let newer = latest.get(&record).is_some_and(|theirs| {
+                    theirs.clock.compare(&entry.clock) == Some(Ordering::Greater)
+                });
+                if !newer {
+                    latest.insert(record, entry);
+                }
+            }
+            remote
+                .put(
+                    INDEX_OBJECT,
+                    cipher.seal(INDEX_OBJECT, &serde_json::to_vec(&latest)?)?,
+                )
+                .await?;
+        }
+        state.last_sync = Some(now);
+        self.save_state(&state).await?;
+        Ok(report)
+    }
+
+    /// Sync on the configured interval for as long as the app runs; `on_synced` receives
+    /// each report so changed configs can be reloaded
+    pub async fn run_background<F, Fut>(&self, on_synced: F)
+    where
+        F: Fn(DeviceSyncReport) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let mut last_run: Option<Instant> = None;
+        loop {
+            tokio::time::sleep(Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+            let settings = self.settings().await;
+            let Some(interval) = settings.interval_secs.filter(|_| settings.enabled) else {
+                continue;
+            };
+            if power::paused() {
+                continue;
+            }
+            if last_run.is_some_and(|at| at.elapsed() < Duration::from_secs(interval)) {
+                continue;
+            }
+            last_run = Some(Instant::now());
+            match self.sync().await {
+                Ok(report) => on_synced(report).await,
+                Err(e) => eprintln!("❌ Device sync failed: {}", e),
+            }
+        }
+    }
+}
+
+async fn upload_key_check(remote: &Remote, cipher: &Cipher, key_file: &KeyFile) -> Result<()> {
+    let check = KeyCheck {
+        salt: key_file.salt.clone(),
+        iterations: key_file.iterations,
+        check: base64().encode(cipher.seal(KEY_CHECK_OBJECT, KEY_CHECK)?),
+    };
+    remote
+        .put(KEY_CHECK_OBJECT, serde_json::to_vec_pretty(&check)?)
+        .await
+}
+
+/// Make sure the backend was set up with the same passphrase; an empty backend is set up
+/// with this device's key
+async fn check_key(remote: &Remote, cipher: &Cipher, key_file: &KeyFile) -> Result<()> {
+    let Some(bytes) = remote.get(KEY_CHECK_OBJECT).await? else {
+        return upload_key_check(remote, cipher, key_file).await;
+    };
+    let check: KeyCheck = serde_json::from_slice(&bytes)?;
+    let sealed = base64().decode(&check.check)?;
+    if cipher.open(KEY_CHECK_OBJECT, &sealed).ok().as_deref() != Some(KEY_CHECK) {
+        return Err(anyhow::anyhow!(
+            "The sync folder was set up with a different passphrase; enter it again"
+        ));
+    }
+    Ok(())
+}
+
+async fn read_index(remote: &Remote, cipher: &Cipher) -> Result<BTreeMap<String, RecordState>> {
+    match remote.get(INDEX_OBJECT).await? {
+        Some(bytes) => Ok(serde_json::from_slice(&cipher.open(INDEX_OBJECT, &bytes)?)?),
+        None => Ok(BTreeMap::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clock(counters: &[(&str, u64)]) -> VectorClock {
+        VectorClock(
+            counters
+                .iter()
+                .map(|(device, counter)| (device.to_string(), *counter))
+                .collect(),
+        )
+    }
+
+    fn cipher(byte: u8) -> Cipher {
+        Cipher::new(&[byte; KEY_LEN * 2]).unwrap()
+    }
+
+    #[test]
+    fn compares_vector_clocks() {
+        let base = clock(&[("a", 1), ("b", 2)]);
+        assert_eq!(base.compare(&base.clone()), Some(Ordering::Equal));
+        // 없는 기기는 0으로 봅니다
+        assert_eq!(
+            base.compare(&clock(&[("a", 1), ("b", 2), ("c", 0)])),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            base.compare(&clock(&[("a", 2), ("b", 2)])),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            base.compare(&clock(&[("a", 1), ("b", 2), ("c", 1)])),
+            Some(Ordering::Less)
+        );
+        assert_eq!(base.compare(&clock(&[("b", 2)])), Some(Ordering::Greater));
+        assert_eq!(base.compare(&clock(&[("a", 2), ("b", 1)])), None);
+        assert_eq!(base.compare(&clock(&[("c", 1)])), None);
+    }
+
+    #[test]
+    fn merged_clocks_dominate_both_sides() {
+        let mut ours = clock(&[("a", 3), ("b", 1)]);
+        let theirs = clock(&[("b", 4), ("c", 1)]);
+        assert_eq!(ours.compare(&theirs), None);
+
+        ours.merge(&theirs);
+        assert_eq!(ours, clock(&[("a", 3), ("b", 4), ("c", 1)]));
+        ours.tick("a");
+        assert_eq!(ours.compare(&theirs), Some(Ordering::Greater));
+        assert_eq!(theirs.compare(&ours), Some(Ordering::Less));
+    }
+
+    #[test]
+    fn sealed_objects_round_trip() {
+        let cipher = cipher(7);
+        let name = cipher.object_name("session:1");
+        let envelope = cipher.seal(&name, b"hello").unwrap();
+        assert!(envelope.starts_with(ENVELOPE_MAGIC));
+        assert_eq!(cipher.open(&name, &envelope).unwrap(), b"hello");
+        // 같은 내용도 매번 다른 nonce로 봉인됩니다
+        assert_ne!(cipher.seal(&name, b"hello").unwrap(), envelope);
+    }
+
+    #[test]
+    fn tampered_objects_are_rejected() {
+        let ours = cipher(7);
+        let envelope = ours.seal("records/a.bin", b"hello").unwrap();
+
+        // 다른 기록의 이름(AAD)으로는 열 수 없습니다
+        assert!(ours.open("records/b.bin", &envelope).is_err());
+        // 다른 키
+        assert!(cipher(8).open("records/a.bin", &envelope).is_err());
+        // 본문이나 태그가 바뀐 경우
+        let mut flipped = envelope.clone();
+        *flipped.last_mut().unwrap() ^= 1;
+        assert!(ours.open("records/a.bin", &flipped).is_err());
+        let mut flipped = envelope.clone();
+        flipped[ENVELOPE_MAGIC.len() + NONCE_LEN] ^= 1;
+        assert!(ours.open("records/a.bin", &flipped).is_err());
+        // 봉투 형식이 아닌 경우
+        assert!(ours.open("records/a.bin", b"plain json").is_err());
+        assert!(ours.open("records/a.bin", ENVELOPE_MAGIC).is_err());
+    }
+
+    #[test]
+    fn object_names_hide_record_keys() {
+        let name = cipher(7).object_name("session:1");
+        assert!(name.starts_with("records/") && name.ends_with(".bin"));
+        assert!(!name.contains("session"));
+        assert_eq!(name, cipher(7).object_name("session:1"));
+        assert_ne!(name, cipher(8).object_name("session:1"));
+    }
+}
@@ -0,0 +1,168 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast;
+
+// setup 단계에서 저장되는 앱 핸들 (모듈에서 이벤트를 보내기 위해 사용)
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+// 외부 구독자(WebSocket)에게 보내는 직렬화된 이벤트
+static EXTERNAL: OnceLock<broadcast::Sender<String>> = OnceLock::new();
+// 느린 구독자는 이 개수만큼 뒤처지면 오래된 이벤트를 놓칩니다
+const EXTERNAL_BUFFER: usize = 1024;
+
+/// Events emitted by the webview itself that external clients also receive
+pub const FORWARDED_WEBVIEW_EVENTS: &[&str] = &["agent-token"];
+
+pub fn init(app: AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+/// False in headless mode, where no webview can answer prompts
+pub fn app_attached() -> bool {
+    APP_HANDLE.get().is_some()
+}
+
+fn external() -> &'static broadcast::Sender<String> {
+    EXTERNAL.get_or_init(|| broadcast::channel(EXTERNAL_BUFFER).0)
+}
+
+/// Emit an event to every window; a no-op before the app has been set up
+///
+/// The same event is mirrored to external subscribers, if any are connected.
+pub fn emit<S: Serialize + Clone>(event: &str, payload: S) {
+    if external().receiver_count() > 0 {
+        if let Ok(payload) = serde_json::to_value(&payload) {
+            publish(event, payload);
+        }
+    }
+    if let Some(app) = APP_HANDLE.get() {
+        if let Err(e) = app.emit(event, payload) {
+            eprintln!("❌ Failed to emit event '{}': {}", event, e);
+        }
+    }
+}
+
+/// Emit an event to one window only, for replies that concern a single chat
+///
+/// External subscribers still receive it, like every other event.
+pub fn emit_to<S: Serialize + Clone>(window: &str, event: &str, payload: S) {
+    if external().receiver_count() > 0 {
+        if let Ok(payload) = serde_json::to_value(&payload) {
+            publish(event, payload);
+        }
+    }
+    if let Some(app) = APP_HANDLE.get() {
+        if let Err(e) = app.emit_to(window, event, payload) {
+            eprintln!("❌ Failed to emit event '{}' to {}: {}", event, window, e);
+        }
+    }
+}
+
+pub fn window_exists(label: &str) -> bool {
+    APP_HANDLE
+        .get()
+        .is_some_and(|app| app.get_webview_window(label).is_some())
+}
+
+/// Window that answers requests no particular window issued: the focused one, else the
+/// main window, else any open window
+pub fn request_window() -> Option<String> {
+    let app = APP_HANDLE.get()?;
+    let windows = app.webview_windows();
+    windows
+        .iter()
+        .find(|(_, window)| window.is_focused().unwrap_or(false))
+        .or_else(|| windows.get_key_value("main"))
+        .or_else(|| windows.iter().next())
+        .map(|(label, _)| label.clone())
+}
+
+/// Send an event to external subscribers only
+pub fn publish(event: &str, payload: serde_json::Value) {
+    let message = serde_json::json!({ "event": event, "payload": payload }).to_string();
+    // 구독자가 없으면 실패하지만 무시해도 됩니다
+    let _ = external().send(message);
+}
+
+/// Receive every emitted event as `{"event": ..., "payload": ...}` JSON text
+pub fn subscribe() -> broadcast::Receiver<String> {
+    external().subscribe()
+}
+
+// 메모리에 보관하는 최대 타임라인 이벤트 수
+const TIMELINE_CAPACITY: usize = 5000;
+const DEFAULT_PAGE_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEvent {
+    /// Monotonically increasing, starting at 1; usable as a cursor
+    pub id: u64,
+    pub timestamp: String,
+    /// e.g. `server-started`, `tool-called`, `approval-requested`, `agent-step`, `crash`
+    pub kind: String,
+    pub server_name: Option<String>,
+    pub detail: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelinePage {
+    pub events: Vec<TimelineEvent>,
+    /// Pass back as `cursor` to continue after the last returned event
+    pub cursor: u64,
+    /// True when events after the given cursor were already dropped from the buffer
+    pub truncated: bool,
+}
+
+struct Timeline {
+    events: VecDeque<TimelineEvent>,
+    next_id: u64,
+}
+
+static TIMELINE: Mutex<Timeline> = Mutex::new(Timeline {
+    events: VecDeque::new(),
+    next_id: 1,
+});
+
+/// Append an event to the activity timeline and push it to the frontend
+pub fn record(kind: &str, server_name: Option<&str>, detail: serde_json::Value) {
+    let event = {
+        let mut timeline = TIMELINE.lock().unwrap_or_else(|e| e.into_inner());
+        let event = TimelineEvent {
+            id: timeline.next_id,
+            timestamp: chrono::Local::now().to_rfc3339(),
+            kind: kind.to_string(),
+            server_name: server_name.map(str::to_string),
+            detail,
+        };
+        timeline.next_id += 1;
+        if timeline.events.len() == TIMELINE_CAPACITY {
+            timeline.events.pop_front();
+        }
+        timeline.events.push_back(event.clone());
+        event
+    };
+    emit("timeline-event", event);
+}
+
+/// Events with an id greater than `cursor`, oldest first
+pub fn since(cursor: u64, limit: Option<usize>) -> TimelinePage {
+    let timeline = TIMELINE.lock().unwrap_or_else(|e| e.into_inner());
+    let oldest = timeline
+        .events
+        .front()
+        .map(|event| event.id)
+        .unwrap_or(timeline.next_id);
+    let events: Vec<TimelineEvent> = timeline
+        .events
+        .iter()
+        .filter(|event| event.id > cursor)
+        .take(limit.unwrap_or(DEFAULT_PAGE_SIZE))
+        .cloned()
+        .collect();
+    TimelinePage {
+        cursor: events.last().map(|event| event.id).unwrap_or(cursor),
+        truncated: cursor + 1 < oldest,
+        events,
+    }
+}
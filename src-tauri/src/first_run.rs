@@ -0,0 +1,307 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+use crate::health::RUNTIME_COMMANDS;
+use crate::integrity;
+use crate::mcp::{MCPServerConfig, MCPServerManager, ToolCallContext};
+use crate::runtime::Runtime;
+
+// 첫 실행 설정을 마쳤는지 기록하는 파일
+const MARKER_FILE: &str = "first_run.json";
+
+/// Servers offered in the first-run flow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StarterServer {
+    Filesystem,
+    Fetch,
+    Memory,
+}
+
+impl StarterServer {
+    pub const ALL: [Self; 3] = [Self::Filesystem, Self::Fetch, Self::Memory];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Filesystem => "filesystem",
+            Self::Fetch => "fetch",
+            Self::Memory => "memory",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Filesystem => "Read and edit files inside one folder",
+            Self::Fetch => "Fetch web pages as markdown",
+            Self::Memory => "Remember facts across chats in a local knowledge graph",
+        }
+    }
+
+    /// Tool called with these arguments to prove the server works
+    fn self_test(self) -> (&'static str, serde_json::Value) {
+        match self {
+            Self::Filesystem => ("list_allowed_directories", json!({})),
+            Self::Fetch => (
+                "fetch",
+                json!({ "url": "https://example.com", "max_length": 200 }),
+            ),
+            Self::Memory => ("read_graph", json!({})),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeInfo {
+    pub name: String,
+    /// Resolved location, `None` when not on PATH
+    pub path: Option<String>,
+}
+
+impl RuntimeInfo {
+    fn available(runtimes: &[RuntimeInfo], name: &str) -> bool {
+        runtimes
+            .iter()
+            .any(|runtime| runtime.name == name && runtime.path.is_some())
+    }
+}
+
+pub fn detect_runtimes() -> Vec<RuntimeInfo> {
+    RUNTIME_COMMANDS
+        .iter()
+        .map(|command| RuntimeInfo {
+            name: command.to_string(),
+            path: integrity::resolve_executable(command).map(|p| p.display().to_string()),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StarterSuggestion {
+    pub server: StarterServer,
+    pub description: String,
+    /// Config that would be started, `None` when no suitable runtime is installed
+    pub config: Option<MCPServerConfig>,
+    /// What to install when `config` is `None`
+    pub missing: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SetupSuggestions {
+    pub first_run: bool,
+    pub runtimes: Vec<RuntimeInfo>,
+    pub suggestions: Vec<StarterSuggestion>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetupSelection {
+    pub server: StarterServer,
+    /// Folder exposed by the filesystem server; defaults to the first workspace root or home
+    #[serde(default)]
+    pub root: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestResult {
+    pub tool_name: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SetupServerResult {
+    pub server: StarterServer,
+    pub config: Option<MCPServerConfig>,
+    pub started: bool,
+    pub self_test: Option<SelfTestResult>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SetupReport {
+    pub completed_at: String,
+    pub runtimes: Vec<RuntimeInfo>,
+    pub servers: Vec<SetupServerResult>,
+}
+
+fn preset_config(
+    name: &str,
+    runtime: Runtime,
+    package: &str,
+    args: Vec<String>,
+    env: serde_json::Value,
+) -> Result<MCPServerConfig> {
+    Ok(serde_json::from_value(json!({
+        "name": name,
+        "runtime": runtime,
+        "package": package,
+        "args": args,
+        "env": env,
+        "env_policy": "clean",
+    }))?)
+}
+
+/// Config for a starter server using the runtimes that are installed
+fn starter_config(
+    server: StarterServer,
+    runtimes: &[RuntimeInfo],
+    app_data_dir: &Path,
+    root: &Path,
+) -> Result<MCPServerConfig, String> {
+    let has = |name: &str| RuntimeInfo::available(runtimes, name);
+    let config = match server {
+        StarterServer::Filesystem if has("npx") => preset_config(
+            server.name(),
+            Runtime::Npx,
+            "@modelcontextprotocol/server-filesystem",
+            vec![root.display().to_string()],
+            json!({}),
+        ),
+        StarterServer::Memory if has("npx") => preset_config(
+            server.name(),
+            Runtime::Npx,
+            "@modelcontextprotocol/server-memory",
+            vec![],
+            // 기본값은 패키지 폴더 안이라 npx 캐시가 지워지면 기억도 사라집니다
+            json!({ "MEMORY_FILE_PATH": app_data_dir.join("memory.json").display().to_string() }),
+        ),
+        StarterServer::Fetch if has("uvx") => preset_config(
+            server.name(),
+            Runtime::Uvx,
+            "mcp-server-fetch",
+            vec![],
+            json!({}),
+        ),
+        StarterServer::Fetch if has("docker") || has("podman") => {
+            let engine = if has("docker") { "docker" } else { "podman" };
+            serde_json::from_value(json!({
+                "name": server.name(),
+                "transport": "docker",
+                "container": { "image": "mcp/fetch", "engine": engine },
+                "env_policy": "clean",
+            }))
+            .map_err(anyhow::Error::from)
+        }
+        StarterServer::Fetch => return Err("Install uv (uvx) or Docker".to_string()),
+        StarterServer::Filesystem | StarterServer::Memory => {
+            return Err("Install Node.js (npx)".to_string())
+        }
+    };
+    config.map_err(|e| e.to_string())
+}
+
+pub async fn is_first_run(app_data_dir: &Path) -> bool {
+    !tokio::fs::try_exists(app_data_dir.join(MARKER_FILE))
+        .await
+        .unwrap_or(false)
+}
+
+pub async fn suggestions(app_data_dir: &Path, default_root: &Path) -> SetupSuggestions {
+    let runtimes = detect_runtimes();
+    let suggestions = StarterServer::ALL
+        .into_iter()
+        .map(
+            |server| match starter_config(server, &runtimes, app_data_dir, default_root) {
+                Ok(config) => StarterSuggestion {
+                    server,
+                    description: server.description().to_string(),
+                    config: Some(config),
+                    missing: None,
+                },
+                Err(missing) => StarterSuggestion {
+                    server,
+                    description: server.description().to_string(),
+                    config: None,
+                    missing: Some(missing),
+                },
+            },
+        )
+        .collect();
+    SetupSuggestions {
+        first_run: is_first_run(app_data_dir).await,
+        runtimes,
+        suggestions,
+    }
+}
+
+async fn set_up_server(
+    manager: &MCPServerManager,
+    selection: &SetupSelection,
+    runtimes: &[RuntimeInfo],
+    app_data_dir: &Path,
+    default_root: &Path,
+) -> SetupServerResult {
+    let mut result = SetupServerResult {
+        server: selection.server,
+        config: None,
+        started: false,
+        self_test: None,
+        error: None,
+    };
+    let root = selection
+        .root
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| default_root.to_path_buf());
+    let config = match starter_config(selection.server, runtimes, app_data_dir, &root) {
+        Ok(config) => config,
+        Err(e) => {
+            result.error = Some(e);
+            return result;
+        }
+    };
+    result.config = Some(config.clone());
+
+    if let Err(e) = manager.start_server(config).await {
+        result.error = Some(format!("Failed to start: {}", e));
+        return result;
+    }
+    result.started = true;
+
+    let (tool_name, arguments) = selection.server.self_test();
+    let call = manager
+        .call_tool(
+            selection.server.name(),
+            tool_name,
+            arguments,
+            &ToolCallContext::default(),
+        )
+        .await;
+    result.self_test = Some(SelfTestResult {
+        tool_name: tool_name.to_string(),
+        success: call.success,
+        error: call.error,
+    });
+    result
+}
+
+/// Generate configs for the selected starter servers, start them and call one tool on each.
+/// The returned configs are what the caller should save; the run is recorded so later
+/// launches skip the first-run flow.
+pub async fn run(
+    manager: &MCPServerManager,
+    app_data_dir: &Path,
+    default_root: &Path,
+    selections: Vec<SetupSelection>,
+) -> Result<SetupReport> {
+    let runtimes = detect_runtimes();
+    let servers =
+        futures::future::join_all(selections.iter().map(|selection| {
+            set_up_server(manager, selection, &runtimes, app_data_dir, default_root)
+        }))
+        .await;
+
+    let report = SetupReport {
+        completed_at: chrono::Local::now().to_rfc3339(),
+        runtimes,
+        servers,
+    };
+    tokio::fs::create_dir_all(app_data_dir).await?;
+    tokio::fs::write(
+        app_data_dir.join(MARKER_FILE),
+        serde_json::to_string_pretty(&report)?,
+    )
+    .await?;
+    Ok(report)
+}
@@ -0,0 +1,238 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// 이보다 큰 파일은 이전 내용을 보관하지 않으므로 되돌릴 수 없습니다
+const MAX_PRE_IMAGE_BYTES: u64 = 10 * 1024 * 1024;
+const MAX_JOURNAL_BYTES: u64 = 512 * 1024 * 1024;
+const MAX_OPERATIONS: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FsOperationKind {
+    Create,
+    Write,
+    Delete,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FsOperation {
+    pub id: String,
+    pub session_id: Option<String>,
+    pub tool: String,
+    pub kind: FsOperationKind,
+    pub path: PathBuf,
+    pub timestamp: String,
+    /// False when the previous content was too large to keep
+    pub undoable: bool,
+    pub undone: bool,
+    #[serde(default)]
+    pre_size: u64,
+    pre_hash: Option<String>,
+    post_hash: Option<String>,
+}
+
+/// A write in progress; pass it to [`FsJournal::finish`] once the file is written
+pub struct PendingOperation {
+    operation: FsOperation,
+    kept_pre_image: bool,
+}
+
+fn hash_file(path: &Path) -> Result<Option<String>> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(Some(format!("{:x}", Sha256::digest(&bytes)))),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Pre-images of files changed by builtin tools, so a single change can be undone
+pub struct FsJournal {
+    dir: PathBuf,
+    operations: Mutex<Option<Vec<FsOperation>>>,
+}
+
+impl FsJournal {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            operations: Mutex::new(None),
+        }
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.dir.join("journal.json")
+    }
+
+    fn pre_image_path(&self, id: &str) -> PathBuf {
+        self.dir.join("pre").join(id)
+    }
+
+    fn with_operations<T>(&self, f: impl FnOnce(&mut Vec<FsOperation>) -> Result<T>) -> Result<T> {
+        let mut guard = self.operations.lock().unwrap_or_else(|e| e.into_inner());
+        if guard.is_none() {
+            *guard = Some(match std::fs::read_to_string(self.index_path()) {
+                Ok(content) => serde_json::from_str(&content)?,
+                Err(e) if e.kind() == ErrorKind::NotFound => Vec::new(),
+                Err(e) => return Err(e.into()),
+            });
+        }
+        f(guard.as_mut().expect("journal was just loaded"))
+    }
+
+    fn persist(&self, operations: &[FsOperation]) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let tmp_path = self.index_path().with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_string_pretty(operations)?)?;
+        std::fs::rename(&tmp_path, self.index_path())?;
+        Ok(())
+    }
+
+    /// 오래된 항목부터 지워 개수와 보관 용량 한도를 지킵니다
+    fn trim(&self, operations: &mut Vec<FsOperation>) {
+        let mut stored: u64 = operations.iter().map(|op| op.pre_size).sum();
+        while operations.len() > MAX_OPERATIONS
+            || (stored > MAX_JOURNAL_BYTES && !operations.is_empty())
+        {
+            let oldest = operations.remove(0);
+            stored -= oldest.pre_size;
+            let _ = std::fs::remove_file(self.pre_image_path(&oldest.id));
+        }
+    }
+
+    /// Capture the current content of `path` before a builtin tool changes it
+    pub fn begin(
+        &self,
+        session_id: Option<&str>,
+        tool: &str,
+        path: &Path,
+    ) -> Result<PendingOperation> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let metadata = match std::fs::metadata(path) {
+            Ok(metadata) => Some(metadata),
+            Err(e) if e.kind() == ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        let mut pre_hash = None;
+        let mut pre_size = 0;
+        if let Some(metadata) = metadata.as_ref().filter(|m| m.is_file()) {
+            if metadata.len() <= MAX_PRE_IMAGE_BYTES {
+                let bytes = std::fs::read(path)?;
+                std::fs::create_dir_all(self.dir.join("pre"))?;
+                std::fs::write(self.pre_image_path(&id), &bytes)?;
+                pre_size = bytes.len() as u64;
+                pre_hash = Some(format!("{:x}", Sha256::digest(&bytes)));
+            }
+        }
+        let kept_pre_image = pre_hash.is_some();
+        Ok(PendingOperation {
+            operation: FsOperation {
+                id,
+                session_id: session_id.map(str::to_string),
+                tool: tool.to_string(),
+                kind: if metadata.is_some() {
+                    FsOperationKind::Write
+                } else {
+                    FsOperationKind::Create
+                },
+                path: path.to_path_buf(),
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                undoable: metadata.is_none() || kept_pre_image,
+                undone: false,
+                pre_size,
+                pre_hash,
+                post_hash: None,
+            },
+            kept_pre_image,
+        })
+    }
+
+    /// Record the operation; nothing is kept when the file did not actually change
+    pub fn finish(&self, pending: PendingOperation) -> Result<()> {
+        let PendingOperation {
+            mut operation,
+            kept_pre_image,
+        } = pending;
+        operation.post_hash = hash_file(&operation.path)?;
+        let unchanged = match operation.kind {
+            FsOperationKind::Create => operation.post_hash.is_none(),
+            _ => kept_pre_image && operation.post_hash == operation.pre_hash,
+        };
+        if unchanged {
+            let _ = std::fs::remove_file(self.pre_image_path(&operation.id));
+            return Ok(());
+        }
+        if operation.post_hash.is_none() {
+            operation.kind = FsOperationKind::Delete;
+        }
+        self.with_operations(|operations| {
+            operations.push(operation);
+            self.trim(operations);
+            self.persist(operations)
+        })
+    }
+
+    /// Operations newest first, optionally only those from one chat session
+    pub fn list(&self, session_id: Option<&str>) -> Result<Vec<FsOperation>> {
+        self.with_operations(|operations| {
+            Ok(operations
+                .iter()
+                .rev()
+                .filter(|op| session_id.is_none_or(|id| op.session_id.as_deref() == Some(id)))
+                .cloned()
+                .collect())
+        })
+    }
+
+    /// Put the file back the way it was before the operation
+    pub fn undo(&self, id: &str) -> Result<FsOperation> {
+        self.with_operations(|operations| {
+            let operation = operations
+                .iter_mut()
+                .find(|op| op.id == id)
+                .ok_or_else(|| anyhow::anyhow!("Unknown file operation '{}'", id))?;
+            if operation.undone {
+                return Err(anyhow::anyhow!("Operation '{}' was already undone", id));
+            }
+            if !operation.undoable {
+                return Err(anyhow::anyhow!(
+                    "The previous content of {} was larger than {} MiB and was not kept",
+                    operation.path.display(),
+                    MAX_PRE_IMAGE_BYTES / 1024 / 1024
+                ));
+            }
+            // 이후에 다른 변경이 있었다면 덮어쓰지 않습니다
+            if hash_file(&operation.path)? != operation.post_hash {
+                return Err(anyhow::anyhow!(
+                    "{} changed after this operation; undo the later operations first",
+                    operation.path.display()
+                ));
+            }
+
+            let pre_image = self.pre_image_path(&operation.id);
+            if operation.pre_hash.is_some() {
+                if let Some(parent) = operation.path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                let file_name = operation.path.file_name().unwrap_or_default();
+                let tmp_path = operation
+                    .path
+                    .with_file_name(format!(".{}.undo-tmp", file_name.to_string_lossy()));
+                std::fs::copy(&pre_image, &tmp_path)?;
+                std::fs::rename(&tmp_path, &operation.path)?;
+                let _ = std::fs::remove_file(&pre_image);
+            } else if operation.post_hash.is_some() {
+                std::fs::remove_file(&operation.path)?;
+            }
+            operation.undone = true;
+            operation.pre_size = 0;
+            let undone = operation.clone();
+            self.persist(operations)?;
+            println!("Undid {} on {}", undone.tool, undone.path.display());
+            Ok(undone)
+        })
+    }
+}
@@ -0,0 +1,408 @@
+use anyhow::Result;
+use git2::{BranchType, DiffFormat, IndexAddOption, Repository, Sort, Status, StatusOptions};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+use crate::api::ask_approval;
+use crate::builtin::tool;
+use crate::mcp::MCPTool;
+use crate::sandbox::{AccessKind, PathPolicy};
+
+// 에이전트 컨텍스트를 넘치지 않도록 diff 출력을 자릅니다
+const MAX_DIFF_BYTES: usize = 100 * 1024;
+const DEFAULT_LOG_LIMIT: usize = 20;
+const MAX_LOG_LIMIT: usize = 200;
+
+fn repo_schema(extra: serde_json::Value, required: &[&str]) -> serde_json::Value {
+    let mut properties = json!({
+        "path": {
+            "type": "string",
+            "description": "Repository directory inside the workspace"
+        }
+    });
+    if let (Some(properties), Some(extra)) = (properties.as_object_mut(), extra.as_object()) {
+        properties.extend(extra.clone());
+    }
+    let mut required: Vec<&str> = required.to_vec();
+    required.insert(0, "path");
+    json!({ "type": "object", "properties": properties, "required": required })
+}
+
+pub fn list_tools() -> Vec<MCPTool> {
+    vec![
+        tool(
+            "git_status",
+            "Show the current branch and changed files of a repository",
+            repo_schema(json!({}), &[]),
+            true,
+        ),
+        tool(
+            "git_diff",
+            "Show unstaged changes, or staged changes with `staged: true`",
+            repo_schema(
+                json!({
+                    "staged": { "type": "boolean" },
+                    "file": { "type": "string", "description": "Limit to one file (repository-relative)" }
+                }),
+                &[],
+            ),
+            true,
+        ),
+        tool(
+            "git_log",
+            "List recent commits reachable from HEAD",
+            repo_schema(json!({ "limit": { "type": "integer" } }), &[]),
+            true,
+        ),
+        tool(
+            "git_blame",
+            "Show the commit and author of every line in a file",
+            repo_schema(
+                json!({ "file": { "type": "string", "description": "Repository-relative path" } }),
+                &["file"],
+            ),
+            true,
+        ),
+        tool(
+            "git_commit",
+            "Commit staged changes (or every tracked change with `all: true`) after the user approves",
+            repo_schema(
+                json!({
+                    "message": { "type": "string" },
+                    "all": { "type": "boolean" }
+                }),
+                &["message"],
+            ),
+            false,
+        ),
+        tool(
+            "git_branch",
+            "List branches, or create (and optionally check out) one after the user approves",
+            repo_schema(
+                json!({
+                    "name": { "type": "string" },
+                    "checkout": { "type": "boolean" }
+                }),
+                &[],
+            ),
+            false,
+        ),
+    ]
+}
+
+/// 저장소를 찾고 작업 트리가 승인된 루트 안에 있는지 확인합니다
+async fn open(
+    policy: &PathPolicy,
+    arguments: &serde_json::Value,
+    access: AccessKind,
+) -> Result<PathBuf> {
+    let path = arguments
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing string argument 'path'"))?;
+    let path = policy.check(Path::new(path), access).await?;
+    let workdir = Repository::discover(&path)?
+        .workdir()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| anyhow::anyhow!("Bare repositories are not supported"))?;
+    // discover는 상위 디렉토리로 올라갈 수 있으므로 작업 트리도 다시 검사합니다
+    policy.check(&workdir, access).await
+}
+
+fn status_code(status: Status) -> (char, char) {
+    let index = if status.is_index_new() {
+        'A'
+    } else if status.is_index_modified() {
+        'M'
+    } else if status.is_index_deleted() {
+        'D'
+    } else if status.is_index_renamed() {
+        'R'
+    } else {
+        ' '
+    };
+    let worktree = if status.is_wt_new() {
+        '?'
+    } else if status.is_wt_modified() {
+        'M'
+    } else if status.is_wt_deleted() {
+        'D'
+    } else if status.is_wt_renamed() {
+        'R'
+    } else {
+        ' '
+    };
+    if status.is_wt_new() && index == ' ' {
+        ('?', '?')
+    } else {
+        (index, worktree)
+    }
+}
+
+fn current_branch(repo: &Repository) -> String {
+    match repo.head() {
+        Ok(head) if head.is_branch() => head.shorthand().unwrap_or("HEAD").to_string(),
+        Ok(head) => format!(
+            "HEAD detached at {}",
+            head.target()
+                .map(|oid| oid.to_string()[..7].to_string())
+                .unwrap_or_default()
+        ),
+        Err(_) => "no commits yet".to_string(),
+    }
+}
+
+fn status(workdir: &Path) -> Result<String> {
+    let repo = Repository::open(workdir)?;
+    let mut options = StatusOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true);
+    let statuses = repo.statuses(Some(&mut options))?;
+    let mut lines = vec![format!("On branch {}", current_branch(&repo))];
+    for entry in statuses.iter() {
+        if entry.status().is_ignored() {
+            continue;
+        }
+        let (index, worktree) = status_code(entry.status());
+        lines.push(format!(
+            "{}{} {}",
+            index,
+            worktree,
+            entry.path().unwrap_or_default()
+        ));
+    }
+    if lines.len() == 1 {
+        lines.push("nothing to commit, working tree clean".to_string());
+    }
+    Ok(lines.join("\n"))
+}
+
+fn diff(workdir: &Path, staged: bool, file: Option<&str>) -> Result<String> {
+    let repo = Repository::open(workdir)?;
+    let mut options = git2::DiffOptions::new();
+    if let Some(file) = file {
+        options.pathspec(file);
+    }
+    let diff = if staged {
+        let head = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+        repo.diff_tree_to_index(head.as_ref(), None, Some(&mut options))?
+    } else {
+        options.include_untracked(true).show_untracked_content(true);
+        repo.diff_index_to_workdir(None, Some(&mut options))?
+    };
+
+    let mut output = String::new();
+    let mut truncated = false;
+    diff.print(DiffFormat::Patch, |_, _, line| {
+        if output.len() >= MAX_DIFF_BYTES {
+            truncated = true;
+            return false;
+        }
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            output.push(line.origin());
+        }
+        output.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })
+    .or_else(|e| if truncated { Ok(()) } else { Err(e) })?;
+    if truncated {
+        output.push_str("\n… diff truncated\n");
+    }
+    if output.is_empty() {
+        output = "No changes".to_string();
+    }
+    Ok(output)
+}
+
+fn log(workdir: &Path, limit: usize) -> Result<String> {
+    let repo = Repository::open(workdir)?;
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    revwalk.set_sorting(Sort::TIME)?;
+    let mut lines = Vec::new();
+    for oid in revwalk.take(limit) {
+        let commit = repo.find_commit(oid?)?;
+        let time = chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        lines.push(format!(
+            "{} {} {} {}",
+            &commit.id().to_string()[..7],
+            time,
+            commit.author().name().unwrap_or("unknown"),
+            commit.summary().ok().flatten().unwrap_or_default()
+        ));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn blame(workdir: &Path, file: &Path) -> Result<String> {
+    let repo = Repository::open(workdir)?;
+    let relative = file.strip_prefix(workdir)?;
+    let blame = repo.blame_file(relative, None)?;
+    let content = std::fs::read_to_string(file)?;
+    let mut lines = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let (id, author) = match blame.get_line(index + 1) {
+            Some(hunk) if !hunk.final_commit_id().is_zero() => (
+                hunk.final_commit_id().to_string()[..7].to_string(),
+                hunk.final_signature()
+                    .and_then(|s| s.name().ok().map(str::to_string))
+                    .unwrap_or_default(),
+            ),
+            _ => ("0000000".to_string(), "Not committed".to_string()),
+        };
+        lines.push(format!("{} ({}) {:>4}: {}", id, author, index + 1, line));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn commit(workdir: &Path, message: &str, all: bool) -> Result<String> {
+    let repo = Repository::open(workdir)?;
+    let mut index = repo.index()?;
+    if all {
+        index.update_all(["*"], None)?;
+        index.add_all(["*"], IndexAddOption::DEFAULT, None)?;
+        index.write()?;
+    }
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+    if parent.as_ref().is_some_and(|p| p.tree_id() == tree.id()) {
+        return Err(anyhow::anyhow!("Nothing to commit"));
+    }
+    let signature = repo
+        .signature()
+        .map_err(|_| anyhow::anyhow!("Set user.name and user.email in git config first"))?;
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    let oid = repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parents,
+    )?;
+    Ok(format!(
+        "[{} {}] {}",
+        current_branch(&repo),
+        &oid.to_string()[..7],
+        message.lines().next().unwrap_or_default()
+    ))
+}
+
+fn branches(workdir: &Path) -> Result<String> {
+    let repo = Repository::open(workdir)?;
+    let mut lines = Vec::new();
+    for branch in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = branch?;
+        let marker = if branch.is_head() { '*' } else { ' ' };
+        lines.push(format!("{} {}", marker, branch.name()?.unwrap_or_default()));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn create_branch(workdir: &Path, name: &str, checkout: bool) -> Result<String> {
+    let repo = Repository::open(workdir)?;
+    let head = repo.head()?.peel_to_commit()?;
+    let branch = repo.branch(name, &head, false)?;
+    if !checkout {
+        return Ok(format!("Created branch {}", name));
+    }
+    let reference = branch.get().name()?.to_string();
+    // 작업 트리의 변경 사항은 덮어쓰지 않습니다 (충돌 시 실패)
+    repo.checkout_tree(
+        head.as_object(),
+        Some(git2::build::CheckoutBuilder::new().safe()),
+    )?;
+    repo.set_head(&reference)?;
+    Ok(format!("Switched to a new branch '{}'", name))
+}
+
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+    tokio::task::spawn_blocking(f).await?
+}
+
+async fn approve(message: String) -> Result<()> {
+    ask_approval("git", message)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+}
+
+/// git 도구를 실행합니다. 쓰기 작업은 사용자 승인이 필요합니다
+pub async fn call_tool(
+    policy: &PathPolicy,
+    tool_name: &str,
+    arguments: &serde_json::Value,
+) -> Result<String> {
+    let str_arg = |name: &str| arguments.get(name).and_then(|v| v.as_str());
+    let bool_arg = |name: &str| {
+        arguments
+            .get(name)
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    };
+
+    match tool_name {
+        "git_status" => {
+            let workdir = open(policy, arguments, AccessKind::Read).await?;
+            blocking(move || status(&workdir)).await
+        }
+        "git_diff" => {
+            let workdir = open(policy, arguments, AccessKind::Read).await?;
+            let staged = bool_arg("staged");
+            let file = str_arg("file").map(str::to_string);
+            blocking(move || diff(&workdir, staged, file.as_deref())).await
+        }
+        "git_log" => {
+            let workdir = open(policy, arguments, AccessKind::Read).await?;
+            let limit = arguments
+                .get("limit")
+                .and_then(|v| v.as_u64())
+                .map(|limit| (limit as usize).clamp(1, MAX_LOG_LIMIT))
+                .unwrap_or(DEFAULT_LOG_LIMIT);
+            blocking(move || log(&workdir, limit)).await
+        }
+        "git_blame" => {
+            let workdir = open(policy, arguments, AccessKind::Read).await?;
+            let file =
+                str_arg("file").ok_or_else(|| anyhow::anyhow!("Missing string argument 'file'"))?;
+            let file = policy.check(&workdir.join(file), AccessKind::Read).await?;
+            blocking(move || blame(&workdir, &file)).await
+        }
+        "git_commit" => {
+            let workdir = open(policy, arguments, AccessKind::Write).await?;
+            let message = str_arg("message")
+                .filter(|m| !m.trim().is_empty())
+                .ok_or_else(|| anyhow::anyhow!("Missing commit message"))?
+                .to_string();
+            let all = bool_arg("all");
+            approve(format!(
+                "The assistant wants to commit{} in {}:\n\n{}",
+                if all { " all changes" } else { "" },
+                workdir.display(),
+                message
+            ))
+            .await?;
+            blocking(move || commit(&workdir, &message, all)).await
+        }
+        "git_branch" => match str_arg("name") {
+            None => {
+                let workdir = open(policy, arguments, AccessKind::Read).await?;
+                blocking(move || branches(&workdir)).await
+            }
+            Some(name) => {
+                let workdir = open(policy, arguments, AccessKind::Write).await?;
+                let name = name.to_string();
+                let checkout = bool_arg("checkout");
+                approve(format!(
+                    "The assistant wants to create{} branch '{}' in {}. Allow?",
+                    if checkout { " and check out" } else { "" },
+                    name,
+                    workdir.display()
+                ))
+                .await?;
+                blocking(move || create_branch(&workdir, &name, checkout)).await
+            }
+        },
+        _ => Err(anyhow::anyhow!("Unknown git tool '{}'", tool_name)),
+    }
+}
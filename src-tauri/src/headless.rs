@@ -0,0 +1,493 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::PathBuf;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+use crate::mcp::{MCPServerConfig, ToolCallContext};
+use crate::webhooks::constant_time_eq;
+use crate::{
+    api, builtin, call_mcp_tool, events, expose_tools, get_app_data_dir, get_attachment_store,
+    get_mcp_manager, get_openapi_bridge, health, load_connection_snapshot, parse_server_configs,
+    prepare_server_config, redact, search_path, telemetry, APP_DATA_DIR,
+};
+
+// tauri.conf.json의 identifier와 같아야 GUI와 같은 app-data 디렉토리를 사용합니다
+pub(crate) const APP_IDENTIFIER: &str = "com.fritzprix.synapticflow";
+
+const USAGE: &str = "Usage: tauri-mcp-agent --headless [--listen <127.0.0.1:port>] [--config <servers.json>] [--no-restore]
+
+Serves newline-delimited JSON requests on stdin/stdout, or on a local TCP socket with --listen:
+  {\"id\": 1, \"method\": \"list_tools\", \"params\": {\"server_name\": \"fs\"}}
+
+Socket clients must first present the HTTP API token from api_settings.json, either with
+  {\"method\": \"authenticate\", \"params\": {\"token\": \"...\"}}
+or a top-level \"token\" field on their first request; anything else closes the connection.
+
+Methods: authenticate, servers, start_server, stop_server, list_tools, call_tool, health,
+events_since, shutdown
+
+The agent loop runs in the app window, so headless mode serves MCP servers and tools only.";
+
+#[derive(Debug, Default)]
+struct HeadlessOptions {
+    listen: Option<String>,
+    config: Option<PathBuf>,
+    restore: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: serde_json::Value,
+    method: String,
+    #[serde(default)]
+    params: serde_json::Value,
+    /// API token, accepted on any request of a socket client that is not authenticated yet
+    #[serde(default)]
+    token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Response {
+    id: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// `--headless`가 주어졌는지 확인합니다
+pub fn requested(args: &[String]) -> bool {
+    args.iter().any(|arg| arg == "--headless")
+}
+
+fn parse_options(args: &[String]) -> Result<HeadlessOptions> {
+    let mut options = HeadlessOptions {
+        restore: true,
+        ..Default::default()
+    };
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--headless" => {}
+            "--listen" => {
+                options.listen = Some(
+                    args.next()
+                        .cloned()
+                        .ok_or_else(|| anyhow::anyhow!("--listen requires an address"))?,
+                )
+            }
+            "--config" => {
+                options.config = Some(
+                    args.next()
+                        .map(PathBuf::from)
+                        .ok_or_else(|| anyhow::anyhow!("--config requires a path"))?,
+                )
+            }
+            "--no-restore" => options.restore = false,
+            other => return Err(anyhow::anyhow!("Unknown argument '{}'", other)),
+        }
+    }
+    Ok(options)
+}
+
+/// Move stdout to a private descriptor so stray `println!` logging cannot
+/// corrupt the protocol stream; fd 1 is pointed at stderr afterwards.
+#[cfg(unix)]
+fn take_stdout() -> Result<tokio::fs::File> {
+    use std::os::fd::FromRawFd;
+
+    // SAFETY: plain descriptor duplication; the duplicate is owned by the returned File
+    unsafe {
+        let protocol_fd = libc::dup(libc::STDOUT_FILENO);
+        if protocol_fd < 0 || libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        Ok(tokio::fs::File::from_std(std::fs::File::from_raw_fd(
+            protocol_fd,
+        )))
+    }
+}
+
+async fn dispatch(method: &str, params: serde_json::Value) -> Result<serde_json::Value> {
+    let param = |name: &str| -> Result<String> {
+        params
+            .get(name)
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("Missing string parameter '{}'", name))
+    };
+    let manager = get_mcp_manager();
+    match method {
+        "servers" => Ok(serde_json::to_value(manager.server_statuses().await)?),
+        "start_server" => {
+            let config: MCPServerConfig = serde_json::from_value(
+                params
+                    .get("config")
+                    .cloned()
+                    .ok_or_else(|| anyhow::anyhow!("Missing parameter 'config'"))?,
+            )?;
+            let config = prepare_server_config(config).await;
+            Ok(json!(manager.start_server(config).await?))
+        }
+        "stop_server" => {
+            manager.stop_server(&param("server_name")?).await?;
+            Ok(serde_json::Value::Null)
+        }
+        "list_tools" => match params.get("server_name").and_then(|v| v.as_str()) {
+            Some(builtin::SERVER_NAME) => Ok(serde_json::to_value(builtin::list_tools())?),
+            Some(server_name) if get_openapi_bridge().contains(server_name).await => Ok(
+                serde_json::to_value(get_openapi_bridge().list_tools(server_name).await)?,
+            ),
+            Some(server_name) => Ok(serde_json::to_value(
+                manager.list_tools(server_name).await?,
+            )?),
+            // 모든 서버의 도구를 모델에 보이는 이름으로 반환합니다
+            None => {
+                let mut tools = manager.list_all_tools().await?;
+                expose_tools(&mut tools).await;
+                Ok(serde_json::to_value(tools)?)
+            }
+        },
+        "call_tool" => {
+            let result = call_mcp_tool(
+                param("server_name")?,
+                param("tool_name")?,
+                params.get("arguments").cloned().unwrap_or(json!({})),
+                Some(ToolCallContext {
+                    session_id: param("session_id").ok(),
+                    agent_id: param("agent_id").ok(),
+                    window: None,
+                }),
+                params.get("bypass_cache").and_then(|v| v.as_bool()),
+            )
+            .await;
+            Ok(serde_json::to_value(result)?)
+        }
+        "health" => Ok(serde_json::to_value(
+            health::report(manager, get_attachment_store(), &get_app_data_dir()).await,
+        )?),
+        "chat" | "agent_run" => Err(anyhow::anyhow!(
+            "The agent loop runs in the app window; headless mode serves MCP servers and tools only"
+        )),
+        "events_since" => Ok(serde_json::to_value(events::since(
+            params.get("cursor").and_then(|v| v.as_u64()).unwrap_or(0),
+            params
+                .get("limit")
+                .and_then(|v| v.as_u64())
+                .map(|limit| limit as usize),
+        ))?),
+        _ => Err(anyhow::anyhow!("Unknown method '{}'", method)),
+    }
+}
+
+/// True when the request carries `token`, as its own field or as `authenticate`'s parameter
+fn presents_token(request: &Request, token: &str) -> bool {
+    let presented = request.token.as_deref().or_else(|| {
+        (request.method == "authenticate")
+            .then(|| request.params.get("token")?.as_str())
+            .flatten()
+    });
+    presented.is_some_and(|presented| constant_time_eq(presented.as_bytes(), token.as_bytes()))
+}
+
+async fn write_response<W: AsyncWrite + Unpin>(writer: &mut W, response: &Response) -> Result<()> {
+    let mut out = serde_json::to_vec(response)?;
+    out.push(b'\n');
+    writer.write_all(&out).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Serve one client until it disconnects; returns true when `shutdown` was requested.
+///
+/// With a `token`, nothing is dispatched until the client presents it; the first
+/// request without it is answered with an error and the connection is closed.
+async fn serve<R, W>(reader: R, mut writer: W, token: Option<&str>) -> Result<bool>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut lines = BufReader::new(reader).lines();
+    let mut authenticated = token.is_none();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let parsed = serde_json::from_str::<Request>(&line);
+        if !authenticated {
+            // 브라우저의 fetch 요청이나 다른 로컬 프로세스가 서버를 실행하지 못하도록 막습니다
+            match (&parsed, token) {
+                (Ok(request), Some(token)) if presents_token(request, token) => {
+                    authenticated = true;
+                }
+                _ => {
+                    let id = parsed.map(|request| request.id).unwrap_or_default();
+                    let response = Response {
+                        id,
+                        result: None,
+                        error: Some("Unauthorized: present the API token first".to_string()),
+                    };
+                    write_response(&mut writer, &response).await?;
+                    return Ok(false);
+                }
+            }
+        }
+        let (response, shutdown) = match parsed {
+            Ok(request) if request.method == "authenticate" => (
+                Response {
+                    id: request.id,
+                    result: Some(json!(true)),
+                    error: None,
+                },
+                false,
+            ),
+            Ok(request) if request.method == "shutdown" => (
+                Response {
+                    id: request.id,
+                    result: Some(serde_json::Value::Null),
+                    error: None,
+                },
+                true,
+            ),
+            Ok(request) => {
+                let response = match dispatch(&request.method, request.params).await {
+                    Ok(result) => Response {
+                        id: request.id,
+                        result: Some(result),
+                        error: None,
+                    },
+                    Err(e) => Response {
+                        id: request.id,
+                        result: None,
+                        error: Some(redact::redact(&e.to_string())),
+                    },
+                };
+                (response, false)
+            }
+            Err(e) => (
+                Response {
+                    id: serde_json::Value::Null,
+                    result: None,
+                    error: Some(format!("Invalid request: {}", e)),
+                },
+                false,
+            ),
+        };
+        write_response(&mut writer, &response).await?;
+        if shutdown {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+async fn start_configured_servers(options: &HeadlessOptions) -> Result<()> {
+    let mut configs = Vec::new();
+    if options.restore {
+        if let Some(snapshot) = load_connection_snapshot()
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?
+        {
+            let report = get_mcp_manager().restore(snapshot).await?;
+            for (name, error) in report.failed {
+                eprintln!("❌ Failed to restore server {}: {}", name, error);
+            }
+        }
+    }
+    if let Some(path) = &options.config {
+        let config: serde_json::Value =
+            serde_json::from_str(&tokio::fs::read_to_string(path).await?)?;
+        for server in parse_server_configs(&config).map_err(|e| anyhow::anyhow!(e))? {
+            configs.push(prepare_server_config(server).await);
+        }
+    }
+    if !configs.is_empty() {
+        let report = get_mcp_manager().start_servers(configs).await?;
+        for (name, error) in report.failed.iter().chain(report.skipped.iter()) {
+            eprintln!("❌ Failed to start server {}: {}", name, error);
+        }
+    }
+    Ok(())
+}
+
+async fn run_async(options: HeadlessOptions) -> Result<()> {
+    let app_data_dir = dirs::data_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine the data directory"))?
+        .join(APP_IDENTIFIER);
+    let _ = APP_DATA_DIR.set(app_data_dir);
+    if let Err(e) = redact::load_custom_rules(&get_app_data_dir().join("redaction_rules.json")) {
+        eprintln!("❌ Failed to load redaction rules: {}", e);
+    }
+    if let Err(e) = telemetry::load_config(&get_app_data_dir().join("telemetry.json")) {
+        eprintln!("❌ Failed to load telemetry config: {}", e);
+    }
+    if let Err(e) = search_path::load_settings(&get_app_data_dir().join("path_settings.json")) {
+        eprintln!("❌ Failed to load PATH settings: {}", e);
+    }
+    tokio::spawn(telemetry::run_exporter());
+    tokio::spawn(get_mcp_manager().run_idle_reaper());
+    get_openapi_bridge().load_saved().await;
+
+    start_configured_servers(&options).await?;
+
+    match &options.listen {
+        Some(address) => {
+            let token = api::load_settings(&get_app_data_dir())?.token;
+            let listener = TcpListener::bind(address).await?;
+            // 토큰이 평문으로 오가므로 로컬 주소만 허용합니다
+            if !listener.local_addr()?.ip().is_loopback() {
+                return Err(anyhow::anyhow!(
+                    "Refusing to listen on non-loopback address {}",
+                    address
+                ));
+            }
+            eprintln!("Headless agent listening on {}", listener.local_addr()?);
+            loop {
+                let (stream, peer) = listener.accept().await?;
+                eprintln!("Client connected: {}", peer);
+                let (reader, writer) = stream.into_split();
+                if serve(reader, writer, Some(&token)).await? {
+                    break;
+                }
+            }
+        }
+        None => {
+            #[cfg(unix)]
+            let writer = take_stdout()?;
+            #[cfg(not(unix))]
+            let writer = tokio::io::stdout();
+            // 표준 입출력은 실행한 사용자만 쓸 수 있으므로 토큰이 필요 없습니다
+            serve(tokio::io::stdin(), writer, None).await?;
+        }
+    }
+
+    for server_name in get_mcp_manager().get_connected_servers().await {
+        let _ = get_mcp_manager().stop_server(&server_name).await;
+    }
+    Ok(())
+}
+
+/// 창을 만들지 않고 MCP 매니저와 도구를 표준 입출력 또는 로컬 소켓으로 제공합니다
+///
+/// Uses the same app-data directory, saved session and redaction rules as the
+/// desktop app, so scripted runs in CI or over SSH see the same servers.
+pub fn run(args: Vec<String>) -> i32 {
+    let options = match parse_options(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("❌ Failed to start runtime: {}", e);
+            return 1;
+        }
+    };
+    match runtime.block_on(run_async(options)) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn exchange(lines: &str, token: Option<&str>) -> (bool, Vec<serde_json::Value>) {
+        let (mut client, server) = tokio::io::duplex(64 * 1024);
+        let (reader, writer) = tokio::io::split(server);
+        client.write_all(lines.as_bytes()).await.unwrap();
+        client.shutdown().await.unwrap();
+        let shutdown = serve(reader, writer, token).await.unwrap();
+
+        let mut output = String::new();
+        tokio::io::AsyncReadExt::read_to_string(&mut client, &mut output)
+            .await
+            .unwrap();
+        let responses = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        (shutdown, responses)
+    }
+
+    #[tokio::test]
+    async fn rejects_clients_without_the_token() {
+        let (shutdown, responses) = exchange(
+            "{\"id\": 1, \"method\": \"shutdown\"}\n{\"id\": 2, \"method\": \"shutdown\"}\n",
+            Some("secret"),
+        )
+        .await;
+        assert!(!shutdown);
+        assert_eq!(responses.len(), 1);
+        assert!(responses[0]["error"]
+            .as_str()
+            .unwrap()
+            .contains("Unauthorized"));
+    }
+
+    #[tokio::test]
+    async fn rejects_http_requests() {
+        let (shutdown, responses) = exchange(
+            "POST / HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n{\"method\": \"shutdown\"}\n",
+            Some("secret"),
+        )
+        .await;
+        assert!(!shutdown);
+        assert_eq!(responses.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_tokens() {
+        let (shutdown, responses) = exchange(
+            "{\"id\": 1, \"method\": \"authenticate\", \"params\": {\"token\": \"guess\"}}\n{\"id\": 2, \"method\": \"shutdown\", \"token\": \"secret\"}\n",
+            Some("secret"),
+        )
+        .await;
+        assert!(!shutdown);
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0]["id"], 1);
+    }
+
+    #[tokio::test]
+    async fn accepts_the_token_once_per_connection() {
+        let (shutdown, responses) = exchange(
+            "{\"id\": 1, \"method\": \"authenticate\", \"params\": {\"token\": \"secret\"}}\n{\"id\": 2, \"method\": \"nope\"}\n{\"id\": 3, \"method\": \"shutdown\"}\n",
+            Some("secret"),
+        )
+        .await;
+        assert!(shutdown);
+        assert_eq!(responses[0]["result"], true);
+        assert!(responses[1]["error"]
+            .as_str()
+            .unwrap()
+            .contains("Unknown method"));
+        assert_eq!(responses[2]["id"], 3);
+    }
+
+    #[tokio::test]
+    async fn accepts_the_token_on_the_first_request() {
+        let (shutdown, responses) = exchange(
+            "{\"id\": 1, \"method\": \"shutdown\", \"token\": \"secret\"}\n",
+            Some("secret"),
+        )
+        .await;
+        assert!(shutdown);
+        assert_eq!(responses[0]["id"], 1);
+    }
+
+    #[tokio::test]
+    async fn stdio_needs_no_token() {
+        let (shutdown, _) = exchange("{\"id\": 1, \"method\": \"shutdown\"}\n", None).await;
+        assert!(shutdown);
+    }
+}
@@ -0,0 +1,216 @@
+use serde::Serialize;
+use std::path::Path;
+use std::time::Instant;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tokio::net::TcpStream;
+
+use crate::attachments::AttachmentStore;
+use crate::integrity;
+use crate::limits;
+use crate::mcp::{MCPServerManager, ServerStatus};
+use crate::retention::{self, StorageStats};
+
+const REACHABILITY_TIMEOUT_SECS: u64 = 3;
+
+// LLM 제공자 API 호스트 (TCP 연결만 확인합니다)
+const LLM_PROVIDER_HOSTS: &[(&str, &str)] = &[
+    ("openai", "api.openai.com:443"),
+    ("anthropic", "api.anthropic.com:443"),
+    ("groq", "api.groq.com:443"),
+    ("gemini", "generativelanguage.googleapis.com:443"),
+    ("fireworks", "api.fireworks.ai:443"),
+    ("cerebras", "api.cerebras.ai:443"),
+];
+
+// 설정 파일 무결성 검사 대상
+const CONFIG_FILES: &[&str] = &[
+    "prompt_templates.json",
+    "workspace_roots.json",
+    "retention.json",
+    "redaction_rules.json",
+    "connection_snapshot.json",
+    "disabled_instructions.json",
+    "telemetry.json",
+    "crash_settings.json",
+    "path_settings.json",
+];
+
+// 서버 실행에 흔히 쓰이는 런타임
+pub(crate) const RUNTIME_COMMANDS: &[&str] = &["node", "npx", "uvx", "python3", "docker", "podman"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerHealth {
+    #[serde(flatten)]
+    pub status: ServerStatus,
+    /// Summed RSS of the server's process tree
+    pub memory_bytes: Option<u64>,
+    pub process_count: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigFileHealth {
+    pub file: String,
+    pub exists: bool,
+    pub valid: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderReachability {
+    pub provider: String,
+    pub host: String,
+    pub reachable: bool,
+    pub latency_ms: Option<f64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvironmentInfo {
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub app_data_dir: String,
+    pub path_entries: usize,
+    /// Resolved location of common server runtimes, `None` when not on PATH
+    pub runtimes: Vec<(String, Option<String>)>,
+}
+
+/// Everything needed for a bug report, gathered in one call
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub generated_at: String,
+    pub servers: Vec<ServerHealth>,
+    pub config_files: Vec<ConfigFileHealth>,
+    pub storage: Option<StorageStats>,
+    pub storage_error: Option<String>,
+    pub providers: Vec<ProviderReachability>,
+    pub environment: EnvironmentInfo,
+}
+
+fn server_health(statuses: Vec<ServerStatus>) -> Vec<ServerHealth> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_memory(),
+    );
+    statuses
+        .into_iter()
+        .map(|status| {
+            let tree = status
+                .pid
+                .map(Pid::from_u32)
+                .filter(|pid| system.process(*pid).is_some())
+                .map(|pid| limits::process_tree(&system, pid));
+            ServerHealth {
+                memory_bytes: tree.as_ref().map(|tree| {
+                    tree.iter()
+                        .filter_map(|pid| system.process(*pid))
+                        .map(|process| process.memory())
+                        .sum()
+                }),
+                process_count: tree.as_ref().map(|tree| tree.len()),
+                status,
+            }
+        })
+        .collect()
+}
+
+async fn config_file_health(app_data_dir: &Path) -> Vec<ConfigFileHealth> {
+    let mut files = Vec::new();
+    for file in CONFIG_FILES {
+        let (exists, error) = match tokio::fs::read_to_string(app_data_dir.join(file)).await {
+            Ok(content) => (
+                true,
+                serde_json::from_str::<serde_json::Value>(&content)
+                    .err()
+                    .map(|e| e.to_string()),
+            ),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (false, None),
+            Err(e) => (true, Some(e.to_string())),
+        };
+        files.push(ConfigFileHealth {
+            file: file.to_string(),
+            exists,
+            valid: error.is_none(),
+            error,
+        });
+    }
+    files
+}
+
+async fn check_provider(provider: &str, host: &str) -> ProviderReachability {
+    let started = Instant::now();
+    let result = tokio::time::timeout(
+        tokio::time::Duration::from_secs(REACHABILITY_TIMEOUT_SECS),
+        TcpStream::connect(host),
+    )
+    .await;
+    let (reachable, error) = match result {
+        Ok(Ok(_)) => (true, None),
+        Ok(Err(e)) => (false, Some(e.to_string())),
+        Err(_) => (false, Some("timed out".to_string())),
+    };
+    ProviderReachability {
+        provider: provider.to_string(),
+        host: host.to_string(),
+        reachable,
+        latency_ms: reachable.then(|| started.elapsed().as_secs_f64() * 1000.0),
+        error,
+    }
+}
+
+fn environment(app_data_dir: &Path) -> EnvironmentInfo {
+    EnvironmentInfo {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        app_data_dir: app_data_dir.display().to_string(),
+        path_entries: std::env::var_os("PATH")
+            .map(|path| std::env::split_paths(&path).count())
+            .unwrap_or(0),
+        runtimes: RUNTIME_COMMANDS
+            .iter()
+            .map(|command| {
+                (
+                    command.to_string(),
+                    integrity::resolve_executable(command).map(|p| p.display().to_string()),
+                )
+            })
+            .collect(),
+    }
+}
+
+/// 버그 리포트에 붙여넣을 수 있는 전체 상태 보고서를 만듭니다
+pub async fn report(
+    manager: &MCPServerManager,
+    store: &AttachmentStore,
+    app_data_dir: &Path,
+) -> HealthReport {
+    let statuses = manager.server_statuses().await;
+    let servers = tokio::task::spawn_blocking(move || server_health(statuses))
+        .await
+        .unwrap_or_default();
+
+    let providers = futures::future::join_all(
+        LLM_PROVIDER_HOSTS
+            .iter()
+            .map(|(provider, host)| check_provider(provider, host)),
+    )
+    .await;
+
+    let (storage, storage_error) = match retention::storage_stats(app_data_dir, store).await {
+        Ok(stats) => (Some(stats), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+
+    HealthReport {
+        generated_at: chrono::Local::now().to_rfc3339(),
+        servers,
+        config_files: config_file_health(app_data_dir).await,
+        storage,
+        storage_error,
+        providers,
+        environment: environment(app_data_dir),
+    }
+}
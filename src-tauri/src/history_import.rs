@@ -0,0 +1,431 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::Read;
+use std::path::Path;
+
+use crate::api::ask_webview;
+
+// 내보내기 파일은 크지만 한 번에 메모리로 읽으므로 상한을 둡니다
+const MAX_EXPORT_BYTES: u64 = 512 * 1024 * 1024;
+// 두 서비스 모두 데이터 내보내기 ZIP 안에 이 이름으로 대화를 담습니다
+const CONVERSATIONS_FILE: &str = "conversations.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryFormat {
+    /// `conversations.json` from a ChatGPT data export
+    Chatgpt,
+    /// `conversations.json` from a Claude data export
+    Claude,
+}
+
+impl HistoryFormat {
+    fn tag(self) -> &'static str {
+        match self {
+            HistoryFormat::Chatgpt => "chatgpt",
+            HistoryFormat::Claude => "claude",
+        }
+    }
+}
+
+/// Sessions and messages in the shape of the webview's database rows
+#[derive(Debug, Default, Serialize)]
+struct HistoryImport {
+    sessions: Vec<Value>,
+    messages: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryImportReport {
+    pub sessions: usize,
+    pub messages: usize,
+    /// Conversations without any text message
+    pub skipped: usize,
+}
+
+/// Import a ChatGPT or Claude export into the session store.
+///
+/// `path` is either the export ZIP or the `conversations.json` inside it.
+/// Ids are derived from the export, so importing the same file again updates
+/// the sessions instead of duplicating them.
+pub async fn import_history(path: &Path, format: HistoryFormat) -> Result<HistoryImportReport> {
+    let size = tokio::fs::metadata(path).await?.len();
+    if size > MAX_EXPORT_BYTES {
+        return Err(anyhow::anyhow!(
+            "{} is too large to import ({} bytes)",
+            path.display(),
+            size
+        ));
+    }
+    let path = path.to_path_buf();
+    let content = tokio::task::spawn_blocking(move || read_conversations(&path)).await??;
+    let document: Value = serde_json::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("The export is not valid JSON: {}", e))?;
+    let conversations = document
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("The export does not contain a list of conversations"))?;
+
+    let (import, skipped) = parse(conversations, format);
+    let report = HistoryImportReport {
+        sessions: import.sessions.len(),
+        messages: import.messages.len(),
+        skipped,
+    };
+    if !import.sessions.is_empty() {
+        ask_webview("history-import", serde_json::to_value(&import)?)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to store imported sessions: {}", e))?;
+    }
+    Ok(report)
+}
+
+fn read_conversations(path: &Path) -> Result<String> {
+    let is_zip = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+    if !is_zip {
+        return Ok(std::fs::read_to_string(path)?);
+    }
+    let mut zip = zip::ZipArchive::new(std::fs::File::open(path)?)?;
+    // 최상위에 있지 않은 내보내기도 있어 파일 이름만 비교합니다
+    let index = (0..zip.len())
+        .find(|i| {
+            zip.name_for_index(*i)
+                .is_some_and(|name| name.rsplit('/').next() == Some(CONVERSATIONS_FILE))
+        })
+        .ok_or_else(|| anyhow::anyhow!("{} has no {}", path.display(), CONVERSATIONS_FILE))?;
+    let mut content = String::new();
+    zip.by_index(index)?.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+/// Map exported conversations to session and message rows; returns the number
+/// of conversations left out because they had no text
+fn parse(conversations: &[Value], format: HistoryFormat) -> (HistoryImport, usize) {
+    let mut import = HistoryImport::default();
+    let mut skipped = 0;
+    for conversation in conversations {
+        let parsed = match format {
+            HistoryFormat::Chatgpt => chatgpt_conversation(conversation),
+            HistoryFormat::Claude => claude_conversation(conversation),
+        };
+        let Some(conversation) = parsed.filter(|c| !c.messages.is_empty()) else {
+            skipped += 1;
+            continue;
+        };
+
+        let session_id = format!("{}-{}", format.tag(), conversation.id);
+        let created_at = conversation
+            .created_at
+            .clone()
+            .or_else(|| conversation.messages.first()?.created_at.clone());
+        let updated_at = conversation
+            .updated_at
+            .clone()
+            .or_else(|| conversation.messages.last()?.created_at.clone())
+            .or_else(|| created_at.clone());
+        import.sessions.push(json!({
+            "id": session_id,
+            "type": "single",
+            "assistants": [],
+            "name": conversation.title,
+            "tags": ["imported", format.tag()],
+            "createdAt": created_at,
+            "updatedAt": updated_at,
+        }));
+        for message in conversation.messages {
+            import.messages.push(json!({
+                "id": format!("{}-{}", session_id, message.id),
+                "sessionId": session_id,
+                "role": message.role,
+                "content": message.content,
+                "createdAt": message.created_at,
+                "updatedAt": message.created_at,
+            }));
+        }
+    }
+    (import, skipped)
+}
+
+struct Conversation {
+    id: String,
+    title: Option<String>,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+    messages: Vec<ImportedMessage>,
+}
+
+struct ImportedMessage {
+    id: String,
+    role: &'static str,
+    content: String,
+    created_at: Option<String>,
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn new_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// ChatGPT stores times as fractional Unix seconds
+fn unix_time(value: &Value, key: &str) -> Option<String> {
+    let seconds = value.get(key)?.as_f64()?;
+    chrono::DateTime::from_timestamp_millis((seconds * 1000.0) as i64).map(|t| t.to_rfc3339())
+}
+
+fn chatgpt_conversation(conversation: &Value) -> Option<Conversation> {
+    let mapping = conversation.get("mapping")?.as_object()?;
+    // 편집·재생성으로 갈라진 대화는 마지막으로 본 가지(current_node)만 가져옵니다
+    let mut path = Vec::new();
+    let mut node_id = str_field(conversation, "current_node");
+    while let Some(id) = node_id {
+        let Some(node) = mapping.get(&id) else { break };
+        if path.len() > mapping.len() {
+            break;
+        }
+        path.push(node);
+        node_id = str_field(node, "parent");
+    }
+    path.reverse();
+    if path.is_empty() {
+        // current_node가 없는 오래된 내보내기는 시간순으로 정렬합니다
+        path = mapping.values().collect();
+        path.sort_by(|a, b| {
+            let time = |node: &Value| {
+                node.pointer("/message/create_time")
+                    .and_then(|t| t.as_f64())
+            };
+            time(a)
+                .partial_cmp(&time(b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+    }
+
+    let messages = path
+        .into_iter()
+        .filter_map(|node| {
+            let message = node.get("message")?;
+            let hidden = message
+                .pointer("/metadata/is_visually_hidden_from_conversation")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            if hidden {
+                return None;
+            }
+            let role = match message.pointer("/author/role")?.as_str()? {
+                "user" => "user",
+                "assistant" => "assistant",
+                "system" => "system",
+                "tool" => "tool",
+                _ => return None,
+            };
+            let content = message.get("content")?;
+            let text = match content.get("parts").and_then(|p| p.as_array()) {
+                // 이미지 등 문자열이 아닌 부분은 건너뜁니다
+                Some(parts) => parts
+                    .iter()
+                    .filter_map(|part| part.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                None => str_field(content, "text").unwrap_or_default(),
+            };
+            if text.trim().is_empty() {
+                return None;
+            }
+            Some(ImportedMessage {
+                id: str_field(message, "id").unwrap_or_else(new_id),
+                role,
+                content: text,
+                created_at: unix_time(message, "create_time"),
+            })
+        })
+        .collect();
+
+    Some(Conversation {
+        id: str_field(conversation, "id")
+            .or_else(|| str_field(conversation, "conversation_id"))
+            .unwrap_or_else(new_id),
+        title: str_field(conversation, "title"),
+        created_at: unix_time(conversation, "create_time"),
+        updated_at: unix_time(conversation, "update_time"),
+        messages,
+    })
+}
+
+fn claude_conversation(conversation: &Value) -> Option<Conversation> {
+    let messages = conversation
+        .get("chat_messages")?
+        .as_array()?
+        .iter()
+        .filter_map(|message| {
+            let role = match message.get("sender")?.as_str()? {
+                "human" => "user",
+                "assistant" => "assistant",
+                _ => return None,
+            };
+            // 최근 내보내기는 text를 비우고 content 블록에만 내용을 담기도 합니다
+            let text = str_field(message, "text").unwrap_or_else(|| {
+                message
+                    .get("content")
+                    .and_then(|c| c.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter(|block| block.get("type").and_then(|t| t.as_str()) == Some("text"))
+                    .filter_map(|block| block.get("text")?.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            });
+            if text.trim().is_empty() {
+                return None;
+            }
+            Some(ImportedMessage {
+                id: str_field(message, "uuid").unwrap_or_else(new_id),
+                role,
+                content: text,
+                created_at: str_field(message, "created_at"),
+            })
+        })
+        .collect();
+
+    Some(Conversation {
+        id: str_field(conversation, "uuid").unwrap_or_else(new_id),
+        title: str_field(conversation, "name"),
+        created_at: str_field(conversation, "created_at"),
+        updated_at: str_field(conversation, "updated_at"),
+        messages,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_the_current_chatgpt_branch() {
+        let export = json!([{
+            "id": "conv-1",
+            "title": "Trip planning",
+            "create_time": 1700000000.5,
+            "update_time": 1700000100.0,
+            "current_node": "b",
+            "mapping": {
+                "root": { "id": "root", "message": null, "parent": null },
+                "sys": {
+                    "id": "sys",
+                    "parent": "root",
+                    "message": {
+                        "id": "sys",
+                        "author": { "role": "system" },
+                        "content": { "content_type": "text", "parts": [""] },
+                        "metadata": { "is_visually_hidden_from_conversation": true }
+                    }
+                },
+                "q": {
+                    "id": "q",
+                    "parent": "sys",
+                    "message": {
+                        "id": "q",
+                        "author": { "role": "user" },
+                        "create_time": 1700000001.0,
+                        "content": { "content_type": "text", "parts": ["Where to go?"] }
+                    }
+                },
+                "a": {
+                    "id": "a",
+                    "parent": "q",
+                    "message": {
+                        "id": "a",
+                        "author": { "role": "assistant" },
+                        "content": { "content_type": "text", "parts": ["Discarded answer"] }
+                    }
+                },
+                "b": {
+                    "id": "b",
+                    "parent": "q",
+                    "message": {
+                        "id": "b",
+                        "author": { "role": "assistant" },
+                        "content": {
+                            "content_type": "multimodal_text",
+                            "parts": [{ "content_type": "image_asset_pointer" }, "Try Lisbon."]
+                        }
+                    }
+                }
+            }
+        }]);
+        let (import, skipped) = parse(export.as_array().unwrap(), HistoryFormat::Chatgpt);
+        assert_eq!(skipped, 0);
+        assert_eq!(import.sessions.len(), 1);
+        assert_eq!(import.sessions[0]["id"], "chatgpt-conv-1");
+        assert_eq!(import.sessions[0]["name"], "Trip planning");
+        assert_eq!(import.sessions[0]["tags"], json!(["imported", "chatgpt"]));
+
+        let contents: Vec<_> = import.messages.iter().map(|m| &m["content"]).collect();
+        assert_eq!(contents, vec!["Where to go?", "Try Lisbon."]);
+        assert_eq!(import.messages[0]["id"], "chatgpt-conv-1-q");
+        assert_eq!(import.messages[0]["role"], "user");
+        assert_eq!(import.messages[0]["createdAt"], "2023-11-14T22:13:21+00:00");
+        assert_eq!(import.messages[1]["role"], "assistant");
+    }
+
+    #[test]
+    fn maps_claude_senders_and_content_blocks() {
+        let export = json!([
+            {
+                "uuid": "c-1",
+                "name": "Refactor",
+                "created_at": "2024-05-01T10:00:00Z",
+                "updated_at": "2024-05-01T10:05:00Z",
+                "chat_messages": [
+                    { "uuid": "m-1", "sender": "human", "text": "Split this fn", "created_at": "2024-05-01T10:00:00Z" },
+                    {
+                        "uuid": "m-2",
+                        "sender": "assistant",
+                        "text": "",
+                        "content": [
+                            { "type": "text", "text": "Sure." },
+                            { "type": "tool_use", "name": "x" }
+                        ]
+                    }
+                ]
+            },
+            { "uuid": "c-2", "name": "Empty", "chat_messages": [] }
+        ]);
+        let (import, skipped) = parse(export.as_array().unwrap(), HistoryFormat::Claude);
+        assert_eq!(skipped, 1);
+        assert_eq!(import.sessions.len(), 1);
+        assert_eq!(import.sessions[0]["id"], "claude-c-1");
+        assert_eq!(import.sessions[0]["updatedAt"], "2024-05-01T10:05:00Z");
+
+        assert_eq!(import.messages.len(), 2);
+        assert_eq!(import.messages[0]["role"], "user");
+        assert_eq!(import.messages[0]["sessionId"], "claude-c-1");
+        assert_eq!(import.messages[1]["role"], "assistant");
+        assert_eq!(import.messages[1]["content"], "Sure.");
+    }
+
+    #[test]
+    fn reads_conversations_from_an_export_zip() {
+        use std::io::Write;
+        use zip::write::SimpleFileOptions;
+
+        let path = std::env::temp_dir().join(format!("history-{}.zip", uuid::Uuid::new_v4()));
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+        zip.start_file("export/conversations.json", SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(b"[]").unwrap();
+        zip.finish().unwrap();
+
+        let content = read_conversations(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(content.unwrap(), "[]");
+    }
+}
@@ -0,0 +1,135 @@
+use futures::future::BoxFuture;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+
+use crate::mcp::{MCPToolAnnotations, ToolCallContext, ToolCallResult};
+
+/// A tool call as seen by hooks
+#[derive(Debug, Clone)]
+pub struct ToolCall {
+    pub server_name: String,
+    pub tool_name: String,
+    pub arguments: serde_json::Value,
+    pub context: ToolCallContext,
+    /// Annotations the owning server declared for the tool, if known
+    pub annotations: Option<MCPToolAnnotations>,
+    /// Set by callers that want a fresh result rather than a cached one
+    pub bypass_cache: bool,
+}
+
+pub enum PreCall {
+    Continue,
+    /// Answer the call without running the tool
+    Respond(ToolCallResult),
+    /// Refuse the call; the reason is returned to the model as the error
+    Veto(String),
+}
+
+/// Middleware around tool execution.
+///
+/// Hooks run in registration order before the call and in reverse order after it,
+/// so the first registered hook wraps all the others.
+pub trait ToolHook: Send + Sync {
+    /// Unique name, used to replace or unregister the hook
+    fn name(&self) -> &str;
+
+    /// May rewrite `call.arguments`, answer the call itself or veto it
+    fn before_call<'a>(&'a self, _call: &'a mut ToolCall) -> BoxFuture<'a, PreCall> {
+        Box::pin(async { PreCall::Continue })
+    }
+
+    /// May rewrite or annotate the result; skipped for the hook that answered the call
+    /// and for the hooks registered after it
+    fn after_call<'a>(
+        &'a self,
+        _call: &'a ToolCall,
+        _result: &'a mut ToolCallResult,
+    ) -> BoxFuture<'a, ()> {
+        Box::pin(async {})
+    }
+}
+
+/// How a call was completed
+pub struct HookedResult {
+    pub result: ToolCallResult,
+    /// Name of the hook that answered or vetoed the call instead of the tool
+    pub answered_by: Option<String>,
+}
+
+#[derive(Default)]
+pub struct HookRegistry {
+    hooks: RwLock<Vec<Arc<dyn ToolHook>>>,
+}
+
+impl HookRegistry {
+    /// Add a hook after the existing ones, replacing any hook with the same name in place
+    pub fn register(&self, hook: Arc<dyn ToolHook>) {
+        let mut hooks = self.hooks.write().unwrap_or_else(|e| e.into_inner());
+        match hooks
+            .iter_mut()
+            .find(|existing| existing.name() == hook.name())
+        {
+            Some(existing) => *existing = hook,
+            None => hooks.push(hook),
+        }
+    }
+
+    pub fn unregister(&self, name: &str) -> bool {
+        let mut hooks = self.hooks.write().unwrap_or_else(|e| e.into_inner());
+        let before = hooks.len();
+        hooks.retain(|hook| hook.name() != name);
+        hooks.len() != before
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.hooks
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|hook| hook.name().to_string())
+            .collect()
+    }
+
+    /// Run `call` through every hook, calling `execute` unless a hook answers or vetoes it
+    pub async fn run<F, Fut>(&self, mut call: ToolCall, execute: F) -> HookedResult
+    where
+        F: FnOnce(ToolCall) -> Fut,
+        Fut: Future<Output = ToolCallResult>,
+    {
+        // 호출 도중 등록이 바뀌어도 영향을 받지 않도록 목록을 복사해 둡니다
+        let hooks = self.hooks.read().unwrap_or_else(|e| e.into_inner()).clone();
+
+        let mut entered = 0;
+        let mut answered = None;
+        for hook in &hooks {
+            match hook.before_call(&mut call).await {
+                PreCall::Continue => entered += 1,
+                PreCall::Respond(result) => {
+                    answered = Some((hook.name().to_string(), result));
+                    break;
+                }
+                PreCall::Veto(reason) => {
+                    let result = ToolCallResult {
+                        success: false,
+                        result: None,
+                        error: Some(format!("Blocked by '{}': {}", hook.name(), reason)),
+                    };
+                    answered = Some((hook.name().to_string(), result));
+                    break;
+                }
+            }
+        }
+
+        let (mut result, answered_by) = match answered {
+            Some((name, result)) => (result, Some(name)),
+            None => (execute(call.clone()).await, None),
+        };
+        for hook in hooks[..entered].iter().rev() {
+            hook.after_call(&call, &mut result).await;
+        }
+        HookedResult {
+            result,
+            answered_by,
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::{autostart, events, parse_server_configs, redact, windows};
+
+/// URL scheme the app handles, e.g. `tauriagent://run?agent=research&prompt=...`
+const URL_SCHEME: &str = "tauriagent";
+const INSTANCE_FILE: &str = "instance.json";
+const CONNECT_TIMEOUT_MS: u64 = 500;
+const REPLY_TIMEOUT_SECS: u64 = 5;
+// 전달되는 인수 한 줄의 최대 크기
+const MAX_MESSAGE_BYTES: u64 = 64 * 1024;
+
+/// Where the running instance listens for later launches
+#[derive(Debug, Serialize, Deserialize)]
+struct InstanceInfo {
+    port: u16,
+    token: String,
+    pid: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ForwardedLaunch {
+    token: String,
+    args: Vec<String>,
+    cwd: Option<PathBuf>,
+}
+
+/// What a launch asked the app to do
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum LaunchRequest {
+    /// `tauri-agent chat "question"`: start a chat with the question
+    Chat { prompt: String },
+    /// A file opened with the app
+    OpenFile { path: PathBuf },
+    /// `tauriagent://run?agent=<name or id>&prompt=...`: run a saved assistant
+    RunAgent { agent: String, prompt: String },
+    /// `tauriagent://install-server?config=<json>`: open the add-server form prefilled with
+    /// an `mcpServers` config
+    InstallServer { config: serde_json::Value },
+}
+
+// 프론트엔드가 가져갈 때까지 보관하는 요청
+static PENDING: Mutex<Vec<LaunchRequest>> = Mutex::new(Vec::new());
+
+/// Requests in a command line (executable first). Flags are skipped so arguments the OS
+/// adds do not get in the way.
+pub fn parse_args(args: &[String], cwd: Option<&Path>) -> Vec<LaunchRequest> {
+    let mut args = args.iter().skip(1).peekable();
+    if args.peek().is_some_and(|arg| *arg == "chat") {
+        let prompt = args.skip(1).cloned().collect::<Vec<_>>().join(" ");
+        return if prompt.trim().is_empty() {
+            Vec::new()
+        } else {
+            vec![LaunchRequest::Chat { prompt }]
+        };
+    }
+    args.filter(|arg| !arg.starts_with('-'))
+        .filter_map(|arg| {
+            // Windows와 Linux는 링크를 인수로 전달합니다
+            if arg.starts_with(&format!("{}:", URL_SCHEME)) {
+                return parse_url(arg);
+            }
+            let path = PathBuf::from(arg);
+            let path = match cwd {
+                Some(cwd) if path.is_relative() => cwd.join(path),
+                _ => path,
+            };
+            Some(LaunchRequest::OpenFile { path })
+        })
+        .collect()
+}
+
+/// Requests in URLs the OS opened the app with; macOS sends these as an event rather than
+/// as arguments
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub fn parse_urls(urls: &[url::Url]) -> Vec<LaunchRequest> {
+    urls.iter()
+        .filter_map(|url| match url.scheme() {
+            "file" => url
+                .to_file_path()
+                .ok()
+                .map(|path| LaunchRequest::OpenFile { path }),
+            _ => parse_url(url.as_str()),
+        })
+        .collect()
+}
+
+fn parse_url(link: &str) -> Option<LaunchRequest> {
+    let result = (|| -> Result<LaunchRequest> {
+        let url = url::Url::parse(link)?;
+        if url.scheme() != URL_SCHEME {
+            return Err(anyhow::anyhow!("Unsupported scheme '{}'", url.scheme()));
+        }
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.to_string())
+                .filter(|value| !value.trim().is_empty())
+                .ok_or_else(|| anyhow::anyhow!("Missing parameter '{}'", name))
+        };
+        match url.host_str() {
+            Some("run") => Ok(LaunchRequest::RunAgent {
+                agent: param("agent")?,
+                prompt: param("prompt")?,
+            }),
+            Some("install-server") => {
+                let config: serde_json::Value = serde_json::from_str(&param("config")?)?;
+                if !config.get("mcpServers").is_some_and(|v| v.is_object()) {
+                    return Err(anyhow::anyhow!("The config has no mcpServers object"));
+                }
+                // 폼을 열기 전에 서버 설정이 올바른지 확인합니다
+                parse_server_configs(&config).map_err(|e| anyhow::anyhow!(e))?;
+                Ok(LaunchRequest::InstallServer { config })
+            }
+            other => Err(anyhow::anyhow!(
+                "Unknown action '{}'",
+                other.unwrap_or_default()
+            )),
+        }
+    })();
+    match result {
+        Ok(request) => Some(request),
+        Err(e) => {
+            eprintln!("❌ Ignoring link {}: {}", redact::redact(link), e);
+            None
+        }
+    }
+}
+
+/// Hand this launch to an instance that is already running. False when none answers, in
+/// which case this process goes on to become the running instance.
+pub fn forward(app_data_dir: &Path, args: &[String]) -> bool {
+    let Some(info) = std::fs::read_to_string(app_data_dir.join(INSTANCE_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str::<InstanceInfo>(&content).ok())
+    else {
+        return false;
+    };
+    let result = (|| -> Result<bool> {
+        let address = SocketAddr::from(([127, 0, 0, 1], info.port));
+        let mut stream =
+            TcpStream::connect_timeout(&address, Duration::from_millis(CONNECT_TIMEOUT_MS))?;
+        stream.set_read_timeout(Some(Duration::from_secs(REPLY_TIMEOUT_SECS)))?;
+        let launch = ForwardedLaunch {
+            token: info.token,
+            args: args.to_vec(),
+            cwd: std::env::current_dir().ok(),
+        };
+        writeln!(stream, "{}", serde_json::to_string(&launch)?)?;
+        let mut reply = String::new();
+        BufReader::new(stream).read_line(&mut reply)?;
+        Ok(reply.trim() == "ok")
+    })();
+    // 연결되지 않으면 이전 실행이 남긴 파일이므로 새 인스턴스로 시작합니다
+    result.unwrap_or(false)
+}
+
+/// Accept launches forwarded by later processes for as long as the app runs
+pub async fn listen(app: AppHandle, app_data_dir: PathBuf) -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let info = InstanceInfo {
+        port: listener.local_addr()?.port(),
+        token: uuid::Uuid::new_v4().simple().to_string(),
+        pid: std::process::id(),
+    };
+    tokio::fs::create_dir_all(&app_data_dir).await?;
+    tokio::fs::write(
+        app_data_dir.join(INSTANCE_FILE),
+        serde_json::to_string(&info)?,
+    )
+    .await?;
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let app = app.clone();
+        let token = info.token.clone();
+        tokio::spawn(async move {
+            if let Err(e) = accept(&app, stream, &token).await {
+                eprintln!("❌ Failed to accept a forwarded launch: {}", e);
+            }
+        });
+    }
+}
+
+async fn accept(app: &AppHandle, stream: tokio::net::TcpStream, token: &str) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    tokio::io::BufReader::new(reader.take(MAX_MESSAGE_BYTES))
+        .read_line(&mut line)
+        .await?;
+    let launch: ForwardedLaunch = serde_json::from_str(&line)?;
+    if launch.token != token {
+        writer.write_all(b"denied\n").await?;
+        return Err(anyhow::anyhow!("Rejected a launch with the wrong token"));
+    }
+    writer.write_all(b"ok\n").await?;
+    // 로그인 항목이 이미 실행 중인 앱을 앞으로 가져오지 않도록 합니다
+    if !autostart::requested(&launch.args) {
+        deliver(app, parse_args(&launch.args, launch.cwd.as_deref()));
+    }
+    Ok(())
+}
+
+/// Bring the app to the front and queue the requests for the frontend, which is told with
+/// a `launch-request` event and collects them with [`take_pending`]
+pub fn deliver(app: &AppHandle, requests: Vec<LaunchRequest>) {
+    let Some(label) = windows::show(app) else {
+        return;
+    };
+    if requests.is_empty() {
+        return;
+    }
+    PENDING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .extend(requests);
+    events::emit_to(&label, "launch-request", ());
+}
+
+/// Launch requests not yet handled; each is returned once
+pub fn take_pending() -> Vec<LaunchRequest> {
+    std::mem::take(&mut *PENDING.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Make this executable the handler for `tauriagent://` links. macOS takes the scheme from
+/// the bundle's Info.plist instead.
+#[cfg(target_os = "linux")]
+pub fn register_url_scheme() -> Result<()> {
+    let file_name = format!("{}-url-handler.desktop", crate::headless::APP_IDENTIFIER);
+    let path = dirs::data_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine the data directory"))?
+        .join("applications")
+        .join(&file_name);
+    let executable = autostart::executable()?.to_string_lossy().to_string();
+    let exec = format!(
+        "\"{}\"",
+        executable.replace('\\', "\\\\").replace('"', "\\\"")
+    );
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(
+        &path,
+        format!(
+            "[Desktop Entry]\nType=Application\nName=SynapticFlow\nExec={} %u\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+            exec, URL_SCHEME
+        ),
+    )?;
+    let output = std::process::Command::new("xdg-mime")
+        .args([
+            "default",
+            &file_name,
+            &format!("x-scheme-handler/{}", URL_SCHEME),
+        ])
+        .output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "xdg-mime failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Make this executable the handler for `tauriagent://` links. macOS takes the scheme from
+/// the bundle's Info.plist instead.
+#[cfg(target_os = "windows")]
+pub fn register_url_scheme() -> Result<()> {
+    let key = format!(r"HKCU\Software\Classes\{}", URL_SCHEME);
+    let command = format!("\"{}\" \"%1\"", autostart::executable()?.display());
+    let entries = [
+        (key.clone(), None, format!("URL:{}", URL_SCHEME)),
+        (key.clone(), Some("URL Protocol"), String::new()),
+        (format!(r"{}\shell\open\command", key), None, command),
+    ];
+    for (key, name, value) in entries {
+        let mut reg = std::process::Command::new("reg");
+        reg.args(["add", &key]);
+        match name {
+            Some(name) => reg.args(["/v", name]),
+            None => reg.arg("/ve"),
+        };
+        let output = reg.args(["/t", "REG_SZ", "/d", &value, "/f"]).output()?;
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "Failed to register the {} scheme: {}",
+                URL_SCHEME,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Make this executable the handler for `tauriagent://` links. macOS takes the scheme from
+/// the bundle's Info.plist instead.
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+pub fn register_url_scheme() -> Result<()> {
+    Ok(())
+}
+
+/// Remove the instance file on exit, unless a newer instance already replaced it
+pub fn release(app_data_dir: &Path) {
+    let path = app_data_dir.join(INSTANCE_FILE);
+    let ours = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<InstanceInfo>(&content).ok())
+        .is_some_and(|info| info.pid == std::process::id());
+    if ours {
+        let _ = std::fs::remove_file(path);
+    }
+}
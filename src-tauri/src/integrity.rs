@@ -0,0 +1,121 @@
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+use crate::search_path;
+
+/// Locate `command` the way the OS would when spawning it
+pub fn resolve_executable(command: &str) -> Option<PathBuf> {
+    let candidate = Path::new(command);
+    if candidate.components().count() > 1 || candidate.is_absolute() {
+        return candidate.is_file().then(|| candidate.to_path_buf());
+    }
+
+    #[cfg(windows)]
+    let extensions: Vec<String> = std::env::var("PATHEXT")
+        .unwrap_or_else(|_| ".EXE;.CMD;.BAT;.COM".to_string())
+        .split(';')
+        .map(|ext| ext.to_lowercase())
+        .collect();
+
+    let path_var = search_path::lookup_path()?;
+    for dir in std::env::split_paths(&path_var) {
+        let full = dir.join(command);
+        if full.is_file() {
+            return Some(full);
+        }
+        #[cfg(windows)]
+        for ext in &extensions {
+            let with_ext = dir.join(format!("{}{}", command, ext));
+            if with_ext.is_file() {
+                return Some(with_ext);
+            }
+        }
+    }
+    None
+}
+
+async fn sha256_file(path: &Path) -> Result<String> {
+    let bytes = tokio::fs::read(path)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+    Ok(format!("{:x}", Sha256::digest(&bytes)))
+}
+
+fn is_npx(command: &str) -> bool {
+    let name = Path::new(command)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    name == "npx"
+}
+
+/// Download the package tarball with `npm pack` and hash it
+async fn sha256_npm_package(package: &str) -> Result<String> {
+    let dir = std::env::temp_dir().join(format!("mcp-pack-{}", uuid::Uuid::new_v4().simple()));
+    tokio::fs::create_dir_all(&dir).await?;
+
+    let output = Command::new(if cfg!(windows) { "npm.cmd" } else { "npm" })
+        .args(["pack", package, "--json", "--pack-destination"])
+        .arg(&dir)
+        .output()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to run npm pack: {}", e));
+
+    let result = async {
+        let output = output?;
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "npm pack {} failed: {}",
+                package,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        let packed: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        let filename = packed
+            .get(0)
+            .and_then(|p| p.get("filename"))
+            .and_then(|f| f.as_str())
+            .ok_or_else(|| anyhow::anyhow!("npm pack did not report a tarball for {}", package))?;
+        sha256_file(&dir.join(filename)).await
+    }
+    .await;
+
+    let _ = tokio::fs::remove_dir_all(&dir).await;
+    result
+}
+
+/// 서버 실행 파일(또는 npx 패키지)의 해시를 계산합니다
+///
+/// Returns the hashed target for messages together with the hex digest.
+pub async fn compute_server_hash(command: &str, args: &[String]) -> Result<(String, String)> {
+    if is_npx(command) {
+        let package = args
+            .iter()
+            .find(|arg| !arg.starts_with('-'))
+            .ok_or_else(|| anyhow::anyhow!("Cannot find the package name in npx arguments"))?;
+        let hash = sha256_npm_package(package).await?;
+        return Ok((format!("npm package {}", package), hash));
+    }
+
+    let executable = resolve_executable(command)
+        .ok_or_else(|| anyhow::anyhow!("Cannot resolve executable '{}'", command))?;
+    let hash = sha256_file(&executable).await?;
+    Ok((executable.display().to_string(), hash))
+}
+
+/// Refuse to continue unless the server binary matches the pinned hash
+pub async fn verify(command: &str, args: &[String], expected: &str) -> Result<()> {
+    let (target, actual) = compute_server_hash(command, args).await?;
+    if !actual.eq_ignore_ascii_case(expected.trim()) {
+        return Err(anyhow::anyhow!(
+            "Integrity check failed for {}: expected sha256 {}, got {}",
+            target,
+            expected,
+            actual
+        ));
+    }
+    println!("Integrity check passed for {}", target);
+    Ok(())
+}
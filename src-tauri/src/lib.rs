@@ -1,191 +1,2465 @@
-use std::sync::OnceLock;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use tauri::{Listener, Manager};
 use tauri_plugin_log::{Target, TargetKind};
 
+mod a2a;
+mod analytics;
+mod api;
+mod archive;
+mod attachments;
+mod autostart;
+mod backups;
+mod benchmark;
+mod browser;
+mod builtin;
+mod chat_bridge;
+mod client_handler;
+mod clipboard;
+mod code_index;
+mod config_export;
+mod config_schema;
+mod config_sync;
+mod config_versions;
+mod conformance;
+mod crash;
+mod device_sync;
+mod events;
+mod first_run;
+mod fs_journal;
+mod git_tools;
+mod headless;
+mod health;
+mod history_import;
+pub mod hooks;
+mod instance;
+mod integrity;
+mod limits;
+mod logbuffer;
 mod mcp;
+mod mcp_import;
+mod ocr;
+mod openapi;
+mod patches;
+mod postprocess;
+mod power;
+mod prompts;
+mod ratelimit;
+mod redact;
+mod remote_config;
+mod remote_transport;
+mod result_cache;
+mod retention;
+mod runtime;
+mod sandbox;
+mod screen;
+mod script_tools;
+mod search_path;
+mod snapshots;
+mod speech;
+mod system_info;
+mod telemetry;
+mod tool_aliases;
+mod tool_conflicts;
+mod tool_export;
+mod tool_priority;
+mod tool_validation;
+mod transcribe;
+#[cfg(desktop)]
+mod updates;
+mod web_search;
+mod webhooks;
+mod websocket;
+mod windows;
+use attachments::{AttachmentInfo, AttachmentStore};
 use mcp::{MCPServerConfig, MCPServerManager, ToolCallResult};
+use prompts::{PromptLibrary, PromptTemplate};
+use retention::{RetentionManager, RetentionPolicy, StorageStats};
+use sandbox::PathPolicy;
 
 // 전역 MCP 서버 매니저
 static MCP_MANAGER: OnceLock<MCPServerManager> = OnceLock::new();
+static PROMPT_LIBRARY: OnceLock<PromptLibrary> = OnceLock::new();
+static ATTACHMENT_STORE: OnceLock<AttachmentStore> = OnceLock::new();
+static RETENTION_MANAGER: OnceLock<RetentionManager> = OnceLock::new();
+static PATH_POLICY: OnceLock<PathPolicy> = OnceLock::new();
+static OPENAPI_BRIDGE: OnceLock<openapi::OpenApiBridge> = OnceLock::new();
+static SCRIPT_TOOLS: OnceLock<script_tools::ScriptToolLibrary> = OnceLock::new();
+static BROWSER_TOOLS: OnceLock<browser::BrowserTools> = OnceLock::new();
+static WEB_SEARCH: OnceLock<web_search::WebSearch> = OnceLock::new();
+static SPEECH_QUEUE: OnceLock<speech::SpeechQueue> = OnceLock::new();
+static CODE_INDEX: OnceLock<code_index::CodeIndex> = OnceLock::new();
+static PATCH_STORE: OnceLock<patches::PatchStore> = OnceLock::new();
+static FS_JOURNAL: OnceLock<fs_journal::FsJournal> = OnceLock::new();
+static POST_PROCESSOR: OnceLock<postprocess::PostProcessor> = OnceLock::new();
+static TOOL_HOOKS: OnceLock<hooks::HookRegistry> = OnceLock::new();
+static RESULT_CACHE: OnceLock<result_cache::ResultCache> = OnceLock::new();
+static SNAPSHOT_STORE: OnceLock<snapshots::SnapshotStore> = OnceLock::new();
+static TOOL_ALIASES: OnceLock<tool_aliases::ToolAliases> = OnceLock::new();
+static CONFLICT_RESOLVER: OnceLock<tool_conflicts::ConflictResolver> = OnceLock::new();
+static TOOL_PRIORITIZER: OnceLock<tool_priority::ToolPrioritizer> = OnceLock::new();
+static TOOL_ANALYTICS: OnceLock<analytics::ToolAnalyticsStore> = OnceLock::new();
+static REMOTE_CONFIGS: OnceLock<remote_config::RemoteConfigs> = OnceLock::new();
+static BACKUP_SERVICE: OnceLock<backups::BackupService> = OnceLock::new();
+static DEVICE_SYNC: OnceLock<device_sync::DeviceSync> = OnceLock::new();
+#[cfg(desktop)]
+static UPDATE_SERVICE: OnceLock<updates::UpdateService> = OnceLock::new();
+// setup 단계에서 결정되는 app-data 디렉토리
+static APP_DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
 
 fn get_mcp_manager() -> &'static MCPServerManager {
     MCP_MANAGER.get_or_init(|| MCPServerManager::new())
 }
 
+fn get_app_data_dir() -> PathBuf {
+    APP_DATA_DIR
+        .get()
+        .cloned()
+        .unwrap_or_else(|| std::env::temp_dir().join("tauri-mcp-agent"))
+}
+
+fn get_prompt_library() -> &'static PromptLibrary {
+    PROMPT_LIBRARY
+        .get_or_init(|| PromptLibrary::new(get_app_data_dir().join("prompt_templates.json")))
+}
+
+fn get_attachment_store() -> &'static AttachmentStore {
+    ATTACHMENT_STORE.get_or_init(|| AttachmentStore::new(get_app_data_dir().join("attachments")))
+}
+
+fn get_retention_manager() -> &'static RetentionManager {
+    RETENTION_MANAGER
+        .get_or_init(|| RetentionManager::new(get_app_data_dir().join("retention.json")))
+}
+
+fn get_path_policy() -> &'static PathPolicy {
+    PATH_POLICY.get_or_init(|| PathPolicy::new(get_app_data_dir().join("workspace_roots.json")))
+}
+
+fn get_openapi_bridge() -> &'static openapi::OpenApiBridge {
+    OPENAPI_BRIDGE.get_or_init(|| {
+        openapi::OpenApiBridge::new(get_app_data_dir().join("openapi_servers.json"))
+    })
+}
+
+fn get_script_tools() -> &'static script_tools::ScriptToolLibrary {
+    SCRIPT_TOOLS.get_or_init(|| {
+        script_tools::ScriptToolLibrary::new(get_app_data_dir().join("script_tools.json"))
+    })
+}
+
+fn get_browser_tools() -> &'static browser::BrowserTools {
+    BROWSER_TOOLS.get_or_init(|| {
+        browser::BrowserTools::new(
+            get_app_data_dir().join("browser.json"),
+            get_app_data_dir().join("browser-profile"),
+        )
+    })
+}
+
+fn get_web_search() -> &'static web_search::WebSearch {
+    WEB_SEARCH
+        .get_or_init(|| web_search::WebSearch::new(get_app_data_dir().join("web_search.json")))
+}
+
+fn get_speech_queue() -> &'static speech::SpeechQueue {
+    SPEECH_QUEUE.get_or_init(|| speech::SpeechQueue::new(get_app_data_dir().join("voices")))
+}
+
+fn get_code_index() -> &'static code_index::CodeIndex {
+    CODE_INDEX
+        .get_or_init(|| code_index::CodeIndex::new(get_app_data_dir().join("code_index.sqlite")))
+}
+
+fn get_patch_store() -> &'static patches::PatchStore {
+    PATCH_STORE.get_or_init(|| patches::PatchStore::new(get_app_data_dir().join("patches")))
+}
+
+fn get_fs_journal() -> &'static fs_journal::FsJournal {
+    FS_JOURNAL.get_or_init(|| fs_journal::FsJournal::new(get_app_data_dir().join("fs_journal")))
+}
+
+fn get_post_processor() -> &'static postprocess::PostProcessor {
+    POST_PROCESSOR.get_or_init(|| {
+        postprocess::PostProcessor::new(get_app_data_dir().join("postprocess.json"))
+    })
+}
+
+fn get_result_cache() -> &'static result_cache::ResultCache {
+    RESULT_CACHE.get_or_init(|| {
+        result_cache::ResultCache::new(get_app_data_dir().join("result_cache.json"))
+    })
+}
+
+fn get_tool_aliases() -> &'static tool_aliases::ToolAliases {
+    TOOL_ALIASES.get_or_init(|| {
+        tool_aliases::ToolAliases::new(get_app_data_dir().join("tool_aliases.json"))
+    })
+}
+
+fn get_conflict_resolver() -> &'static tool_conflicts::ConflictResolver {
+    CONFLICT_RESOLVER.get_or_init(|| {
+        tool_conflicts::ConflictResolver::new(get_app_data_dir().join("tool_conflicts.json"))
+    })
+}
+
+fn get_tool_prioritizer() -> &'static tool_priority::ToolPrioritizer {
+    TOOL_PRIORITIZER.get_or_init(|| {
+        tool_priority::ToolPrioritizer::new(get_app_data_dir().join("tool_priority.json"))
+    })
+}
+
+fn get_tool_analytics_store() -> &'static analytics::ToolAnalyticsStore {
+    TOOL_ANALYTICS.get_or_init(|| {
+        analytics::ToolAnalyticsStore::new(get_app_data_dir().join("tool_analytics.json"))
+    })
+}
+
+fn get_backup_service() -> &'static backups::BackupService {
+    BACKUP_SERVICE.get_or_init(|| {
+        let app_data_dir = get_app_data_dir();
+        let settings_path = app_data_dir.join("backup_settings.json");
+        backups::BackupService::new(app_data_dir, settings_path)
+    })
+}
+
+fn get_device_sync() -> &'static device_sync::DeviceSync {
+    DEVICE_SYNC.get_or_init(|| device_sync::DeviceSync::new(get_app_data_dir()))
+}
+
+#[cfg(desktop)]
+fn get_update_service() -> &'static updates::UpdateService {
+    UPDATE_SERVICE.get_or_init(|| {
+        updates::UpdateService::new(get_app_data_dir().join("update_settings.json"))
+    })
+}
+
+fn get_remote_configs() -> &'static remote_config::RemoteConfigs {
+    REMOTE_CONFIGS.get_or_init(|| {
+        remote_config::RemoteConfigs::new(
+            get_app_data_dir().join("remote_config_sources.json"),
+            get_app_data_dir().join("remote_config_cache.json"),
+        )
+    })
+}
+
+/// Turn `server__tool` prefixed tools into the list the model sees:
+/// drop duplicates hidden by the collision strategy, order and trim by priority,
+/// then apply aliases
+async fn expose_tools(tools: &mut Vec<mcp::MCPTool>) {
+    get_conflict_resolver().apply(tools).await;
+    get_tool_prioritizer().apply(tools).await;
+    get_tool_aliases().apply(tools).await;
+}
+
+/// Hooks around every tool call; register more with [`hooks::HookRegistry::register`].
+/// Post-processing wraps the cache so cached results are processed too.
+pub fn get_tool_hooks() -> &'static hooks::HookRegistry {
+    TOOL_HOOKS.get_or_init(|| {
+        let hooks = hooks::HookRegistry::default();
+        hooks.register(Arc::new(postprocess::PostProcessHook(get_post_processor())));
+        hooks.register(Arc::new(result_cache::ResultCacheHook(get_result_cache())));
+        hooks
+    })
+}
+
+fn get_snapshot_store() -> &'static snapshots::SnapshotStore {
+    SNAPSHOT_STORE
+        .get_or_init(|| snapshots::SnapshotStore::new(get_app_data_dir().join("snapshots")))
+}
+
+fn connection_snapshot_path() -> PathBuf {
+    get_app_data_dir().join("connection_snapshot.json")
+}
+
+/// 종료 시 실행 중인 서버 상태를 저장합니다
+async fn save_connection_snapshot() {
+    let snapshot = get_mcp_manager().snapshot().await;
+    let path = connection_snapshot_path();
+    let result = async {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, serde_json::to_string_pretty(&snapshot)?).await?;
+        anyhow::Ok(())
+    }
+    .await;
+    match result {
+        Ok(()) => println!(
+            "Saved connection snapshot with {} servers",
+            snapshot.servers.len()
+        ),
+        Err(e) => eprintln!("❌ Failed to save connection snapshot: {}", e),
+    }
+}
+
+fn instruction_settings_path() -> PathBuf {
+    get_app_data_dir().join("disabled_instructions.json")
+}
+
+async fn load_connection_snapshot() -> Result<Option<mcp::ConnectionSnapshot>, String> {
+    match tokio::fs::read_to_string(connection_snapshot_path()).await {
+        Ok(content) => serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| format!("Invalid connection snapshot: {}", e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
-fn greet(name: &str) -> String {
-    format!("Hello, {}! You've been greeted from Rust!", name)
+fn greet(name: &str) -> String {
+    format!("Hello, {}! You've been greeted from Rust!", name)
+}
+
+/// Expand settings that depend on app state before handing a config to the manager
+async fn prepare_server_config(mut config: MCPServerConfig) -> MCPServerConfig {
+    // 원격 소스가 관리하는 서버는 읽기 전용이라 로컬 설정 대신 관리 정의로 시작합니다
+    if let Some(managed) = get_remote_configs()
+        .managed_servers()
+        .await
+        .into_iter()
+        .find(|managed| managed.name == config.name)
+    {
+        println!("Starting managed definition of server {}", config.name);
+        config = managed;
+    }
+    if let Some(container) = config.container.as_mut() {
+        if container.mount_workspace_roots {
+            for root in get_path_policy().roots().await {
+                let root = root.display().to_string();
+                container.volumes.push(format!("{}:{}", root, root));
+            }
+        }
+    }
+    config
+}
+
+#[tauri::command]
+async fn start_mcp_server(config: MCPServerConfig) -> Result<String, String> {
+    let config = prepare_server_config(config).await;
+    get_mcp_manager()
+        .start_server(config)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Compute the sha256 to pin in a server's config
+#[tauri::command]
+async fn compute_server_hash(config: MCPServerConfig) -> Result<String, String> {
+    let (command, args) = runtime::unwrapped_command_line(&config).map_err(|e| e.to_string())?;
+    integrity::compute_server_hash(&command, &args)
+        .await
+        .map(|(_, hash)| hash)
+        .map_err(|e| e.to_string())
+}
+
+/// Every server config the backend knows: running or idle-stopped servers first,
+/// then the last saved snapshot and remote managed servers
+async fn known_server_configs() -> Vec<MCPServerConfig> {
+    let mut configs = get_mcp_manager().snapshot().await.servers;
+    if let Ok(Some(snapshot)) = load_connection_snapshot().await {
+        configs.extend(snapshot.servers);
+    }
+    configs.extend(get_remote_configs().managed_servers().await);
+    let mut seen = HashSet::new();
+    configs.retain(|config| seen.insert(config.name.clone()));
+    configs
+}
+
+/// Start every known server of `group` concurrently, honouring `depends_on` ordering
+#[tauri::command]
+async fn start_group(name: String) -> Result<mcp::StartupReport, String> {
+    let configs: Vec<MCPServerConfig> = known_server_configs()
+        .await
+        .into_iter()
+        .filter(|config| config.group.as_deref() == Some(name.as_str()))
+        .collect();
+    if configs.is_empty() {
+        return Err(format!("No servers in group '{}'", name));
+    }
+    start_mcp_servers(configs).await
+}
+
+#[tauri::command]
+async fn stop_group(name: String) -> Result<mcp::GroupStopReport, String> {
+    Ok(get_mcp_manager().stop_group(&name).await)
+}
+
+/// Group name → names of the known servers in it
+#[tauri::command]
+async fn get_server_groups() -> Result<BTreeMap<String, Vec<String>>, String> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for config in known_server_configs().await {
+        if let Some(group) = config.group {
+            groups.entry(group).or_default().push(config.name);
+        }
+    }
+    Ok(groups)
+}
+
+/// Start several servers, honouring `depends_on` ordering
+#[tauri::command]
+async fn start_mcp_servers(configs: Vec<MCPServerConfig>) -> Result<mcp::StartupReport, String> {
+    let mut prepared = Vec::new();
+    for config in configs {
+        prepared.push(prepare_server_config(config).await);
+    }
+    get_mcp_manager()
+        .start_servers(prepared)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn stop_mcp_server(server_name: String) -> Result<(), String> {
+    get_mcp_manager()
+        .stop_server(&server_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Route a call to the builtin, script, browser, OpenAPI or MCP server that owns the tool
+async fn dispatch_tool_call(
+    server_name: &str,
+    tool_name: &str,
+    arguments: serde_json::Value,
+    context: &mcp::ToolCallContext,
+) -> ToolCallResult {
+    // 깊은 곳의 승인 요청도 호출 주체를 보여줄 수 있도록 컨텍스트를 유지합니다
+    let call = async {
+        if server_name == builtin::SERVER_NAME {
+            builtin::call_tool(
+                get_path_policy(),
+                get_attachment_store(),
+                context,
+                tool_name,
+                arguments,
+            )
+            .await
+        } else if server_name == script_tools::SERVER_NAME {
+            get_script_tools().call_tool(tool_name, arguments).await
+        } else if server_name == browser::SERVER_NAME {
+            get_browser_tools()
+                .call_tool(get_attachment_store(), tool_name, arguments)
+                .await
+        } else if get_openapi_bridge().contains(server_name).await {
+            get_openapi_bridge()
+                .call_tool(server_name, tool_name, arguments)
+                .await
+        } else {
+            get_mcp_manager()
+                .call_tool(server_name, tool_name, arguments, context)
+                .await
+        }
+    };
+    context.clone().scope(call).await
+}
+
+/// Annotations the owning server declared for a tool, if it is known
+async fn tool_annotations(server_name: &str, tool_name: &str) -> Option<mcp::MCPToolAnnotations> {
+    let tools = match server_name {
+        builtin::SERVER_NAME => builtin::list_tools(),
+        script_tools::SERVER_NAME => get_script_tools().list_tools().await,
+        browser::SERVER_NAME => browser::list_tools(),
+        _ if get_openapi_bridge().contains(server_name).await => get_openapi_bridge()
+            .list_tools(server_name)
+            .await
+            .unwrap_or_default(),
+        _ => {
+            return get_mcp_manager()
+                .cached_tool_annotations(server_name, tool_name)
+                .await
+        }
+    };
+    tools
+        .into_iter()
+        .find(|tool| tool.name == tool_name)
+        .and_then(|tool| tool.annotations)
+}
+
+/// Run the tool itself, sharing one in-flight request between identical read-only calls
+async fn execute_tool_call(call: hooks::ToolCall) -> ToolCallResult {
+    match result_cache::cache_key(&call) {
+        Some(key) => {
+            let server_name = call.server_name.clone();
+            get_result_cache()
+                .coalesce(&server_name, key, async move {
+                    dispatch_tool_call(
+                        &call.server_name,
+                        &call.tool_name,
+                        call.arguments,
+                        &call.context,
+                    )
+                    .await
+                })
+                .await
+        }
+        None => {
+            dispatch_tool_call(
+                &call.server_name,
+                &call.tool_name,
+                call.arguments,
+                &call.context,
+            )
+            .await
+        }
+    }
+}
+
+#[tauri::command]
+async fn call_mcp_tool(
+    server_name: String,
+    tool_name: String,
+    arguments: serde_json::Value,
+    context: Option<mcp::ToolCallContext>,
+    bypass_cache: Option<bool>,
+) -> ToolCallResult {
+    let context = context.unwrap_or_default();
+    let started = std::time::SystemTime::now();
+    let call = hooks::ToolCall {
+        annotations: tool_annotations(&server_name, &tool_name).await,
+        server_name: server_name.clone(),
+        tool_name: tool_name.clone(),
+        arguments,
+        context: context.clone(),
+        bypass_cache: bypass_cache.unwrap_or(false),
+    };
+    let hooks::HookedResult {
+        result,
+        answered_by,
+    } = get_tool_hooks().run(call, execute_tool_call).await;
+
+    events::record(
+        "tool-called",
+        Some(&server_name),
+        serde_json::json!({
+            "tool_name": tool_name,
+            "success": result.success,
+            "error": result.error,
+            "answered_by": answered_by,
+            "session_id": context.session_id,
+            "agent_id": context.agent_id,
+        }),
+    );
+    get_tool_analytics_store()
+        .record(
+            &server_name,
+            &tool_name,
+            result.success,
+            started.elapsed().unwrap_or_default(),
+            context.session_id.as_deref(),
+        )
+        .await;
+    telemetry::increment("mcp.tool_calls", &server_name);
+    if !result.success {
+        telemetry::increment("mcp.tool_errors", &server_name);
+    }
+    let span_name = format!("mcp.tool_call {}", tool_name);
+    let mut attributes = vec![
+        ("mcp.server".to_string(), server_name),
+        ("mcp.tool".to_string(), tool_name),
+    ];
+    if let Some(session_id) = context.session_id {
+        attributes.push(("agent.session_id".to_string(), session_id));
+    }
+    if let Some(agent_id) = context.agent_id {
+        attributes.push(("agent.id".to_string(), agent_id));
+    }
+    telemetry::record_span(telemetry::Span {
+        name: span_name,
+        kind: telemetry::SpanKind::Client,
+        start: started,
+        end: std::time::SystemTime::now(),
+        attributes,
+        error: result.error.clone(),
+    });
+    result
+}
+
+/// Calls, success rate, latency and sessions per tool over `range` (default: week),
+/// plus connected servers nobody called as candidates to disable
+#[tauri::command]
+async fn get_tool_analytics(
+    range: Option<analytics::AnalyticsRange>,
+) -> Result<analytics::ToolAnalytics, String> {
+    let manager = get_mcp_manager();
+    let tools = manager.snapshot().await.tools;
+    let servers: Vec<analytics::ServerUsageInput> = manager
+        .server_statuses()
+        .await
+        .into_iter()
+        .map(|status| analytics::ServerUsageInput {
+            tool_count: tools.get(&status.name).map_or(0, Vec::len),
+            server_name: status.name,
+            pid: status.pid,
+        })
+        .collect();
+    Ok(get_tool_analytics_store()
+        .report(range.unwrap_or_default(), &servers)
+        .await)
+}
+
+/// Measure cold-start, latency percentiles and error rate for one tool
+#[tauri::command]
+async fn benchmark_tool(
+    server_name: String,
+    tool_name: String,
+    arguments: serde_json::Value,
+    iterations: usize,
+    concurrency: usize,
+) -> benchmark::BenchmarkReport {
+    benchmark::run(&server_name, &tool_name, iterations, concurrency, || {
+        call_mcp_tool(
+            server_name.clone(),
+            tool_name.clone(),
+            arguments.clone(),
+            None,
+            Some(true),
+        )
+    })
+    .await
+}
+
+/// Builtin, browser, script, OpenAPI and MCP tools, each prefixed `server__tool`
+async fn connected_tools() -> anyhow::Result<Vec<mcp::MCPTool>> {
+    let mut tools = builtin::list_tools();
+    for tool in &mut tools {
+        tool.name = format!("{}__{}", builtin::SERVER_NAME, tool.name);
+    }
+    for mut tool in browser::list_tools() {
+        tool.name = format!("{}__{}", browser::SERVER_NAME, tool.name);
+        tools.push(tool);
+    }
+    for mut tool in get_script_tools().list_tools().await {
+        tool.name = format!("{}__{}", script_tools::SERVER_NAME, tool.name);
+        tools.push(tool);
+    }
+    tools.extend(get_openapi_bridge().list_all_tools().await);
+    tools.extend(get_mcp_manager().list_all_tools().await?);
+    Ok(tools)
+}
+
+/// Every connected tool as the model sees it, see [`expose_tools`]
+async fn all_connected_tools() -> anyhow::Result<Vec<mcp::MCPTool>> {
+    let mut tools = connected_tools().await?;
+    expose_tools(&mut tools).await;
+    Ok(tools)
+}
+
+/// Make a config file restored on disk take effect; false when that needs a restart
+async fn reload_config(file: &str) -> bool {
+    let app_data_dir = get_app_data_dir();
+    match file {
+        "tool_aliases.json" => get_tool_aliases().reload().await,
+        "tool_conflicts.json" => get_conflict_resolver().reload().await,
+        "tool_priority.json" => get_tool_prioritizer().reload().await,
+        "postprocess.json" => get_post_processor().reload().await,
+        "result_cache.json" => get_result_cache().reload().await,
+        "workspace_roots.json" => get_path_policy().reload().await,
+        "retention.json" => get_retention_manager().reload().await,
+        "prompt_templates.json" => get_prompt_library().reload().await,
+        "script_tools.json" => get_script_tools().reload().await,
+        "remote_config_sources.json" => get_remote_configs().reload().await,
+        "backup_settings.json" => get_backup_service().reload().await,
+        "device_sync.json" => get_device_sync().reload().await,
+        #[cfg(desktop)]
+        "update_settings.json" => get_update_service().reload().await,
+        "autostart.json" => {
+            return autostart::load_settings(&app_data_dir)
+                .and_then(|settings| autostart::apply(&settings))
+                .is_ok()
+        }
+        "redaction_rules.json" => {
+            return redact::load_custom_rules(&app_data_dir.join(file)).is_ok()
+        }
+        "chat_bridge.json" => {
+            return match chat_bridge::load_settings(&app_data_dir) {
+                Ok(settings) => chat_bridge::apply(&settings).await.is_ok(),
+                Err(_) => false,
+            }
+        }
+        "telemetry.json" => return telemetry::load_config(&app_data_dir.join(file)).is_ok(),
+        "power_policy.json" => return power::load_policy(&app_data_dir.join(file)).is_ok(),
+        "path_settings.json" => {
+            return search_path::load_settings(&app_data_dir.join(file)).is_ok()
+        }
+        // 매번 파일에서 읽는 설정
+        "web_search.json" | "browser.json" | "webhooks.json" | "crash_settings.json" => {}
+        _ => return false,
+    }
+    true
+}
+
+/// Backups written before each config change, newest first
+#[tauri::command]
+async fn list_config_versions(
+    file: Option<String>,
+) -> Result<Vec<config_versions::ConfigVersion>, String> {
+    tokio::task::spawn_blocking(move || config_versions::list(file.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Restore a config file from a backup and reload it where the app allows
+#[tauri::command]
+async fn rollback_config(version_id: String) -> Result<config_versions::RollbackReport, String> {
+    let mut report = config_versions::rollback(&version_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    report.reloaded = reload_config(&report.file).await;
+    Ok(report)
+}
+
+#[tauri::command]
+async fn get_config_sync_status() -> Result<config_sync::ConfigSyncStatus, String> {
+    let app_data_dir = get_app_data_dir();
+    tokio::task::spawn_blocking(move || config_sync::status(&app_data_dir))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Pick up config files that a pull changed
+async fn reload_pulled(report: &config_sync::SyncReport) {
+    for file in &report.pulled {
+        if !reload_config(file).await {
+            println!(
+                "{} was updated by config sync and applies after a restart",
+                file
+            );
+        }
+    }
+}
+
+/// Enable, disable or re-point git sync of the config directory.
+/// Enabling initializes the repository and syncs once with the remote.
+#[tauri::command]
+async fn set_config_sync_settings(
+    settings: config_sync::ConfigSyncSettings,
+) -> Result<config_sync::SyncReport, String> {
+    let report = config_sync::configure(&get_app_data_dir(), settings)
+        .await
+        .map_err(|e| e.to_string())?;
+    reload_pulled(&report).await;
+    Ok(report)
+}
+
+/// Commit local config changes, pull from the remote and push
+#[tauri::command]
+async fn sync_config_now() -> Result<config_sync::SyncReport, String> {
+    let report = config_sync::sync(&get_app_data_dir())
+        .await
+        .map_err(|e| e.to_string())?;
+    reload_pulled(&report).await;
+    Ok(report)
+}
+
+/// Open another chat window sharing the running servers and stores, optionally on a session
+#[tauri::command]
+fn open_chat_window(app: tauri::AppHandle, session_id: Option<String>) -> Result<String, String> {
+    windows::open_chat(&app, session_id.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn list_chat_windows(app: tauri::AppHandle) -> Vec<windows::ChatWindow> {
+    windows::list(&app)
+}
+
+/// Requests from launching the app with arguments, including later launches that were
+/// forwarded to this instance
+#[tauri::command]
+fn take_launch_requests() -> Vec<instance::LaunchRequest> {
+    instance::take_pending()
+}
+
+#[tauri::command]
+async fn get_device_sync_status() -> device_sync::DeviceSyncStatus {
+    get_device_sync().status().await
+}
+
+/// Pick up config files that a device sync changed
+async fn reload_device_synced(report: &device_sync::DeviceSyncReport) {
+    for file in &report.configs {
+        if !reload_config(file).await {
+            println!(
+                "{} was updated by device sync and applies after a restart",
+                file
+            );
+        }
+    }
+}
+
+/// Choose the sync backend and schedule. A passphrase derives this device's key; it must
+/// match the one used by devices already syncing through the backend. Enabling syncs once.
+#[tauri::command]
+async fn set_device_sync_settings(
+    settings: device_sync::DeviceSyncSettings,
+    passphrase: Option<String>,
+) -> Result<device_sync::DeviceSyncReport, String> {
+    let enabled = settings.enabled;
+    get_device_sync()
+        .save_settings(settings, passphrase)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !enabled {
+        return Ok(device_sync::DeviceSyncReport::default());
+    }
+    sync_devices_now().await
+}
+
+/// Encrypt and exchange configs and sessions with the other devices now
+#[tauri::command]
+async fn sync_devices_now() -> Result<device_sync::DeviceSyncReport, String> {
+    let report = get_device_sync().sync().await.map_err(|e| e.to_string())?;
+    reload_device_synced(&report).await;
+    Ok(report)
+}
+
+#[tauri::command]
+async fn get_backup_settings() -> backups::BackupSettings {
+    get_backup_service().settings().await
+}
+
+/// Choose the backup directory, schedule and how many archives to keep
+#[tauri::command]
+async fn set_backup_settings(settings: backups::BackupSettings) -> Result<(), String> {
+    get_backup_service()
+        .save_settings(settings)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Archive the config store, memory DB and session DB into the backup directory now
+#[tauri::command]
+async fn create_backup_now() -> Result<backups::BackupInfo, String> {
+    get_backup_service()
+        .create()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Backups in the backup directory, newest first
+#[tauri::command]
+async fn list_backups() -> Result<Vec<backups::BackupInfo>, String> {
+    get_backup_service().list().await.map_err(|e| e.to_string())
+}
+
+/// Restore a backup's config files and sessions, reloading configs where the app allows
+#[tauri::command]
+async fn restore_backup(id: String) -> Result<backups::BackupRestoreReport, String> {
+    let mut report = get_backup_service()
+        .restore(&id)
+        .await
+        .map_err(|e| e.to_string())?;
+    for file in &report.restored {
+        if !reload_config(file).await {
+            report.restart_required.push(file.clone());
+        }
+    }
+    Ok(report)
+}
+
+/// False for builds without an updater public key; the UI hides update controls then
+#[cfg(desktop)]
+#[tauri::command]
+fn is_updater_configured(app: tauri::AppHandle) -> bool {
+    updates::signing_configured(&app)
+}
+
+#[cfg(desktop)]
+#[tauri::command]
+async fn get_update_settings() -> updates::UpdateSettings {
+    get_update_service().settings().await
+}
+
+/// Turn scheduled update checks on or off and set how often they run
+#[cfg(desktop)]
+#[tauri::command]
+async fn set_update_settings(
+    settings: updates::UpdateSettings,
+) -> Result<updates::UpdateSettings, String> {
+    get_update_service()
+        .save_settings(settings)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Newer version offered to this installation, if any
+#[cfg(desktop)]
+#[tauri::command]
+async fn check_for_updates(app: tauri::AppHandle) -> Result<Option<updates::UpdateInfo>, String> {
+    get_update_service()
+        .check(&app)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Download, verify and install the update found by the last check, then restart
+#[cfg(desktop)]
+#[tauri::command]
+async fn install_update(app: tauri::AppHandle) -> Result<(), String> {
+    get_update_service()
+        .install(&app)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Register a URL serving a shared server config; it is fetched now and every
+/// `refresh_interval` seconds into a read-only managed section
+#[tauri::command]
+async fn add_remote_config_source(
+    url: String,
+    refresh_interval: u64,
+) -> Result<remote_config::ManagedSection, String> {
+    get_remote_configs()
+        .add_source(&url, refresh_interval)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn remove_remote_config_source(url: String) -> Result<(), String> {
+    get_remote_configs()
+        .remove_source(&url)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn list_remote_config_sources() -> Result<remote_config::RemoteConfigState, String> {
+    Ok(get_remote_configs().state().await)
+}
+
+#[tauri::command]
+async fn refresh_remote_config_source(
+    url: String,
+) -> Result<remote_config::ManagedSection, String> {
+    get_remote_configs()
+        .refresh(&url)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Server configs from every remote source, merged into one list
+#[tauri::command]
+async fn get_managed_servers() -> Result<Vec<MCPServerConfig>, String> {
+    Ok(get_remote_configs().managed_servers().await)
+}
+
+/// Managed servers that are not running, run with other settings, or were dropped upstream
+#[tauri::command]
+async fn get_managed_config_drift() -> Result<Vec<remote_config::ServerDrift>, String> {
+    let running = get_mcp_manager().snapshot().await.servers;
+    Ok(get_remote_configs().drift(&running).await)
+}
+
+/// Write the running (or last saved) server configs as a Claude Desktop `mcpServers` file.
+/// Env values are replaced with `${NAME}` placeholders.
+#[tauri::command]
+async fn export_config_claude_format(
+    path: String,
+) -> Result<config_export::ClaudeExportReport, String> {
+    let mut configs = get_mcp_manager().snapshot().await.servers;
+    if configs.is_empty() {
+        configs = load_connection_snapshot()
+            .await?
+            .map(|snapshot| snapshot.servers)
+            .unwrap_or_default();
+    }
+    configs.sort_by(|a, b| a.name.cmp(&b.name));
+    config_export::export(&configs, std::path::Path::new(&path))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Write the connected tools as OpenAI, Anthropic or Gemini function definitions
+#[tauri::command]
+async fn export_tool_definitions(
+    format: tool_export::ExportFormat,
+    path: String,
+) -> Result<usize, String> {
+    let tools = all_connected_tools().await.map_err(|e| e.to_string())?;
+    tool_export::export(&tools, format, std::path::Path::new(&path))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn list_mcp_tools(server_name: String) -> Result<Vec<mcp::MCPTool>, String> {
+    if server_name == builtin::SERVER_NAME {
+        return Ok(builtin::list_tools());
+    }
+    if server_name == script_tools::SERVER_NAME {
+        return Ok(get_script_tools().list_tools().await);
+    }
+    if server_name == browser::SERVER_NAME {
+        return Ok(browser::list_tools());
+    }
+    if let Some(tools) = get_openapi_bridge().list_tools(&server_name).await {
+        return Ok(tools);
+    }
+    get_mcp_manager()
+        .list_tools(&server_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn list_mcp_prompts(server_name: String) -> Result<Vec<rmcp::model::Prompt>, String> {
+    get_mcp_manager()
+        .list_prompts(&server_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn list_mcp_resources(server_name: String) -> Result<Vec<rmcp::model::Resource>, String> {
+    get_mcp_manager()
+        .list_resources(&server_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn list_mcp_resource_templates(
+    server_name: String,
+) -> Result<Vec<rmcp::model::ResourceTemplate>, String> {
+    get_mcp_manager()
+        .list_resource_templates(&server_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn complete_argument(
+    server_name: String,
+    reference: rmcp::model::Reference,
+    argument: String,
+    partial: String,
+) -> Result<rmcp::model::CompletionInfo, String> {
+    get_mcp_manager()
+        .complete_argument(&server_name, reference, &argument, &partial)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_server_log_level(
+    server_name: String,
+    level: rmcp::model::LoggingLevel,
+) -> Result<(), String> {
+    get_mcp_manager()
+        .set_log_level(&server_name, level)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn subscribe_resource(server_name: String, uri: String) -> Result<(), String> {
+    get_mcp_manager()
+        .subscribe_resource(&server_name, &uri)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn unsubscribe_resource(server_name: String, uri: String) -> Result<(), String> {
+    get_mcp_manager()
+        .unsubscribe_resource(&server_name, &uri)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_resource_subscriptions() -> HashMap<String, Vec<String>> {
+    get_mcp_manager().get_subscriptions().await
+}
+
+/// Claude format(`mcpServers` 객체)과 기존 `servers` 배열 형식을 모두 지원합니다
+fn parse_server_configs(config: &serde_json::Value) -> Result<Vec<MCPServerConfig>, String> {
+    let (errors, warnings): (Vec<_>, Vec<_>) = config_schema::validate(config)
+        .into_iter()
+        .partition(|d| d.severity == config_schema::DiagnosticSeverity::Error);
+    if !warnings.is_empty() {
+        eprintln!("⚠️ [TAURI] {}", config_schema::describe(&warnings));
+    }
+    if !errors.is_empty() {
+        return Err(format!(
+            "Invalid server config:\n{}",
+            config_schema::describe(&errors)
+        ));
+    }
+    let servers_config =
+        if let Some(mcp_servers) = config.get("mcpServers").and_then(|v| v.as_object()) {
+            // Claude format: mcpServers 객체를 MCPServerConfig 배열로 변환
+            println!("🚀 [TAURI] Processing Claude format (mcpServers)");
+            let mut server_list = Vec::new();
+
+            for (name, server_config) in mcp_servers.iter() {
+                let mut server_value = server_config.clone();
+                // name 필드 추가; transport가 없으면 url의 스킴으로 정하고, url도 없으면 stdio입니다
+                if let serde_json::Value::Object(ref mut obj) = server_value {
+                    obj.insert("name".to_string(), serde_json::Value::String(name.clone()));
+                    if !obj.contains_key("transport") {
+                        let url = obj.get("url").and_then(|v| v.as_str());
+                        let transport = runtime::implied_transport(url);
+                        obj.insert("transport".to_string(), transport.into());
+                    }
+                }
+                let server_cfg: mcp::MCPServerConfig = serde_json::from_value(server_value)
+                    .map_err(|e| format!("Invalid server config: {}", e))?;
+                server_list.push(server_cfg);
+            }
+            server_list
+        } else if let Some(servers_array) = config.get("servers").and_then(|v| v.as_array()) {
+            // 기존 format: servers 배열
+            println!("🚀 [TAURI] Processing legacy format (servers array)");
+            let mut server_list = Vec::new();
+            for server_value in servers_array {
+                let server_cfg: mcp::MCPServerConfig = serde_json::from_value(server_value.clone())
+                    .map_err(|e| format!("Invalid server config: {}", e))?;
+                server_list.push(server_cfg);
+            }
+            server_list
+        } else {
+            return Err("Invalid config: missing mcpServers object or servers array".to_string());
+        };
+    Ok(servers_config)
+}
+
+/// Transports this build can start; stdio and docker servers are unavailable on mobile
+#[tauri::command]
+fn get_platform_capabilities() -> runtime::PlatformCapabilities {
+    runtime::capabilities()
+}
+
+/// Check a server config document and locate each problem for inline display
+#[tauri::command]
+async fn validate_server_config(
+    config: serde_json::Value,
+) -> Result<Vec<config_schema::ConfigDiagnostic>, String> {
+    Ok(config_schema::validate(&config))
+}
+
+/// Preview importing a `.mcp.json` file into `current`, the assistant's `mcpConfig`
+#[tauri::command]
+async fn preview_mcp_import(
+    path: String,
+    current: serde_json::Value,
+) -> Result<mcp_import::ImportPreview, String> {
+    mcp_import::preview(std::path::Path::new(&path), &current)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Import a ChatGPT or Claude data export (ZIP or `conversations.json`) as sessions
+#[tauri::command]
+async fn import_history(
+    path: String,
+    format: history_import::HistoryFormat,
+) -> Result<history_import::HistoryImportReport, String> {
+    history_import::import_history(std::path::Path::new(&path), format)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// JSON Schema of one server entry, for editors that offer completion
+#[tauri::command]
+async fn get_server_config_schema() -> Result<serde_json::Value, String> {
+    Ok(config_schema::server_schema().clone())
+}
+
+#[tauri::command]
+async fn list_tools_from_config(config: serde_json::Value) -> Result<Vec<mcp::MCPTool>, String> {
+    println!("🚀 [TAURI] list_tools_from_config called!");
+    println!(
+        "🚀 [TAURI] Config received: {}",
+        redact::redact(&serde_json::to_string_pretty(&config).unwrap_or_default())
+    );
+
+    let servers_config = parse_server_configs(&config)?;
+
+    println!(
+        "🚀 [TAURI] Found {} servers in config",
+        servers_config.len()
+    );
+
+    let manager = get_mcp_manager();
+
+    let mut all_tools: Vec<mcp::MCPTool> = Vec::new();
+
+    // Start servers from config in dependency order
+    let mut prepared = Vec::new();
+    for server_cfg in servers_config {
+        prepared.push(prepare_server_config(server_cfg).await);
+    }
+    let server_names: Vec<String> = prepared.iter().map(|c| c.name.clone()).collect();
+    let report = manager
+        .start_servers(prepared)
+        .await
+        .map_err(|e| e.to_string())?;
+    for (server_name, e) in report.failed.iter().chain(report.skipped.iter()) {
+        eprintln!("❌ [TAURI] Failed to start server {}: {}", server_name, e);
+    }
+
+    // Collect tools from every server that is running
+    for server_name in server_names {
+        if !manager.is_server_alive(&server_name).await {
+            continue; // Skip to the next server if this one failed to start
+        }
+
+        // Fetch tools for the server we just ensured is running
+        match manager.list_tools(&server_name).await {
+            Ok(mut tools) => {
+                println!(
+                    "✅ [TAURI] Found {} tools for server '{}'",
+                    tools.len(),
+                    server_name
+                );
+                // Prefix tool names with server name to avoid conflicts
+                for tool in &mut tools {
+                    tool.name = format!("{}__{}", server_name, tool.name);
+                }
+                all_tools.extend(tools);
+            }
+            Err(e) => {
+                eprintln!(
+                    "❌ [TAURI] Error listing tools for '{}': {}",
+                    server_name, e
+                );
+                // Continue to the next server
+            }
+        }
+    }
+
+    expose_tools(&mut all_tools).await;
+    println!("✅ [TAURI] Total tools collected: {}", all_tools.len());
+    Ok(all_tools)
+}
+
+#[tauri::command]
+async fn get_connected_servers() -> Vec<String> {
+    get_mcp_manager().get_connected_servers().await
+}
+
+#[tauri::command]
+async fn get_server_info(server_name: String) -> Result<mcp::MCPServerInfo, String> {
+    get_mcp_manager()
+        .get_server_info(&server_name)
+        .await
+        .ok_or_else(|| format!("Server '{}' is not connected", server_name))
+}
+
+/// Instructions from connected servers, with their system-prompt toggle
+#[tauri::command]
+async fn get_server_instructions() -> Vec<mcp::ServerInstructions> {
+    get_mcp_manager().server_instructions().await
+}
+
+#[tauri::command]
+async fn set_server_instructions_enabled(server_name: String, enabled: bool) -> Result<(), String> {
+    let manager = get_mcp_manager();
+    manager
+        .set_instructions_enabled(&server_name, enabled)
+        .await;
+    let disabled = manager.disabled_instructions().await;
+    let content = serde_json::to_string_pretty(&disabled).map_err(|e| e.to_string())?;
+    config_versions::write(&instruction_settings_path(), content)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Exercise a connected server and return a report to attach to bug reports
+#[tauri::command]
+async fn run_server_conformance(
+    server_name: String,
+) -> Result<conformance::ConformanceReport, String> {
+    conformance::run(get_mcp_manager(), &server_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Timeline events after `cursor`; pass 0 to start from the oldest retained event
+#[tauri::command]
+fn get_events_since(cursor: u64, limit: Option<usize>) -> events::TimelinePage {
+    events::since(cursor, limit)
+}
+
+/// Frontend-originated timeline entries such as approvals and agent steps
+#[tauri::command]
+fn record_timeline_event(kind: String, server_name: Option<String>, detail: serde_json::Value) {
+    events::record(&kind, server_name.as_deref(), detail);
+}
+
+#[tauri::command]
+async fn list_openapi_servers() -> Vec<openapi::OpenApiConfig> {
+    get_openapi_bridge().configs().await
+}
+
+/// Load an OpenAPI document and expose its operations as tools of a virtual server
+#[tauri::command]
+async fn register_openapi_server(
+    config: openapi::OpenApiConfig,
+) -> Result<Vec<mcp::MCPTool>, String> {
+    if config.name == builtin::SERVER_NAME
+        || config.name == script_tools::SERVER_NAME
+        || config.name == browser::SERVER_NAME
+        || get_mcp_manager().is_server_alive(&config.name).await
+    {
+        return Err(format!("Server name '{}' is already in use", config.name));
+    }
+    get_openapi_bridge()
+        .register(config)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn remove_openapi_server(name: String) -> Result<(), String> {
+    get_openapi_bridge()
+        .remove(&name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn list_script_tools() -> Result<Vec<script_tools::ScriptTool>, String> {
+    get_script_tools().list().await.map_err(|e| e.to_string())
+}
+
+/// Save a Deno-sandboxed script as a tool of the `scripts` virtual server
+#[tauri::command]
+async fn create_script_tool(tool: script_tools::ScriptTool) -> Result<(), String> {
+    get_script_tools()
+        .save(tool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delete_script_tool(name: String) -> Result<(), String> {
+    get_script_tools()
+        .delete(&name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Offline speech-to-text; partial results arrive as `transcription-partial` events
+#[tauri::command]
+async fn transcribe_audio(
+    source: transcribe::AudioSource,
+    model: Option<String>,
+    language: Option<String>,
+    request_id: Option<String>,
+) -> Result<transcribe::Transcript, String> {
+    transcribe::transcribe(
+        request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        &get_app_data_dir(),
+        source,
+        model.as_deref(),
+        language.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_browser_settings() -> browser::BrowserSettings {
+    get_browser_tools().settings().await
+}
+
+/// Update the browser domain allowlist; a running browser is restarted on next use
+#[tauri::command]
+async fn set_browser_settings(settings: browser::BrowserSettings) -> Result<(), String> {
+    get_browser_tools()
+        .save_settings(&settings)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_web_search_settings() -> web_search::WebSearchSettings {
+    get_web_search().settings().await
+}
+
+/// Configure the engines behind the `web_search` builtin tool, tried in order
+#[tauri::command]
+async fn set_web_search_settings(settings: web_search::WebSearchSettings) -> Result<(), String> {
+    get_web_search()
+        .save_settings(&settings)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_postprocess_settings() -> postprocess::PostProcessSettings {
+    get_post_processor().settings().await
+}
+
+/// Configure how tool output is cleaned up and trimmed before it reaches the model
+#[tauri::command]
+async fn set_postprocess_settings(
+    settings: postprocess::PostProcessSettings,
+) -> Result<(), String> {
+    get_post_processor()
+        .save_settings(&settings)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_result_cache_settings() -> result_cache::ResultCacheSettings {
+    get_result_cache().settings().await
+}
+
+/// Configure caching of read-only tool results; saving clears the cache
+#[tauri::command]
+async fn set_result_cache_settings(
+    settings: result_cache::ResultCacheSettings,
+) -> Result<(), String> {
+    get_result_cache()
+        .save_settings(settings)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_tool_alias_settings() -> tool_aliases::ToolAliasSettings {
+    get_tool_aliases().settings().await
+}
+
+/// Rename tools per server, e.g. `filesystem__read_text_file` to `read_file`
+#[tauri::command]
+async fn set_tool_alias_settings(settings: tool_aliases::ToolAliasSettings) -> Result<(), String> {
+    get_tool_aliases()
+        .save_settings(settings)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Server and tool behind a name the model called, whether an alias or `server__tool`
+#[tauri::command]
+async fn resolve_tool_name(name: String) -> Option<tool_aliases::ResolvedToolName> {
+    get_tool_aliases().resolve(&name).await
+}
+
+#[tauri::command]
+async fn get_tool_conflict_settings() -> tool_conflicts::ToolConflictSettings {
+    get_conflict_resolver().settings().await
+}
+
+/// Choose how tools exported by several servers under one name are exposed
+#[tauri::command]
+async fn set_tool_conflict_settings(
+    settings: tool_conflicts::ToolConflictSettings,
+) -> Result<(), String> {
+    get_conflict_resolver()
+        .save_settings(settings)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_tool_priority_settings() -> tool_priority::ToolPrioritySettings {
+    get_tool_prioritizer().settings().await
+}
+
+/// Set the order tools are listed to the model in, and how many are kept
+#[tauri::command]
+async fn set_tool_priority_settings(
+    settings: tool_priority::ToolPrioritySettings,
+) -> Result<(), String> {
+    get_tool_prioritizer()
+        .save_settings(settings)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Tool names exported by more than one connected server
+#[tauri::command]
+async fn get_tool_conflicts() -> Result<Vec<tool_conflicts::ToolConflict>, String> {
+    let tools = connected_tools().await.map_err(|e| e.to_string())?;
+    Ok(get_conflict_resolver().conflicts(&tools).await)
+}
+
+/// Check every connected tool against the generic rules and each provider in `targets`
+/// (all of them by default), so tools a model would reject or misread show up before use
+#[tauri::command]
+async fn validate_all_tools(
+    targets: Option<Vec<tool_validation::ValidationTarget>>,
+) -> Result<Vec<tool_validation::ToolReport>, String> {
+    let targets = targets.unwrap_or_else(|| tool_validation::ValidationTarget::ALL.to_vec());
+    let tools = connected_tools().await.map_err(|e| e.to_string())?;
+    Ok(tools
+        .iter()
+        .map(|tool| tool_validation::validate(tool, &targets))
+        .collect())
+}
+
+/// A server's tools without those `target` would reject
+#[tauri::command]
+async fn get_validated_tools(
+    server_name: String,
+    target: tool_validation::ValidationTarget,
+) -> Result<Vec<mcp::MCPTool>, String> {
+    get_mcp_manager()
+        .get_validated_tools(&server_name, target)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Names of the registered tool hooks, outermost first
+#[tauri::command]
+fn list_tool_hooks() -> Vec<String> {
+    get_tool_hooks().names()
+}
+
+#[tauri::command]
+async fn get_result_cache_stats() -> result_cache::ResultCacheStats {
+    get_result_cache().stats().await
+}
+
+#[tauri::command]
+async fn clear_result_cache() {
+    get_result_cache().clear().await;
+}
+
+/// Queue text for playback; `voice` may name a platform voice or a piper `.onnx` model
+#[tauri::command]
+async fn speak_text(text: String, voice: Option<String>) -> Result<u64, String> {
+    get_speech_queue()
+        .speak(text, voice)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn stop_speaking() {
+    get_speech_queue().stop();
+}
+
+/// File changes made by builtin tools, newest first
+#[tauri::command]
+async fn list_fs_operations(
+    session_id: Option<String>,
+) -> Result<Vec<fs_journal::FsOperation>, String> {
+    tokio::task::spawn_blocking(move || get_fs_journal().list(session_id.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Restore a file to its content before one builtin tool operation
+#[tauri::command]
+async fn undo_fs_operation(op_id: String) -> Result<fs_journal::FsOperation, String> {
+    tokio::task::spawn_blocking(move || get_fs_journal().undo(&op_id))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Take a full snapshot of a workspace directory that can later be restored in one step
+#[tauri::command]
+async fn create_workspace_snapshot(dir: String) -> Result<snapshots::Snapshot, String> {
+    let root = get_path_policy()
+        .check(std::path::Path::new(&dir), sandbox::AccessKind::Read)
+        .await
+        .map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || get_snapshot_store().create(&root, "Manual", None))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Workspace snapshots newest first, optionally only those of one directory
+#[tauri::command]
+async fn list_workspace_snapshots(dir: Option<String>) -> Result<Vec<snapshots::Snapshot>, String> {
+    tokio::task::spawn_blocking(move || {
+        let root = dir.map(|dir| std::fs::canonicalize(&dir).unwrap_or_else(|_| dir.into()));
+        get_snapshot_store().list(root.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Restore a workspace directory to the state captured by a snapshot
+#[tauri::command]
+async fn restore_snapshot(id: String) -> Result<snapshots::RestoreReport, String> {
+    let snapshot = get_snapshot_store().get(&id).map_err(|e| e.to_string())?;
+    get_path_policy()
+        .check(&snapshot.root, sandbox::AccessKind::Write)
+        .await
+        .map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || get_snapshot_store().restore(&id))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Bring the code index for a workspace directory up to date ahead of the first query
+#[tauri::command]
+async fn index_code_workspace(root: String) -> Result<code_index::IndexStats, String> {
+    let root = get_path_policy()
+        .check(std::path::Path::new(&root), sandbox::AccessKind::Read)
+        .await
+        .map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || get_code_index().refresh(&root))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Aggregated diagnostics meant to be copy-pasted into bug reports
+#[tauri::command]
+async fn get_health_report() -> health::HealthReport {
+    health::report(
+        get_mcp_manager(),
+        get_attachment_store(),
+        &get_app_data_dir(),
+    )
+    .await
+}
+
+/// Folder offered to the filesystem starter server: the first workspace root, else home
+async fn default_setup_root() -> PathBuf {
+    match get_path_policy().roots().await.into_iter().next() {
+        Some(root) => root,
+        None => dirs::home_dir().unwrap_or_else(get_app_data_dir),
+    }
+}
+
+/// Installed runtimes and the starter servers they can run, for the first-run flow
+#[tauri::command]
+async fn get_first_run_suggestions() -> first_run::SetupSuggestions {
+    first_run::suggestions(&get_app_data_dir(), &default_setup_root().await).await
+}
+
+/// Start the selected starter servers and self-test each with one tool call.
+/// The generated configs are returned for the caller to save.
+#[tauri::command]
+async fn run_first_time_setup(
+    selections: Vec<first_run::SetupSelection>,
+) -> Result<first_run::SetupReport, String> {
+    first_run::run(
+        get_mcp_manager(),
+        &get_app_data_dir(),
+        &default_setup_root().await,
+        selections,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn check_server_status(server_name: String) -> bool {
+    get_mcp_manager().is_server_alive(&server_name).await
+}
+
+#[tauri::command]
+async fn check_all_servers_status() -> std::collections::HashMap<String, bool> {
+    get_mcp_manager().check_all_servers().await
+}
+
+#[tauri::command]
+async fn list_prompt_templates() -> Result<Vec<PromptTemplate>, String> {
+    get_prompt_library().list().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_prompt_template(name: String, version: Option<u32>) -> Result<PromptTemplate, String> {
+    get_prompt_library()
+        .get(&name, version)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn save_prompt_template(template: PromptTemplate) -> Result<PromptTemplate, String> {
+    get_prompt_library()
+        .save(template)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delete_prompt_template(name: String) -> Result<(), String> {
+    get_prompt_library()
+        .delete(&name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn render_prompt_template(
+    name: String,
+    variables: serde_json::Map<String, serde_json::Value>,
+    version: Option<u32>,
+) -> Result<String, String> {
+    let template = get_prompt_library()
+        .get(&name, version)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // 템플릿에서 사용할 수 있는 동적 컨텍스트
+    let context = prompts::render_context(
+        get_mcp_manager().get_connected_servers().await,
+        &get_path_policy().roots().await,
+    );
+
+    prompts::render(&template, &variables, &context).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn start_mcp_server(config: MCPServerConfig) -> Result<String, String> {
-    get_mcp_manager()
-        .start_server(config)
+async fn store_attachment(
+    data: String,
+    mime_type: Option<String>,
+) -> Result<AttachmentInfo, String> {
+    use base64::Engine;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| format!("Invalid base64 attachment data: {}", e))?;
+    get_attachment_store()
+        .store_bytes(&bytes, mime_type)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Store a file from disk; only files inside the workspace roots can be attached
 #[tauri::command]
-async fn stop_mcp_server(server_name: String) -> Result<(), String> {
-    get_mcp_manager()
-        .stop_server(&server_name)
+async fn store_attachment_file(
+    path: String,
+    mime_type: Option<String>,
+) -> Result<AttachmentInfo, String> {
+    let path = get_path_policy()
+        .check(std::path::Path::new(&path), sandbox::AccessKind::Read)
+        .await
+        .map_err(|e| e.to_string())?;
+    get_attachment_store()
+        .store_file(&path, mime_type)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn call_mcp_tool(
-    server_name: String,
-    tool_name: String,
-    arguments: serde_json::Value,
-) -> ToolCallResult {
-    get_mcp_manager()
-        .call_tool(&server_name, &tool_name, arguments)
+async fn get_attachment_info(hash: String) -> Result<AttachmentInfo, String> {
+    get_attachment_store()
+        .info(&hash)
         .await
+        .map_err(|e| e.to_string())
 }
 
+/// Stream an attachment to the frontend in raw chunks, returning the total size
 #[tauri::command]
-async fn list_mcp_tools(server_name: String) -> Result<Vec<mcp::MCPTool>, String> {
+async fn get_attachment(
+    hash: String,
+    on_chunk: tauri::ipc::Channel<tauri::ipc::InvokeResponseBody>,
+) -> Result<u64, String> {
+    use tokio::io::AsyncReadExt;
+
+    let mut file = get_attachment_store()
+        .open(&hash)
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut total = 0u64;
+    loop {
+        let read = file.read(&mut buffer).await.map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        on_chunk
+            .send(tauri::ipc::InvokeResponseBody::Raw(buffer[..read].to_vec()))
+            .map_err(|e| e.to_string())?;
+        total += read as u64;
+    }
+    Ok(total)
+}
+
+#[tauri::command]
+async fn retain_attachment(hash: String) -> Result<AttachmentInfo, String> {
+    get_attachment_store()
+        .retain(&hash)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn release_attachment(hash: String) -> Result<(), String> {
+    get_attachment_store()
+        .release(&hash)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_retention_policy() -> Result<RetentionPolicy, String> {
+    get_retention_manager()
+        .get_policy()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_retention_policy(policy: RetentionPolicy) -> Result<(), String> {
+    get_retention_manager()
+        .set_policy(policy)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_storage_stats() -> Result<StorageStats, String> {
+    retention::storage_stats(&get_app_data_dir(), get_attachment_store())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn compact_storage() -> Result<retention::CompactionReport, String> {
+    get_retention_manager()
+        .compact(get_attachment_store())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_workspace_roots() -> Vec<String> {
+    get_path_policy()
+        .roots()
+        .await
+        .iter()
+        .map(|root| root.display().to_string())
+        .collect()
+}
+
+#[tauri::command]
+async fn add_workspace_root(path: String) -> Result<String, String> {
+    get_path_policy()
+        .add_root(std::path::Path::new(&path))
+        .await
+        .map(|root| root.display().to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn remove_workspace_root(path: String) -> Result<(), String> {
+    get_path_policy()
+        .remove_root(std::path::Path::new(&path))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Denied builtin tool accesses, newest first
+#[tauri::command]
+async fn get_sandbox_violations(
+    limit: Option<usize>,
+) -> Result<Vec<sandbox::SandboxViolation>, String> {
+    tokio::task::spawn_blocking(move || get_path_policy().violations(limit.unwrap_or(100)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// The snapshot left by the previous run, if any, so the UI can offer to restore it
+#[tauri::command]
+async fn get_previous_session() -> Result<Option<mcp::ConnectionSnapshot>, String> {
+    load_connection_snapshot().await
+}
+
+#[tauri::command]
+async fn restore_previous_session() -> Result<mcp::RestoreReport, String> {
+    let snapshot = load_connection_snapshot()
+        .await?
+        .ok_or_else(|| "No previous session to restore".to_string())?;
     get_mcp_manager()
-        .list_tools(&server_name)
+        .restore(snapshot)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn list_tools_from_config(config: serde_json::Value) -> Result<Vec<mcp::MCPTool>, String> {
-    println!("🚀 [TAURI] list_tools_from_config called!");
-    println!(
-        "🚀 [TAURI] Config received: {}",
-        serde_json::to_string_pretty(&config).unwrap_or_default()
-    );
+async fn get_cached_tools() -> HashMap<String, Vec<mcp::MCPTool>> {
+    get_mcp_manager().get_cached_tools().await
+}
 
-    // Claude format을 지원: mcpServers 또는 servers 배열을 처리
-    let servers_config =
-        if let Some(mcp_servers) = config.get("mcpServers").and_then(|v| v.as_object()) {
-            // Claude format: mcpServers 객체를 MCPServerConfig 배열로 변환
-            println!("🚀 [TAURI] Processing Claude format (mcpServers)");
-            let mut server_list = Vec::new();
+#[tauri::command]
+fn test_redaction(sample: String) -> redact::RedactionTestResult {
+    redact::test(&sample)
+}
 
-            for (name, server_config) in mcp_servers.iter() {
-                let mut server_value = server_config.clone();
-                // name 필드 추가
-                if let serde_json::Value::Object(ref mut obj) = server_value {
-                    obj.insert("name".to_string(), serde_json::Value::String(name.clone()));
-                    obj.insert(
-                        "transport".to_string(),
-                        serde_json::Value::String("stdio".to_string()),
-                    );
-                }
-                let server_cfg: mcp::MCPServerConfig = serde_json::from_value(server_value)
-                    .map_err(|e| format!("Invalid server config: {}", e))?;
-                server_list.push(server_cfg);
-            }
-            server_list
-        } else if let Some(servers_array) = config.get("servers").and_then(|v| v.as_array()) {
-            // 기존 format: servers 배열
-            println!("🚀 [TAURI] Processing legacy format (servers array)");
-            let mut server_list = Vec::new();
-            for server_value in servers_array {
-                let server_cfg: mcp::MCPServerConfig = serde_json::from_value(server_value.clone())
-                    .map_err(|e| format!("Invalid server config: {}", e))?;
-                server_list.push(server_cfg);
-            }
-            server_list
-        } else {
-            return Err("Invalid config: missing mcpServers object or servers array".to_string());
-        };
+#[tauri::command]
+fn get_redaction_rules() -> Vec<redact::RedactionRule> {
+    redact::custom_rules()
+}
 
-    println!(
-        "🚀 [TAURI] Found {} servers in config",
-        servers_config.len()
-    );
+#[tauri::command]
+fn set_redaction_rules(rules: Vec<redact::RedactionRule>) -> Result<(), String> {
+    redact::save_custom_rules(&get_app_data_dir().join("redaction_rules.json"), rules)
+        .map_err(|e| e.to_string())
+}
 
-    let manager = get_mcp_manager();
+#[tauri::command]
+fn get_telemetry_config() -> telemetry::TelemetryConfig {
+    telemetry::config()
+}
 
-    let mut all_tools: Vec<mcp::MCPTool> = Vec::new();
+#[tauri::command]
+fn set_telemetry_config(config: telemetry::TelemetryConfig) -> Result<(), String> {
+    telemetry::set_config(config).map_err(|e| e.to_string())
+}
 
-    // Start servers from config and collect their tools
-    for server_cfg in servers_config {
-        let server_name = server_cfg.name.clone();
-        if !manager.is_server_alive(&server_name).await {
-            println!("🚀 [TAURI] Starting server: {}", server_name);
-            if let Err(e) = manager.start_server(server_cfg).await {
-                eprintln!("❌ [TAURI] Failed to start server {}: {}", server_name, e);
-                continue; // Skip to the next server if this one fails to start
-            }
-            tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
-        } else {
-            println!("🚀 [TAURI] Server {} already running", server_name);
-        }
+#[tauri::command]
+fn get_power_status() -> power::PowerStatus {
+    power::status()
+}
 
-        // Fetch tools for the server we just ensured is running
-        match manager.list_tools(&server_name).await {
-            Ok(mut tools) => {
-                println!(
-                    "✅ [TAURI] Found {} tools for server '{}'",
-                    tools.len(),
-                    server_name
-                );
-                // Prefix tool names with server name to avoid conflicts
-                for tool in &mut tools {
-                    tool.name = format!("{}__{}", server_name, tool.name);
-                }
-                all_tools.extend(tools);
-            }
-            Err(e) => {
-                eprintln!(
-                    "❌ [TAURI] Error listing tools for '{}': {}",
-                    server_name, e
-                );
-                // Continue to the next server
-            }
-        }
-    }
+/// Choose when scheduled background work pauses to save battery
+#[tauri::command]
+fn set_power_policy(policy: power::PowerPolicy) -> Result<power::PowerStatus, String> {
+    power::set_policy(policy).map_err(|e| e.to_string())
+}
 
-    println!("✅ [TAURI] Total tools collected: {}", all_tools.len());
-    Ok(all_tools)
+#[tauri::command]
+fn get_path_settings() -> search_path::PathSettings {
+    search_path::settings()
 }
 
+/// Set the directories added to every server's PATH
 #[tauri::command]
-async fn get_connected_servers() -> Vec<String> {
-    get_mcp_manager().get_connected_servers().await
+fn set_path_settings(
+    settings: search_path::PathSettings,
+) -> Result<search_path::PathSettings, String> {
+    search_path::set_settings(settings).map_err(|e| e.to_string())
 }
 
+/// Common install locations (`~/.local/bin`, asdf shims, volta, ...) that exist but
+/// are not searched yet
 #[tauri::command]
-async fn check_server_status(server_name: String) -> bool {
-    get_mcp_manager().is_server_alive(&server_name).await
+fn suggest_path_entries() -> Vec<String> {
+    search_path::suggestions()
 }
 
+/// LLM requests run in the frontend, so it reports their spans here
 #[tauri::command]
-async fn check_all_servers_status() -> std::collections::HashMap<String, bool> {
-    get_mcp_manager().check_all_servers().await
+fn record_llm_span(
+    provider: String,
+    model: String,
+    start_ms: u64,
+    end_ms: u64,
+    error: Option<String>,
+) {
+    let at = |ms: u64| std::time::UNIX_EPOCH + std::time::Duration::from_millis(ms);
+    telemetry::record_span(telemetry::Span {
+        name: format!("llm.request {}", model),
+        kind: telemetry::SpanKind::Client,
+        start: at(start_ms),
+        end: at(end_ms),
+        attributes: vec![
+            ("llm.provider".to_string(), provider),
+            ("llm.model".to_string(), model),
+        ],
+        error,
+    });
+}
+
+#[tauri::command]
+fn list_crash_reports() -> Result<Vec<crash::CrashReport>, String> {
+    crash::list(&get_app_data_dir()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_crash_report_settings() -> crash::CrashSettings {
+    crash::load_settings(&get_app_data_dir())
+}
+
+#[tauri::command]
+fn set_crash_report_settings(settings: crash::CrashSettings) -> Result<(), String> {
+    crash::save_settings(&get_app_data_dir(), &settings).map_err(|e| e.to_string())
+}
+
+/// Opt-in: sends a report only when the user asks and an endpoint is configured
+#[tauri::command]
+async fn submit_crash_report(id: String) -> Result<(), String> {
+    crash::submit(&get_app_data_dir(), &id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_api_settings() -> Result<api::ApiSettings, String> {
+    api::load_settings(&get_app_data_dir()).map_err(|e| e.to_string())
+}
+
+/// Enable, disable or move the local REST API; the token is kept
+#[tauri::command]
+async fn set_api_settings(enabled: bool, port: u16) -> Result<api::ApiSettings, String> {
+    let mut settings = api::load_settings(&get_app_data_dir()).map_err(|e| e.to_string())?;
+    settings.enabled = enabled;
+    settings.port = port;
+    api::save_settings(&get_app_data_dir(), &settings).map_err(|e| e.to_string())?;
+    api::apply(&settings).await.map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+#[tauri::command]
+fn get_chat_bridge_settings() -> Result<chat_bridge::ChatBridgeSettings, String> {
+    chat_bridge::load_settings(&get_app_data_dir()).map_err(|e| e.to_string())
+}
+
+/// Save the Slack/Discord bridge settings and reconnect with them
+#[tauri::command]
+async fn set_chat_bridge_settings(settings: chat_bridge::ChatBridgeSettings) -> Result<(), String> {
+    chat_bridge::save_settings(&get_app_data_dir(), &settings).map_err(|e| e.to_string())?;
+    chat_bridge::apply(&settings)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_autostart_settings() -> Result<autostart::AutostartSettings, String> {
+    autostart::load_settings(&get_app_data_dir()).map_err(|e| e.to_string())
+}
+
+/// Register or remove the login entry; background launches start hidden in the tray
+#[tauri::command]
+fn set_autostart_settings(
+    settings: autostart::AutostartSettings,
+) -> Result<autostart::AutostartSettings, String> {
+    autostart::apply(&settings).map_err(|e| e.to_string())?;
+    autostart::save_settings(&get_app_data_dir(), &settings).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+/// Invalidate the current API token; running clients must use the new one
+#[tauri::command]
+async fn regenerate_api_token() -> Result<api::ApiSettings, String> {
+    let settings = api::regenerate_token(&get_app_data_dir()).map_err(|e| e.to_string())?;
+    api::apply(&settings).await.map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+/// Create a signed webhook that starts a run of `agent_id` through the local API
+#[tauri::command]
+fn register_webhook(agent_id: String) -> Result<webhooks::WebhookRegistration, String> {
+    let settings = api::load_settings(&get_app_data_dir()).map_err(|e| e.to_string())?;
+    let webhook = webhooks::register(&get_app_data_dir(), &agent_id).map_err(|e| e.to_string())?;
+    Ok(webhooks::WebhookRegistration {
+        url: webhooks::url(settings.port, &webhook.id),
+        webhook,
+    })
+}
+
+#[tauri::command]
+fn list_webhooks() -> Result<Vec<webhooks::WebhookRegistration>, String> {
+    let settings = api::load_settings(&get_app_data_dir()).map_err(|e| e.to_string())?;
+    Ok(webhooks::load(&get_app_data_dir())
+        .into_iter()
+        .map(|webhook| webhooks::WebhookRegistration {
+            url: webhooks::url(settings.port, &webhook.id),
+            webhook,
+        })
+        .collect())
+}
+
+#[tauri::command]
+fn remove_webhook(id: String) -> Result<(), String> {
+    webhooks::remove(&get_app_data_dir(), &id).map_err(|e| e.to_string())
+}
+
+/// Answer an `api-request` event forwarded to the webview
+#[tauri::command]
+fn resolve_api_request(
+    id: String,
+    result: Option<serde_json::Value>,
+    error: Option<String>,
+) -> Result<(), String> {
+    let reply = match error {
+        Some(error) => Err(error),
+        None => Ok(result.unwrap_or_default()),
+    };
+    if api::resolve_webview_request(&id, reply) {
+        Ok(())
+    } else {
+        Err(format!("No pending API request '{}'", id))
+    }
+}
+
+/// Recent backend log lines for the in-app console
+#[tauri::command]
+fn get_recent_logs(
+    logs: tauri::State<'_, Arc<logbuffer::LogBuffer>>,
+    level: Option<String>,
+    limit: Option<usize>,
+    target_filter: Option<String>,
+) -> Result<Vec<logbuffer::LogEntry>, String> {
+    let level = level
+        .map(|level| level.parse::<log::LevelFilter>())
+        .transpose()
+        .map_err(|e| format!("Invalid log level: {}", e))?;
+    Ok(logs.recent(level, limit, target_filter.as_deref()))
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    crash::install_panic_hook(get_app_data_dir);
+    let args: Vec<String> = std::env::args().collect();
+    if headless::requested(&args) {
+        std::process::exit(headless::run(args));
+    }
+    // 이미 실행 중인 앱이 있으면 인수를 넘기고 MCP 서버를 다시 띄우지 않고 종료합니다
+    if let Some(data_dir) = dirs::data_dir() {
+        if instance::forward(&data_dir.join(headless::APP_IDENTIFIER), &args) {
+            println!("Forwarded to the running instance");
+            return;
+        }
+    }
+    let log_buffer = Arc::new(logbuffer::LogBuffer::new(logbuffer::DEFAULT_CAPACITY));
+
     tauri::Builder::default()
+        .manage(log_buffer.clone())
         .plugin(
             tauri_plugin_log::Builder::new()
                 .targets([
                     Target::new(TargetKind::Stdout),
                     Target::new(TargetKind::LogDir { file_name: None }),
                     Target::new(TargetKind::Webview),
+                    Target::new(TargetKind::Dispatch(log_buffer.dispatch())),
                 ])
+                // 모든 로그 레코드는 기록되기 전에 마스킹됩니다
+                .format(|out, message, record| {
+                    out.finish(format_args!(
+                        "{}[{}][{}] {}",
+                        chrono::Local::now().format("[%Y-%m-%d][%H:%M:%S]"),
+                        record.target(),
+                        record.level(),
+                        redact::redact(&message.to_string())
+                    ))
+                })
                 .build(),
         )
         .plugin(tauri_plugin_opener::init())
+        .setup(move |app| {
+            #[cfg(desktop)]
+            if updates::signing_configured(app.handle()) {
+                app.handle()
+                    .plugin(tauri_plugin_updater::Builder::new().build())?;
+            } else {
+                println!("Updates are disabled: this build has no updater public key");
+            }
+            let _ = APP_DATA_DIR.set(app.path().app_data_dir()?);
+            events::init(app.handle().clone());
+            // 웹뷰에서 생성되는 이벤트(에이전트 토큰 등)도 외부 구독자에게 전달합니다
+            for name in events::FORWARDED_WEBVIEW_EVENTS {
+                app.listen_any(*name, move |event| {
+                    if let Ok(payload) = serde_json::from_str(event.payload()) {
+                        events::publish(name, payload);
+                    }
+                });
+            }
+            if let Err(e) =
+                redact::load_custom_rules(&get_app_data_dir().join("redaction_rules.json"))
+            {
+                eprintln!("❌ Failed to load redaction rules: {}", e);
+            }
+            if let Err(e) = telemetry::load_config(&get_app_data_dir().join("telemetry.json")) {
+                eprintln!("❌ Failed to load telemetry config: {}", e);
+            }
+            if let Err(e) = power::load_policy(&get_app_data_dir().join("power_policy.json")) {
+                eprintln!("❌ Failed to load power policy: {}", e);
+            }
+            if let Err(e) =
+                search_path::load_settings(&get_app_data_dir().join("path_settings.json"))
+            {
+                eprintln!("❌ Failed to load PATH settings: {}", e);
+            }
+            tauri::async_runtime::spawn(power::run_monitor());
+            tauri::async_runtime::spawn(telemetry::run_exporter());
+            tauri::async_runtime::spawn(get_openapi_bridge().load_saved());
+            tauri::async_runtime::spawn(async {
+                let result = match api::load_settings(&get_app_data_dir()) {
+                    Ok(settings) => api::apply(&settings).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    eprintln!("❌ Failed to start local API: {}", e);
+                }
+            });
+            tauri::async_runtime::spawn(async {
+                let result = match chat_bridge::load_settings(&get_app_data_dir()) {
+                    Ok(settings) => chat_bridge::apply(&settings).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    eprintln!("❌ Failed to start the chat bridge: {}", e);
+                }
+            });
+            tauri::async_runtime::spawn(async {
+                if let Ok(content) = tokio::fs::read_to_string(instruction_settings_path()).await {
+                    if let Ok(disabled) = serde_json::from_str(&content) {
+                        get_mcp_manager().set_disabled_instructions(disabled).await;
+                    }
+                }
+                get_mcp_manager().run_idle_reaper().await;
+            });
+            tauri::async_runtime::spawn(get_remote_configs().run_background());
+            tauri::async_runtime::spawn(get_tool_analytics_store().run_background());
+            tauri::async_runtime::spawn(get_backup_service().run_background());
+            #[cfg(desktop)]
+            tauri::async_runtime::spawn(get_update_service().run_background(app.handle().clone()));
+            tauri::async_runtime::spawn(
+                get_device_sync()
+                    .run_background(|report| async move { reload_device_synced(&report).await }),
+            );
+            tauri::async_runtime::spawn(async {
+                get_retention_manager()
+                    .run_background(get_attachment_store())
+                    .await;
+            });
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = instance::listen(handle, get_app_data_dir()).await {
+                    eprintln!("❌ Failed to accept launches from other processes: {}", e);
+                }
+            });
+            // 개발 빌드가 설치된 앱 대신 링크를 받지 않도록 릴리스 빌드에서만 등록합니다
+            if !cfg!(debug_assertions) {
+                tauri::async_runtime::spawn_blocking(|| {
+                    if let Err(e) = instance::register_url_scheme() {
+                        eprintln!("❌ Failed to register the link handler: {}", e);
+                    }
+                });
+            }
+            #[cfg(desktop)]
+            windows::create_tray(app.handle())?;
+            // 메인 창은 숨겨진 채로 만들어지며 백그라운드 모드에서는 트레이에만 남습니다
+            if autostart::requested(&args) {
+                tauri::async_runtime::spawn(async {
+                    if let Err(e) = autostart::warm_up().await {
+                        eprintln!("❌ Failed to warm up servers: {}", e);
+                    }
+                });
+            } else {
+                let cwd = std::env::current_dir().ok();
+                instance::deliver(app.handle(), instance::parse_args(&args, cwd.as_deref()));
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             start_mcp_server,
+            compute_server_hash,
+            start_mcp_servers,
             stop_mcp_server,
+            start_group,
+            stop_group,
+            get_server_groups,
             call_mcp_tool,
+            benchmark_tool,
             list_mcp_tools,
+            list_mcp_prompts,
+            list_mcp_resources,
+            list_mcp_resource_templates,
+            complete_argument,
+            set_server_log_level,
+            subscribe_resource,
+            unsubscribe_resource,
+            get_resource_subscriptions,
             list_tools_from_config,
             get_connected_servers,
+            get_server_info,
+            get_server_instructions,
+            set_server_instructions_enabled,
+            run_server_conformance,
+            get_health_report,
+            list_openapi_servers,
+            export_tool_definitions,
+            export_config_claude_format,
+            list_config_versions,
+            rollback_config,
+            get_config_sync_status,
+            set_config_sync_settings,
+            sync_config_now,
+            open_chat_window,
+            list_chat_windows,
+            take_launch_requests,
+            get_device_sync_status,
+            set_device_sync_settings,
+            sync_devices_now,
+            get_backup_settings,
+            set_backup_settings,
+            create_backup_now,
+            list_backups,
+            restore_backup,
+            #[cfg(desktop)]
+            is_updater_configured,
+            #[cfg(desktop)]
+            get_update_settings,
+            #[cfg(desktop)]
+            set_update_settings,
+            #[cfg(desktop)]
+            check_for_updates,
+            #[cfg(desktop)]
+            install_update,
+            add_remote_config_source,
+            remove_remote_config_source,
+            list_remote_config_sources,
+            refresh_remote_config_source,
+            get_managed_servers,
+            get_managed_config_drift,
+            get_platform_capabilities,
+            validate_server_config,
+            preview_mcp_import,
+            import_history,
+            get_server_config_schema,
+            register_openapi_server,
+            remove_openapi_server,
+            list_script_tools,
+            create_script_tool,
+            delete_script_tool,
+            transcribe_audio,
+            speak_text,
+            get_browser_settings,
+            get_web_search_settings,
+            set_web_search_settings,
+            get_postprocess_settings,
+            set_postprocess_settings,
+            get_result_cache_settings,
+            set_result_cache_settings,
+            get_result_cache_stats,
+            clear_result_cache,
+            list_tool_hooks,
+            get_tool_alias_settings,
+            set_tool_alias_settings,
+            resolve_tool_name,
+            get_tool_conflict_settings,
+            set_tool_conflict_settings,
+            get_tool_priority_settings,
+            get_tool_analytics,
+            set_tool_priority_settings,
+            get_tool_conflicts,
+            validate_all_tools,
+            get_validated_tools,
+            get_first_run_suggestions,
+            run_first_time_setup,
+            set_browser_settings,
+            stop_speaking,
+            index_code_workspace,
+            list_fs_operations,
+            undo_fs_operation,
+            create_workspace_snapshot,
+            list_workspace_snapshots,
+            restore_snapshot,
+            get_events_since,
+            record_timeline_event,
             check_server_status,
-            check_all_servers_status
+            check_all_servers_status,
+            list_prompt_templates,
+            get_prompt_template,
+            save_prompt_template,
+            delete_prompt_template,
+            render_prompt_template,
+            store_attachment,
+            store_attachment_file,
+            get_attachment_info,
+            get_attachment,
+            retain_attachment,
+            release_attachment,
+            get_retention_policy,
+            set_retention_policy,
+            get_storage_stats,
+            compact_storage,
+            get_workspace_roots,
+            add_workspace_root,
+            remove_workspace_root,
+            get_sandbox_violations,
+            test_redaction,
+            get_redaction_rules,
+            set_redaction_rules,
+            get_recent_logs,
+            get_api_settings,
+            set_api_settings,
+            get_autostart_settings,
+            set_autostart_settings,
+            regenerate_api_token,
+            get_chat_bridge_settings,
+            set_chat_bridge_settings,
+            resolve_api_request,
+            register_webhook,
+            list_webhooks,
+            remove_webhook,
+            get_telemetry_config,
+            set_telemetry_config,
+            get_power_status,
+            set_power_policy,
+            get_path_settings,
+            set_path_settings,
+            suggest_path_entries,
+            record_llm_span,
+            list_crash_reports,
+            get_crash_report_settings,
+            set_crash_report_settings,
+            submit_crash_report,
+            get_previous_session,
+            restore_previous_session,
+            get_cached_tools
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, event| {
+            #[cfg(any(target_os = "macos", target_os = "ios"))]
+            if let tauri::RunEvent::Opened { urls } = &event {
+                instance::deliver(_app, instance::parse_urls(urls));
+            }
+            if let tauri::RunEvent::Exit = event {
+                tauri::async_runtime::block_on(save_connection_snapshot());
+                if let Some(analytics) = TOOL_ANALYTICS.get() {
+                    if let Err(e) = tauri::async_runtime::block_on(analytics.flush()) {
+                        eprintln!("❌ Failed to save tool analytics: {}", e);
+                    }
+                }
+                if let Some(browser) = BROWSER_TOOLS.get() {
+                    tauri::async_runtime::block_on(browser.shutdown());
+                }
+                instance::release(&get_app_data_dir());
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_server_configs_keeps_each_transport() {
+        let config = serde_json::json!({
+            "mcpServers": {
+                "local": { "command": "node", "args": ["server.js"] },
+                "hosted": { "url": "https://mcp.example.com/mcp" },
+                "socket": { "url": "wss://mcp.example.com/ws" },
+                "pinned": { "transport": "http", "url": "ws://127.0.0.1:9000" },
+                "isolated": {
+                    "transport": "docker",
+                    "command": "python",
+                    "args": ["-m", "server"],
+                    "container": { "image": "python:3.12-slim", "network": "none" }
+                }
+            }
+        });
+        let servers = parse_server_configs(&config).unwrap();
+        let server = |name: &str| servers.iter().find(|s| s.name == name).unwrap();
+
+        assert_eq!(server("local").transport, "stdio");
+        assert_eq!(server("hosted").transport, "http");
+        assert_eq!(server("socket").transport, "websocket");
+        assert_eq!(server("pinned").transport, "http");
+        let isolated = server("isolated");
+        assert_eq!(isolated.transport, "docker");
+        let container = isolated.container.as_ref().unwrap();
+        assert_eq!(container.image, "python:3.12-slim");
+        assert_eq!(container.network.as_deref(), Some("none"));
+    }
 }
@@ -0,0 +1,277 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tokio::sync::Mutex;
+
+use crate::events;
+use crate::mcp::MCPConnection;
+
+const SAMPLE_INTERVAL_SECS: u64 = 2;
+// CPU 제한은 이 개수의 샘플 평균으로 판단합니다 (순간적인 스파이크 무시)
+const CPU_WINDOW: usize = 5;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ResourceLimits {
+    pub max_memory_mb: Option<u64>,
+    /// Fraction of one core, e.g. `0.5` for half a core
+    pub cpu_limit: Option<f64>,
+}
+
+impl ResourceLimits {
+    pub fn is_empty(&self) -> bool {
+        self.max_memory_mb.is_none() && self.cpu_limit.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LimitExceeded {
+    pub server_name: String,
+    pub pid: u32,
+    pub reason: String,
+}
+
+/// Cap a server's address space before it starts, so the kernel refuses
+/// allocations past the limit instead of waiting for the next poll.
+///
+/// `RLIMIT_AS` applies to each process on its own; the tree-wide total is
+/// still enforced by [`watch`].
+#[cfg(unix)]
+pub fn limit_memory_at_spawn(cmd: &mut tokio::process::Command, limits: &ResourceLimits) {
+    let Some(max_mb) = limits.max_memory_mb else {
+        return;
+    };
+    let bytes = max_mb.saturating_mul(1024 * 1024) as libc::rlim_t;
+    // SAFETY: fork 이후 exec 이전에는 async-signal-safe 호출인 setrlimit만 사용합니다
+    unsafe {
+        cmd.pre_exec(move || {
+            let limit = libc::rlimit {
+                rlim_cur: bytes,
+                rlim_max: bytes,
+            };
+            // 한도를 올릴 수 없는 경우 등 실패하면 폴링에 맡깁니다
+            libc::setrlimit(libc::RLIMIT_AS, &limit);
+            Ok(())
+        });
+    }
+}
+
+/// Put a freshly spawned server in a Job Object whose committed memory is
+/// capped; processes it starts later join the same job.
+#[cfg(windows)]
+pub fn limit_memory_after_spawn(pid: u32, limits: &ResourceLimits) -> std::io::Result<()> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+        SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_JOB_MEMORY,
+    };
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE,
+    };
+
+    let Some(max_mb) = limits.max_memory_mb else {
+        return Ok(());
+    };
+    // SAFETY: 핸들은 이 함수 안에서만 쓰고 닫습니다. 작업 개체는 프로세스가
+    // 남아 있는 동안 유지됩니다
+    unsafe {
+        let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+        if job.is_null() {
+            return Err(std::io::Error::last_os_error());
+        }
+        let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+        info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_JOB_MEMORY;
+        info.JobMemoryLimit = max_mb.saturating_mul(1024 * 1024) as usize;
+
+        let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid);
+        let result = if process.is_null()
+            || SetInformationJobObject(
+                job,
+                JobObjectExtendedLimitInformation,
+                &info as *const JOBOBJECT_EXTENDED_LIMIT_INFORMATION as *const std::ffi::c_void,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            ) == 0
+            || AssignProcessToJobObject(job, process) == 0
+        {
+            Err(std::io::Error::last_os_error())
+        } else {
+            Ok(())
+        };
+        if !process.is_null() {
+            CloseHandle(process);
+        }
+        CloseHandle(job);
+        result
+    }
+}
+
+/// Pids of `root` and all of its descendants
+pub(crate) fn process_tree(system: &System, root: Pid) -> Vec<Pid> {
+    let mut tree = vec![root];
+    let mut seen: HashSet<Pid> = HashSet::from([root]);
+    let mut index = 0;
+    while index < tree.len() {
+        let parent = tree[index];
+        for (pid, process) in system.processes() {
+            if process.parent() == Some(parent) && seen.insert(*pid) {
+                tree.push(*pid);
+            }
+        }
+        index += 1;
+    }
+    tree
+}
+
+/// Summed RSS of each root's process tree; roots that are not running are left out
+pub fn tree_memory_bytes(roots: &[u32]) -> HashMap<u32, u64> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_memory(),
+    );
+    roots
+        .iter()
+        .filter(|pid| system.process(Pid::from_u32(**pid)).is_some())
+        .map(|pid| {
+            let bytes = process_tree(&system, Pid::from_u32(*pid))
+                .iter()
+                .filter_map(|pid| system.process(*pid))
+                .map(|process| process.memory())
+                .sum();
+            (*pid, bytes)
+        })
+        .collect()
+}
+
+/// Sample a server's process tree until it exits, killing it when it exceeds its limits.
+///
+/// Memory is the summed RSS of the tree; CPU is averaged over a short window so
+/// start-up spikes don't trip the limit. This is the fallback for whatever the
+/// spawn-time limits don't cover (CPU, and memory where the OS limit could not
+/// be applied).
+pub async fn watch(
+    server_name: String,
+    pid: u32,
+    limits: ResourceLimits,
+    connections: Arc<Mutex<HashMap<String, MCPConnection>>>,
+) {
+    let root = Pid::from_u32(pid);
+    let mut system = System::new();
+    let mut cpu_samples: VecDeque<f64> = VecDeque::with_capacity(CPU_WINDOW);
+
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(SAMPLE_INTERVAL_SECS)).await;
+
+        // 연결이 사라졌거나 다른 프로세스로 교체되었으면 감시를 중단합니다
+        let still_running = connections
+            .lock()
+            .await
+            .get(&server_name)
+            .is_some_and(|c| c.pid == Some(pid));
+        if !still_running {
+            return;
+        }
+
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing().with_memory().with_cpu(),
+        );
+        if system.process(root).is_none() {
+            return;
+        }
+
+        let tree = process_tree(&system, root);
+        let (memory_bytes, cpu_percent) = tree
+            .iter()
+            .filter_map(|pid| system.process(*pid))
+            .fold((0u64, 0f64), |(mem, cpu), p| {
+                (mem + p.memory(), cpu + p.cpu_usage() as f64)
+            });
+
+        let mut reason = None;
+        if let Some(max_mb) = limits.max_memory_mb {
+            let used_mb = memory_bytes / (1024 * 1024);
+            if used_mb > max_mb {
+                reason = Some(format!(
+                    "memory usage {} MB exceeded the {} MB limit",
+                    used_mb, max_mb
+                ));
+            }
+        }
+        if let Some(cpu_limit) = limits.cpu_limit {
+            if cpu_samples.len() == CPU_WINDOW {
+                cpu_samples.pop_front();
+            }
+            cpu_samples.push_back(cpu_percent);
+            let average = cpu_samples.iter().sum::<f64>() / cpu_samples.len() as f64;
+            if cpu_samples.len() == CPU_WINDOW && average > cpu_limit * 100.0 {
+                reason = reason.or(Some(format!(
+                    "CPU usage {:.0}% exceeded the {:.0}% limit",
+                    average,
+                    cpu_limit * 100.0
+                )));
+            }
+        }
+
+        if let Some(reason) = reason {
+            eprintln!("❌ [LIMITS] Killing server '{}': {}", server_name, reason);
+            for pid in tree.iter().rev() {
+                if let Some(process) = system.process(*pid) {
+                    process.kill();
+                }
+            }
+            if let Some(connection) = connections.lock().await.remove(&server_name) {
+                let _ = connection.client.cancel().await;
+            }
+            events::emit(
+                "mcp-server-limit-exceeded",
+                LimitExceeded {
+                    server_name,
+                    pid,
+                    reason,
+                },
+            );
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn memory_limit_applies_to_the_spawned_process() {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.args(["-c", "ulimit -v"]);
+        let limits = ResourceLimits {
+            max_memory_mb: Some(512),
+            cpu_limit: None,
+        };
+        limit_memory_at_spawn(&mut cmd, &limits);
+        let output = cmd.output().await.unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "524288");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn no_memory_limit_leaves_the_process_alone() {
+        let ulimit = || {
+            let mut cmd = tokio::process::Command::new("sh");
+            cmd.args(["-c", "ulimit -v"]);
+            cmd
+        };
+        let mut cmd = ulimit();
+        let limits = ResourceLimits {
+            max_memory_mb: None,
+            cpu_limit: Some(0.5),
+        };
+        limit_memory_at_spawn(&mut cmd, &limits);
+        let limited = cmd.output().await.unwrap();
+        let inherited = ulimit().output().await.unwrap();
+        assert_eq!(limited.stdout, inherited.stdout);
+    }
+}
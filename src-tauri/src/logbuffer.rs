@@ -0,0 +1,78 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tauri_plugin_log::fern;
+
+// 메모리에 보관하는 최대 로그 레코드 수
+pub const DEFAULT_CAPACITY: usize = 2000;
+const DEFAULT_LIMIT: usize = 200;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    /// The formatted, redacted line as written to the other log targets
+    pub message: String,
+}
+
+/// Bounded ring buffer of recent backend log records for the in-app console
+pub struct LogBuffer {
+    entries: Mutex<VecDeque<(log::Level, LogEntry)>>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    fn push(&self, record: &log::Record) {
+        let entry = LogEntry {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            level: record.level().to_string(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        };
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back((record.level(), entry));
+    }
+
+    /// Most recent entries in chronological order
+    ///
+    /// `level` is the least severe level to include; `target_filter` matches a
+    /// substring of the record target.
+    pub fn recent(
+        &self,
+        level: Option<log::LevelFilter>,
+        limit: Option<usize>,
+        target_filter: Option<&str>,
+    ) -> Vec<LogEntry> {
+        let level = level.unwrap_or(log::LevelFilter::Trace);
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let mut matching: Vec<LogEntry> = entries
+            .iter()
+            .rev()
+            .filter(|(record_level, entry)| {
+                *record_level <= level
+                    && target_filter.is_none_or(|filter| entry.target.contains(filter))
+            })
+            .take(limit.unwrap_or(DEFAULT_LIMIT))
+            .map(|(_, entry)| entry.clone())
+            .collect();
+        matching.reverse();
+        matching
+    }
+
+    /// Log plugin target that feeds this buffer
+    pub fn dispatch(self: &Arc<Self>) -> fern::Dispatch {
+        let buffer = self.clone();
+        fern::Dispatch::new().chain(fern::Output::call(move |record| buffer.push(record)))
+    }
+}
@@ -1,32 +1,191 @@
 use anyhow::Result;
 use rmcp::{
-    model::CallToolRequestParam,
-    service::{RoleClient, RunningService},
-    transport::{ConfigureCommandExt, TokioChildProcess},
-    ServiceExt,
+    model::{
+        ArgumentInfo, CallToolRequestParam, CompleteRequestParam, CompletionInfo, LoggingLevel,
+        PaginatedRequestParam, Prompt, Reference, Resource, ResourceTemplate, SetLevelRequestParam,
+        SubscribeRequestParam, Tool, UnsubscribeRequestParam,
+    },
+    service::{Peer, RoleClient, RunningService},
+    transport::{ConfigureCommandExt, IntoTransport, TokioChildProcess},
+    ClientHandler, ServiceExt,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Instant, SystemTime};
 use tokio::process::Command;
 use tokio::sync::Mutex;
 
+use crate::client_handler::MCPClientHandler;
+use crate::events;
+use crate::integrity;
+use crate::limits::{self, ResourceLimits};
+use crate::ratelimit::CallLimiter;
+use crate::redact;
+use crate::remote_transport;
+use crate::runtime::{self, Runtime};
+use crate::search_path;
+use crate::telemetry::{self, Span, SpanKind};
+use crate::tool_validation::{self, Severity, ToolReport, ValidationNotice, ValidationTarget};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MCPServerConfig {
     pub name: String,
     pub command: Option<String>,
     pub args: Option<Vec<String>>,
     pub env: Option<HashMap<String, String>>,
+    /// Launcher preset expanded to `command`/`args` at spawn time
+    #[serde(default)]
+    pub runtime: Option<Runtime>,
+    /// WSL distribution for the `wsl` runtime; the default distribution when unset
+    #[serde(default)]
+    pub distro: Option<String>,
+    /// Package (or image, for `docker`) started by `runtime`
+    #[serde(default)]
+    pub package: Option<String>,
     #[serde(default = "default_transport")]
-    pub transport: String, // "stdio" | "http" | "websocket"
+    pub transport: String, // "stdio" | "http" | "websocket" | "docker"
     pub url: Option<String>,
     pub port: Option<u16>,
+    /// Directories searched before the app's PATH when starting this server; a leading
+    /// `~` is the home directory
+    #[serde(default)]
+    pub path: Vec<String>,
+    /// How much of the app's environment a stdio server inherits
+    #[serde(default)]
+    pub env_policy: EnvPolicy,
+    /// Variables passed through from the app's environment with `inherit-list`
+    #[serde(default)]
+    pub inherit_env: Option<Vec<String>>,
+    /// Container settings for the `docker` transport
+    #[serde(default)]
+    pub container: Option<ContainerConfig>,
+    /// Kill the server when its process tree uses more memory than this
+    #[serde(default)]
+    pub max_memory_mb: Option<u64>,
+    /// Kill the server when it keeps using more than this many cores (e.g. `0.5`)
+    #[serde(default)]
+    pub cpu_limit: Option<f64>,
+    /// Expected sha256 of the resolved executable (or npm tarball for `npx`)
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Maximum number of tool calls in flight at once
+    #[serde(default)]
+    pub max_concurrent_calls: Option<usize>,
+    /// Token-bucket rate limit for tool calls
+    #[serde(default)]
+    pub requests_per_minute: Option<u32>,
+    /// Stop the server after this long without tool activity; it restarts on the next call
+    #[serde(default)]
+    pub idle_timeout_secs: Option<u64>,
+    /// Servers that must be running and responsive before this one starts
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Fail a tool call that takes longer than this
+    #[serde(default)]
+    pub call_timeout_secs: Option<u64>,
+    /// Tool stack (e.g. `coding`) started and stopped together
+    #[serde(default)]
+    pub group: Option<String>,
+    /// Warm up when the app starts in background mode at login, so the first prompt does
+    /// not wait for a cold install
+    #[serde(default)]
+    pub autostart: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContainerConfig {
+    pub image: String,
+    /// Container engine binary, `docker` (default) or `podman`
+    #[serde(default)]
+    pub engine: Option<String>,
+    /// `missing` | `always` | `never`, passed as `--pull`
+    #[serde(default)]
+    pub pull: Option<String>,
+    /// Extra `-v` mounts in `host:container[:ro]` form
+    #[serde(default)]
+    pub volumes: Vec<String>,
+    /// Mount every approved workspace root at the same path inside the container
+    #[serde(default)]
+    pub mount_workspace_roots: bool,
+    /// Memory limit, e.g. `512m`
+    #[serde(default)]
+    pub memory: Option<String>,
+    /// CPU limit, e.g. `1.5`
+    #[serde(default)]
+    pub cpus: Option<String>,
+    /// Network mode, e.g. `none` for servers that need no network
+    #[serde(default)]
+    pub network: Option<String>,
 }
 
 fn default_transport() -> String {
     "stdio".to_string()
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EnvPolicy {
+    /// Pass the whole parent environment; configs without `env_policy` keep this so
+    /// existing servers do not lose variables on upgrade
+    #[default]
+    InheritAll,
+    /// Minimal environment plus the variables named in `inherit_env`
+    InheritList,
+    /// Minimal environment plus the server's own `env`; written into newly created servers
+    Clean,
+}
+
+// 프로세스 실행에 필요한 최소한의 환경 변수
+#[cfg(windows)]
+const BASELINE_ENV_VARS: &[&str] = &[
+    "PATH",
+    "PATHEXT",
+    "SystemRoot",
+    "SystemDrive",
+    "ComSpec",
+    "TEMP",
+    "TMP",
+    "USERPROFILE",
+    "APPDATA",
+    "LOCALAPPDATA",
+    "ProgramData",
+    "ProgramFiles",
+    "USERNAME",
+];
+#[cfg(not(windows))]
+const BASELINE_ENV_VARS: &[&str] = &["PATH", "HOME", "USER", "LANG", "LC_ALL", "TMPDIR", "SHELL"];
+
+/// Build the environment a stdio server is spawned with, or `None` to inherit everything
+fn build_child_env(config: &MCPServerConfig) -> Option<HashMap<String, String>> {
+    if config.env_policy == EnvPolicy::InheritAll {
+        return None;
+    }
+
+    let mut names: Vec<&str> = BASELINE_ENV_VARS.to_vec();
+    if config.env_policy == EnvPolicy::InheritList {
+        if let Some(list) = &config.inherit_env {
+            names.extend(list.iter().map(|s| s.as_str()));
+        }
+    }
+
+    let mut env: HashMap<String, String> = names
+        .into_iter()
+        .filter_map(|name| {
+            std::env::var(name)
+                .ok()
+                .map(|value| (name.to_string(), value))
+        })
+        .collect();
+    if let Some(extra) = &config.env {
+        env.extend(extra.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    Some(env)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MCPToolInputSchema {
     #[serde(rename = "type")]
@@ -56,62 +215,470 @@ impl Default for MCPToolInputSchema {
     }
 }
 
+/// Behaviour hints a server declares for a tool; they are hints, not guarantees
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MCPToolAnnotations {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_only_hint: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destructive_hint: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idempotent_hint: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open_world_hint: Option<bool>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MCPTool {
     pub name: String,
     pub description: String,
     pub input_schema: MCPToolInputSchema,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<MCPToolAnnotations>,
+}
+
+/// One page of a server's tools/list response
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolsPage {
+    pub server_name: String,
+    pub page: usize,
+    pub tools: Vec<MCPTool>,
+    pub done: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolCallResult {
     pub success: bool,
     pub result: Option<serde_json::Value>,
     pub error: Option<String>,
 }
 
+/// Which chat session or agent run triggered a tool call
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolCallContext {
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Assistant, webhook agent or scheduled task that issued the call
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    /// Label of the window that issued the call; prompts such as approvals go to it
+    #[serde(default)]
+    pub window: Option<String>,
+}
+
+tokio::task_local! {
+    // 승인 요청처럼 깊은 곳에서도 호출 주체를 알 수 있도록 호출 동안 유지합니다
+    static CURRENT_CONTEXT: ToolCallContext;
+}
+
+impl ToolCallContext {
+    /// Run `future` with this context visible to [`ToolCallContext::current`]
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_CONTEXT.scope(self, future).await
+    }
+
+    /// Context of the tool call running on this task, if any
+    pub fn current() -> Option<ToolCallContext> {
+        CURRENT_CONTEXT.try_with(|context| context.clone()).ok()
+    }
+
+    /// Human-readable attribution such as `agent 'x' in session 'y'`
+    pub fn describe(&self) -> Option<String> {
+        match (&self.agent_id, &self.session_id) {
+            (Some(agent), Some(session)) => {
+                Some(format!("agent '{}' in session '{}'", agent, session))
+            }
+            (Some(agent), None) => Some(format!("agent '{}'", agent)),
+            (None, Some(session)) => Some(format!("session '{}'", session)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Running servers and their tool lists, saved on shutdown and restored on the next launch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConnectionSnapshot {
+    pub servers: Vec<MCPServerConfig>,
+    pub tools: HashMap<String, Vec<MCPTool>>,
+    pub saved_at: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RestoreReport {
+    pub started: Vec<String>,
+    pub failed: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StartupReport {
+    /// Servers grouped by start order; each stage starts concurrently
+    pub stages: Vec<Vec<String>>,
+    pub started: Vec<String>,
+    pub failed: HashMap<String, String>,
+    /// Servers not started because a dependency failed
+    pub skipped: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GroupStopReport {
+    pub stopped: Vec<String>,
+    pub failed: HashMap<String, String>,
+}
+
+/// Group configs into start stages with Kahn's algorithm, failing on cycles
+pub fn dependency_stages(configs: &[MCPServerConfig]) -> Result<Vec<Vec<String>>> {
+    let names: std::collections::HashSet<&str> = configs.iter().map(|c| c.name.as_str()).collect();
+    let mut remaining: HashMap<&str, Vec<&str>> = configs
+        .iter()
+        .map(|c| {
+            // 이 배치에 없는 의존성은 이미 실행 중인지 시작 시점에 확인합니다
+            let deps = c
+                .depends_on
+                .iter()
+                .map(|d| d.as_str())
+                .filter(|d| names.contains(d))
+                .collect();
+            (c.name.as_str(), deps)
+        })
+        .collect();
+
+    let mut stages = Vec::new();
+    while !remaining.is_empty() {
+        let mut ready: Vec<String> = remaining
+            .iter()
+            .filter(|(_, deps)| deps.is_empty())
+            .map(|(name, _)| name.to_string())
+            .collect();
+        if ready.is_empty() {
+            let mut cycle: Vec<&str> = remaining.keys().copied().collect();
+            cycle.sort();
+            return Err(anyhow::anyhow!(
+                "Dependency cycle between servers: {}",
+                cycle.join(", ")
+            ));
+        }
+        ready.sort();
+        for name in &ready {
+            remaining.remove(name.as_str());
+        }
+        for deps in remaining.values_mut() {
+            deps.retain(|d| !ready.iter().any(|r| r == d));
+        }
+        stages.push(ready);
+    }
+    Ok(stages)
+}
+
+// 이 클라이언트가 구현한 MCP 프로토콜 버전
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2024-11-05", "2025-03-26"];
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ServerCapabilityFlags {
+    pub tools: bool,
+    pub resources: bool,
+    pub prompts: bool,
+    pub logging: bool,
+    pub completions: bool,
+    /// Whether this client offers sampling to the server
+    pub sampling: bool,
+}
+
+/// What a server reported during initialize
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MCPServerInfo {
+    pub server_name: String,
+    pub protocol_version: String,
+    pub protocol_supported: bool,
+    pub implementation_name: String,
+    pub implementation_version: String,
+    pub instructions: Option<String>,
+    pub capabilities: ServerCapabilityFlags,
+}
+
+impl MCPServerInfo {
+    fn from_peer(server_name: &str, client: &MCPClient) -> Self {
+        let Some(info) = client.peer_info() else {
+            return Self {
+                server_name: server_name.to_string(),
+                ..Default::default()
+            };
+        };
+        let protocol_version = info.protocol_version.to_string();
+        Self {
+            server_name: server_name.to_string(),
+            protocol_supported: SUPPORTED_PROTOCOL_VERSIONS.contains(&protocol_version.as_str()),
+            protocol_version,
+            implementation_name: info.server_info.name.clone(),
+            implementation_version: info.server_info.version.clone(),
+            instructions: info.instructions.clone(),
+            capabilities: ServerCapabilityFlags {
+                tools: info.capabilities.tools.is_some(),
+                resources: info.capabilities.resources.is_some(),
+                prompts: info.capabilities.prompts.is_some(),
+                logging: info.capabilities.logging.is_some(),
+                completions: info.capabilities.completions.is_some(),
+                sampling: client.service().get_info().capabilities.sampling.is_some(),
+            },
+        }
+    }
+}
+
+/// A connected server's instructions for the model and whether they are included
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerInstructions {
+    pub server_name: String,
+    pub instructions: String,
+    pub enabled: bool,
+}
+
+pub type MCPClient = RunningService<RoleClient, MCPClientHandler>;
+
 pub struct MCPConnection {
-    pub client: RunningService<RoleClient, ()>,
+    pub client: MCPClient,
+    pub config: MCPServerConfig,
+    /// Negotiated protocol version, implementation and capabilities
+    pub info: MCPServerInfo,
+    pub last_activity: Instant,
+    /// Tool calls waiting for a response; the idle reaper skips busy servers
+    pub in_flight: Arc<AtomicUsize>,
+    /// Set when the server runs inside a container that must be removed on stop
+    pub container: Option<RunningContainer>,
+    pub pid: Option<u32>,
+    pub limiter: Arc<CallLimiter>,
+}
+
+/// Counts a tool call as in flight until dropped, cancelled calls included
+struct InFlightCall(Arc<AtomicUsize>);
+
+impl InFlightCall {
+    fn new(counter: Arc<AtomicUsize>) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(counter)
+    }
+}
+
+impl Drop for InFlightCall {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub struct RunningContainer {
+    pub engine: String,
+    pub name: String,
 }
 
 pub struct MCPServerManager {
     connections: Arc<Mutex<HashMap<String, MCPConnection>>>,
+    /// Servers stopped by the idle reaper, restarted lazily on their next call
+    idle_stopped: Arc<Mutex<HashMap<String, MCPServerConfig>>>,
+    /// Last successful tools/list result per server
+    tool_cache: Arc<Mutex<HashMap<String, Vec<MCPTool>>>>,
+    /// Servers whose instructions are left out of the system prompt
+    disabled_instructions: Arc<Mutex<HashSet<String>>>,
+    /// Subscribed resource URIs per server, replayed whenever the server (re)starts
+    subscriptions: Arc<Mutex<HashMap<String, HashSet<String>>>>,
+    /// Most recent start or tool-call error per server, for diagnostics
+    last_errors: Arc<Mutex<HashMap<String, String>>>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerStatus {
+    pub name: String,
+    pub transport: String,
+    pub connected: bool,
+    pub idle_stopped: bool,
+    pub pid: Option<u32>,
+    pub container: Option<String>,
+    pub idle_secs: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+// 유휴 서버 확인 주기
+const IDLE_CHECK_INTERVAL_SECS: u64 = 15;
+const READY_TIMEOUT_SECS: u64 = 30;
+// 페이지네이션 상한 (잘못된 커서를 돌려주는 서버 대비)
+const MAX_LIST_PAGES: usize = 1000;
+
 impl MCPServerManager {
     pub fn new() -> Self {
         Self {
             connections: Arc::new(Mutex::new(HashMap::new())),
+            idle_stopped: Arc::new(Mutex::new(HashMap::new())),
+            tool_cache: Arc::new(Mutex::new(HashMap::new())),
+            disabled_instructions: Arc::new(Mutex::new(HashSet::new())),
+            subscriptions: Arc::new(Mutex::new(HashMap::new())),
+            last_errors: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
     /// MCP 서버를 시작하고 연결합니다
     pub async fn start_server(&self, config: MCPServerConfig) -> Result<String> {
-        match config.transport.as_str() {
-            "stdio" => self.start_stdio_server(config).await,
-            "http" => {
-                // HTTP 서버는 외부에서 이미 실행 중이라고 가정
-                Ok(format!("HTTP server configured: {}", config.name))
-            }
-            "websocket" => {
-                // WebSocket 서버는 외부에서 이미 실행 중이라고 가정
-                Ok(format!("WebSocket server configured: {}", config.name))
+        self.idle_stopped.lock().await.remove(&config.name);
+        if let Some(env) = &config.env {
+            redact::register_secrets(env.values());
+        }
+
+        let started = SystemTime::now();
+        let transport = runtime::transport(&config).to_string();
+        let attributes = vec![
+            ("mcp.server".to_string(), config.name.clone()),
+            ("mcp.transport".to_string(), transport.clone()),
+        ];
+        let result = match transport.as_str() {
+            local if !runtime::transport_available(local) => {
+                Err(runtime::unavailable_transport(local))
             }
-            _ => Err(anyhow::anyhow!(
-                "Unsupported transport: {}",
-                config.transport
-            )),
+            "stdio" => self.start_stdio_server(config).await,
+            "docker" => self.start_container_server(config).await,
+            "http" | "websocket" => self.start_remote_server(config, &transport).await,
+            _ => Err(anyhow::anyhow!("Unsupported transport: {}", transport)),
+        };
+        if let Err(e) = &result {
+            self.last_errors
+                .lock()
+                .await
+                .insert(attributes[0].1.clone(), e.to_string());
+        }
+        match &result {
+            Ok(_) => events::record(
+                "server-started",
+                Some(&attributes[0].1),
+                serde_json::json!({}),
+            ),
+            Err(e) => events::record(
+                "server-start-failed",
+                Some(&attributes[0].1),
+                serde_json::json!({ "error": e.to_string() }),
+            ),
         }
+        telemetry::record_span(Span {
+            name: "mcp.server.start".to_string(),
+            kind: SpanKind::Internal,
+            start: started,
+            end: SystemTime::now(),
+            attributes,
+            error: result.as_ref().err().map(|e| e.to_string()),
+        });
+        result
     }
 
     async fn start_stdio_server(&self, config: MCPServerConfig) -> Result<String> {
-        let command = config
-            .command
-            .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("Command is required for stdio transport"))?;
+        if let Some(expected) = &config.sha256 {
+            let (command, args) = runtime::unwrapped_command_line(&config)?;
+            integrity::verify(&command, &args, expected).await?;
+        }
+        let (command, args) = runtime::command_line(&config)?;
+
+        self.connect_child(&config, &command, &args, None).await
+    }
+
+    /// 이미 실행 중인 원격 서버에 연결합니다
+    async fn start_remote_server(
+        &self,
+        config: MCPServerConfig,
+        transport: &str,
+    ) -> Result<String> {
+        let url = config.url.clone().ok_or_else(|| {
+            anyhow::anyhow!("\"url\" is required for the {} transport", transport)
+        })?;
+        let (outgoing, incoming) = match transport {
+            "websocket" => remote_transport::websocket(&url).await?,
+            _ => remote_transport::http(&url)?,
+        };
+        self.connect_transport(&config, (outgoing, incoming), None, None)
+            .await
+    }
 
-        let default_args = vec![];
-        let args = config.args.as_ref().unwrap_or(&default_args);
+    /// 컨테이너 안에서 stdio 서버를 실행합니다 (`docker run -i`)
+    async fn start_container_server(&self, config: MCPServerConfig) -> Result<String> {
+        let container = runtime::container(&config)?;
+        let engine = container
+            .engine
+            .clone()
+            .unwrap_or_else(|| "docker".to_string());
+        let safe_name: String = config
+            .name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let container_name = format!(
+            "mcp-{}-{}",
+            safe_name,
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        );
+
+        let mut args: Vec<String> = vec![
+            "run".into(),
+            "-i".into(),
+            "--rm".into(),
+            "--name".into(),
+            container_name.clone(),
+            "--label".into(),
+            "tauri-mcp-agent=1".into(),
+        ];
+        if let Some(pull) = &container.pull {
+            args.push(format!("--pull={}", pull));
+        }
+        // 컨테이너에서는 엔진이 직접 리소스 제한을 적용합니다
+        let memory = container
+            .memory
+            .clone()
+            .or(config.max_memory_mb.map(|mb| format!("{}m", mb)));
+        if let Some(memory) = memory {
+            args.push(format!("--memory={}", memory));
+        }
+        let cpus = container
+            .cpus
+            .clone()
+            .or(config.cpu_limit.map(|cpus| cpus.to_string()));
+        if let Some(cpus) = cpus {
+            args.push(format!("--cpus={}", cpus));
+        }
+        if let Some(network) = &container.network {
+            args.push(format!("--network={}", network));
+        }
+        for volume in &container.volumes {
+            args.push("-v".into());
+            args.push(volume.clone());
+        }
+        // 값은 CLI 프로세스의 환경으로 전달해서 프로세스 목록에 노출되지 않게 합니다
+        if let Some(env) = &config.env {
+            for key in env.keys() {
+                args.push("-e".into());
+                args.push(key.clone());
+            }
+        }
+        args.push(container.image.clone());
+        match &config.command {
+            Some(command) => {
+                args.extend(runtime::container_command(command, config.args.as_ref())?)
+            }
+            None => args.extend(config.args.clone().unwrap_or_default()),
+        }
+
+        let running = RunningContainer {
+            engine: engine.clone(),
+            name: container_name,
+        };
+        self.connect_child(&config, &engine, &args, Some(running))
+            .await
+    }
+
+    async fn connect_child(
+        &self,
+        config: &MCPServerConfig,
+        command: &str,
+        args: &[String],
+        container: Option<RunningContainer>,
+    ) -> Result<String> {
+        let child_env = build_child_env(config);
+        let limits = ResourceLimits {
+            max_memory_mb: config.max_memory_mb,
+            cpu_limit: config.cpu_limit,
+        };
 
         // Create command with rmcp - configure returns the modified command
         let cmd = Command::new(command).configure(|cmd| {
@@ -119,22 +686,111 @@ impl MCPServerManager {
                 cmd.arg(arg);
             }
 
-            // Set environment variables if any
-            if let Some(env) = &config.env {
-                for (key, value) in env {
-                    cmd.env(key, value);
+            match &child_env {
+                Some(env) => {
+                    // 부모 프로세스의 환경을 물려받지 않습니다
+                    cmd.env_clear();
+                    cmd.envs(env);
+                }
+                None => {
+                    if let Some(env) = &config.env {
+                        cmd.envs(env);
+                    }
                 }
             }
+            // 실행 파일도 이 PATH로 찾습니다
+            let base = match &child_env {
+                Some(env) => env.get("PATH"),
+                None => config.env.as_ref().and_then(|env| env.get("PATH")),
+            };
+            if let Some(path) = search_path::augmented(&config.path, base.map(OsString::from)) {
+                cmd.env("PATH", path);
+            }
+            if config.runtime == Some(Runtime::Wsl) {
+                let inherited = match &child_env {
+                    Some(env) => env.get("WSLENV").cloned(),
+                    None => std::env::var("WSLENV").ok(),
+                };
+                cmd.env("WSLENV", runtime::wslenv(config, inherited.as_deref()));
+            }
+            // 컨테이너는 엔진이 제한을 적용하므로 CLI 프로세스에는 걸지 않습니다
+            #[cfg(unix)]
+            if container.is_none() {
+                limits::limit_memory_at_spawn(cmd, &limits);
+            }
         });
 
         // Create transport and connect using RMCP pattern
         let transport = TokioChildProcess::new(cmd)?;
-        println!("Created transport for command: {} {:?}", command, args);
+        let pid = transport.id();
+        println!(
+            "Created transport for command: {}",
+            redact::redact(&format!("{} {:?}", command, args))
+        );
+        #[cfg(windows)]
+        if let (Some(pid), None) = (pid, &container) {
+            if let Err(e) = limits::limit_memory_after_spawn(pid, &limits) {
+                eprintln!(
+                    "⚠️ [LIMITS] Could not apply the memory limit to '{}': {}",
+                    config.name, e
+                );
+            }
+        }
+
+        self.connect_transport(config, transport, pid, container)
+            .await
+    }
 
-        let client = ().serve(transport).await?;
+    /// Initialize an MCP session over `transport` and register the connection
+    pub(crate) async fn connect_transport<T, E, A>(
+        &self,
+        config: &MCPServerConfig,
+        transport: T,
+        pid: Option<u32>,
+        container: Option<RunningContainer>,
+    ) -> Result<String>
+    where
+        T: IntoTransport<RoleClient, E, A>,
+        E: std::error::Error + From<std::io::Error> + Send + Sync + 'static,
+    {
+        let handler = MCPClientHandler::new(config.name.clone(), self.tool_cache.clone());
+        let client = handler.serve(transport).await?;
         println!("Successfully connected to MCP server: {}", config.name);
 
-        let connection = MCPConnection { client };
+        let limits = ResourceLimits {
+            max_memory_mb: config.max_memory_mb,
+            cpu_limit: config.cpu_limit,
+        };
+        if let (Some(pid), None, false) = (pid, &container, limits.is_empty()) {
+            tokio::spawn(limits::watch(
+                config.name.clone(),
+                pid,
+                limits,
+                self.connections.clone(),
+            ));
+        }
+
+        let info = MCPServerInfo::from_peer(&config.name, &client);
+        if !info.protocol_supported {
+            println!(
+                "Warning: Server {} negotiated unsupported protocol version '{}'",
+                config.name, info.protocol_version
+            );
+        }
+
+        let connection = MCPConnection {
+            client,
+            config: config.clone(),
+            info,
+            last_activity: Instant::now(),
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            container,
+            pid,
+            limiter: Arc::new(CallLimiter::new(
+                config.max_concurrent_calls,
+                config.requests_per_minute,
+            )),
+        };
 
         // Store connection
         {
@@ -142,6 +798,7 @@ impl MCPServerManager {
             connections.insert(config.name.clone(), connection);
             println!("Stored connection for server: {}", config.name);
         }
+        self.resubscribe(&config.name).await;
 
         Ok(format!(
             "Started and connected to MCP server: {}",
@@ -151,15 +808,71 @@ impl MCPServerManager {
 
     /// MCP 서버를 중지합니다
     pub async fn stop_server(&self, server_name: &str) -> Result<()> {
-        let mut connections = self.connections.lock().await;
+        self.idle_stopped.lock().await.remove(server_name);
+        self.tool_cache.lock().await.remove(server_name);
+        let connection = self.connections.lock().await.remove(server_name);
+        if let Some(connection) = connection {
+            Self::shut_down(server_name, connection).await;
+        }
+        Ok(())
+    }
 
-        if let Some(connection) = connections.remove(server_name) {
-            // Cancel the client connection
-            let _ = connection.client.cancel().await;
-            println!("Stopped MCP server: {}", server_name);
+    /// Close a connection already removed from the map, and its container if any
+    async fn shut_down(server_name: &str, connection: MCPConnection) {
+        let stopped_at = SystemTime::now();
+        // Cancel the client connection
+        let _ = connection.client.cancel().await;
+
+        // Killing the CLI does not always stop an attached container
+        if let Some(container) = connection.container {
+            match Command::new(&container.engine)
+                .args(["rm", "-f", &container.name])
+                .output()
+                .await
+            {
+                Ok(_) => println!("Removed container {}", container.name),
+                Err(e) => println!(
+                    "Warning: Failed to remove container {}: {}",
+                    container.name, e
+                ),
+            }
         }
+        println!("Stopped MCP server: {}", server_name);
+        events::record("server-stopped", Some(server_name), serde_json::json!({}));
+        telemetry::record_span(Span {
+            name: "mcp.server.stop".to_string(),
+            kind: SpanKind::Internal,
+            start: stopped_at,
+            end: SystemTime::now(),
+            attributes: vec![("mcp.server".to_string(), server_name.to_string())],
+            error: None,
+        });
+    }
 
-        Ok(())
+    /// Stop every running or idle-stopped server of `group` concurrently
+    pub async fn stop_group(&self, group: &str) -> GroupStopReport {
+        let names: Vec<String> = self
+            .snapshot()
+            .await
+            .servers
+            .into_iter()
+            .filter(|config| config.group.as_deref() == Some(group))
+            .map(|config| config.name)
+            .collect();
+        let stops = names.into_iter().map(|name| async move {
+            let result = self.stop_server(&name).await;
+            (name, result)
+        });
+        let mut report = GroupStopReport::default();
+        for (name, result) in futures::future::join_all(stops).await {
+            match result {
+                Ok(()) => report.stopped.push(name),
+                Err(e) => {
+                    report.failed.insert(name, e.to_string());
+                }
+            }
+        }
+        report
     }
 
     /// 도구를 호출합니다
@@ -168,39 +881,164 @@ impl MCPServerManager {
         server_name: &str,
         tool_name: &str,
         arguments: serde_json::Value,
+        context: &ToolCallContext,
     ) -> ToolCallResult {
-        let connections = self.connections.lock().await;
-
-        if let Some(connection) = connections.get(server_name) {
-            // RMCP API 사용 - CallToolRequestParam 구조체 사용
-            let args_map = if let serde_json::Value::Object(obj) = arguments {
-                obj
-            } else {
-                serde_json::Map::new()
+        if let Err(e) = self.restart_if_idle_stopped(server_name).await {
+            return ToolCallResult {
+                success: false,
+                result: None,
+                error: Some(e.to_string()),
             };
+        }
 
-            let call_param = CallToolRequestParam {
-                name: tool_name.to_string().into(),
-                arguments: Some(args_map),
-            };
+        // 호출 중에는 연결 맵을 잠그지 않아서 다른 호출이 동시에 진행될 수 있습니다
+        let (peer, limiter, call_timeout, _in_flight) = {
+            let mut connections = self.connections.lock().await;
+            match connections.get_mut(server_name) {
+                Some(connection) => {
+                    connection.last_activity = Instant::now();
+                    (
+                        connection.client.peer().clone(),
+                        connection.limiter.clone(),
+                        connection.config.call_timeout_secs,
+                        InFlightCall::new(connection.in_flight.clone()),
+                    )
+                }
+                None => {
+                    return ToolCallResult {
+                        success: false,
+                        result: None,
+                        error: Some(format!("Server '{}' not found", server_name)),
+                    }
+                }
+            }
+        };
 
-            match connection.client.call_tool(call_param).await {
-                Ok(result) => ToolCallResult {
-                    success: true,
-                    result: Some(serde_json::to_value(result).unwrap_or(serde_json::Value::Null)),
-                    error: None,
-                },
-                Err(e) => ToolCallResult {
+        let _permit = if limiter.is_unlimited() {
+            None
+        } else {
+            Some(limiter.acquire(server_name, tool_name).await)
+        };
+
+        // RMCP API 사용 - CallToolRequestParam 구조체 사용
+        let args_map = if let serde_json::Value::Object(obj) = arguments {
+            obj
+        } else {
+            serde_json::Map::new()
+        };
+
+        let call_param = CallToolRequestParam {
+            name: tool_name.to_string().into(),
+            arguments: Some(args_map),
+        };
+
+        let result = match call_timeout {
+            Some(secs) => tokio::time::timeout(
+                tokio::time::Duration::from_secs(secs),
+                peer.call_tool(call_param),
+            )
+            .await
+            .unwrap_or_else(|_| {
+                Err(rmcp::ServiceError::Timeout {
+                    timeout: std::time::Duration::from_secs(secs),
+                })
+            }),
+            None => peer.call_tool(call_param).await,
+        };
+        if let Some(connection) = self.connections.lock().await.get_mut(server_name) {
+            connection.last_activity = Instant::now();
+        }
+
+        match result {
+            Ok(result) => ToolCallResult {
+                success: true,
+                result: Some(serde_json::to_value(result).unwrap_or(serde_json::Value::Null)),
+                error: None,
+            },
+            Err(e) => {
+                let attribution = context
+                    .describe()
+                    .map(|who| format!(" (called by {})", who))
+                    .unwrap_or_default();
+                self.last_errors.lock().await.insert(
+                    server_name.to_string(),
+                    format!("{}: {}{}", tool_name, e, attribution),
+                );
+                ToolCallResult {
                     success: false,
                     result: None,
                     error: Some(e.to_string()),
-                },
+                }
             }
-        } else {
-            ToolCallResult {
-                success: false,
-                result: None,
-                error: Some(format!("Server '{}' not found", server_name)),
+        }
+    }
+
+    /// Start a server again if the idle reaper stopped it
+    async fn restart_if_idle_stopped(&self, server_name: &str) -> Result<()> {
+        let config = self.idle_stopped.lock().await.remove(server_name);
+        if let Some(config) = config {
+            println!("Restarting idle-stopped MCP server: {}", server_name);
+            if let Err(e) = self.start_server(config.clone()).await {
+                // 다음 호출이 다시 시작을 시도할 수 있도록 되돌려 놓습니다
+                if !self.connections.lock().await.contains_key(server_name) {
+                    self.idle_stopped
+                        .lock()
+                        .await
+                        .entry(server_name.to_string())
+                        .or_insert(config);
+                }
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// A stdio server with no call in flight and no activity for its idle timeout
+    fn is_idle(connection: &MCPConnection) -> bool {
+        runtime::transport(&connection.config) == "stdio"
+            && connection.in_flight.load(Ordering::SeqCst) == 0
+            && connection
+                .config
+                .idle_timeout_secs
+                .is_some_and(|timeout| connection.last_activity.elapsed().as_secs() >= timeout)
+    }
+
+    /// 유휴 시간이 지난 서버를 주기적으로 중지합니다
+    pub async fn run_idle_reaper(&self) {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(IDLE_CHECK_INTERVAL_SECS)).await;
+
+            let idle: Vec<String> = {
+                let connections = self.connections.lock().await;
+                connections
+                    .iter()
+                    .filter(|(_, c)| Self::is_idle(c))
+                    .map(|(name, _)| name.clone())
+                    .collect()
+            };
+
+            for name in idle {
+                // 중지하는 동안 idle_stopped를 잠가 두어서, 그 사이 들어온 호출은
+                // 중지가 끝난 뒤 재시작을 기다립니다
+                let mut idle_stopped = self.idle_stopped.lock().await;
+                let connection = {
+                    let mut connections = self.connections.lock().await;
+                    match connections.get(&name) {
+                        Some(connection) if Self::is_idle(connection) => connections.remove(&name),
+                        _ => None,
+                    }
+                };
+                let Some(connection) = connection else {
+                    continue;
+                };
+                println!(
+                    "Stopping idle MCP server '{}' after {}s without activity",
+                    name,
+                    connection.config.idle_timeout_secs.unwrap_or_default()
+                );
+                idle_stopped.insert(name.clone(), connection.config.clone());
+                self.tool_cache.lock().await.remove(&name);
+                Self::shut_down(&name, connection).await;
             }
         }
     }
@@ -268,58 +1106,405 @@ impl MCPServerManager {
         }
     }
 
-    /// 사용 가능한 도구 목록을 가져옵니다
-    pub async fn list_tools(&self, server_name: &str) -> Result<Vec<MCPTool>> {
+    /// Clone a server's peer so requests don't hold the connections lock
+    pub(crate) async fn peer(&self, server_name: &str) -> Result<Peer<RoleClient>> {
+        self.restart_if_idle_stopped(server_name).await?;
         let connections = self.connections.lock().await;
+        connections
+            .get(server_name)
+            .map(|connection| connection.client.peer().clone())
+            .ok_or_else(|| anyhow::anyhow!("Server '{}' not found", server_name))
+    }
+
+    pub(crate) fn convert_tool(tool: Tool) -> MCPTool {
+        // Convert the input schema to our structured format
+        let input_schema_value = serde_json::to_value(tool.input_schema).unwrap_or_else(|e| {
+            println!(
+                "Warning: Failed to serialize input_schema for tool {}: {}",
+                tool.name, e
+            );
+            serde_json::Value::Object(serde_json::Map::new())
+        });
 
-        if let Some(connection) = connections.get(server_name) {
-            println!("Found connection for server: {}", server_name);
-
-            match connection.client.list_all_tools().await {
-                Ok(tools_response) => {
-                    println!("Raw tools response: {:?}", tools_response);
-                    let mut tools = Vec::new();
-
-                    for tool in tools_response {
-                        println!("Processing tool: {:?}", tool);
-
-                        // Convert the input schema to our structured format
-                        let input_schema_value = serde_json::to_value(tool.input_schema)
-                            .unwrap_or_else(|e| {
-                                println!(
-                                    "Warning: Failed to serialize input_schema for tool {}: {}",
-                                    tool.name, e
-                                );
-                                serde_json::Value::Object(serde_json::Map::new())
-                            });
-
-                        let structured_schema = Self::convert_input_schema(input_schema_value);
-
-                        let mcp_tool = MCPTool {
-                            name: tool.name.to_string(),
-                            description: tool.description.unwrap_or_default().to_string(),
-                            input_schema: structured_schema,
-                        };
-
-                        println!(
-                            "Converted tool: {} with schema type: {}",
-                            mcp_tool.name, mcp_tool.input_schema.schema_type
-                        );
-                        tools.push(mcp_tool);
+        MCPTool {
+            name: tool.name.to_string(),
+            description: tool.description.unwrap_or_default().to_string(),
+            input_schema: Self::convert_input_schema(input_schema_value),
+            annotations: tool.annotations.map(|a| MCPToolAnnotations {
+                title: a.title,
+                read_only_hint: a.read_only_hint,
+                destructive_hint: a.destructive_hint,
+                idempotent_hint: a.idempotent_hint,
+                open_world_hint: a.open_world_hint,
+            }),
+        }
+    }
+
+    /// 사용 가능한 도구 목록을 가져옵니다 (모든 페이지를 따라갑니다)
+    ///
+    /// Each page is emitted as `mcp-tools-page` so the UI can show large catalogs
+    /// while the rest is still loading.
+    pub async fn list_tools(&self, server_name: &str) -> Result<Vec<MCPTool>> {
+        let peer = self.peer(server_name).await?;
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+        let mut page = 0;
+
+        loop {
+            let result = peer
+                .list_tools(Some(PaginatedRequestParam {
+                    cursor: cursor.clone(),
+                }))
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to list tools: {}", e))?;
+            let page_tools: Vec<MCPTool> =
+                result.tools.into_iter().map(Self::convert_tool).collect();
+
+            // 같은 커서를 반복해서 돌려주는 서버에서 무한 루프를 막습니다
+            let next_cursor = result
+                .next_cursor
+                .filter(|next| Some(next) != cursor.as_ref());
+            events::emit(
+                "mcp-tools-page",
+                ToolsPage {
+                    server_name: server_name.to_string(),
+                    page,
+                    tools: page_tools.clone(),
+                    done: next_cursor.is_none(),
+                },
+            );
+            tools.extend(page_tools);
+            page += 1;
+
+            cursor = next_cursor;
+            if cursor.is_none() || page >= MAX_LIST_PAGES {
+                break;
+            }
+        }
+
+        println!(
+            "Listed {} tools from server {} in {} page(s)",
+            tools.len(),
+            server_name,
+            page
+        );
+        self.tool_cache
+            .lock()
+            .await
+            .insert(server_name.to_string(), tools.clone());
+
+        // 목록이 바뀔 때마다 모든 제공자 기준으로 다시 검사해 문제가 있으면 알립니다
+        let reports: Vec<ToolReport> = tools
+            .iter()
+            .map(|tool| tool_validation::validate(tool, &ValidationTarget::ALL))
+            .filter(|report| !report.findings.is_empty())
+            .collect();
+        if !reports.is_empty() {
+            events::emit(
+                "mcp-tool-validation",
+                ValidationNotice {
+                    server_name: server_name.to_string(),
+                    reports,
+                },
+            );
+        }
+        Ok(tools)
+    }
+
+    /// 리소스 변경 알림을 구독합니다. 서버가 재시작되어도 유지됩니다
+    pub async fn subscribe_resource(&self, server_name: &str, uri: &str) -> Result<()> {
+        self.peer(server_name)
+            .await?
+            .subscribe(SubscribeRequestParam {
+                uri: uri.to_string(),
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to subscribe to {}: {}", uri, e))?;
+        self.subscriptions
+            .lock()
+            .await
+            .entry(server_name.to_string())
+            .or_default()
+            .insert(uri.to_string());
+        Ok(())
+    }
+
+    pub async fn unsubscribe_resource(&self, server_name: &str, uri: &str) -> Result<()> {
+        let removed = self
+            .subscriptions
+            .lock()
+            .await
+            .get_mut(server_name)
+            .is_some_and(|uris| uris.remove(uri));
+        if !removed {
+            return Err(anyhow::anyhow!(
+                "Not subscribed to {} on {}",
+                uri,
+                server_name
+            ));
+        }
+        // 서버가 이미 중지되었으면 레지스트리에서만 제거합니다
+        if self.is_server_alive(server_name).await {
+            self.peer(server_name)
+                .await?
+                .unsubscribe(UnsubscribeRequestParam {
+                    uri: uri.to_string(),
+                })
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to unsubscribe from {}: {}", uri, e))?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_subscriptions(&self) -> HashMap<String, Vec<String>> {
+        self.subscriptions
+            .lock()
+            .await
+            .iter()
+            .filter(|(_, uris)| !uris.is_empty())
+            .map(|(server, uris)| (server.clone(), uris.iter().cloned().collect()))
+            .collect()
+    }
+
+    /// Replay registered subscriptions after a server (re)connects
+    async fn resubscribe(&self, server_name: &str) {
+        let uris: Vec<String> = match self.subscriptions.lock().await.get(server_name) {
+            Some(uris) => uris.iter().cloned().collect(),
+            None => return,
+        };
+        let peer = match self.connections.lock().await.get(server_name) {
+            Some(connection) => connection.client.peer().clone(),
+            None => return,
+        };
+        for uri in uris {
+            if let Err(e) = peer
+                .subscribe(SubscribeRequestParam { uri: uri.clone() })
+                .await
+            {
+                println!(
+                    "Warning: Failed to resubscribe to {} on {}: {}",
+                    uri, server_name, e
+                );
+            }
+        }
+    }
+
+    /// 프롬프트 인자나 리소스 템플릿 변수의 자동완성 후보를 요청합니다
+    ///
+    /// Servers without the completions capability get an empty result instead of an error.
+    pub async fn complete_argument(
+        &self,
+        server_name: &str,
+        reference: Reference,
+        argument: &str,
+        partial: &str,
+    ) -> Result<CompletionInfo> {
+        let supported = self
+            .get_server_info(server_name)
+            .await
+            .is_some_and(|info| info.capabilities.completions);
+        if !supported {
+            return Ok(CompletionInfo {
+                values: Vec::new(),
+                total: None,
+                has_more: None,
+            });
+        }
+
+        let result = self
+            .peer(server_name)
+            .await?
+            .complete(CompleteRequestParam {
+                r#ref: reference,
+                argument: ArgumentInfo {
+                    name: argument.to_string(),
+                    value: partial.to_string(),
+                },
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to complete '{}': {}", argument, e))?;
+        Ok(result.completion)
+    }
+
+    /// Send logging/setLevel to a server that declared the logging capability
+    pub async fn set_log_level(&self, server_name: &str, level: LoggingLevel) -> Result<()> {
+        let supported = self
+            .get_server_info(server_name)
+            .await
+            .is_some_and(|info| info.capabilities.logging);
+        if !supported {
+            return Err(anyhow::anyhow!(
+                "Server '{}' does not support logging",
+                server_name
+            ));
+        }
+        self.peer(server_name)
+            .await?
+            .set_level(SetLevelRequestParam { level })
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to set log level: {}", e))
+    }
+
+    pub async fn list_prompts(&self, server_name: &str) -> Result<Vec<Prompt>> {
+        self.peer(server_name)
+            .await?
+            .list_all_prompts()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list prompts: {}", e))
+    }
+
+    pub async fn list_resources(&self, server_name: &str) -> Result<Vec<Resource>> {
+        self.peer(server_name)
+            .await?
+            .list_all_resources()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list resources: {}", e))
+    }
+
+    pub async fn list_resource_templates(
+        &self,
+        server_name: &str,
+    ) -> Result<Vec<ResourceTemplate>> {
+        self.peer(server_name)
+            .await?
+            .list_all_resource_templates()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list resource templates: {}", e))
+    }
+
+    /// Annotations from the last tools/list result of a server
+    pub async fn cached_tool_annotations(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+    ) -> Option<MCPToolAnnotations> {
+        self.tool_cache
+            .lock()
+            .await
+            .get(server_name)?
+            .iter()
+            .find(|tool| tool.name == tool_name)?
+            .annotations
+            .clone()
+    }
+
+    /// Cached tool lists, available without contacting the servers
+    pub async fn get_cached_tools(&self) -> HashMap<String, Vec<MCPTool>> {
+        self.tool_cache.lock().await.clone()
+    }
+
+    /// 현재 실행 중인 서버와 도구 목록을 스냅샷으로 만듭니다
+    pub async fn snapshot(&self) -> ConnectionSnapshot {
+        let mut servers: Vec<MCPServerConfig> = {
+            let connections = self.connections.lock().await;
+            connections.values().map(|c| c.config.clone()).collect()
+        };
+        // 유휴 상태로 중지된 서버도 다음 실행 때 복원합니다
+        servers.extend(self.idle_stopped.lock().await.values().cloned());
+
+        ConnectionSnapshot {
+            tools: self.tool_cache.lock().await.clone(),
+            servers,
+            saved_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Rehydrate the tool cache and restart every server in the snapshot concurrently
+    pub async fn restore(&self, snapshot: ConnectionSnapshot) -> Result<RestoreReport> {
+        self.tool_cache.lock().await.extend(snapshot.tools);
+
+        let startup = self.start_servers(snapshot.servers).await?;
+        let mut report = RestoreReport {
+            started: startup.started,
+            failed: startup.failed,
+        };
+        report.failed.extend(startup.skipped);
+        for name in report.failed.keys() {
+            println!("Warning: Failed to restore server {}", name);
+            self.tool_cache.lock().await.remove(name);
+        }
+        Ok(report)
+    }
+
+    /// 의존성 순서대로 서버를 시작합니다. 같은 단계의 서버는 동시에 시작됩니다
+    pub async fn start_servers(&self, configs: Vec<MCPServerConfig>) -> Result<StartupReport> {
+        let stages = dependency_stages(&configs)?;
+        let mut by_name: HashMap<String, MCPServerConfig> =
+            configs.into_iter().map(|c| (c.name.clone(), c)).collect();
+        let mut report = StartupReport {
+            stages: stages.clone(),
+            ..Default::default()
+        };
+
+        for stage in stages {
+            let mut to_start = Vec::new();
+            for name in stage {
+                let Some(config) = by_name.remove(&name) else {
+                    continue;
+                };
+                let mut blocked = None;
+                for dep in &config.depends_on {
+                    if report.failed.contains_key(dep) || report.skipped.contains_key(dep) {
+                        blocked = Some(format!("dependency '{}' failed to start", dep));
+                    } else if !self.is_server_alive(dep).await {
+                        blocked = Some(format!("dependency '{}' is not running", dep));
                     }
+                }
+                match blocked {
+                    Some(reason) => {
+                        report.skipped.insert(name, reason);
+                    }
+                    None => to_start.push(config),
+                }
+            }
 
-                    println!("Successfully converted {} tools", tools.len());
-                    Ok(tools)
+            let starts = to_start.into_iter().map(|config| async move {
+                let name = config.name.clone();
+                if self.is_server_alive(&name).await {
+                    return (name, Ok(()));
                 }
-                Err(e) => {
-                    println!("Error listing tools: {}", e);
-                    Err(anyhow::anyhow!("Failed to list tools: {}", e))
+                let result = async {
+                    self.start_server(config).await?;
+                    self.wait_until_ready(&name).await
+                }
+                .await;
+                (name, result)
+            });
+            for (name, result) in futures::future::join_all(starts).await {
+                match result {
+                    Ok(()) => report.started.push(name),
+                    Err(e) => {
+                        println!("Warning: Failed to start server {}: {}", name, e);
+                        report.failed.insert(name, e.to_string());
+                    }
                 }
             }
-        } else {
-            println!("Server '{}' not found in connections", server_name);
-            Err(anyhow::anyhow!("Server '{}' not found", server_name))
         }
+
+        Ok(report)
+    }
+
+    /// Readiness check: the server must answer tools/list before dependents start
+    async fn wait_until_ready(&self, server_name: &str) -> Result<()> {
+        let peer = {
+            let connections = self.connections.lock().await;
+            match connections.get(server_name) {
+                Some(connection) => connection.client.peer().clone(),
+                // HTTP/WebSocket 서버는 외부에서 관리되므로 확인하지 않습니다
+                None => return Ok(()),
+            }
+        };
+        tokio::time::timeout(
+            tokio::time::Duration::from_secs(READY_TIMEOUT_SECS),
+            peer.list_tools(None),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("Server '{}' did not become ready", server_name))?
+        .map_err(|e| anyhow::anyhow!("Server '{}' is not ready: {}", server_name, e))?;
+        Ok(())
     }
 
     /// Get tools from all connected servers
@@ -358,6 +1543,94 @@ impl MCPServerManager {
         connections.keys().cloned().collect()
     }
 
+    /// 서버가 초기화 때 알려준 정보를 반환합니다
+    pub async fn get_server_info(&self, server_name: &str) -> Option<MCPServerInfo> {
+        self.connections
+            .lock()
+            .await
+            .get(server_name)
+            .map(|connection| connection.info.clone())
+    }
+
+    /// 연결된 서버들의 instructions 목록 (시스템 프롬프트용)
+    pub async fn server_instructions(&self) -> Vec<ServerInstructions> {
+        let disabled = self.disabled_instructions.lock().await;
+        let connections = self.connections.lock().await;
+        let mut result: Vec<ServerInstructions> = connections
+            .values()
+            .filter_map(|connection| {
+                let instructions = connection.info.instructions.as_ref()?.trim();
+                (!instructions.is_empty()).then(|| ServerInstructions {
+                    server_name: connection.config.name.clone(),
+                    instructions: instructions.to_string(),
+                    enabled: !disabled.contains(&connection.config.name),
+                })
+            })
+            .collect();
+        result.sort_by(|a, b| a.server_name.cmp(&b.server_name));
+        result
+    }
+
+    pub async fn set_instructions_enabled(&self, server_name: &str, enabled: bool) {
+        let mut disabled = self.disabled_instructions.lock().await;
+        if enabled {
+            disabled.remove(server_name);
+        } else {
+            disabled.insert(server_name.to_string());
+        }
+    }
+
+    pub async fn disabled_instructions(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .disabled_instructions
+            .lock()
+            .await
+            .iter()
+            .cloned()
+            .collect();
+        names.sort();
+        names
+    }
+
+    pub async fn set_disabled_instructions(&self, names: Vec<String>) {
+        *self.disabled_instructions.lock().await = names.into_iter().collect();
+    }
+
+    /// Connected and idle-stopped servers with their last recorded error
+    pub async fn server_statuses(&self) -> Vec<ServerStatus> {
+        let last_errors = self.last_errors.lock().await.clone();
+        let mut statuses: Vec<ServerStatus> = {
+            let connections = self.connections.lock().await;
+            connections
+                .values()
+                .map(|connection| ServerStatus {
+                    name: connection.config.name.clone(),
+                    transport: runtime::transport(&connection.config).to_string(),
+                    connected: !connection.client.is_transport_closed(),
+                    idle_stopped: false,
+                    pid: connection.pid,
+                    container: connection.container.as_ref().map(|c| c.name.clone()),
+                    idle_secs: Some(connection.last_activity.elapsed().as_secs()),
+                    last_error: last_errors.get(&connection.config.name).cloned(),
+                })
+                .collect()
+        };
+        for config in self.idle_stopped.lock().await.values() {
+            statuses.push(ServerStatus {
+                name: config.name.clone(),
+                transport: runtime::transport(config).to_string(),
+                connected: false,
+                idle_stopped: true,
+                pid: None,
+                container: None,
+                idle_secs: None,
+                last_error: last_errors.get(&config.name).cloned(),
+            });
+        }
+        statuses.sort_by(|a, b| a.name.cmp(&b.name));
+        statuses
+    }
+
     /// 특정 서버가 연결되어 있는지 확인합니다
     pub async fn is_server_alive(&self, server_name: &str) -> bool {
         let connections = self.connections.lock().await;
@@ -378,46 +1651,45 @@ impl MCPServerManager {
 
     /// Validate if a tool schema is compatible with AI service expectations
     pub fn validate_tool_schema(tool: &MCPTool) -> Result<()> {
-        // Ensure the schema type is 'object'
-        if tool.input_schema.schema_type != "object" {
-            return Err(anyhow::anyhow!(
-                "Tool '{}' has invalid schema type '{}', expected 'object'",
+        match tool_validation::validate(tool, &[])
+            .findings
+            .into_iter()
+            .find(|finding| finding.severity == Severity::Error)
+        {
+            Some(finding) => Err(anyhow::anyhow!(
+                "Tool '{}' is invalid at '{}': {}",
                 tool.name,
-                tool.input_schema.schema_type
-            ));
-        }
-
-        // Validate required fields exist in properties
-        if let Some(required) = &tool.input_schema.required {
-            for req_field in required {
-                if !tool.input_schema.properties.contains_key(req_field) {
-                    return Err(anyhow::anyhow!(
-                        "Tool '{}' requires field '{}' but it's not defined in properties",
-                        tool.name,
-                        req_field
-                    ));
-                }
-            }
+                finding.path,
+                finding.message
+            )),
+            None => Ok(()),
         }
-
-        Ok(())
     }
 
-    /// Get validated tools that are compatible with the AI service
-    pub async fn get_validated_tools(&self, server_name: &str) -> Result<Vec<MCPTool>> {
+    /// Get validated tools that are compatible with the given provider
+    pub async fn get_validated_tools(
+        &self,
+        server_name: &str,
+        target: ValidationTarget,
+    ) -> Result<Vec<MCPTool>> {
         let tools = self.list_tools(server_name).await?;
         let mut validated_tools = Vec::new();
 
         for tool in tools {
-            match Self::validate_tool_schema(&tool) {
-                Ok(()) => {
-                    println!("Tool '{}' passed validation", tool.name);
-                    validated_tools.push(tool);
-                }
-                Err(e) => {
-                    println!("Tool '{}' failed validation: {}", tool.name, e);
-                    // Optionally, you could try to fix the schema or skip the tool
-                }
+            let report = tool_validation::validate(&tool, &[target]);
+            if report.passes(target) {
+                validated_tools.push(tool);
+            } else {
+                println!(
+                    "Tool '{}' failed validation: {}",
+                    tool.name,
+                    report
+                        .findings
+                        .iter()
+                        .map(|finding| finding.message.as_str())
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                );
             }
         }
 
@@ -431,3 +1703,6 @@ impl Drop for MCPServerManager {
         // when connections are dropped
     }
 }
+
+#[cfg(test)]
+mod tests;
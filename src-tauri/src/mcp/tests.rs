@@ -0,0 +1,239 @@
+//! Manager tests against an in-process mock MCP server.
+
+use rmcp::{
+    model::{
+        CallToolRequestParam, CallToolResult, Content, ListToolsResult, PaginatedRequestParam,
+        ServerCapabilities, ServerInfo, Tool,
+    },
+    service::{RequestContext, RoleServer},
+    ServerHandler, ServiceExt,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use super::*;
+
+/// Serves three tools over two tools/list pages
+struct MockServer;
+
+fn mock_tool(name: &'static str) -> Tool {
+    let schema = json!({ "type": "object", "properties": {} });
+    Tool::new(
+        name,
+        format!("mock {}", name),
+        Arc::new(schema.as_object().cloned().unwrap_or_default()),
+    )
+}
+
+impl ServerHandler for MockServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            instructions: Some("Use echo to repeat text.".to_string()),
+            ..Default::default()
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, rmcp::Error> {
+        Ok(match request.and_then(|r| r.cursor).as_deref() {
+            None => ListToolsResult {
+                next_cursor: Some("page-2".to_string()),
+                tools: vec![mock_tool("echo"), mock_tool("fail")],
+            },
+            _ => ListToolsResult::with_all_items(vec![mock_tool("slow")]),
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        match request.name.as_ref() {
+            "echo" => {
+                let text = request
+                    .arguments
+                    .and_then(|args| args.get("text").cloned())
+                    .and_then(|v| v.as_str().map(str::to_string))
+                    .unwrap_or_default();
+                Ok(CallToolResult::success(vec![Content::text(text)]))
+            }
+            "slow" => {
+                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                Ok(CallToolResult::success(vec![Content::text("done")]))
+            }
+            "fail" => Err(rmcp::Error::internal_error("mock failure", None)),
+            other => Err(rmcp::Error::invalid_params(
+                format!("unknown tool {}", other),
+                None,
+            )),
+        }
+    }
+}
+
+fn mock_config(name: &str) -> MCPServerConfig {
+    serde_json::from_value(json!({ "name": name, "call_timeout_secs": 1 }))
+        .expect("valid mock config")
+}
+
+/// Connect `name` to a fresh mock server over an in-memory duplex pipe
+async fn connect_mock(manager: &MCPServerManager, name: &str) {
+    let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+    tokio::spawn(async move {
+        if let Ok(server) = MockServer.serve(server_io).await {
+            let _ = server.waiting().await;
+        }
+    });
+    manager
+        .connect_transport(&mock_config(name), client_io, None, None)
+        .await
+        .expect("mock server connects");
+}
+
+#[tokio::test]
+async fn lists_tools_across_pages() {
+    let manager = MCPServerManager::new();
+    connect_mock(&manager, "mock").await;
+
+    let mut names: Vec<String> = manager
+        .list_tools("mock")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|tool| tool.name)
+        .collect();
+    names.sort();
+    assert_eq!(names, ["echo", "fail", "slow"]);
+    assert_eq!(manager.get_cached_tools().await["mock"].len(), 3);
+}
+
+#[tokio::test]
+async fn records_server_info() {
+    let manager = MCPServerManager::new();
+    connect_mock(&manager, "mock").await;
+
+    let info = manager.get_server_info("mock").await.unwrap();
+    assert!(info.protocol_supported);
+    assert!(info.capabilities.tools);
+    assert!(!info.capabilities.resources);
+    assert_eq!(
+        info.instructions.as_deref(),
+        Some("Use echo to repeat text.")
+    );
+}
+
+#[tokio::test]
+async fn calls_tool() {
+    let manager = MCPServerManager::new();
+    connect_mock(&manager, "mock").await;
+
+    let result = manager
+        .call_tool(
+            "mock",
+            "echo",
+            json!({ "text": "hello" }),
+            &ToolCallContext::default(),
+        )
+        .await;
+    assert!(result.success, "{:?}", result.error);
+    assert_eq!(result.result.unwrap()["content"][0]["text"], "hello");
+}
+
+#[tokio::test]
+async fn propagates_tool_errors() {
+    let manager = MCPServerManager::new();
+    connect_mock(&manager, "mock").await;
+
+    let result = manager
+        .call_tool("mock", "fail", json!({}), &ToolCallContext::default())
+        .await;
+    assert!(!result.success);
+    assert!(result.error.unwrap().contains("mock failure"));
+}
+
+#[tokio::test]
+async fn times_out_slow_calls() {
+    let manager = MCPServerManager::new();
+    connect_mock(&manager, "mock").await;
+
+    let result = manager
+        .call_tool("mock", "slow", json!({}), &ToolCallContext::default())
+        .await;
+    assert!(!result.success);
+    assert!(result.error.unwrap().to_lowercase().contains("timeout"));
+
+    // 타임아웃 이후에도 연결은 계속 사용할 수 있어야 합니다
+    let result = manager
+        .call_tool(
+            "mock",
+            "echo",
+            json!({ "text": "still here" }),
+            &ToolCallContext::default(),
+        )
+        .await;
+    assert!(result.success);
+}
+
+#[tokio::test]
+async fn stops_and_reconnects() {
+    let manager = MCPServerManager::new();
+    connect_mock(&manager, "mock").await;
+    manager.list_tools("mock").await.unwrap();
+    assert!(manager.is_server_alive("mock").await);
+
+    manager.stop_server("mock").await.unwrap();
+    assert!(!manager.is_server_alive("mock").await);
+    assert!(!manager.get_cached_tools().await.contains_key("mock"));
+    let result = manager
+        .call_tool("mock", "echo", json!({}), &ToolCallContext::default())
+        .await;
+    assert!(result.error.unwrap().contains("not found"));
+
+    connect_mock(&manager, "mock").await;
+    let result = manager
+        .call_tool(
+            "mock",
+            "echo",
+            json!({ "text": "again" }),
+            &ToolCallContext::default(),
+        )
+        .await;
+    assert!(result.success);
+}
+
+#[tokio::test]
+async fn keeps_idle_stopped_server_when_restart_fails() {
+    let manager = MCPServerManager::new();
+    // 명령이 없는 설정은 시작할 수 없습니다
+    manager
+        .idle_stopped
+        .lock()
+        .await
+        .insert("broken".to_string(), mock_config("broken"));
+
+    let result = manager
+        .call_tool("broken", "echo", json!({}), &ToolCallContext::default())
+        .await;
+    assert!(!result.success);
+    assert!(manager.idle_stopped.lock().await.contains_key("broken"));
+}
+
+#[test]
+fn orders_dependencies_and_detects_cycles() {
+    let mut a = mock_config("a");
+    let mut b = mock_config("b");
+    let c = mock_config("c");
+    a.depends_on = vec!["b".to_string(), "c".to_string()];
+    b.depends_on = vec!["c".to_string()];
+
+    let stages = dependency_stages(&[a.clone(), b.clone(), c]).unwrap();
+    assert_eq!(stages, vec![vec!["c"], vec!["b"], vec!["a"]]);
+
+    b.depends_on = vec!["a".to_string()];
+    let err = dependency_stages(&[a, b]).unwrap_err();
+    assert!(err.to_string().contains("cycle"));
+}
@@ -0,0 +1,109 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+use crate::config_schema::{self, ConfigDiagnostic, DiagnosticSeverity};
+
+// 공유된 설정 파일로 보기에는 너무 큰 파일은 읽지 않습니다
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ServerChangeKind {
+    Added,
+    Changed,
+    Unchanged,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerChange {
+    pub name: String,
+    pub change: ServerChangeKind,
+    pub config: Value,
+    /// The entry this one would replace
+    pub previous: Option<Value>,
+}
+
+/// What importing a `.mcp.json` file would do to the current config
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreview {
+    pub path: PathBuf,
+    /// In file order
+    pub servers: Vec<ServerChange>,
+    pub diagnostics: Vec<ConfigDiagnostic>,
+    /// False while any diagnostic is an error
+    pub importable: bool,
+    /// The file's servers as an `mcpServers` document, ready to merge
+    pub config: Value,
+}
+
+/// Servers of a `{"mcpServers": {...}}` or `{"servers": [...]}` document keyed by name
+fn servers_by_name(config: &Value) -> Map<String, Value> {
+    if let Some(servers) = config.get("mcpServers").and_then(|v| v.as_object()) {
+        return servers.clone();
+    }
+    config
+        .get("servers")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let mut entry = entry.as_object()?.clone();
+            let name = entry.remove("name")?.as_str()?.to_string();
+            Some((name, Value::Object(entry)))
+        })
+        .collect()
+}
+
+/// Parse and validate a server config file and compare it with `current`, the
+/// `mcpConfig` it would be merged into. Nothing is written.
+pub async fn preview(path: &Path, current: &Value) -> Result<ImportPreview> {
+    let size = tokio::fs::metadata(path).await?.len();
+    if size > MAX_FILE_BYTES {
+        return Err(anyhow::anyhow!(
+            "{} is too large for a server config ({} bytes)",
+            path.display(),
+            size
+        ));
+    }
+    let content = tokio::fs::read_to_string(path).await?;
+    let document: Value = serde_json::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("{} is not valid JSON: {}", path.display(), e))?;
+
+    let diagnostics = config_schema::validate(&document);
+    let existing = current
+        .get("mcpServers")
+        .and_then(|v| v.as_object())
+        .cloned()
+        .unwrap_or_default();
+    let servers_map = servers_by_name(&document);
+    let servers = servers_map
+        .iter()
+        .map(|(name, config)| {
+            let previous = existing.get(name).cloned();
+            let change = match &previous {
+                None => ServerChangeKind::Added,
+                Some(previous) if previous == config => ServerChangeKind::Unchanged,
+                Some(_) => ServerChangeKind::Changed,
+            };
+            ServerChange {
+                name: name.clone(),
+                change,
+                config: config.clone(),
+                previous,
+            }
+        })
+        .collect();
+
+    Ok(ImportPreview {
+        path: path.to_path_buf(),
+        servers,
+        importable: !servers_map.is_empty()
+            && !diagnostics
+                .iter()
+                .any(|d| d.severity == DiagnosticSeverity::Error),
+        diagnostics,
+        config: serde_json::json!({ "mcpServers": servers_map }),
+    })
+}
@@ -0,0 +1,162 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::integrity;
+
+const DEFAULT_LANGUAGE: &str = "eng";
+const OCR_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub enum OcrInput {
+    Path(PathBuf),
+    /// Encoded image bytes (PNG, JPEG, ...) piped to tesseract on stdin
+    Bytes(Vec<u8>),
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct BoundingBox {
+    pub left: u32,
+    pub top: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl BoundingBox {
+    fn union(self, other: BoundingBox) -> BoundingBox {
+        let right = (self.left + self.width).max(other.left + other.width);
+        let bottom = (self.top + self.height).max(other.top + other.height);
+        let left = self.left.min(other.left);
+        let top = self.top.min(other.top);
+        BoundingBox {
+            left,
+            top,
+            width: right - left,
+            height: bottom - top,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OcrLine {
+    pub text: String,
+    /// Mean word confidence, 0-100
+    pub confidence: f32,
+    pub bbox: BoundingBox,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OcrResult {
+    pub text: String,
+    pub lines: Vec<OcrLine>,
+}
+
+/// 언어 코드는 `eng`, `kor+eng` 형태만 허용합니다
+fn validate_language(lang: &str) -> Result<()> {
+    let valid = !lang.is_empty()
+        && lang.split('+').all(|part| {
+            !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "Invalid OCR language '{}' (use tesseract codes such as eng or kor+eng)",
+            lang
+        ))
+    }
+}
+
+struct LineWords {
+    words: Vec<String>,
+    confidences: Vec<f32>,
+    bbox: BoundingBox,
+}
+
+/// tesseract TSV 출력의 단어(level 5) 행을 줄 단위로 묶습니다
+fn parse_tsv(tsv: &str) -> Vec<OcrLine> {
+    // (page, block, paragraph, line) 순서로 정렬됩니다
+    let mut lines: BTreeMap<(u32, u32, u32, u32), LineWords> = BTreeMap::new();
+    for row in tsv.lines().skip(1) {
+        let columns: Vec<&str> = row.splitn(12, '\t').collect();
+        if columns.len() < 12 || columns[0] != "5" {
+            continue;
+        }
+        let word = columns[11].trim();
+        if word.is_empty() {
+            continue;
+        }
+        let number = |index: usize| columns[index].parse::<u32>().unwrap_or_default();
+        let key = (number(1), number(2), number(3), number(4));
+        let bbox = BoundingBox {
+            left: number(6),
+            top: number(7),
+            width: number(8),
+            height: number(9),
+        };
+        let confidence = columns[10].parse::<f32>().unwrap_or_default().max(0.0);
+        let line = lines.entry(key).or_insert_with(|| LineWords {
+            words: Vec::new(),
+            confidences: Vec::new(),
+            bbox,
+        });
+        line.words.push(word.to_string());
+        line.confidences.push(confidence);
+        line.bbox = line.bbox.union(bbox);
+    }
+    lines
+        .into_values()
+        .map(|line| OcrLine {
+            text: line.words.join(" "),
+            confidence: line.confidences.iter().sum::<f32>() / line.confidences.len() as f32,
+            bbox: line.bbox,
+        })
+        .collect()
+}
+
+/// Recognize text with the locally installed tesseract; images never leave the machine
+pub async fn recognize(input: OcrInput, lang: Option<&str>) -> Result<OcrResult> {
+    let lang = lang.unwrap_or(DEFAULT_LANGUAGE);
+    validate_language(lang)?;
+    let tesseract = integrity::resolve_executable("tesseract").ok_or_else(|| {
+        anyhow::anyhow!("Tesseract is not installed (tesseract not found on PATH)")
+    })?;
+
+    let mut command = Command::new(tesseract);
+    match &input {
+        OcrInput::Path(path) => command.arg(path).stdin(Stdio::null()),
+        OcrInput::Bytes(_) => command.arg("stdin").stdin(Stdio::piped()),
+    };
+    command
+        .args(["stdout", "-l", lang, "tsv"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let mut child = command.spawn()?;
+    if let (OcrInput::Bytes(bytes), Some(mut stdin)) = (input, child.stdin.take()) {
+        // 출력 파이프가 차기 전에 모두 쓰도록 별도 태스크에서 입력합니다
+        tokio::spawn(async move {
+            let _ = stdin.write_all(&bytes).await;
+        });
+    }
+
+    let output = tokio::time::timeout(OCR_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| anyhow::anyhow!("OCR timed out after {} seconds", OCR_TIMEOUT.as_secs()))??;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("tesseract failed: {}", stderr.trim()));
+    }
+
+    let lines = parse_tsv(&String::from_utf8_lossy(&output.stdout));
+    let text = lines
+        .iter()
+        .map(|line| line.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(OcrResult { text, lines })
+}
@@ -0,0 +1,523 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::sync::RwLock;
+
+use crate::builtin::{error_result, text_result};
+use crate::config_versions;
+use crate::mcp::{MCPTool, MCPToolAnnotations, ToolCallResult};
+
+const HTTP_METHODS: &[&str] = &["get", "put", "post", "delete", "patch", "head", "options"];
+// 순환 참조 스키마에서 무한히 펼치지 않도록 제한합니다
+const MAX_REF_DEPTH: usize = 8;
+const REQUEST_TIMEOUT_SECS: u64 = 60;
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OpenApiAuth {
+    #[default]
+    None,
+    Bearer {
+        token: String,
+    },
+    Header {
+        name: String,
+        value: String,
+    },
+    Query {
+        name: String,
+        value: String,
+    },
+    Basic {
+        username: String,
+        password: String,
+    },
+}
+
+/// A REST API exposed to the agent as a virtual MCP server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenApiConfig {
+    /// Virtual server name used to route tool calls
+    pub name: String,
+    /// Path or http(s) URL of an OpenAPI 3.x JSON document
+    pub spec: String,
+    /// Overrides the first entry of the document's `servers`
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub auth: OpenApiAuth,
+    /// Extra headers sent with every request
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ParamLocation {
+    Path,
+    Query,
+    Header,
+}
+
+#[derive(Debug, Clone)]
+struct Operation {
+    tool: MCPTool,
+    method: reqwest::Method,
+    path: String,
+    params: Vec<(String, ParamLocation)>,
+}
+
+struct LoadedApi {
+    config: OpenApiConfig,
+    base_url: String,
+    operations: Vec<Operation>,
+}
+
+/// Registry of loaded OpenAPI documents, persisted as a list of configs
+pub struct OpenApiBridge {
+    apis: RwLock<HashMap<String, LoadedApi>>,
+    config_path: PathBuf,
+}
+
+/// Inline local `#/...` references
+fn resolve_refs(
+    spec: &serde_json::Value,
+    value: &serde_json::Value,
+    depth: usize,
+) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            if let Some(reference) = map.get("$ref").and_then(|r| r.as_str()) {
+                if depth >= MAX_REF_DEPTH {
+                    return json!({});
+                }
+                return reference
+                    .strip_prefix('#')
+                    .and_then(|pointer| spec.pointer(pointer))
+                    .map(|target| resolve_refs(spec, target, depth + 1))
+                    .unwrap_or_else(|| json!({}));
+            }
+            serde_json::Value::Object(
+                map.iter()
+                    .map(|(key, value)| (key.clone(), resolve_refs(spec, value, depth)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(items) => serde_json::Value::Array(
+            items
+                .iter()
+                .map(|item| resolve_refs(spec, item, depth))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn tool_name(operation: &serde_json::Value, method: &str, path: &str) -> String {
+    let raw = operation
+        .get("operationId")
+        .and_then(|id| id.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{}_{}", method, path));
+    let mut name: String = raw
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .trim_matches('_')
+        .to_string();
+    name.truncate(MAX_TOOL_NAME_LEN);
+    name
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn argument_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// OpenAPI 문서의 각 operation을 MCP 도구로 변환합니다
+fn parse_operations(spec: &serde_json::Value) -> Result<Vec<Operation>> {
+    let version = spec.get("openapi").and_then(|v| v.as_str()).unwrap_or("");
+    if !version.starts_with("3.") {
+        return Err(anyhow::anyhow!(
+            "Only OpenAPI 3.x documents are supported (found '{}')",
+            version
+        ));
+    }
+    let paths = spec
+        .get("paths")
+        .and_then(|p| p.as_object())
+        .ok_or_else(|| anyhow::anyhow!("OpenAPI document has no paths"))?;
+
+    let mut operations = Vec::new();
+    for (path, item) in paths {
+        let item = resolve_refs(spec, item, 0);
+        let shared_params = item
+            .get("parameters")
+            .and_then(|p| p.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        for method in HTTP_METHODS {
+            let Some(operation) = item.get(*method) else {
+                continue;
+            };
+            let mut properties = serde_json::Map::new();
+            let mut required = Vec::new();
+            let mut params = Vec::new();
+
+            let operation_params = operation
+                .get("parameters")
+                .and_then(|p| p.as_array())
+                .cloned()
+                .unwrap_or_default();
+            for param in shared_params.iter().chain(operation_params.iter()) {
+                let (Some(name), Some(location)) = (
+                    param.get("name").and_then(|n| n.as_str()),
+                    param.get("in").and_then(|l| l.as_str()),
+                ) else {
+                    continue;
+                };
+                let location = match location {
+                    "path" => ParamLocation::Path,
+                    "query" => ParamLocation::Query,
+                    "header" => ParamLocation::Header,
+                    // 쿠키 파라미터는 지원하지 않습니다
+                    _ => continue,
+                };
+                let mut schema = param
+                    .get("schema")
+                    .cloned()
+                    .unwrap_or_else(|| json!({ "type": "string" }));
+                if let (Some(description), Some(object)) =
+                    (param.get("description"), schema.as_object_mut())
+                {
+                    object.insert("description".to_string(), description.clone());
+                }
+                properties.insert(name.to_string(), schema);
+                if (location == ParamLocation::Path
+                    || param.get("required").and_then(|r| r.as_bool()) == Some(true))
+                    && !required.iter().any(|r| r == name)
+                {
+                    required.push(name.to_string());
+                }
+                params.retain(|(existing, _): &(String, ParamLocation)| existing != name);
+                params.push((name.to_string(), location));
+            }
+
+            if let Some(body) = operation.get("requestBody") {
+                let schema = body
+                    .pointer("/content/application~1json/schema")
+                    .cloned()
+                    .unwrap_or_else(|| json!({ "type": "object" }));
+                properties.insert("body".to_string(), schema);
+                if body.get("required").and_then(|r| r.as_bool()) == Some(true) {
+                    required.push("body".to_string());
+                }
+            }
+
+            let description = operation
+                .get("summary")
+                .or_else(|| operation.get("description"))
+                .and_then(|d| d.as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{} {}", method.to_uppercase(), path));
+            let input_schema = json!({
+                "type": "object",
+                "properties": properties,
+                "required": required,
+            });
+            let read_only = matches!(*method, "get" | "head" | "options");
+            operations.push(Operation {
+                tool: MCPTool {
+                    name: tool_name(operation, method, path),
+                    description,
+                    input_schema: serde_json::from_value(input_schema).unwrap_or_default(),
+                    annotations: Some(MCPToolAnnotations {
+                        read_only_hint: Some(read_only),
+                        destructive_hint: Some(*method == "delete"),
+                        idempotent_hint: Some(read_only || matches!(*method, "put" | "delete")),
+                        open_world_hint: Some(true),
+                        ..Default::default()
+                    }),
+                },
+                method: reqwest::Method::from_bytes(method.to_uppercase().as_bytes())?,
+                path: path.clone(),
+                params,
+            });
+        }
+    }
+    Ok(operations)
+}
+
+async fn fetch_spec(location: &str) -> Result<serde_json::Value> {
+    let content = if location.starts_with("http://") || location.starts_with("https://") {
+        let response = reqwest::get(location).await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "{} returned {}",
+                location,
+                response.status()
+            ));
+        }
+        response.text().await?
+    } else {
+        tokio::fs::read_to_string(location).await?
+    };
+    serde_json::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("OpenAPI document must be JSON: {}", e))
+}
+
+fn base_url(config: &OpenApiConfig, spec: &serde_json::Value) -> Result<String> {
+    if let Some(base_url) = &config.base_url {
+        return Ok(base_url.trim_end_matches('/').to_string());
+    }
+    let server_url = spec
+        .pointer("/servers/0/url")
+        .and_then(|u| u.as_str())
+        .ok_or_else(|| {
+            anyhow::anyhow!("No base_url configured and the document lists no servers")
+        })?;
+    if server_url.starts_with("http://") || server_url.starts_with("https://") {
+        return Ok(server_url.trim_end_matches('/').to_string());
+    }
+    // 상대 경로 서버 URL은 문서 URL을 기준으로 해석합니다
+    let spec_url = reqwest::Url::parse(&config.spec).map_err(|_| {
+        anyhow::anyhow!(
+            "Server URL '{}' is relative; set base_url explicitly",
+            server_url
+        )
+    })?;
+    Ok(spec_url
+        .join(server_url)?
+        .to_string()
+        .trim_end_matches('/')
+        .to_string())
+}
+
+async fn load(config: OpenApiConfig) -> Result<LoadedApi> {
+    let spec = fetch_spec(&config.spec).await?;
+    let operations = parse_operations(&spec)?;
+    Ok(LoadedApi {
+        base_url: base_url(&config, &spec)?,
+        config,
+        operations,
+    })
+}
+
+impl OpenApiBridge {
+    pub fn new(config_path: PathBuf) -> Self {
+        Self {
+            apis: RwLock::new(HashMap::new()),
+            config_path,
+        }
+    }
+
+    fn read_configs(&self) -> Vec<OpenApiConfig> {
+        std::fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    async fn persist(&self) -> Result<()> {
+        let configs: Vec<OpenApiConfig> = self
+            .apis
+            .read()
+            .await
+            .values()
+            .map(|api| api.config.clone())
+            .collect();
+        config_versions::write(&self.config_path, serde_json::to_string_pretty(&configs)?).await?;
+        Ok(())
+    }
+
+    /// 저장된 모든 API 문서를 불러옵니다. 실패한 항목은 건너뜁니다
+    pub async fn load_saved(&self) {
+        for config in self.read_configs() {
+            let name = config.name.clone();
+            match load(config).await {
+                Ok(api) => {
+                    println!(
+                        "Loaded OpenAPI server {} ({} operations)",
+                        name,
+                        api.operations.len()
+                    );
+                    self.apis.write().await.insert(name, api);
+                }
+                Err(e) => eprintln!("❌ Failed to load OpenAPI server {}: {}", name, e),
+            }
+        }
+    }
+
+    /// Load (or reload) a document and persist its config
+    pub async fn register(&self, config: OpenApiConfig) -> Result<Vec<MCPTool>> {
+        let name = config.name.clone();
+        let api = load(config).await?;
+        let tools = api.operations.iter().map(|op| op.tool.clone()).collect();
+        self.apis.write().await.insert(name, api);
+        self.persist().await?;
+        Ok(tools)
+    }
+
+    pub async fn remove(&self, name: &str) -> Result<()> {
+        if self.apis.write().await.remove(name).is_none() {
+            return Err(anyhow::anyhow!(
+                "OpenAPI server '{}' is not registered",
+                name
+            ));
+        }
+        self.persist().await
+    }
+
+    pub async fn configs(&self) -> Vec<OpenApiConfig> {
+        self.apis
+            .read()
+            .await
+            .values()
+            .map(|api| api.config.clone())
+            .collect()
+    }
+
+    pub async fn contains(&self, name: &str) -> bool {
+        self.apis.read().await.contains_key(name)
+    }
+
+    pub async fn list_tools(&self, name: &str) -> Option<Vec<MCPTool>> {
+        self.apis
+            .read()
+            .await
+            .get(name)
+            .map(|api| api.operations.iter().map(|op| op.tool.clone()).collect())
+    }
+
+    /// Tools of every registered API, prefixed `server__tool` like MCP servers
+    pub async fn list_all_tools(&self) -> Vec<MCPTool> {
+        let apis = self.apis.read().await;
+        apis.iter()
+            .flat_map(|(name, api)| {
+                api.operations.iter().map(move |op| MCPTool {
+                    name: format!("{}__{}", name, op.tool.name),
+                    ..op.tool.clone()
+                })
+            })
+            .collect()
+    }
+
+    /// operation을 HTTP 요청으로 실행합니다
+    pub async fn call_tool(
+        &self,
+        name: &str,
+        tool_name: &str,
+        arguments: serde_json::Value,
+    ) -> ToolCallResult {
+        let request = {
+            let apis = self.apis.read().await;
+            let Some(api) = apis.get(name) else {
+                return error_result(format!("OpenAPI server '{}' is not registered", name));
+            };
+            let Some(operation) = api.operations.iter().find(|op| op.tool.name == tool_name) else {
+                return error_result(format!("Unknown operation '{}' on {}", tool_name, name));
+            };
+            match build_request(api, operation, &arguments) {
+                Ok(request) => request,
+                Err(e) => return error_result(e.to_string()),
+            }
+        };
+
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(e) => return error_result(format!("Request failed: {}", e)),
+        };
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if status.is_success() {
+            text_result(body)
+        } else {
+            error_result(format!("HTTP {}: {}", status, body))
+        }
+    }
+}
+
+fn build_request(
+    api: &LoadedApi,
+    operation: &Operation,
+    arguments: &serde_json::Value,
+) -> Result<reqwest::RequestBuilder> {
+    let mut path = operation.path.clone();
+    let mut query = Vec::new();
+    let mut headers = Vec::new();
+    for (param, location) in &operation.params {
+        let Some(value) = arguments.get(param).filter(|v| !v.is_null()) else {
+            if *location == ParamLocation::Path {
+                return Err(anyhow::anyhow!("Missing path parameter '{}'", param));
+            }
+            continue;
+        };
+        let value = argument_to_string(value);
+        match location {
+            ParamLocation::Path => {
+                path = path.replace(&format!("{{{}}}", param), &percent_encode(&value))
+            }
+            ParamLocation::Query => query.push((param.clone(), value)),
+            ParamLocation::Header => headers.push((param.clone(), value)),
+        }
+    }
+
+    let mut url = reqwest::Url::parse(&format!("{}{}", api.base_url, path))?;
+    {
+        let mut pairs = url.query_pairs_mut();
+        for (name, value) in &query {
+            pairs.append_pair(name, value);
+        }
+        if let OpenApiAuth::Query { name, value } = &api.config.auth {
+            pairs.append_pair(name, value);
+        }
+    }
+    if url.query() == Some("") {
+        url.set_query(None);
+    }
+
+    let mut request = reqwest::Client::new()
+        .request(operation.method.clone(), url)
+        .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS));
+    for (name, value) in api
+        .config
+        .headers
+        .iter()
+        .chain(headers.iter().map(|(n, v)| (n, v)))
+    {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    request = match &api.config.auth {
+        OpenApiAuth::Bearer { token } => request.bearer_auth(token),
+        OpenApiAuth::Header { name, value } => request.header(name.as_str(), value.as_str()),
+        OpenApiAuth::Basic { username, password } => request.basic_auth(username, Some(password)),
+        OpenApiAuth::Query { .. } | OpenApiAuth::None => request,
+    };
+    if let Some(body) = arguments.get("body") {
+        request = request.json(body);
+    }
+    Ok(request)
+}
@@ -0,0 +1,534 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+use crate::api::ask_approval;
+
+// 승인 대화상자에 보여줄 diff 미리보기 길이
+const MAX_PREVIEW_CHARS: usize = 4000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatchStatus {
+    Proposed,
+    Applied,
+    RolledBack,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchRecord {
+    pub id: String,
+    pub file: PathBuf,
+    pub diff: String,
+    pub status: PatchStatus,
+    pub hunks: usize,
+    pub added: usize,
+    pub removed: usize,
+    pub created_at: String,
+    /// `None` when the patch creates the file
+    original_hash: Option<String>,
+    patched_hash: String,
+}
+
+#[derive(Debug, Clone)]
+enum HunkLine {
+    Context(String),
+    Removed(String),
+    Added(String),
+}
+
+#[derive(Debug, Clone)]
+struct Hunk {
+    old_start: usize,
+    lines: Vec<HunkLine>,
+    /// `\ No newline at end of file` followed the last new line
+    new_no_eol: bool,
+}
+
+fn parse_range(range: &str) -> Result<(usize, usize)> {
+    let invalid = || anyhow::anyhow!("Invalid hunk range '{}'", range);
+    let (start, count) = match range.split_once(',') {
+        Some((start, count)) => (start, count.parse().map_err(|_| invalid())?),
+        None => (range, 1),
+    };
+    Ok((start.parse().map_err(|_| invalid())?, count))
+}
+
+/// 단일 파일에 대한 unified diff를 hunk 목록으로 해석합니다
+fn parse_diff(diff: &str) -> Result<Vec<Hunk>> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let mut remaining = (0usize, 0usize);
+    let mut file_headers = 0;
+    let mut last_kind = ' ';
+    for line in diff.lines() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if remaining == (0, 0) {
+            if line.starts_with("--- ") {
+                file_headers += 1;
+                if file_headers > 1 {
+                    return Err(anyhow::anyhow!(
+                        "The diff touches more than one file; propose one patch per file"
+                    ));
+                }
+                continue;
+            }
+            if let Some(header) = line.strip_prefix("@@ ") {
+                let ranges = header
+                    .split(" @@")
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("Invalid hunk header '{}'", line))?;
+                let (old, new) = ranges
+                    .split_once(' ')
+                    .ok_or_else(|| anyhow::anyhow!("Invalid hunk header '{}'", line))?;
+                let (old_start, old_count) =
+                    parse_range(old.strip_prefix('-').unwrap_or_default())?;
+                let (_, new_count) = parse_range(new.strip_prefix('+').unwrap_or_default())?;
+                hunks.push(Hunk {
+                    old_start,
+                    lines: Vec::new(),
+                    new_no_eol: false,
+                });
+                remaining = (old_count, new_count);
+                continue;
+            }
+            if line.starts_with("\\ ") {
+                if let Some(hunk) = hunks.last_mut() {
+                    hunk.new_no_eol |= last_kind != '-';
+                }
+            }
+            // 헤더가 선언한 줄 수를 넘는 diff 줄을 머리글로 보고 버리면 변경이 빠집니다
+            let is_header = line.starts_with("+++ ") || line.starts_with("--- ");
+            if !hunks.is_empty() && !is_header && line.starts_with([' ', '-', '+']) {
+                return Err(anyhow::anyhow!(
+                    "Hunk {} has more lines than its header declares",
+                    hunks.len()
+                ));
+            }
+            // diff --git, index, +++ 등 나머지 머리글은 무시합니다
+            continue;
+        }
+
+        let number = hunks.len();
+        let hunk = hunks.last_mut().expect("a hunk header was parsed");
+        let (kind, text) = match line.chars().next() {
+            Some(kind @ (' ' | '-' | '+')) => (kind, line[1..].to_string()),
+            // 일부 도구는 빈 문맥 줄의 공백을 지웁니다
+            None => (' ', String::new()),
+            Some('\\') => {
+                hunk.new_no_eol |= last_kind != '-';
+                continue;
+            }
+            Some(_) => {
+                return Err(anyhow::anyhow!(
+                    "Hunk {} ends early: expected {} more old and {} more new lines",
+                    number,
+                    remaining.0,
+                    remaining.1
+                ))
+            }
+        };
+        let (old, new) = match kind {
+            ' ' => (1, 1),
+            '-' => (1, 0),
+            _ => (0, 1),
+        };
+        if remaining.0 < old || remaining.1 < new {
+            return Err(anyhow::anyhow!(
+                "Hunk {} has more lines than its header declares",
+                number
+            ));
+        }
+        remaining = (remaining.0 - old, remaining.1 - new);
+        last_kind = kind;
+        hunk.lines.push(match kind {
+            ' ' => HunkLine::Context(text),
+            '-' => HunkLine::Removed(text),
+            _ => HunkLine::Added(text),
+        });
+    }
+    if remaining != (0, 0) {
+        return Err(anyhow::anyhow!(
+            "The last hunk is truncated: expected {} more old and {} more new lines",
+            remaining.0,
+            remaining.1
+        ));
+    }
+    if hunks.is_empty() {
+        return Err(anyhow::anyhow!("The diff contains no hunks"));
+    }
+    Ok(hunks)
+}
+
+/// 문맥이 정확히 일치하는 위치를 예상 위치에서 가까운 순서로 찾습니다
+fn locate(lines: &[&str], expected: &[&str], start: usize, hint: usize) -> Option<usize> {
+    let last = lines.len().checked_sub(expected.len())?;
+    if start > last {
+        return None;
+    }
+    let matches_at = |at: usize| lines[at..at + expected.len()] == *expected;
+    let hint = hint.clamp(start, last);
+    (0..=(last - start)).find_map(|distance| {
+        [hint.checked_sub(distance), hint.checked_add(distance)]
+            .into_iter()
+            .flatten()
+            .filter(|&at| at >= start && at <= last)
+            .find(|&at| matches_at(at))
+    })
+}
+
+/// Apply hunks to `original`, keeping its line endings; context must match exactly
+fn apply_hunks(original: &str, hunks: &[Hunk]) -> Result<String> {
+    let eol = if original.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let had_trailing_eol = original.is_empty() || original.ends_with('\n');
+    let lines: Vec<&str> = if original.is_empty() {
+        Vec::new()
+    } else {
+        original
+            .strip_suffix('\n')
+            .unwrap_or(original)
+            .split('\n')
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .collect()
+    };
+
+    let mut output: Vec<String> = Vec::new();
+    let mut cursor = 0;
+    let mut trailing_eol = had_trailing_eol;
+    for (index, hunk) in hunks.iter().enumerate() {
+        let expected: Vec<&str> = hunk
+            .lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Removed(text) => Some(text.as_str()),
+                HunkLine::Added(_) => None,
+            })
+            .collect();
+        // 새 파일(@@ -0,0 ...)은 시작 줄 번호가 0입니다
+        let hint = hunk.old_start.saturating_sub(1);
+        let at = locate(&lines, &expected, cursor, hint).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Hunk {} does not match the file near line {}",
+                index + 1,
+                hunk.old_start
+            )
+        })?;
+        output.extend(lines[cursor..at].iter().map(|line| line.to_string()));
+        output.extend(hunk.lines.iter().filter_map(|line| match line {
+            HunkLine::Context(text) | HunkLine::Added(text) => Some(text.clone()),
+            HunkLine::Removed(_) => None,
+        }));
+        cursor = at + expected.len();
+        if cursor == lines.len() {
+            trailing_eol = !hunk.new_no_eol;
+        }
+    }
+    output.extend(lines[cursor..].iter().map(|line| line.to_string()));
+
+    let mut patched = output.join(eol);
+    if trailing_eol && !output.is_empty() {
+        patched.push_str(eol);
+    }
+    Ok(patched)
+}
+
+fn hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+async fn read_optional(path: &Path) -> Result<Option<String>> {
+    match tokio::fs::read_to_string(path).await {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 같은 디렉토리의 임시 파일에 쓴 뒤 이름을 바꿔 원자적으로 교체합니다
+async fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("'{}' is not a file path", path.display()))?;
+    let tmp_path = path.with_file_name(format!(".{}.patch-tmp", file_name.to_string_lossy()));
+    tokio::fs::write(&tmp_path, content).await?;
+    if let Ok(metadata) = tokio::fs::metadata(path).await {
+        tokio::fs::set_permissions(&tmp_path, metadata.permissions()).await?;
+    }
+    if let Err(e) = tokio::fs::rename(&tmp_path, path).await {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+fn preview(diff: &str) -> String {
+    match diff.char_indices().nth(MAX_PREVIEW_CHARS) {
+        Some((index, _)) => format!("{}\n… diff truncated", &diff[..index]),
+        None => diff.to_string(),
+    }
+}
+
+/// Proposed patches with a backup of each file they change, kept in app-data
+pub struct PatchStore {
+    dir: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl PatchStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn record_path(&self, id: &str) -> Result<PathBuf> {
+        // id는 파일 이름으로 쓰이므로 uuid 형식만 허용합니다
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+            return Err(anyhow::anyhow!("Unknown patch '{}'", id));
+        }
+        Ok(self.dir.join(format!("{}.json", id)))
+    }
+
+    fn backup_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.orig", id))
+    }
+
+    async fn load(&self, id: &str) -> Result<PatchRecord> {
+        let content = tokio::fs::read_to_string(self.record_path(id)?)
+            .await
+            .map_err(|_| anyhow::anyhow!("Unknown patch '{}'", id))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    async fn save(&self, record: &PatchRecord) -> Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        tokio::fs::write(
+            self.record_path(&record.id)?,
+            serde_json::to_string_pretty(record)?,
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get(&self, id: &str) -> Result<PatchRecord> {
+        self.load(id).await
+    }
+
+    /// Validate `diff` against the current file and store it for review; nothing is written
+    pub async fn propose(&self, file: PathBuf, diff: String) -> Result<PatchRecord> {
+        let hunks = parse_diff(&diff)?;
+        let original = read_optional(&file).await?;
+        let patched = apply_hunks(original.as_deref().unwrap_or_default(), &hunks)?;
+        let count = |f: fn(&HunkLine) -> bool| {
+            hunks
+                .iter()
+                .flat_map(|hunk| &hunk.lines)
+                .filter(|line| f(line))
+                .count()
+        };
+        let record = PatchRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            file,
+            status: PatchStatus::Proposed,
+            hunks: hunks.len(),
+            added: count(|line| matches!(line, HunkLine::Added(_))),
+            removed: count(|line| matches!(line, HunkLine::Removed(_))),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            original_hash: original.as_deref().map(hash),
+            patched_hash: hash(&patched),
+            diff,
+        };
+        let _guard = self.lock.lock().await;
+        self.save(&record).await?;
+        Ok(record)
+    }
+
+    /// Apply a proposed patch after the user approves, keeping a backup for rollback
+    pub async fn apply(&self, id: &str) -> Result<PatchRecord> {
+        let _guard = self.lock.lock().await;
+        let mut record = self.load(id).await?;
+        if record.status != PatchStatus::Proposed {
+            return Err(anyhow::anyhow!(
+                "Patch '{}' was already {}",
+                id,
+                match record.status {
+                    PatchStatus::Applied => "applied",
+                    _ => "rolled back",
+                }
+            ));
+        }
+        ask_approval(
+            "patch",
+            format!(
+                "The assistant wants to change {} ({} hunks, +{} -{}):\n\n{}",
+                record.file.display(),
+                record.hunks,
+                record.added,
+                record.removed,
+                preview(&record.diff)
+            ),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        // 제안 이후 파일이 바뀌었으면 다시 제안하도록 합니다
+        let original = read_optional(&record.file).await?;
+        if original.as_deref().map(hash) != record.original_hash {
+            return Err(anyhow::anyhow!(
+                "{} changed since the patch was proposed; propose it again",
+                record.file.display()
+            ));
+        }
+        let patched = apply_hunks(
+            original.as_deref().unwrap_or_default(),
+            &parse_diff(&record.diff)?,
+        )?;
+        if let Some(original) = &original {
+            tokio::fs::create_dir_all(&self.dir).await?;
+            tokio::fs::write(self.backup_path(id), original).await?;
+        }
+        write_atomic(&record.file, &patched).await?;
+
+        record.status = PatchStatus::Applied;
+        self.save(&record).await?;
+        println!("Applied patch {} to {}", id, record.file.display());
+        Ok(record)
+    }
+
+    /// Restore the file as it was before an applied patch
+    pub async fn rollback(&self, id: &str) -> Result<PatchRecord> {
+        let _guard = self.lock.lock().await;
+        let mut record = self.load(id).await?;
+        if record.status != PatchStatus::Applied {
+            return Err(anyhow::anyhow!("Patch '{}' is not applied", id));
+        }
+        let current = read_optional(&record.file).await?;
+        if current.as_deref().map(hash) != Some(record.patched_hash.clone()) {
+            return Err(anyhow::anyhow!(
+                "{} changed after the patch was applied; refusing to overwrite it",
+                record.file.display()
+            ));
+        }
+        ask_approval(
+            "patch",
+            format!(
+                "The assistant wants to undo its patch to {}. Allow?",
+                record.file.display()
+            ),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        let backup = self.backup_path(id);
+        match record.original_hash {
+            Some(_) => {
+                let original = tokio::fs::read_to_string(&backup).await?;
+                write_atomic(&record.file, &original).await?;
+                let _ = tokio::fs::remove_file(&backup).await;
+            }
+            // 패치가 새로 만든 파일이면 삭제합니다
+            None => tokio::fs::remove_file(&record.file).await?,
+        }
+
+        record.status = PatchStatus::RolledBack;
+        self.save(&record).await?;
+        println!("Rolled back patch {} on {}", id, record.file.display());
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patch(original: &str, diff: &str) -> Result<String> {
+        apply_hunks(original, &parse_diff(diff)?)
+    }
+
+    #[test]
+    fn keeps_crlf_line_endings() {
+        let diff = "--- a/f\n+++ b/f\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n";
+        assert_eq!(patch("a\r\nb\r\nc\r\n", diff).unwrap(), "a\r\nB\r\nc\r\n");
+        // diff 자체가 CRLF여도 같은 결과입니다
+        let crlf_diff = diff.replace('\n', "\r\n");
+        assert_eq!(
+            patch("a\r\nb\r\nc\r\n", &crlf_diff).unwrap(),
+            "a\r\nB\r\nc\r\n"
+        );
+    }
+
+    #[test]
+    fn honours_missing_newline_markers() {
+        let keep = "@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+B\n\\ No newline at end of file\n";
+        assert_eq!(patch("a\nb", keep).unwrap(), "a\nB");
+
+        let add = "@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+b\n";
+        assert_eq!(patch("a\nb", add).unwrap(), "a\nb\n");
+
+        let remove = "@@ -1,2 +1,2 @@\n a\n-b\n+b\n\\ No newline at end of file\n";
+        assert_eq!(patch("a\nb\n", remove).unwrap(), "a\nb");
+    }
+
+    #[test]
+    fn creates_new_files() {
+        let diff = "--- /dev/null\n+++ b/new.txt\n@@ -0,0 +1,2 @@\n+x\n+y\n";
+        assert_eq!(patch("", diff).unwrap(), "x\ny\n");
+    }
+
+    #[test]
+    fn finds_hunks_at_an_offset() {
+        let diff = "@@ -2,3 +2,3 @@\n b\n-c\n+C\n d\n";
+        let original = "new1\nnew2\nnew3\na\nb\nc\nd\ne\n";
+        assert_eq!(
+            patch(original, diff).unwrap(),
+            "new1\nnew2\nnew3\na\nb\nC\nd\ne\n"
+        );
+    }
+
+    #[test]
+    fn rejects_mismatched_context() {
+        let diff = "@@ -1,2 +1,2 @@\n a\n-x\n+y\n";
+        let error = patch("a\nb\n", diff).unwrap_err().to_string();
+        assert!(error.contains("does not match"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_multi_file_diffs() {
+        let diff = "--- a/one\n+++ b/one\n@@ -1 +1 @@\n-a\n+b\n--- a/two\n+++ b/two\n@@ -1 +1 @@\n-c\n+d\n";
+        let error = parse_diff(diff).unwrap_err().to_string();
+        assert!(error.contains("more than one file"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_truncated_hunks() {
+        let error = parse_diff("@@ -1,3 +1,3 @@\n a\n-b\n")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("truncated"), "{}", error);
+
+        let error = parse_diff("@@ -1,3 +1,3 @@\n a\ngarbage\n")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("ends early"), "{}", error);
+
+        let error = parse_diff("@@ -1 +1 @@\n-a\n+b\n+c\n")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("more lines than"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_diffs_without_hunks() {
+        assert!(parse_diff("--- a/f\n+++ b/f\n").is_err());
+        assert!(parse_diff("@@ -x +1 @@\n").is_err());
+    }
+}
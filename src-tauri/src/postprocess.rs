@@ -0,0 +1,258 @@
+use anyhow::Result;
+use futures::future::BoxFuture;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::RwLock;
+
+use crate::config_versions;
+use crate::hooks::{ToolCall, ToolHook};
+use crate::mcp::ToolCallResult;
+use crate::redact;
+
+// 토큰 수는 문자 4개를 1토큰으로 어림합니다
+const CHARS_PER_TOKEN: usize = 4;
+
+fn any() -> String {
+    "*".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostProcessRule {
+    /// Server name, or `*` for every server
+    #[serde(default = "any")]
+    pub server: String,
+    /// Tool name, or `*` for every tool of the server
+    #[serde(default = "any")]
+    pub tool: String,
+    #[serde(default)]
+    pub html_to_markdown: bool,
+    /// Collapse runs of spaces and blank lines
+    #[serde(default)]
+    pub collapse_whitespace: bool,
+    /// Regular expressions whose matches are replaced with `[REDACTED]`
+    #[serde(default)]
+    pub redact_patterns: Vec<String>,
+    /// Approximate token budget for the text content (4 characters per token)
+    #[serde(default)]
+    pub max_tokens: Option<usize>,
+}
+
+impl PostProcessRule {
+    fn matches(&self, server_name: &str, tool_name: &str) -> bool {
+        (self.server == "*" || self.server == server_name)
+            && (self.tool == "*" || self.tool == tool_name)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PostProcessSettings {
+    /// The first rule matching a call is applied
+    #[serde(default)]
+    pub rules: Vec<PostProcessRule>,
+}
+
+struct CompiledRule {
+    rule: PostProcessRule,
+    redactions: Vec<Regex>,
+}
+
+fn compile(settings: &PostProcessSettings) -> Result<Vec<CompiledRule>> {
+    settings
+        .rules
+        .iter()
+        .map(|rule| {
+            let redactions = rule
+                .redact_patterns
+                .iter()
+                .map(|pattern| {
+                    Regex::new(pattern).map_err(|e| {
+                        anyhow::anyhow!(
+                            "Invalid redaction pattern for {}/{}: {}",
+                            rule.server,
+                            rule.tool,
+                            e
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(CompiledRule {
+                rule: rule.clone(),
+                redactions,
+            })
+        })
+        .collect()
+}
+
+fn looks_like_html(text: &str) -> bool {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(r"(?i)<(!doctype|html|head|body|div|p|table|span|a|ul|h[1-6])[\s>]").unwrap()
+    });
+    text.trim_start().starts_with('<') && re.is_match(text)
+}
+
+/// html2md는 script/style 내용을 그대로 남기므로 먼저 제거합니다
+fn html_to_markdown(html: &str) -> String {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(r"(?is)<(script|style|noscript|svg)\b.*?</(script|style|noscript|svg)>").unwrap()
+    });
+    html2md::parse_html(&re.replace_all(html, ""))
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    static SPACES: OnceLock<Regex> = OnceLock::new();
+    static BLANK_LINES: OnceLock<Regex> = OnceLock::new();
+    let spaces = SPACES.get_or_init(|| Regex::new(r"[ \t\u{a0}]+").unwrap());
+    let blank_lines = BLANK_LINES.get_or_init(|| Regex::new(r"\n{3,}").unwrap());
+    let lines: Vec<String> = text
+        .lines()
+        .map(|line| spaces.replace_all(line, " ").trim().to_string())
+        .collect();
+    blank_lines
+        .replace_all(&lines.join("\n"), "\n\n")
+        .trim()
+        .to_string()
+}
+
+/// Rewrites tool output according to per-tool rules before it reaches the model
+pub struct PostProcessor {
+    settings_path: PathBuf,
+    rules: RwLock<Option<Arc<Vec<CompiledRule>>>>,
+}
+
+impl PostProcessor {
+    pub fn new(settings_path: PathBuf) -> Self {
+        Self {
+            settings_path,
+            rules: RwLock::new(None),
+        }
+    }
+
+    /// Forget the cached copy so the next read picks up the file on disk
+    pub async fn reload(&self) {
+        *self.rules.write().await = None;
+    }
+
+    pub async fn settings(&self) -> PostProcessSettings {
+        tokio::fs::read_to_string(&self.settings_path)
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub async fn save_settings(&self, settings: &PostProcessSettings) -> Result<()> {
+        let compiled = compile(settings)?;
+        config_versions::write(&self.settings_path, serde_json::to_string_pretty(settings)?)
+            .await?;
+        *self.rules.write().await = Some(Arc::new(compiled));
+        Ok(())
+    }
+
+    async fn rules(&self) -> Arc<Vec<CompiledRule>> {
+        if let Some(rules) = self.rules.read().await.as_ref() {
+            return rules.clone();
+        }
+        let rules = Arc::new(compile(&self.settings().await).unwrap_or_else(|e| {
+            eprintln!("❌ Invalid post-processing settings, ignoring them: {}", e);
+            Vec::new()
+        }));
+        *self.rules.write().await = Some(rules.clone());
+        rules
+    }
+
+    /// Apply the first matching rule to the text content of a successful result
+    pub async fn apply(&self, server_name: &str, tool_name: &str, result: &mut ToolCallResult) {
+        let rules = self.rules().await;
+        let Some(compiled) = rules
+            .iter()
+            .find(|compiled| compiled.rule.matches(server_name, tool_name))
+        else {
+            return;
+        };
+        let Some(content) = result
+            .result
+            .as_mut()
+            .and_then(|value| value.get_mut("content"))
+            .and_then(|content| content.as_array_mut())
+        else {
+            return;
+        };
+
+        let rule = &compiled.rule;
+        let mut budget = rule.max_tokens.map(|tokens| tokens * CHARS_PER_TOKEN);
+        let mut truncated = 0;
+        content.retain_mut(|item| {
+            if item.get("type").and_then(|t| t.as_str()) != Some("text") {
+                return true;
+            }
+            let Some(mut text) = item
+                .get("text")
+                .and_then(|t| t.as_str())
+                .map(str::to_string)
+            else {
+                return true;
+            };
+            if rule.html_to_markdown && looks_like_html(&text) {
+                text = html_to_markdown(&text);
+            }
+            for regex in &compiled.redactions {
+                text = regex.replace_all(&text, redact::MASK).into_owned();
+            }
+            if rule.collapse_whitespace {
+                text = collapse_whitespace(&text);
+            }
+            if let Some(remaining) = budget.as_mut() {
+                // 예산을 다 쓴 뒤의 텍스트 항목은 통째로 뺍니다
+                if *remaining == 0 {
+                    truncated += text.chars().count();
+                    return false;
+                }
+                let length = text.chars().count();
+                if length > *remaining {
+                    let cut = text
+                        .char_indices()
+                        .nth(*remaining)
+                        .map_or(text.len(), |(index, _)| index);
+                    truncated += length - *remaining;
+                    text.truncate(cut);
+                    *remaining = 0;
+                } else {
+                    *remaining -= length;
+                }
+            }
+            item["text"] = serde_json::Value::String(text);
+            true
+        });
+        if truncated > 0 {
+            content.push(serde_json::json!({
+                "type": "text",
+                "text": format!(
+                    "[... {} characters truncated to fit the {} token limit]",
+                    truncated,
+                    rule.max_tokens.unwrap_or_default()
+                ),
+            }));
+        }
+    }
+}
+
+/// Runs [`PostProcessor::apply`] on every result, including cached ones
+pub struct PostProcessHook(pub &'static PostProcessor);
+
+impl ToolHook for PostProcessHook {
+    fn name(&self) -> &str {
+        "post-process"
+    }
+
+    fn after_call<'a>(
+        &'a self,
+        call: &'a ToolCall,
+        result: &'a mut ToolCallResult,
+    ) -> BoxFuture<'a, ()> {
+        Box::pin(self.0.apply(&call.server_name, &call.tool_name, result))
+    }
+}
@@ -0,0 +1,253 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+
+use crate::config_versions;
+use crate::events;
+
+// 전원 상태를 다시 읽는 주기
+const POLL_INTERVAL_SECS: u64 = 60;
+
+fn default_low_battery_percent() -> u8 {
+    20
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PowerMode {
+    /// Never pause background work
+    AlwaysRun,
+    /// Pause whenever the machine runs on battery
+    PauseOnBattery,
+    /// Pause when the battery is low or the OS low-power mode is on
+    #[default]
+    PauseOnLowBattery,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerPolicy {
+    #[serde(default)]
+    pub mode: PowerMode,
+    /// Battery level at or below which `pause-on-low-battery` pauses, while unplugged
+    #[serde(default = "default_low_battery_percent")]
+    pub low_battery_percent: u8,
+}
+
+impl Default for PowerPolicy {
+    fn default() -> Self {
+        Self {
+            mode: PowerMode::default(),
+            low_battery_percent: default_low_battery_percent(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PowerState {
+    pub on_battery: bool,
+    /// None on machines without a battery
+    pub battery_percent: Option<u8>,
+    pub low_power_mode: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PowerStatus {
+    pub policy: PowerPolicy,
+    pub state: PowerState,
+    /// Scheduled backups, syncs, refreshes and cleanups are on hold
+    pub paused: bool,
+}
+
+#[derive(Default)]
+struct Power {
+    policy: PowerPolicy,
+    policy_path: Option<PathBuf>,
+    state: PowerState,
+}
+
+static POWER: OnceLock<RwLock<Power>> = OnceLock::new();
+
+fn power() -> &'static RwLock<Power> {
+    POWER.get_or_init(|| RwLock::new(Power::default()))
+}
+
+fn should_pause(policy: &PowerPolicy, state: &PowerState) -> bool {
+    match policy.mode {
+        PowerMode::AlwaysRun => false,
+        PowerMode::PauseOnBattery => state.on_battery,
+        PowerMode::PauseOnLowBattery => {
+            state.low_power_mode
+                || (state.on_battery
+                    && state
+                        .battery_percent
+                        .is_some_and(|percent| percent <= policy.low_battery_percent))
+        }
+    }
+}
+
+/// Background loops skip their work while this is true
+pub fn paused() -> bool {
+    let power = power().read().unwrap_or_else(|e| e.into_inner());
+    should_pause(&power.policy, &power.state)
+}
+
+pub fn status() -> PowerStatus {
+    let power = power().read().unwrap_or_else(|e| e.into_inner());
+    PowerStatus {
+        policy: power.policy.clone(),
+        state: power.state.clone(),
+        paused: should_pause(&power.policy, &power.state),
+    }
+}
+
+/// Load the persisted policy, ignoring a missing file
+pub fn load_policy(path: &Path) -> Result<()> {
+    let policy = match std::fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => PowerPolicy::default(),
+        Err(e) => return Err(e.into()),
+    };
+    let mut power = power().write().unwrap_or_else(|e| e.into_inner());
+    power.policy = policy;
+    power.policy_path = Some(path.to_path_buf());
+    Ok(())
+}
+
+pub fn set_policy(policy: PowerPolicy) -> Result<PowerStatus> {
+    if policy.low_battery_percent > 100 {
+        return Err(anyhow::anyhow!("low_battery_percent must be at most 100"));
+    }
+    let path = {
+        let mut power = power().write().unwrap_or_else(|e| e.into_inner());
+        power.policy = policy.clone();
+        power.policy_path.clone()
+    };
+    if let Some(path) = path {
+        config_versions::write_blocking(&path, &serde_json::to_string_pretty(&policy)?)?;
+    }
+    Ok(status())
+}
+
+#[cfg(target_os = "linux")]
+fn read_state() -> PowerState {
+    let mut state = PowerState::default();
+    let Ok(supplies) = std::fs::read_dir("/sys/class/power_supply") else {
+        return state;
+    };
+    let mut on_ac = false;
+    let mut levels = Vec::new();
+    for supply in supplies.flatten() {
+        let path = supply.path();
+        let read = |name: &str| {
+            std::fs::read_to_string(path.join(name))
+                .map(|value| value.trim().to_string())
+                .unwrap_or_default()
+        };
+        match read("type").as_str() {
+            "Mains" | "USB" => on_ac |= read("online") == "1",
+            // 마우스 등 주변기기 배터리는 제외합니다
+            "Battery" if read("scope") != "Device" => {
+                if let Ok(capacity) = read("capacity").parse::<u8>() {
+                    levels.push(capacity);
+                }
+                if read("status") == "Discharging" {
+                    state.on_battery = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    state.on_battery &= !on_ac;
+    if !levels.is_empty() {
+        state.battery_percent =
+            Some((levels.iter().map(|&l| l as u32).sum::<u32>() / levels.len() as u32) as u8);
+    }
+    state.low_power_mode = std::fs::read_to_string("/sys/firmware/acpi/platform_profile")
+        .is_ok_and(|profile| profile.trim() == "low-power");
+    state
+}
+
+#[cfg(target_os = "macos")]
+fn read_state() -> PowerState {
+    let run = |args: &[&str]| {
+        std::process::Command::new("pmset")
+            .args(args)
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+            .unwrap_or_default()
+    };
+    let batt = run(&["-g", "batt"]);
+    PowerState {
+        on_battery: batt.contains("'Battery Power'"),
+        // 예: "-InternalBattery-0 (id=...)	87%; discharging; ..."
+        battery_percent: batt
+            .split_whitespace()
+            .find_map(|word| word.strip_suffix("%;")?.parse().ok()),
+        low_power_mode: run(&["-g"])
+            .lines()
+            .any(|line| line.split_whitespace().collect::<Vec<_>>() == ["lowpowermode", "1"]),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn read_state() -> PowerState {
+    #[repr(C)]
+    #[derive(Default)]
+    struct SystemPowerStatus {
+        ac_line_status: u8,
+        battery_flag: u8,
+        battery_life_percent: u8,
+        system_status_flag: u8,
+        _battery_life_time: u32,
+        _battery_full_life_time: u32,
+    }
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+    }
+
+    let mut status = SystemPowerStatus::default();
+    // SAFETY: the struct matches SYSTEM_POWER_STATUS and outlives the call
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return PowerState::default();
+    }
+    // 128은 배터리 없음, 255는 알 수 없음
+    let has_battery = status.battery_flag & 128 == 0 && status.battery_flag != 255;
+    PowerState {
+        on_battery: has_battery && status.ac_line_status == 0,
+        battery_percent: (has_battery && status.battery_life_percent <= 100)
+            .then_some(status.battery_life_percent),
+        // 절전 모드(Battery saver)
+        low_power_mode: status.system_status_flag == 1,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn read_state() -> PowerState {
+    PowerState::default()
+}
+
+/// Track the power state for as long as the app runs, announcing pauses and resumes
+/// with a `power-status` event
+pub async fn run_monitor() {
+    let mut was_paused = None;
+    loop {
+        match tokio::task::spawn_blocking(read_state).await {
+            Ok(state) => power().write().unwrap_or_else(|e| e.into_inner()).state = state,
+            Err(e) => eprintln!("❌ Failed to read the power state: {}", e),
+        }
+        let status = status();
+        if was_paused != Some(status.paused) {
+            if was_paused.is_some() {
+                println!(
+                    "{} background work",
+                    if status.paused { "Pausing" } else { "Resuming" }
+                );
+            }
+            was_paused = Some(status.paused);
+            events::emit("power-status", status);
+        }
+        tokio::time::sleep(tokio::time::Duration::from_secs(POLL_INTERVAL_SECS)).await;
+    }
+}
@@ -0,0 +1,314 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+
+use crate::config_versions;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptVariableType {
+    #[default]
+    String,
+    Number,
+    Boolean,
+    List,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptVariable {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub var_type: PromptVariableType,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub body: String,
+    #[serde(default)]
+    pub variables: Vec<PromptVariable>,
+    /// Assigned by the library on save, starting at 1
+    #[serde(default)]
+    pub version: u32,
+    /// Unix timestamp (seconds) of when this version was saved
+    #[serde(default)]
+    pub updated_at: u64,
+}
+
+/// Named prompt templates with full version history, persisted as JSON in app-data
+pub struct PromptLibrary {
+    path: PathBuf,
+    templates: Mutex<Option<HashMap<String, Vec<PromptTemplate>>>>,
+}
+
+impl PromptLibrary {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            templates: Mutex::new(None),
+        }
+    }
+
+    /// Forget the cached copy so the next read picks up the file on disk
+    pub async fn reload(&self) {
+        *self.templates.lock().await = None;
+    }
+
+    async fn load(&self) -> Result<HashMap<String, Vec<PromptTemplate>>> {
+        match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn loaded<'a>(
+        &self,
+        cache: &'a mut Option<HashMap<String, Vec<PromptTemplate>>>,
+    ) -> Result<&'a mut HashMap<String, Vec<PromptTemplate>>> {
+        if cache.is_none() {
+            *cache = Some(self.load().await?);
+        }
+        Ok(cache.get_or_insert_with(HashMap::new))
+    }
+
+    async fn persist(&self, templates: &HashMap<String, Vec<PromptTemplate>>) -> Result<()> {
+        config_versions::write(&self.path, serde_json::to_string_pretty(templates)?).await?;
+        Ok(())
+    }
+
+    /// 최신 버전의 템플릿 목록을 반환합니다
+    pub async fn list(&self) -> Result<Vec<PromptTemplate>> {
+        let mut guard = self.templates.lock().await;
+        let templates = self.loaded(&mut guard).await?;
+        let mut latest: Vec<PromptTemplate> = templates
+            .values()
+            .filter_map(|versions| versions.last().cloned())
+            .collect();
+        latest.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(latest)
+    }
+
+    /// Get a template by name, either the latest or a specific version
+    pub async fn get(&self, name: &str, version: Option<u32>) -> Result<PromptTemplate> {
+        let mut guard = self.templates.lock().await;
+        let versions = self
+            .loaded(&mut guard)
+            .await?
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("Prompt template '{}' not found", name))?;
+
+        match version {
+            Some(v) => versions
+                .iter()
+                .find(|t| t.version == v)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Prompt template '{}' has no version {}", name, v)),
+            None => versions
+                .last()
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Prompt template '{}' not found", name)),
+        }
+    }
+
+    /// Save a template as a new version, keeping previous versions
+    pub async fn save(&self, mut template: PromptTemplate) -> Result<PromptTemplate> {
+        if template.name.trim().is_empty() {
+            return Err(anyhow::anyhow!("Prompt template name must not be empty"));
+        }
+        for variable in &template.variables {
+            if let Some(default) = &variable.default {
+                check_variable_type(variable, default)?;
+            }
+        }
+
+        let mut guard = self.templates.lock().await;
+        let templates = self.loaded(&mut guard).await?;
+        let versions = templates.entry(template.name.clone()).or_default();
+
+        template.version = versions.last().map(|t| t.version + 1).unwrap_or(1);
+        template.updated_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        versions.push(template.clone());
+
+        self.persist(templates).await?;
+        println!(
+            "Saved prompt template '{}' (version {})",
+            template.name, template.version
+        );
+        Ok(template)
+    }
+
+    /// Delete a template and all its versions
+    pub async fn delete(&self, name: &str) -> Result<()> {
+        let mut guard = self.templates.lock().await;
+        let templates = self.loaded(&mut guard).await?;
+        if templates.remove(name).is_none() {
+            return Err(anyhow::anyhow!("Prompt template '{}' not found", name));
+        }
+        self.persist(templates).await
+    }
+}
+
+fn check_variable_type(variable: &PromptVariable, value: &serde_json::Value) -> Result<()> {
+    let ok = match variable.var_type {
+        PromptVariableType::String => value.is_string(),
+        PromptVariableType::Number => value.is_number(),
+        PromptVariableType::Boolean => value.is_boolean(),
+        PromptVariableType::List => value.is_array(),
+    };
+    if ok {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "Variable '{}' expects type {:?}, got {}",
+            variable.name,
+            variable.var_type,
+            value
+        ))
+    }
+}
+
+fn value_to_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .map(value_to_text)
+            .collect::<Vec<_>>()
+            .join(", "),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Values every template can use without declaring them: `date`, `time`,
+/// `connected_servers` and `workspace_roots` (the sandbox's approved directories).
+pub fn render_context(
+    connected_servers: Vec<String>,
+    workspace_roots: &[PathBuf],
+) -> HashMap<String, serde_json::Value> {
+    let now = chrono::Local::now();
+    let roots: Vec<String> = workspace_roots
+        .iter()
+        .map(|root| root.display().to_string())
+        .collect();
+    HashMap::from([
+        (
+            "date".to_string(),
+            serde_json::Value::String(now.format("%Y-%m-%d").to_string()),
+        ),
+        (
+            "time".to_string(),
+            serde_json::Value::String(now.format("%H:%M").to_string()),
+        ),
+        (
+            "connected_servers".to_string(),
+            serde_json::json!(connected_servers),
+        ),
+        ("workspace_roots".to_string(), serde_json::json!(roots)),
+    ])
+}
+
+/// Render a template, substituting `{{name}}` placeholders.
+///
+/// Declared variables are type-checked and fall back to their defaults; any other
+/// placeholder is looked up in `context` (date, connected servers, ...).
+pub fn render(
+    template: &PromptTemplate,
+    variables: &serde_json::Map<String, serde_json::Value>,
+    context: &HashMap<String, serde_json::Value>,
+) -> Result<String> {
+    let mut values: HashMap<&str, &serde_json::Value> = HashMap::new();
+    for (key, value) in context {
+        values.insert(key.as_str(), value);
+    }
+    for variable in &template.variables {
+        match variables.get(&variable.name).or(variable.default.as_ref()) {
+            Some(value) => {
+                check_variable_type(variable, value)?;
+                values.insert(variable.name.as_str(), value);
+            }
+            None if variable.required => {
+                return Err(anyhow::anyhow!(
+                    "Missing required variable '{}' for template '{}'",
+                    variable.name,
+                    template.name
+                ));
+            }
+            None => {}
+        }
+    }
+
+    let mut output = String::with_capacity(template.body.len());
+    let mut rest = template.body.as_str();
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or_else(|| {
+            anyhow::anyhow!("Unclosed placeholder in template '{}'", template.name)
+        })?;
+        let key = after[..end].trim();
+        if let Some(value) = values.get(key) {
+            output.push_str(&value_to_text(value));
+        } else if !template.variables.iter().any(|v| v.name == key) {
+            return Err(anyhow::anyhow!(
+                "Unknown placeholder '{{{{{}}}}}' in template '{}'",
+                key,
+                template.name
+            ));
+        }
+        rest = &after[end + 2..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(body: &str) -> PromptTemplate {
+        PromptTemplate {
+            name: "test".to_string(),
+            description: None,
+            body: body.to_string(),
+            variables: Vec::new(),
+            version: 1,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn renders_workspace_roots_from_context() {
+        let roots = [PathBuf::from("/work/app"), PathBuf::from("/work/docs")];
+        let context = render_context(vec!["files".to_string()], &roots);
+        let rendered = render(
+            &template("Roots: {{workspace_roots}}; servers: {{ connected_servers }}"),
+            &serde_json::Map::new(),
+            &context,
+        )
+        .unwrap();
+        assert_eq!(rendered, "Roots: /work/app, /work/docs; servers: files");
+    }
+
+    #[test]
+    fn unknown_placeholders_are_rejected() {
+        let context = render_context(Vec::new(), &[]);
+        assert!(render(&template("{{missing}}"), &serde_json::Map::new(), &context).is_err());
+    }
+}
@@ -0,0 +1,242 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::{Mutex, Notify, Semaphore, SemaphorePermit};
+use tokio::time::{Duration, Instant};
+
+use crate::events;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuedCall {
+    pub server_name: String,
+    pub tool_name: String,
+    /// Number of calls waiting ahead of this one, 0-based
+    pub position: usize,
+}
+
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(requests_per_minute: u32) -> Self {
+        let capacity = requests_per_minute.max(1) as f64;
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_sec: capacity / 60.0,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Take a token, or return how long to wait for the next one
+    fn try_take(&mut self) -> Result<(), Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.refill_per_sec,
+            ))
+        }
+    }
+}
+
+/// Per-server concurrency cap and token-bucket rate limit for tool calls
+pub struct CallLimiter {
+    semaphore: Option<Semaphore>,
+    bucket: Option<Mutex<TokenBucket>>,
+    // 대기 중인 호출의 번호 (도착 순서)
+    waiting: std::sync::Mutex<Vec<u64>>,
+    next_ticket: AtomicU64,
+    // 대기 중인 호출 하나가 빠질 때마다 알림
+    admitted: Notify,
+}
+
+pub struct CallPermit<'a> {
+    _permit: Option<SemaphorePermit<'a>>,
+}
+
+impl CallLimiter {
+    pub fn new(max_concurrent_calls: Option<usize>, requests_per_minute: Option<u32>) -> Self {
+        Self {
+            semaphore: max_concurrent_calls.map(|max| Semaphore::new(max.max(1))),
+            bucket: requests_per_minute.map(|rpm| Mutex::new(TokenBucket::new(rpm))),
+            waiting: std::sync::Mutex::new(Vec::new()),
+            next_ticket: AtomicU64::new(0),
+            admitted: Notify::new(),
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.semaphore.is_none() && self.bucket.is_none()
+    }
+
+    /// 호출 가능할 때까지 대기합니다. 대기해야 하면 큐 위치 이벤트를 보내고,
+    /// 앞선 호출이 빠질 때마다 바뀐 위치를 다시 보냅니다
+    pub async fn acquire(&self, server_name: &str, tool_name: &str) -> CallPermit<'_> {
+        let must_wait = self
+            .semaphore
+            .as_ref()
+            .is_some_and(|s| s.available_permits() == 0)
+            || self.waiting_count() > 0;
+        // future가 취소되어도 Drop에서 대기열에서 빠집니다
+        let waiting = WaitingCall::enter(self);
+        let notify = |position| {
+            events::emit(
+                "mcp-tool-queued",
+                QueuedCall {
+                    server_name: server_name.to_string(),
+                    tool_name: tool_name.to_string(),
+                    position,
+                },
+            )
+        };
+        let mut position = waiting.position();
+        if must_wait {
+            notify(position);
+        }
+
+        let admission = self.admit();
+        tokio::pin!(admission);
+        loop {
+            let admitted = self.admitted.notified();
+            tokio::select! {
+                permit = &mut admission => return CallPermit { _permit: permit },
+                _ = admitted => {
+                    let current = waiting.position();
+                    if must_wait && current < position {
+                        notify(current);
+                    }
+                    position = current;
+                }
+            }
+        }
+    }
+
+    async fn admit(&self) -> Option<SemaphorePermit<'_>> {
+        let permit = match &self.semaphore {
+            Some(semaphore) => semaphore.acquire().await.ok(),
+            None => None,
+        };
+        if let Some(bucket) = &self.bucket {
+            loop {
+                let wait = match bucket.lock().await.try_take() {
+                    Ok(()) => break,
+                    Err(wait) => wait,
+                };
+                tokio::time::sleep(wait).await;
+            }
+        }
+        permit
+    }
+
+    fn waiting_count(&self) -> usize {
+        self.waiting.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+/// A call's place in the wait queue, released when admitted or cancelled
+struct WaitingCall<'a> {
+    limiter: &'a CallLimiter,
+    ticket: u64,
+}
+
+impl<'a> WaitingCall<'a> {
+    fn enter(limiter: &'a CallLimiter) -> Self {
+        let ticket = limiter.next_ticket.fetch_add(1, Ordering::SeqCst);
+        limiter
+            .waiting
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(ticket);
+        Self { limiter, ticket }
+    }
+
+    /// Number of calls that arrived earlier and are still waiting
+    fn position(&self) -> usize {
+        self.limiter
+            .waiting
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|ticket| **ticket < self.ticket)
+            .count()
+    }
+}
+
+impl Drop for WaitingCall<'_> {
+    fn drop(&mut self) {
+        self.limiter
+            .waiting
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|ticket| *ticket != self.ticket);
+        self.limiter.admitted.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn cancelled_waiters_leave_the_queue() {
+        let limiter = CallLimiter::new(Some(1), None);
+        let held = limiter.acquire("server", "tool").await;
+        let cancelled =
+            tokio::time::timeout(Duration::from_millis(20), limiter.acquire("server", "tool"))
+                .await;
+        assert!(cancelled.is_err());
+        assert_eq!(limiter.waiting_count(), 0);
+
+        drop(held);
+        let _permit = limiter.acquire("server", "tool").await;
+        assert_eq!(limiter.waiting_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn positions_advance_as_earlier_calls_are_admitted() {
+        let limiter = CallLimiter::new(Some(1), None);
+        let first = WaitingCall::enter(&limiter);
+        let second = WaitingCall::enter(&limiter);
+        let third = WaitingCall::enter(&limiter);
+        assert_eq!(
+            (first.position(), second.position(), third.position()),
+            (0, 1, 2)
+        );
+
+        drop(first);
+        assert_eq!((second.position(), third.position()), (0, 1));
+        drop(second);
+        assert_eq!(third.position(), 0);
+    }
+
+    #[tokio::test]
+    async fn waiters_are_admitted_after_the_permit_is_released() {
+        let limiter = std::sync::Arc::new(CallLimiter::new(Some(1), None));
+        let held = limiter.acquire("server", "tool").await;
+        let waiter = {
+            let limiter = limiter.clone();
+            tokio::spawn(async move {
+                let _permit = limiter.acquire("server", "tool").await;
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(limiter.waiting_count(), 1);
+
+        drop(held);
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter admitted")
+            .unwrap();
+        assert_eq!(limiter.waiting_count(), 0);
+    }
+}
@@ -0,0 +1,363 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use tokio::sync::RwLock;
+
+use crate::config_versions;
+use crate::events;
+use crate::mcp::MCPServerConfig;
+use crate::power;
+
+const FETCH_TIMEOUT_SECS: u64 = 30;
+// 백그라운드 루프가 갱신할 소스를 확인하는 주기
+const CHECK_INTERVAL_SECS: u64 = 30;
+const MIN_REFRESH_INTERVAL_SECS: u64 = 60;
+
+fn default_refresh_interval_secs() -> u64 {
+    3600
+}
+
+/// A URL serving a shared `mcpServers` (or `servers`) document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteConfigSource {
+    pub url: String,
+    #[serde(default = "default_refresh_interval_secs")]
+    pub refresh_interval_secs: u64,
+}
+
+/// Servers fetched from one source. The app never edits them; they change only when the
+/// source does.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ManagedSection {
+    pub url: String,
+    pub fetched_at: Option<String>,
+    /// Unix seconds of the last attempt, successful or not
+    #[serde(default)]
+    pub checked_at: u64,
+    pub servers: Vec<MCPServerConfig>,
+    /// Servers the last refresh dropped from the source
+    #[serde(default)]
+    pub removed: Vec<String>,
+    /// Error of the last attempt; the previous servers are kept
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DriftKind {
+    /// Managed but not running
+    NotRunning,
+    /// Running with settings that differ from the managed definition
+    Modified,
+    /// Still running although the source no longer lists it
+    Removed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerDrift {
+    pub server_name: String,
+    pub source: String,
+    pub kind: DriftKind,
+    /// Top-level config fields that differ, for `modified`
+    pub fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteConfigState {
+    pub sources: Vec<RemoteConfigSource>,
+    pub sections: Vec<ManagedSection>,
+}
+
+/// Emitted as `managed-config-changed` when a refresh changes a source's servers
+#[derive(Debug, Clone, Serialize)]
+pub struct ManagedConfigChange {
+    pub url: String,
+    pub servers: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Config comparable with a running server's; volumes added for workspace roots are
+/// dropped because they are filled in at start
+fn comparable(config: &MCPServerConfig) -> BTreeMap<String, serde_json::Value> {
+    let mut value = serde_json::to_value(config).unwrap_or_default();
+    if let Some(container) = value.get_mut("container").and_then(|c| c.as_object_mut()) {
+        if container.get("mount_workspace_roots") == Some(&serde_json::Value::Bool(true)) {
+            container.remove("volumes");
+        }
+    }
+    match value {
+        serde_json::Value::Object(map) => map.into_iter().collect(),
+        _ => BTreeMap::new(),
+    }
+}
+
+fn changed_fields(managed: &MCPServerConfig, running: &MCPServerConfig) -> Vec<String> {
+    let (managed, running) = (comparable(managed), comparable(running));
+    managed
+        .iter()
+        .filter(|(key, value)| running.get(*key) != Some(*value))
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+async fn fetch(url: &str) -> Result<Vec<MCPServerConfig>> {
+    let response = reqwest::Client::new()
+        .get(url)
+        .timeout(std::time::Duration::from_secs(FETCH_TIMEOUT_SECS))
+        .send()
+        .await?
+        .error_for_status()?;
+    let document: serde_json::Value = response.json().await?;
+    crate::parse_server_configs(&document).map_err(anyhow::Error::msg)
+}
+
+/// Shared server configs pulled from URLs, kept as read-only managed sections
+pub struct RemoteConfigs {
+    sources_path: PathBuf,
+    cache_path: PathBuf,
+    sources: RwLock<Option<Vec<RemoteConfigSource>>>,
+    sections: RwLock<Option<BTreeMap<String, ManagedSection>>>,
+}
+
+impl RemoteConfigs {
+    pub fn new(sources_path: PathBuf, cache_path: PathBuf) -> Self {
+        Self {
+            sources_path,
+            cache_path,
+            sources: RwLock::new(None),
+            sections: RwLock::new(None),
+        }
+    }
+
+    /// Forget the cached copy so the next read picks up the file on disk
+    pub async fn reload(&self) {
+        *self.sources.write().await = None;
+    }
+
+    pub async fn sources(&self) -> Vec<RemoteConfigSource> {
+        if let Some(sources) = self.sources.read().await.as_ref() {
+            return sources.clone();
+        }
+        let sources: Vec<RemoteConfigSource> = tokio::fs::read_to_string(&self.sources_path)
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        *self.sources.write().await = Some(sources.clone());
+        sources
+    }
+
+    async fn save_sources(&self, sources: Vec<RemoteConfigSource>) -> Result<()> {
+        config_versions::write(&self.sources_path, serde_json::to_string_pretty(&sources)?).await?;
+        *self.sources.write().await = Some(sources);
+        Ok(())
+    }
+
+    /// Last fetched sections; a cache on disk keeps them available offline
+    pub async fn sections(&self) -> BTreeMap<String, ManagedSection> {
+        if let Some(sections) = self.sections.read().await.as_ref() {
+            return sections.clone();
+        }
+        let sections: BTreeMap<String, ManagedSection> =
+            tokio::fs::read_to_string(&self.cache_path)
+                .await
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+                .unwrap_or_default();
+        *self.sections.write().await = Some(sections.clone());
+        sections
+    }
+
+    pub async fn state(&self) -> RemoteConfigState {
+        RemoteConfigState {
+            sources: self.sources().await,
+            sections: self.sections().await.into_values().collect(),
+        }
+    }
+
+    async fn store_section(&self, section: ManagedSection) -> Result<()> {
+        let mut sections = self.sections().await;
+        sections.insert(section.url.clone(), section);
+        // 가져온 결과는 사용자 설정이 아니므로 버전 관리 없이 저장합니다
+        tokio::fs::write(&self.cache_path, serde_json::to_string_pretty(&sections)?).await?;
+        *self.sections.write().await = Some(sections);
+        Ok(())
+    }
+
+    /// Register a source (or change its interval) and fetch it right away
+    pub async fn add_source(
+        &self,
+        url: &str,
+        refresh_interval_secs: u64,
+    ) -> Result<ManagedSection> {
+        let parsed = reqwest::Url::parse(url)?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(anyhow::anyhow!("Only http(s) config sources are supported"));
+        }
+        if refresh_interval_secs < MIN_REFRESH_INTERVAL_SECS {
+            return Err(anyhow::anyhow!(
+                "refresh_interval_secs must be at least {}",
+                MIN_REFRESH_INTERVAL_SECS
+            ));
+        }
+        let mut sources = self.sources().await;
+        sources.retain(|source| source.url != url);
+        sources.push(RemoteConfigSource {
+            url: url.to_string(),
+            refresh_interval_secs,
+        });
+        self.save_sources(sources).await?;
+        self.refresh(url).await
+    }
+
+    pub async fn remove_source(&self, url: &str) -> Result<()> {
+        let mut sources = self.sources().await;
+        let before = sources.len();
+        sources.retain(|source| source.url != url);
+        if sources.len() == before {
+            return Err(anyhow::anyhow!("Config source '{}' not found", url));
+        }
+        self.save_sources(sources).await?;
+
+        let mut sections = self.sections().await;
+        if sections.remove(url).is_some() {
+            tokio::fs::write(&self.cache_path, serde_json::to_string_pretty(&sections)?).await?;
+            *self.sections.write().await = Some(sections);
+        }
+        Ok(())
+    }
+
+    /// Fetch one source. A failed fetch is recorded on the section and keeps its servers.
+    pub async fn refresh(&self, url: &str) -> Result<ManagedSection> {
+        if !self.sources().await.iter().any(|source| source.url == url) {
+            return Err(anyhow::anyhow!("Config source '{}' not found", url));
+        }
+        let mut section = self.sections().await.remove(url).unwrap_or(ManagedSection {
+            url: url.to_string(),
+            ..Default::default()
+        });
+        section.checked_at = chrono::Utc::now().timestamp() as u64;
+
+        match fetch(url).await {
+            Ok(servers) => {
+                let names: HashSet<&str> = servers.iter().map(|s| s.name.as_str()).collect();
+                let removed: Vec<String> = section
+                    .servers
+                    .iter()
+                    .filter(|server| !names.contains(server.name.as_str()))
+                    .map(|server| server.name.clone())
+                    .collect();
+                let changed = serde_json::to_value(&section.servers).ok()
+                    != serde_json::to_value(&servers).ok();
+                if changed && section.fetched_at.is_some() {
+                    events::emit(
+                        "managed-config-changed",
+                        ManagedConfigChange {
+                            url: url.to_string(),
+                            servers: servers.iter().map(|s| s.name.clone()).collect(),
+                            removed: removed.clone(),
+                        },
+                    );
+                }
+                if changed {
+                    section.removed = removed;
+                }
+                section.servers = servers;
+                section.fetched_at = Some(chrono::Local::now().to_rfc3339());
+                section.error = None;
+            }
+            Err(e) => {
+                eprintln!("❌ Failed to fetch config source {}: {}", url, e);
+                section.error = Some(e.to_string());
+            }
+        }
+        self.store_section(section.clone()).await?;
+        Ok(section)
+    }
+
+    /// Servers of every source; when two sources define the same name the first one wins
+    pub async fn managed_servers(&self) -> Vec<MCPServerConfig> {
+        let sections = self.sections().await;
+        let mut seen = HashSet::new();
+        let mut servers = Vec::new();
+        for source in self.sources().await {
+            for server in sections
+                .get(&source.url)
+                .map(|section| section.servers.as_slice())
+                .unwrap_or_default()
+            {
+                if seen.insert(server.name.clone()) {
+                    servers.push(server.clone());
+                } else {
+                    eprintln!(
+                        "⚠️ Managed server '{}' from {} is already defined by another source",
+                        server.name, source.url
+                    );
+                }
+            }
+        }
+        servers
+    }
+
+    /// Compare the managed sections with the servers that are running
+    pub async fn drift(&self, running: &[MCPServerConfig]) -> Vec<ServerDrift> {
+        let sections = self.sections().await;
+        let mut drift = Vec::new();
+        for source in self.sources().await {
+            let Some(section) = sections.get(&source.url) else {
+                continue;
+            };
+            for managed in &section.servers {
+                let (kind, fields) = match running.iter().find(|r| r.name == managed.name) {
+                    None => (DriftKind::NotRunning, Vec::new()),
+                    Some(config) => {
+                        let fields = changed_fields(managed, config);
+                        if fields.is_empty() {
+                            continue;
+                        }
+                        (DriftKind::Modified, fields)
+                    }
+                };
+                drift.push(ServerDrift {
+                    server_name: managed.name.clone(),
+                    source: source.url.clone(),
+                    kind,
+                    fields,
+                });
+            }
+            for name in &section.removed {
+                if running.iter().any(|r| &r.name == name) {
+                    drift.push(ServerDrift {
+                        server_name: name.clone(),
+                        source: source.url.clone(),
+                        kind: DriftKind::Removed,
+                        fields: Vec::new(),
+                    });
+                }
+            }
+        }
+        drift
+    }
+
+    /// Refresh each source once its interval has passed, for as long as the app runs
+    pub async fn run_background(&self) {
+        loop {
+            if power::paused() {
+                tokio::time::sleep(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+                continue;
+            }
+            let now = chrono::Utc::now().timestamp() as u64;
+            let sections = self.sections().await;
+            for source in self.sources().await {
+                let checked_at = sections.get(&source.url).map_or(0, |s| s.checked_at);
+                if now.saturating_sub(checked_at) >= source.refresh_interval_secs {
+                    if let Err(e) = self.refresh(&source.url).await {
+                        eprintln!("❌ Failed to refresh config source {}: {}", source.url, e);
+                    }
+                }
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use anyhow::{Context, Result};
+use async_tungstenite::tungstenite::Message;
+use futures::channel::mpsc::{self, UnboundedSender};
+use futures::{future, Sink, SinkExt, Stream, StreamExt};
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
+use reqwest::StatusCode;
+use rmcp::model::{ErrorData, JsonRpcError, JsonRpcMessage, JsonRpcVersion2_0, RequestId};
+use rmcp::service::{RoleClient, RxJsonRpcMessage, TxJsonRpcMessage};
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Messages the client sends to a remote server
+pub type Outgoing = Pin<Box<dyn Sink<TxJsonRpcMessage<RoleClient>, Error = io::Error> + Send>>;
+/// Messages a remote server sends back
+pub type Incoming = Pin<Box<dyn Stream<Item = RxJsonRpcMessage<RoleClient>> + Send>>;
+
+const SESSION_HEADER: &str = "mcp-session-id";
+
+struct HttpSession {
+    client: reqwest::Client,
+    url: String,
+    session_id: std::sync::Mutex<Option<String>>,
+    incoming: UnboundedSender<RxJsonRpcMessage<RoleClient>>,
+}
+
+/// Connect to a server over the streamable HTTP transport: every message is POSTed
+/// to `url` and the server answers with JSON or a short event stream.
+pub fn http(url: &str) -> Result<(Outgoing, Incoming)> {
+    reqwest::Url::parse(url).with_context(|| format!("Invalid server URL: {}", url))?;
+    let (incoming, received) = mpsc::unbounded();
+    let session = Arc::new(HttpSession {
+        client: reqwest::Client::new(),
+        url: url.to_string(),
+        session_id: std::sync::Mutex::new(None),
+        incoming,
+    });
+    // 긴 도구 호출이 다른 요청을 막지 않도록 요청마다 따로 보냅니다
+    let outgoing = futures::sink::unfold(session, |session, message| async move {
+        tokio::spawn(Arc::clone(&session).deliver(message));
+        Ok::<_, io::Error>(session)
+    });
+    Ok((Box::pin(outgoing), Box::pin(received)))
+}
+
+impl HttpSession {
+    async fn deliver(self: Arc<Self>, message: TxJsonRpcMessage<RoleClient>) {
+        let request_id = match &message {
+            JsonRpcMessage::Request(request) => Some(request.id.clone()),
+            _ => None,
+        };
+        let replies = match self.post(&message).await {
+            Ok(replies) => replies,
+            Err(e) => match request_id {
+                // 응답을 기다리는 호출이 멈추지 않도록 오류 응답으로 돌려줍니다
+                Some(id) => vec![failed_request(id, &e)],
+                None => {
+                    eprintln!("⚠️ [MCP] Could not send to {}: {:#}", self.url, e);
+                    Vec::new()
+                }
+            },
+        };
+        for reply in replies {
+            let _ = self.incoming.unbounded_send(reply);
+        }
+    }
+
+    async fn post(
+        &self,
+        message: &TxJsonRpcMessage<RoleClient>,
+    ) -> Result<Vec<RxJsonRpcMessage<RoleClient>>> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header(ACCEPT, "application/json, text/event-stream")
+            .json(message);
+        let session_id = self.session_id.lock().unwrap().clone();
+        if let Some(session_id) = session_id {
+            request = request.header(SESSION_HEADER, session_id);
+        }
+        let response = request.send().await?;
+        if let Some(session_id) = response
+            .headers()
+            .get(SESSION_HEADER)
+            .and_then(|value| value.to_str().ok())
+        {
+            *self.session_id.lock().unwrap() = Some(session_id.to_string());
+        }
+
+        let status = response.status();
+        if status == StatusCode::ACCEPTED {
+            return Ok(Vec::new());
+        }
+        let event_stream = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"));
+        let body = response.text().await?;
+        if !status.is_success() {
+            anyhow::bail!("Server responded with {}: {}", status, body.trim());
+        }
+        if event_stream {
+            event_data(&body)
+                .iter()
+                .map(|data| Ok(serde_json::from_str(data)?))
+                .collect()
+        } else if body.trim().is_empty() {
+            Ok(Vec::new())
+        } else {
+            Ok(vec![serde_json::from_str(&body)?])
+        }
+    }
+}
+
+fn failed_request(id: RequestId, error: &anyhow::Error) -> RxJsonRpcMessage<RoleClient> {
+    JsonRpcMessage::Error(JsonRpcError {
+        jsonrpc: JsonRpcVersion2_0,
+        id,
+        error: ErrorData::internal_error(format!("{:#}", error), None),
+    })
+}
+
+/// Payloads of the events in a `text/event-stream` body, skipping events without data
+fn event_data(body: &str) -> Vec<String> {
+    let body = body.replace("\r\n", "\n");
+    body.split("\n\n")
+        .filter_map(|event| {
+            let data: Vec<&str> = event
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|data| data.strip_prefix(' ').unwrap_or(data))
+                .collect();
+            (!data.is_empty()).then(|| data.join("\n"))
+        })
+        .filter(|data| !data.trim().is_empty())
+        .collect()
+}
+
+/// Connect to a server that exchanges one JSON-RPC message per WebSocket text frame
+pub async fn websocket(url: &str) -> Result<(Outgoing, Incoming)> {
+    let (socket, _) = async_tungstenite::tokio::connect_async(url)
+        .await
+        .with_context(|| format!("Could not connect to {}", url))?;
+    let (sink, stream) = socket.split();
+    let outgoing =
+        sink.sink_map_err(io::Error::other)
+            .with(|message: TxJsonRpcMessage<RoleClient>| {
+                future::ready(
+                    serde_json::to_string(&message)
+                        .map(Message::text)
+                        .map_err(io::Error::from),
+                )
+            });
+    let incoming = stream
+        .take_while(|frame| future::ready(frame.is_ok()))
+        .filter_map(|frame| {
+            future::ready(match frame {
+                Ok(Message::Text(text)) => match serde_json::from_str(&text) {
+                    Ok(message) => Some(message),
+                    Err(e) => {
+                        eprintln!("⚠️ [MCP] Ignoring a malformed WebSocket message: {}", e);
+                        None
+                    }
+                },
+                _ => None,
+            })
+        });
+    Ok((Box::pin(outgoing), Box::pin(incoming)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_data_joins_lines_and_skips_empty_events() {
+        let body =
+            "event: message\r\ndata: {\"a\":\r\ndata: 1}\r\n\r\n: keep-alive\n\nid: 2\ndata:{}\n\n";
+        assert_eq!(event_data(body), vec!["{\"a\":\n1}", "{}"]);
+    }
+
+    #[tokio::test]
+    async fn unreachable_server_fails_the_pending_request() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+        drop(listener);
+
+        let (mut outgoing, mut incoming) = http(&url).unwrap();
+        let request: TxJsonRpcMessage<RoleClient> = serde_json::from_value(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "ping"
+        }))
+        .unwrap();
+        outgoing.send(request).await.unwrap();
+
+        match incoming.next().await {
+            Some(JsonRpcMessage::Error(error)) => assert_eq!(error.id, RequestId::Number(7)),
+            other => panic!("expected an error response, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn invalid_url_is_rejected() {
+        assert!(http("not a url").is_err());
+    }
+}
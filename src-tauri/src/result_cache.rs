@@ -0,0 +1,348 @@
+use anyhow::Result;
+use futures::future::{BoxFuture, FutureExt, Shared};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::config_versions;
+use crate::hooks::{PreCall, ToolCall, ToolHook};
+use crate::mcp::{MCPToolAnnotations, ToolCallResult};
+use crate::{browser, builtin, script_tools, telemetry};
+
+const MAX_ENTRIES: usize = 500;
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_ttl() -> u64 {
+    300
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultCacheSettings {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_ttl")]
+    pub ttl_secs: u64,
+    /// Servers whose results are never cached
+    #[serde(default)]
+    pub disabled_servers: Vec<String>,
+}
+
+impl Default for ResultCacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            ttl_secs: default_ttl(),
+            disabled_servers: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ResultCacheStats {
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+    /// Calls that joined an identical call already in flight instead of being sent
+    pub deduped: u64,
+    pub in_flight: usize,
+}
+
+/// Only read-only or idempotent tools may be answered from the cache
+pub fn is_cacheable(annotations: Option<&MCPToolAnnotations>) -> bool {
+    annotations.is_some_and(|a| a.read_only_hint == Some(true) || a.idempotent_hint == Some(true))
+}
+
+/// 키 순서가 달라도 같은 인자로 취급되도록 객체 키를 정렬해 직렬화합니다
+fn canonical_json(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (index, key) in keys.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::String(key.clone()).to_string());
+                out.push(':');
+                canonical_json(&map[key], out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                canonical_json(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// Cache key for a call; missing arguments are the same as `{}`
+pub fn key(server_name: &str, tool_name: &str, arguments: &serde_json::Value) -> String {
+    let mut canonical = String::new();
+    match arguments {
+        serde_json::Value::Null => canonical.push_str("{}"),
+        arguments => canonical_json(arguments, &mut canonical),
+    }
+    let digest = Sha256::digest(canonical.as_bytes());
+    format!("{}\u{0}{}\u{0}{:x}", server_name, tool_name, digest)
+}
+
+/// Key under which a call may be cached.
+/// `None` for tools that may change state and for local tools, which read state other tools change.
+pub fn cache_key(call: &ToolCall) -> Option<String> {
+    let local = [
+        builtin::SERVER_NAME,
+        script_tools::SERVER_NAME,
+        browser::SERVER_NAME,
+    ];
+    (!local.contains(&call.server_name.as_str()) && is_cacheable(call.annotations.as_ref()))
+        .then(|| key(&call.server_name, &call.tool_name, &call.arguments))
+}
+
+struct Entry {
+    server_name: String,
+    result: ToolCallResult,
+    stored_at: Instant,
+}
+
+#[derive(Default)]
+struct CacheState {
+    settings: Option<ResultCacheSettings>,
+    entries: HashMap<String, Entry>,
+    hits: u64,
+    misses: u64,
+}
+
+type SharedCall = Shared<BoxFuture<'static, ToolCallResult>>;
+
+/// Short-lived cache of successful results from read-only tool calls
+pub struct ResultCache {
+    settings_path: PathBuf,
+    state: Mutex<CacheState>,
+    in_flight: Mutex<HashMap<String, SharedCall>>,
+    deduped: AtomicU64,
+}
+
+impl ResultCache {
+    pub fn new(settings_path: PathBuf) -> Self {
+        Self {
+            settings_path,
+            state: Mutex::new(CacheState::default()),
+            in_flight: Mutex::new(HashMap::new()),
+            deduped: AtomicU64::new(0),
+        }
+    }
+
+    /// Forget the cached copy so the next read picks up the file on disk
+    pub async fn reload(&self) {
+        let mut state = self.state.lock().await;
+        state.settings = None;
+        state.entries.clear();
+    }
+
+    /// Run `call` unless an identical call is already in flight, in which case share its result
+    pub async fn coalesce(
+        &'static self,
+        server_name: &str,
+        key: String,
+        call: impl Future<Output = ToolCallResult> + Send + 'static,
+    ) -> ToolCallResult {
+        let shared = {
+            let mut in_flight = self.in_flight.lock().await;
+            match in_flight.get(&key) {
+                Some(existing) => {
+                    self.deduped.fetch_add(1, Ordering::Relaxed);
+                    telemetry::increment("mcp.tool_calls_deduped", server_name);
+                    existing.clone()
+                }
+                None => {
+                    // 처음 호출한 쪽이 취소되어도 기다리는 다른 호출을 위해 끝까지 실행합니다
+                    let task = tokio::spawn(call);
+                    let shared = async move {
+                        task.await.unwrap_or_else(|e| ToolCallResult {
+                            success: false,
+                            result: None,
+                            error: Some(e.to_string()),
+                        })
+                    }
+                    .boxed()
+                    .shared();
+                    in_flight.insert(key.clone(), shared.clone());
+                    let finished = shared.clone();
+                    tokio::spawn(async move {
+                        finished.await;
+                        self.in_flight.lock().await.remove(&key);
+                    });
+                    shared
+                }
+            }
+        };
+        shared.await
+    }
+
+    pub async fn settings(&self) -> ResultCacheSettings {
+        let mut state = self.state.lock().await;
+        self.loaded_settings(&mut state).await.clone()
+    }
+
+    async fn loaded_settings<'a>(&self, state: &'a mut CacheState) -> &'a ResultCacheSettings {
+        if state.settings.is_none() {
+            state.settings = Some(
+                tokio::fs::read_to_string(&self.settings_path)
+                    .await
+                    .ok()
+                    .and_then(|content| serde_json::from_str(&content).ok())
+                    .unwrap_or_default(),
+            );
+        }
+        state.settings.as_ref().expect("settings were just loaded")
+    }
+
+    pub async fn save_settings(&self, settings: ResultCacheSettings) -> Result<()> {
+        config_versions::write(
+            &self.settings_path,
+            serde_json::to_string_pretty(&settings)?,
+        )
+        .await?;
+        let mut state = self.state.lock().await;
+        state.entries.clear();
+        state.settings = Some(settings);
+        Ok(())
+    }
+
+    /// A fresh cached result, or `None` when caching is off for the server or the entry expired
+    pub async fn get(&self, server_name: &str, key: &str) -> Option<ToolCallResult> {
+        let mut state = self.state.lock().await;
+        let settings = self.loaded_settings(&mut state).await;
+        if !settings.enabled || settings.disabled_servers.iter().any(|s| s == server_name) {
+            return None;
+        }
+        let ttl = Duration::from_secs(settings.ttl_secs);
+        let fresh = state
+            .entries
+            .get(key)
+            .filter(|entry| entry.stored_at.elapsed() < ttl)
+            .map(|entry| entry.result.clone());
+        if fresh.is_some() {
+            state.hits += 1;
+        } else {
+            state.misses += 1;
+            state.entries.remove(key);
+        }
+        fresh
+    }
+
+    pub async fn insert(&self, server_name: &str, key: String, result: &ToolCallResult) {
+        let mut state = self.state.lock().await;
+        let settings = self.loaded_settings(&mut state).await;
+        if !result.success
+            || !settings.enabled
+            || settings.disabled_servers.iter().any(|s| s == server_name)
+        {
+            return;
+        }
+        let ttl = Duration::from_secs(settings.ttl_secs);
+        if state.entries.len() >= MAX_ENTRIES {
+            // 만료된 항목을 먼저 지우고, 그래도 가득 차면 가장 오래된 항목을 지웁니다
+            state
+                .entries
+                .retain(|_, entry| entry.stored_at.elapsed() < ttl);
+            if state.entries.len() >= MAX_ENTRIES {
+                if let Some(oldest) = state
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.stored_at)
+                    .map(|(key, _)| key.clone())
+                {
+                    state.entries.remove(&oldest);
+                }
+            }
+        }
+        state.entries.insert(
+            key,
+            Entry {
+                server_name: server_name.to_string(),
+                result: result.clone(),
+                stored_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Drop a server's entries, e.g. after one of its tools changed something
+    pub async fn invalidate_server(&self, server_name: &str) {
+        self.state
+            .lock()
+            .await
+            .entries
+            .retain(|_, entry| entry.server_name != server_name);
+    }
+
+    pub async fn clear(&self) {
+        self.state.lock().await.entries.clear();
+    }
+
+    pub async fn stats(&self) -> ResultCacheStats {
+        let in_flight = self.in_flight.lock().await.len();
+        let state = self.state.lock().await;
+        ResultCacheStats {
+            entries: state.entries.len(),
+            hits: state.hits,
+            misses: state.misses,
+            deduped: self.deduped.load(Ordering::Relaxed),
+            in_flight,
+        }
+    }
+}
+
+/// Answers repeated read-only calls from the cache and stores fresh results
+pub struct ResultCacheHook(pub &'static ResultCache);
+
+impl ToolHook for ResultCacheHook {
+    fn name(&self) -> &str {
+        "result-cache"
+    }
+
+    fn before_call<'a>(&'a self, call: &'a mut ToolCall) -> BoxFuture<'a, PreCall> {
+        Box::pin(async move {
+            let Some(key) = cache_key(call).filter(|_| !call.bypass_cache) else {
+                return PreCall::Continue;
+            };
+            match self.0.get(&call.server_name, &key).await {
+                Some(result) => {
+                    telemetry::increment("mcp.tool_cache_hits", &call.server_name);
+                    PreCall::Respond(result)
+                }
+                None => PreCall::Continue,
+            }
+        })
+    }
+
+    fn after_call<'a>(
+        &'a self,
+        call: &'a ToolCall,
+        result: &'a mut ToolCallResult,
+    ) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            match cache_key(call) {
+                Some(key) => self.0.insert(&call.server_name, key, result).await,
+                // 상태를 바꿀 수 있는 호출 뒤에는 같은 서버의 캐시를 비웁니다
+                None => self.0.invalidate_server(&call.server_name).await,
+            }
+        })
+    }
+}
@@ -0,0 +1,208 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+use crate::api::ask_webview;
+use crate::attachments::{AttachmentCompaction, AttachmentStore};
+use crate::config_versions;
+use crate::events;
+use crate::power;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Cap for the attachment store; the oldest blobs are evicted above it
+    #[serde(default)]
+    pub max_attachment_bytes: Option<u64>,
+    /// Sessions not updated for this many days are deleted with their messages
+    #[serde(default)]
+    pub max_session_age_days: Option<u32>,
+    /// How often the background task enforces the policy
+    #[serde(default = "default_enforce_interval_secs")]
+    pub enforce_interval_secs: u64,
+}
+
+fn default_enforce_interval_secs() -> u64 {
+    3600
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_attachment_bytes: None,
+            max_session_age_days: None,
+            enforce_interval_secs: default_enforce_interval_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CompactionReport {
+    #[serde(flatten)]
+    pub attachments: AttachmentCompaction,
+    /// Sessions deleted for being older than `max_session_age_days`
+    pub removed_sessions: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageStats {
+    pub app_data_dir: String,
+    pub total_bytes: u64,
+    /// Size of each top-level entry in the app-data directory
+    pub entries: HashMap<String, u64>,
+    pub attachment_count: usize,
+    pub attachment_bytes: u64,
+}
+
+pub struct RetentionManager {
+    policy_path: PathBuf,
+    policy: Mutex<Option<RetentionPolicy>>,
+}
+
+impl RetentionManager {
+    pub fn new(policy_path: PathBuf) -> Self {
+        Self {
+            policy_path,
+            policy: Mutex::new(None),
+        }
+    }
+
+    /// Forget the cached copy so the next read picks up the file on disk
+    pub async fn reload(&self) {
+        *self.policy.lock().await = None;
+    }
+
+    pub async fn get_policy(&self) -> Result<RetentionPolicy> {
+        let mut guard = self.policy.lock().await;
+        if let Some(policy) = guard.as_ref() {
+            return Ok(policy.clone());
+        }
+        let policy = match tokio::fs::read_to_string(&self.policy_path).await {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => RetentionPolicy::default(),
+            Err(e) => return Err(e.into()),
+        };
+        *guard = Some(policy.clone());
+        Ok(policy)
+    }
+
+    pub async fn set_policy(&self, policy: RetentionPolicy) -> Result<()> {
+        if policy.enforce_interval_secs == 0 {
+            return Err(anyhow::anyhow!(
+                "enforce_interval_secs must be greater than 0"
+            ));
+        }
+        if policy.max_session_age_days == Some(0) {
+            return Err(anyhow::anyhow!(
+                "max_session_age_days must be greater than 0"
+            ));
+        }
+        config_versions::write(&self.policy_path, serde_json::to_string_pretty(&policy)?).await?;
+        *self.policy.lock().await = Some(policy);
+        Ok(())
+    }
+
+    /// 정리 작업을 수행하고 정책의 용량 제한과 세션 보관 기간을 적용합니다
+    pub async fn compact(&self, store: &AttachmentStore) -> Result<CompactionReport> {
+        let policy = self.get_policy().await?;
+        let mut attachments = store.compact().await?;
+        if let Some(max_bytes) = policy.max_attachment_bytes {
+            let evicted = store.evict_to_size(max_bytes).await?;
+            attachments.removed_blobs += evicted.removed_blobs;
+            attachments.removed_index_entries += evicted.removed_index_entries;
+            attachments.reclaimed_bytes += evicted.reclaimed_bytes;
+            attachments.over_cap_bytes = evicted.over_cap_bytes;
+        }
+
+        // 세션은 웹뷰의 데이터베이스에 있으므로 창이 없으면 다음 실행으로 미룹니다
+        let mut removed_sessions = 0;
+        if let (Some(days), true) = (policy.max_session_age_days, events::app_attached()) {
+            let removed = ask_webview(
+                "retention",
+                serde_json::json!({ "max_session_age_days": days }),
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to delete old sessions: {}", e))?;
+            removed_sessions = removed.as_u64().unwrap_or(0) as usize;
+        }
+
+        Ok(CompactionReport {
+            attachments,
+            removed_sessions,
+        })
+    }
+
+    /// Periodically enforce the policy for as long as the app runs
+    pub async fn run_background(&self, store: &AttachmentStore) {
+        loop {
+            let interval = self
+                .get_policy()
+                .await
+                .map(|p| p.enforce_interval_secs)
+                .unwrap_or_else(|_| default_enforce_interval_secs());
+            tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+            if power::paused() {
+                continue;
+            }
+
+            match self.compact(store).await {
+                Ok(report)
+                    if report.attachments.reclaimed_bytes > 0 || report.removed_sessions > 0 =>
+                {
+                    println!(
+                        "Retention: removed {} blobs and {} sessions, reclaimed {} bytes",
+                        report.attachments.removed_blobs,
+                        report.removed_sessions,
+                        report.attachments.reclaimed_bytes
+                    )
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("❌ Retention enforcement failed: {}", e),
+            }
+        }
+    }
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| dir_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+pub async fn storage_stats(app_data_dir: &Path, store: &AttachmentStore) -> Result<StorageStats> {
+    let dir = app_data_dir.to_path_buf();
+    let entries = tokio::task::spawn_blocking(move || {
+        let mut entries = HashMap::new();
+        if let Ok(read_dir) = std::fs::read_dir(&dir) {
+            for entry in read_dir.filter_map(|entry| entry.ok()) {
+                entries.insert(
+                    entry.file_name().to_string_lossy().to_string(),
+                    dir_size(&entry.path()),
+                );
+            }
+        }
+        entries
+    })
+    .await?;
+
+    let attachments = store.list().await?;
+    Ok(StorageStats {
+        app_data_dir: app_data_dir.display().to_string(),
+        total_bytes: entries.values().sum(),
+        entries,
+        attachment_count: attachments.len(),
+        attachment_bytes: attachments.iter().map(|info| info.size).sum(),
+    })
+}
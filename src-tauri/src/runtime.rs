@@ -0,0 +1,446 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::path::Path;
+
+use crate::mcp::{ContainerConfig, MCPServerConfig};
+
+/// Launchers a config can name instead of spelling out `command` and `args`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Runtime {
+    /// `npx -y <package>` (Node.js)
+    Npx,
+    /// `uvx <package>` (Python, via uv)
+    Uvx,
+    /// `<package>` is an image run with the `docker` transport
+    Docker,
+    /// `deno run -A <package>`; bare names are taken from npm
+    Deno,
+    /// `wsl.exe -d <distro> -- <command> <args>`: runs a Linux-only server inside WSL
+    /// on Windows. Windows paths in the arguments are translated to `/mnt/<drive>/...`.
+    Wsl,
+}
+
+impl Runtime {
+    pub const ALL: [Self; 5] = [Self::Npx, Self::Uvx, Self::Docker, Self::Deno, Self::Wsl];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Npx => "npx",
+            Self::Uvx => "uvx",
+            Self::Docker => "docker",
+            Self::Deno => "deno",
+            Self::Wsl => "wsl",
+        }
+    }
+}
+
+/// Windows cannot spawn `npx.cmd` directly, so it goes through `cmd /c`
+pub fn platform_command(command: &str, args: Vec<String>) -> (String, Vec<String>) {
+    if cfg!(windows) && command == "npx" {
+        let mut wrapped = vec!["/c".to_string(), command.to_string()];
+        wrapped.extend(args);
+        ("cmd".to_string(), wrapped)
+    } else {
+        (command.to_string(), args)
+    }
+}
+
+fn package(config: &MCPServerConfig) -> Result<&str> {
+    config
+        .package
+        .as_deref()
+        .filter(|package| !package.trim().is_empty())
+        .ok_or_else(|| anyhow::anyhow!("\"package\" is required with a runtime"))
+}
+
+/// Transport the server actually uses; the docker runtime implies the `docker` transport
+pub fn transport(config: &MCPServerConfig) -> &str {
+    match config.runtime {
+        Some(Runtime::Docker) => "docker",
+        _ => config.transport.as_str(),
+    }
+}
+
+/// Transport of a config entry that does not name one: remote when it has a `url`
+pub fn implied_transport(url: Option<&str>) -> &'static str {
+    match url {
+        Some(url) if url.starts_with("ws://") || url.starts_with("wss://") => "websocket",
+        Some(_) => "http",
+        None => "stdio",
+    }
+}
+
+pub const TRANSPORTS: &[&str] = &["stdio", "http", "websocket", "docker"];
+/// Transports that spawn a local process
+const LOCAL_TRANSPORTS: &[&str] = &["stdio", "docker"];
+
+/// What this build can run. Mobile builds cannot spawn processes, so the manager
+/// only reaches remote servers there.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlatformCapabilities {
+    pub mobile: bool,
+    pub transports: Vec<&'static str>,
+}
+
+pub fn capabilities() -> PlatformCapabilities {
+    PlatformCapabilities {
+        mobile: cfg!(mobile),
+        transports: TRANSPORTS
+            .iter()
+            .copied()
+            .filter(|transport| transport_available(transport))
+            .collect(),
+    }
+}
+
+pub fn transport_available(transport: &str) -> bool {
+    cfg!(desktop) || !LOCAL_TRANSPORTS.contains(&transport)
+}
+
+/// Error for a config whose transport this build cannot start
+pub fn unavailable_transport(transport: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "The {} transport is not available on this platform; only remote (http, websocket) servers can be used",
+        transport
+    )
+}
+
+/// Split a shell-style command line into words. Single quotes keep their content as
+/// is; elsewhere a backslash escapes a quote, a backslash or whitespace and is kept
+/// before anything else, so unquoted Windows paths survive.
+pub fn split_words(line: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(anyhow::anyhow!("Unterminated single quote")),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.peek() {
+                            Some(&next @ ('"' | '\\' | '$' | '`')) => {
+                                word.push(next);
+                                chars.next();
+                            }
+                            _ => word.push('\\'),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(anyhow::anyhow!("Unterminated double quote")),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                match chars.peek() {
+                    Some(&next) if next.is_whitespace() || matches!(next, '"' | '\'' | '\\') => {
+                        word.push(next);
+                        chars.next();
+                    }
+                    _ => word.push('\\'),
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// `command` and `args` as configured. Shared configs often put the whole command
+/// line in `command` (`uvx mcp-server-git --repo .`); without `args` it is split into
+/// words, unless it names an existing file whose path contains spaces.
+fn split_command(command: &str, args: Option<&Vec<String>>) -> Result<(String, Vec<String>)> {
+    if let Some(args) = args {
+        return Ok((command.to_string(), args.clone()));
+    }
+    let command = command.trim();
+    if !command.contains(char::is_whitespace) || Path::new(command).is_file() {
+        return Ok((command.to_string(), Vec::new()));
+    }
+    let mut words = split_command_words(command)?.into_iter();
+    let program = words
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Command is empty"))?;
+    Ok((program, words.collect()))
+}
+
+fn split_command_words(command: &str) -> Result<Vec<String>> {
+    split_words(command)
+        .map_err(|e| anyhow::anyhow!("Could not parse command \"{}\": {}", command, e))
+}
+
+/// Words after the image of a container server, split like [`split_command`].
+/// Paths inside the image can't be checked on the host, so one with spaces must
+/// be quoted.
+pub(crate) fn container_command(command: &str, args: Option<&Vec<String>>) -> Result<Vec<String>> {
+    match args {
+        Some(args) => Ok(std::iter::once(command.to_string())
+            .chain(args.iter().cloned())
+            .collect()),
+        None => split_command_words(command.trim()),
+    }
+}
+
+/// Command line of a stdio server: the runtime preset expanded for this platform,
+/// or `command` and `args` as written. The config's `args` follow the package.
+pub fn command_line(config: &MCPServerConfig) -> Result<(String, Vec<String>)> {
+    let (command, args) = unwrapped_command_line(config)?;
+    Ok(match config.runtime {
+        Some(_) => platform_command(&command, args),
+        None => (command, args),
+    })
+}
+
+/// [`command_line`] before the Windows `cmd /c` wrapping, naming the program that
+/// actually serves; integrity pins are checked against this one
+pub fn unwrapped_command_line(config: &MCPServerConfig) -> Result<(String, Vec<String>)> {
+    let extra = config.args.clone().unwrap_or_default();
+    let Some(runtime) = config.runtime else {
+        let command = config
+            .command
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("Command is required for stdio transport"))?;
+        return split_command(command, config.args.as_ref());
+    };
+    if runtime == Runtime::Wsl {
+        return wsl_command_line(config);
+    }
+    let package = package(config)?.to_string();
+    let mut args = match runtime {
+        Runtime::Npx => vec!["-y".to_string(), package],
+        Runtime::Uvx => vec![package],
+        Runtime::Deno => {
+            // 스킴이 없는 이름은 npm 패키지로 봅니다
+            let specifier = if package.contains(':') {
+                package
+            } else {
+                format!("npm:{}", package)
+            };
+            vec!["run".to_string(), "-A".to_string(), specifier]
+        }
+        Runtime::Docker => {
+            return Err(anyhow::anyhow!(
+                "The docker runtime runs through the docker transport"
+            ))
+        }
+        Runtime::Wsl => unreachable!("handled above"),
+    };
+    args.extend(extra);
+    Ok((runtime.name().to_string(), args))
+}
+
+/// Linux path of a Windows path as WSL mounts it: `C:\work\repo` becomes
+/// `/mnt/c/work/repo` and `\\wsl$\Ubuntu\home\me` becomes `/home/me`.
+/// Anything else, relative paths included, is returned unchanged.
+pub fn wsl_path(value: &str) -> Cow<'_, str> {
+    let bytes = value.as_bytes();
+    if bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && (bytes[2] == b'\\' || bytes[2] == b'/')
+    {
+        let rest = value[3..].replace('\\', "/");
+        let drive = (bytes[0] as char).to_ascii_lowercase();
+        return Cow::Owned(format!("/mnt/{}/{}", drive, rest.trim_end_matches('/')));
+    }
+    // WSL 파일 시스템을 가리키는 UNC 경로는 배포판 안의 경로로 바꿉니다
+    for prefix in [r"\\wsl$\", r"\\wsl.localhost\"] {
+        if let Some(rest) = value.strip_prefix(prefix) {
+            let path = rest.split_once('\\').map_or("", |(_, path)| path);
+            return Cow::Owned(format!("/{}", path.replace('\\', "/")));
+        }
+    }
+    Cow::Borrowed(value)
+}
+
+/// An argument with Windows paths translated, including the value of `--flag=C:\...`
+fn wsl_arg(arg: &str) -> String {
+    if let Cow::Owned(path) = wsl_path(arg) {
+        return path;
+    }
+    match arg.split_once('=') {
+        Some((flag, value)) => format!("{}={}", flag, wsl_path(value)),
+        None => arg.to_string(),
+    }
+}
+
+fn wsl_command_line(config: &MCPServerConfig) -> Result<(String, Vec<String>)> {
+    if !cfg!(windows) {
+        return Err(anyhow::anyhow!(
+            "The wsl runtime is only available on Windows"
+        ));
+    }
+    let command = config
+        .command
+        .as_deref()
+        .filter(|command| !command.trim().is_empty())
+        .ok_or_else(|| anyhow::anyhow!("\"command\" is required with the wsl runtime"))?;
+    let (command, command_args) = split_command(command, config.args.as_ref())?;
+    let mut args = Vec::new();
+    if let Some(distro) = &config.distro {
+        args.extend(["-d".to_string(), distro.clone()]);
+    }
+    args.push("--".to_string());
+    args.push(wsl_arg(&command));
+    args.extend(command_args.iter().map(|arg| wsl_arg(arg)));
+    Ok(("wsl.exe".to_string(), args))
+}
+
+/// `WSLENV` that forwards the config's variables into WSL, which otherwise drops them.
+/// Variables holding a Windows path get the `/p` flag so WSL translates them too.
+pub fn wslenv(config: &MCPServerConfig, inherited: Option<&str>) -> String {
+    let mut entries: Vec<String> = inherited
+        .into_iter()
+        .flat_map(|value| value.split(':'))
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect();
+    let mut names: Vec<(&String, &String)> = config.env.iter().flatten().collect();
+    names.sort();
+    for (name, value) in names {
+        let flag = if matches!(wsl_path(value), Cow::Owned(_)) {
+            "/p"
+        } else {
+            ""
+        };
+        entries.push(format!("{}{}", name, flag));
+    }
+    entries.join(":")
+}
+
+/// Container of a `docker` transport server; the docker runtime fills in the image
+pub fn container(config: &MCPServerConfig) -> Result<Cow<'_, ContainerConfig>> {
+    if let Some(container) = &config.container {
+        return Ok(Cow::Borrowed(container));
+    }
+    if config.runtime == Some(Runtime::Docker) {
+        return Ok(Cow::Owned(ContainerConfig {
+            image: package(config)?.to_string(),
+            ..Default::default()
+        }));
+    }
+    Err(anyhow::anyhow!(
+        "A container block is required for docker transport"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(line: &str) -> Vec<String> {
+        split_words(line).unwrap()
+    }
+
+    #[test]
+    fn splits_on_whitespace() {
+        assert_eq!(
+            words("  uvx  mcp-server-git\t--repo . "),
+            ["uvx", "mcp-server-git", "--repo", "."]
+        );
+        assert!(words("   ").is_empty());
+    }
+
+    #[test]
+    fn keeps_quoted_words_together() {
+        assert_eq!(
+            words(r#"node "my server.js" --name 'two words'"#),
+            ["node", "my server.js", "--name", "two words"]
+        );
+        assert_eq!(words(r#"--opt="a b"c"#), ["--opt=a bc"]);
+    }
+
+    #[test]
+    fn handles_escapes() {
+        assert_eq!(words(r"my\ server.js"), ["my server.js"]);
+        assert_eq!(words(r#""say \"hi\"""#), [r#"say "hi""#]);
+        assert_eq!(words(r"'no \escape'"), [r"no \escape"]);
+        assert_eq!(words(r#"\"quoted\""#), [r#""quoted""#]);
+    }
+
+    #[test]
+    fn keeps_windows_backslashes() {
+        assert_eq!(
+            words(r#""C:\Program Files\nodejs\node.exe" C:\servers\index.js"#),
+            [r"C:\Program Files\nodejs\node.exe", r"C:\servers\index.js"]
+        );
+    }
+
+    #[test]
+    fn keeps_empty_quoted_words() {
+        assert_eq!(words("cmd '' \"\" end"), ["cmd", "", "", "end"]);
+    }
+
+    #[test]
+    fn rejects_unterminated_quotes() {
+        assert!(split_words("node 'server.js").is_err());
+        assert!(split_words(r#"node "server.js"#).is_err());
+        assert!(split_words(r#"node "server.js\""#).is_err());
+    }
+
+    #[test]
+    fn splits_commands_only_without_args() {
+        let (program, args) = split_command("uvx mcp-server-git --repo .", None).unwrap();
+        assert_eq!(program, "uvx");
+        assert_eq!(args, ["mcp-server-git", "--repo", "."]);
+
+        let given = vec!["--flag".to_string()];
+        let (program, args) = split_command("my tool", Some(&given)).unwrap();
+        assert_eq!(program, "my tool");
+        assert_eq!(args, ["--flag"]);
+    }
+
+    #[test]
+    fn keeps_existing_paths_with_spaces_whole() {
+        let dir = std::env::temp_dir().join(format!("runtime {}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let server = dir.join("my server");
+        std::fs::write(&server, "").unwrap();
+
+        let result = split_command(server.to_str().unwrap(), None);
+        let _ = std::fs::remove_dir_all(&dir);
+        let (program, args) = result.unwrap();
+        assert_eq!(program, server.to_str().unwrap());
+        assert!(args.is_empty());
+    }
+
+    #[test]
+    fn splits_container_commands() {
+        assert_eq!(
+            container_command("python -m server --port 8080", None).unwrap(),
+            ["python", "-m", "server", "--port", "8080"]
+        );
+        let given = vec!["-m".to_string(), "server".to_string()];
+        assert_eq!(
+            container_command("python", Some(&given)).unwrap(),
+            ["python", "-m", "server"]
+        );
+        assert!(container_command("python 'server", None).is_err());
+    }
+}
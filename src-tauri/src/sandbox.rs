@@ -0,0 +1,339 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::io::{ErrorKind, Write};
+use std::path::{Component, Path, PathBuf};
+use tokio::sync::RwLock;
+
+use crate::config_versions;
+use crate::events;
+
+// 이보다 커지면 이전 기록을 `.1` 파일로 넘기고 새로 시작합니다
+const MAX_VIOLATION_LOG_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccessKind {
+    Read,
+    Write,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandboxViolation {
+    pub timestamp: String,
+    pub path: String,
+    pub access: AccessKind,
+    pub reason: String,
+    /// Directory the user could approve to allow this access
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggested_root: Option<String>,
+}
+
+/// Path policy for builtin tools: every path must resolve inside a user-approved root.
+pub struct PathPolicy {
+    roots_file: PathBuf,
+    roots: RwLock<Option<Vec<PathBuf>>>,
+    /// Append-only JSON Lines record of denied accesses, next to the roots file
+    violations_file: PathBuf,
+    violations_lock: std::sync::Mutex<()>,
+}
+
+impl PathPolicy {
+    pub fn new(roots_file: PathBuf) -> Self {
+        let violations_file = roots_file.with_file_name("sandbox_violations.jsonl");
+        Self {
+            roots_file,
+            roots: RwLock::new(None),
+            violations_file,
+            violations_lock: std::sync::Mutex::new(()),
+        }
+    }
+
+    /// Forget the cached copy so the next read picks up the file on disk
+    pub async fn reload(&self) {
+        *self.roots.write().await = None;
+    }
+
+    pub async fn roots(&self) -> Vec<PathBuf> {
+        if let Some(roots) = self.roots.read().await.as_ref() {
+            return roots.clone();
+        }
+        let roots: Vec<PathBuf> = match tokio::fs::read_to_string(&self.roots_file).await {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                eprintln!("❌ Invalid workspace roots file, ignoring it: {}", e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        *self.roots.write().await = Some(roots.clone());
+        roots
+    }
+
+    async fn save_roots(&self, roots: Vec<PathBuf>) -> Result<()> {
+        config_versions::write(&self.roots_file, serde_json::to_string_pretty(&roots)?).await?;
+        *self.roots.write().await = Some(roots);
+        Ok(())
+    }
+
+    /// 새 워크스페이스 루트를 승인합니다 (심볼릭 링크를 풀어서 저장)
+    pub async fn add_root(&self, root: &Path) -> Result<PathBuf> {
+        let canonical = tokio::fs::canonicalize(root)
+            .await
+            .map_err(|e| anyhow::anyhow!("Cannot use '{}' as a root: {}", root.display(), e))?;
+        if !canonical.is_dir() {
+            return Err(anyhow::anyhow!("'{}' is not a directory", root.display()));
+        }
+        let mut roots = self.roots().await;
+        if !roots.contains(&canonical) {
+            roots.push(canonical.clone());
+            self.save_roots(roots).await?;
+        }
+        println!("Approved workspace root: {}", canonical.display());
+        Ok(canonical)
+    }
+
+    pub async fn remove_root(&self, root: &Path) -> Result<()> {
+        let canonical = tokio::fs::canonicalize(root)
+            .await
+            .unwrap_or_else(|_| root.to_path_buf());
+        let mut roots = self.roots().await;
+        roots.retain(|r| r != &canonical && r != root);
+        self.save_roots(roots).await
+    }
+
+    /// Resolve `path` and make sure it stays inside an approved root.
+    ///
+    /// Relative paths are resolved against the first root. Symlinks are followed
+    /// for the part of the path that exists, so links pointing outside a root are
+    /// rejected; components that don't exist yet may not contain `..`.
+    pub async fn check(&self, path: &Path, access: AccessKind) -> Result<PathBuf> {
+        let roots = self.roots().await;
+        if roots.is_empty() {
+            return Err(self.violation(
+                path,
+                access,
+                "No workspace roots are approved",
+                path.parent(),
+            ));
+        }
+
+        let absolute = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            roots[0].join(path)
+        };
+        let resolved = match resolve(&absolute).await {
+            Ok(resolved) => resolved,
+            Err(e) => return Err(self.violation(path, access, &e.to_string(), None)),
+        };
+
+        if roots.iter().any(|root| resolved.starts_with(root)) {
+            Ok(resolved)
+        } else {
+            let suggestion = if resolved.is_dir() {
+                Some(resolved.as_path())
+            } else {
+                resolved.parent()
+            };
+            Err(self.violation(
+                &resolved,
+                access,
+                "Path is outside the approved workspace roots",
+                suggestion,
+            ))
+        }
+    }
+
+    fn violation(
+        &self,
+        path: &Path,
+        access: AccessKind,
+        reason: &str,
+        suggested_root: Option<&Path>,
+    ) -> anyhow::Error {
+        let violation = SandboxViolation {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            path: path.display().to_string(),
+            access,
+            reason: reason.to_string(),
+            suggested_root: suggested_root.map(|p| p.display().to_string()),
+        };
+        eprintln!(
+            "❌ [SANDBOX] Denied {:?} access to {}: {}",
+            access, violation.path, reason
+        );
+        if let Err(e) = self.record(&violation) {
+            eprintln!("⚠️ [SANDBOX] Could not record the violation: {}", e);
+        }
+        events::emit("sandbox-violation", violation);
+        anyhow::anyhow!("Access denied to '{}': {}", path.display(), reason)
+    }
+
+    fn rotated_violations_file(&self) -> PathBuf {
+        self.violations_file.with_extension("jsonl.1")
+    }
+
+    fn record(&self, violation: &SandboxViolation) -> Result<()> {
+        let _guard = self
+            .violations_lock
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(parent) = self.violations_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        if std::fs::metadata(&self.violations_file)
+            .is_ok_and(|metadata| metadata.len() >= MAX_VIOLATION_LOG_BYTES)
+        {
+            std::fs::rename(&self.violations_file, self.rotated_violations_file())?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.violations_file)?;
+        writeln!(file, "{}", serde_json::to_string(violation)?)?;
+        Ok(())
+    }
+
+    /// Recorded violations, newest first, at most `limit` of them
+    pub fn violations(&self, limit: usize) -> Result<Vec<SandboxViolation>> {
+        let _guard = self
+            .violations_lock
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let mut violations = Vec::new();
+        for file in [self.rotated_violations_file(), self.violations_file.clone()] {
+            let content = match std::fs::read_to_string(&file) {
+                Ok(content) => content,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            // 중간에 끊긴 줄은 건너뜁니다
+            violations.extend(
+                content
+                    .lines()
+                    .filter_map(|line| serde_json::from_str::<SandboxViolation>(line).ok()),
+            );
+        }
+        violations.reverse();
+        violations.truncate(limit);
+        Ok(violations)
+    }
+}
+
+/// Canonicalize the longest existing prefix and append the remaining components
+async fn resolve(path: &Path) -> Result<PathBuf> {
+    let mut existing = path.to_path_buf();
+    let mut remainder = Vec::new();
+    loop {
+        match tokio::fs::canonicalize(&existing).await {
+            Ok(canonical) => {
+                let mut resolved = canonical;
+                for component in remainder.iter().rev() {
+                    resolved.push(component);
+                }
+                return Ok(resolved);
+            }
+            Err(_) => {
+                // 대상이 없는 링크는 canonicalize가 실패하지만 쓰기는 링크를 따라가므로 거부합니다
+                if tokio::fs::symlink_metadata(&existing)
+                    .await
+                    .is_ok_and(|metadata| metadata.file_type().is_symlink())
+                {
+                    return Err(anyhow::anyhow!(
+                        "'{}' is a symlink whose target does not exist",
+                        existing.display()
+                    ));
+                }
+                let name = match existing.components().next_back() {
+                    Some(Component::Normal(name)) => name.to_os_string(),
+                    Some(Component::ParentDir) => {
+                        return Err(anyhow::anyhow!("'..' is not allowed in new paths"))
+                    }
+                    _ => return Err(anyhow::anyhow!("Cannot resolve '{}'", path.display())),
+                };
+                remainder.push(name);
+                if !existing.pop() {
+                    return Err(anyhow::anyhow!("Cannot resolve '{}'", path.display()));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fresh directory holding an approved `root` and an unapproved `outside`
+    async fn setup() -> (PathPolicy, PathBuf, PathBuf) {
+        let base =
+            std::env::temp_dir().join(format!("sandbox-test-{}", uuid::Uuid::new_v4().simple()));
+        let root = base.join("root");
+        let outside = base.join("outside");
+        tokio::fs::create_dir_all(&root).await.unwrap();
+        tokio::fs::create_dir_all(&outside).await.unwrap();
+        let policy = PathPolicy::new(base.join("roots.json"));
+        let root = policy.add_root(&root).await.unwrap();
+        let outside = tokio::fs::canonicalize(&outside).await.unwrap();
+        (policy, root, outside)
+    }
+
+    #[tokio::test]
+    async fn resolves_relative_paths_against_the_first_root() {
+        let (policy, root, _) = setup().await;
+        let resolved = policy
+            .check(Path::new("notes/todo.md"), AccessKind::Write)
+            .await
+            .unwrap();
+        assert_eq!(resolved, root.join("notes/todo.md"));
+    }
+
+    #[tokio::test]
+    async fn rejects_parent_dir_in_new_components() {
+        let (policy, root, _) = setup().await;
+        let path = root.join("missing/../../outside/file");
+        assert!(policy.check(&path, AccessKind::Write).await.is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn rejects_symlinks_to_outside() {
+        let (policy, root, outside) = setup().await;
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+        let path = root.join("link/file");
+        assert!(policy.check(&path, AccessKind::Write).await.is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn rejects_dangling_symlinks() {
+        let (policy, root, outside) = setup().await;
+        std::os::unix::fs::symlink(outside.join("new-file"), root.join("dangling")).unwrap();
+        let path = root.join("dangling");
+        assert!(policy.check(&path, AccessKind::Write).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn records_violations_newest_first() {
+        let (policy, _, outside) = setup().await;
+        for name in ["first", "second"] {
+            let path = outside.join(name);
+            assert!(policy.check(&path, AccessKind::Write).await.is_err());
+        }
+
+        let violations = policy.violations(10).unwrap();
+        assert_eq!(violations.len(), 2);
+        assert!(violations[0].path.ends_with("second"));
+        assert_eq!(violations[0].access, AccessKind::Write);
+        assert_eq!(violations[0].suggested_root.as_deref(), outside.to_str());
+        assert_eq!(policy.violations(1).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_everything_without_roots() {
+        let base =
+            std::env::temp_dir().join(format!("sandbox-test-{}", uuid::Uuid::new_v4().simple()));
+        let policy = PathPolicy::new(base.join("roots.json"));
+        let path = std::env::temp_dir().join("file");
+        assert!(policy.check(&path, AccessKind::Read).await.is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use anyhow::Result;
+use std::path::Path;
+use tokio::process::Command;
+
+use crate::api::ask_approval;
+use crate::attachments::{AttachmentInfo, AttachmentStore};
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+use crate::integrity;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CaptureTarget {
+    /// Every monitor
+    Screen,
+    /// One window chosen with the OS picker
+    Window,
+}
+
+impl CaptureTarget {
+    fn describe(self) -> &'static str {
+        match self {
+            CaptureTarget::Screen => "your entire screen",
+            CaptureTarget::Window => "a window you pick",
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn first_available(candidates: &[&str]) -> Option<std::path::PathBuf> {
+    candidates
+        .iter()
+        .find_map(|name| integrity::resolve_executable(name))
+}
+
+#[cfg(target_os = "macos")]
+async fn run_capture(target: CaptureTarget, output: &Path) -> Result<()> {
+    let mut command = Command::new("screencapture");
+    command.args(["-x", "-t", "png"]);
+    if target == CaptureTarget::Window {
+        // 대화형 창 선택 모드 (Esc로 취소 가능)
+        command.args(["-i", "-W"]);
+    }
+    command.arg(output).status().await?;
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+async fn run_capture(target: CaptureTarget, output: &Path) -> Result<()> {
+    // Windows에는 명령줄 창 선택기가 없어서 승인 후 3초 뒤 활성 창을 캡처합니다
+    let script = r#"
+Add-Type -AssemblyName System.Windows.Forms, System.Drawing
+Add-Type @'
+using System; using System.Runtime.InteropServices;
+public struct RECT { public int Left, Top, Right, Bottom; }
+public static class Win32 {
+  [DllImport("user32.dll")] public static extern IntPtr GetForegroundWindow();
+  [DllImport("user32.dll")] public static extern bool GetWindowRect(IntPtr h, out RECT r);
+}
+'@
+if ($env:CAPTURE_WINDOW) {
+  Start-Sleep -Seconds 3
+  $r = New-Object RECT
+  [Win32]::GetWindowRect([Win32]::GetForegroundWindow(), [ref]$r) | Out-Null
+  $bounds = [Drawing.Rectangle]::FromLTRB($r.Left, $r.Top, $r.Right, $r.Bottom)
+} else {
+  $bounds = [Windows.Forms.SystemInformation]::VirtualScreen
+}
+$bmp = New-Object Drawing.Bitmap $bounds.Width, $bounds.Height
+$g = [Drawing.Graphics]::FromImage($bmp)
+$g.CopyFromScreen($bounds.Location, [Drawing.Point]::Empty, $bounds.Size)
+$bmp.Save($env:CAPTURE_OUTPUT, [Drawing.Imaging.ImageFormat]::Png)
+"#;
+    let mut command = Command::new("powershell");
+    command
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .env("CAPTURE_OUTPUT", output);
+    if target == CaptureTarget::Window {
+        command.env("CAPTURE_WINDOW", "1");
+    }
+    let result = command.output().await?;
+    if !result.status.success() {
+        return Err(anyhow::anyhow!(
+            "Screen capture failed: {}",
+            String::from_utf8_lossy(&result.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+async fn run_capture(target: CaptureTarget, output: &Path) -> Result<()> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let mut command = match target {
+        CaptureTarget::Window if wayland && first_available(&["slurp"]).is_some() => {
+            let grim = first_available(&["grim"])
+                .ok_or_else(|| anyhow::anyhow!("grim is required for capture on Wayland"))?;
+            let region = Command::new("slurp").output().await?;
+            if !region.status.success() {
+                return Ok(());
+            }
+            let mut command = Command::new(grim);
+            command
+                .arg("-g")
+                .arg(String::from_utf8_lossy(&region.stdout).trim());
+            command
+        }
+        CaptureTarget::Screen if wayland && first_available(&["grim"]).is_some() => {
+            Command::new("grim")
+        }
+        _ => {
+            let Some(tool) = first_available(&["gnome-screenshot", "spectacle", "import", "scrot"])
+            else {
+                return Err(anyhow::anyhow!(
+                    "No screenshot tool found (install gnome-screenshot, spectacle, ImageMagick or grim)"
+                ));
+            };
+            let name = tool
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let window = target == CaptureTarget::Window;
+            let mut command = Command::new(&tool);
+            match name.as_str() {
+                "gnome-screenshot" if window => command.args(["-w", "-f"]),
+                "gnome-screenshot" => command.arg("-f"),
+                "spectacle" if window => command.args(["-b", "-n", "-a", "-o"]),
+                "spectacle" => command.args(["-b", "-n", "-f", "-o"]),
+                // import은 창 모드에서 사용자가 클릭한 창을 캡처합니다
+                "import" if window => &mut command,
+                "import" => command.args(["-window", "root"]),
+                _ if window => command.arg("-s"),
+                _ => &mut command,
+            };
+            command
+        }
+    };
+    command.arg(output).status().await?;
+    Ok(())
+}
+
+/// Ask the user, capture to PNG and store it as an attachment
+///
+/// Returns the attachment handle rather than image bytes so large captures
+/// never cross IPC as base64.
+pub async fn capture(store: &AttachmentStore, target: CaptureTarget) -> Result<AttachmentInfo> {
+    ask_approval(
+        "screen-capture",
+        format!(
+            "The assistant wants to capture {}. Allow?",
+            target.describe()
+        ),
+    )
+    .await
+    .map_err(|e| anyhow::anyhow!(e))?;
+    let output = std::env::temp_dir().join(format!("capture-{}.png", uuid::Uuid::new_v4()));
+    let result = run_capture(target, &output).await;
+    let stored = match result {
+        // 사용자가 선택기를 취소하면 파일이 만들어지지 않습니다
+        Ok(()) if !output.is_file() => Err(anyhow::anyhow!("Screen capture was cancelled")),
+        Ok(()) => {
+            store
+                .store_file(&output, Some("image/png".to_string()))
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    let _ = tokio::fs::remove_file(&output).await;
+    stored
+}
@@ -0,0 +1,299 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::Mutex;
+
+use crate::builtin::{error_result, text_result};
+use crate::config_versions;
+use crate::integrity;
+use crate::mcp::{MCPTool, MCPToolAnnotations, MCPToolInputSchema, ToolCallResult};
+
+/// Virtual server name used to route script tool calls
+pub const SERVER_NAME: &str = "scripts";
+
+// 사용자 출력과 결과를 구분하기 위한 표식
+const RESULT_MARKER: &str = "__SCRIPT_TOOL_RESULT__";
+const MAX_TIMEOUT_SECS: u64 = 300;
+const MAX_MEMORY_MB: u32 = 4096;
+
+fn default_timeout_secs() -> u64 {
+    10
+}
+
+fn default_memory_mb() -> u32 {
+    128
+}
+
+/// A small JavaScript/TypeScript tool run by Deno with no permissions unless granted
+///
+/// `source` must define `function handler(args)` (it may be async); its return
+/// value is JSON-serialized as the tool result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptTool {
+    pub name: String,
+    pub description: String,
+    pub input_schema: MCPToolInputSchema,
+    pub source: String,
+    /// Paths passed to `--allow-read`
+    #[serde(default)]
+    pub allow_read: Vec<String>,
+    /// Hosts passed to `--allow-net`
+    #[serde(default)]
+    pub allow_net: Vec<String>,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// V8 heap cap
+    #[serde(default = "default_memory_mb")]
+    pub memory_mb: u32,
+}
+
+impl ScriptTool {
+    fn to_tool(&self) -> MCPTool {
+        // 네트워크를 허용하면 외부에 요청을 보내 상태를 바꿀 수 있습니다
+        let read_only = self.allow_net.is_empty();
+        MCPTool {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            input_schema: self.input_schema.clone(),
+            annotations: Some(MCPToolAnnotations {
+                read_only_hint: Some(read_only),
+                destructive_hint: read_only.then_some(false),
+                open_world_hint: Some(!read_only),
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+/// User-defined script tools, persisted as JSON in app-data
+pub struct ScriptToolLibrary {
+    path: PathBuf,
+    tools: Mutex<Option<HashMap<String, ScriptTool>>>,
+}
+
+fn wrap_source(source: &str) -> String {
+    format!(
+        "{source}\n\n\
+         const __input = await new Response(Deno.stdin.readable).text();\n\
+         const __result = await handler(JSON.parse(__input || \"{{}}\"));\n\
+         console.log(\"\\n{RESULT_MARKER}\" + JSON.stringify(__result ?? null));\n"
+    )
+}
+
+impl ScriptToolLibrary {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            tools: Mutex::new(None),
+        }
+    }
+
+    /// Forget the cached copy so the next read picks up the file on disk
+    pub async fn reload(&self) {
+        *self.tools.lock().await = None;
+    }
+
+    async fn loaded<'a>(
+        &self,
+        cache: &'a mut Option<HashMap<String, ScriptTool>>,
+    ) -> Result<&'a mut HashMap<String, ScriptTool>> {
+        if cache.is_none() {
+            *cache = Some(match tokio::fs::read_to_string(&self.path).await {
+                Ok(content) => serde_json::from_str(&content)?,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+                Err(e) => return Err(e.into()),
+            });
+        }
+        Ok(cache.get_or_insert_with(HashMap::new))
+    }
+
+    async fn persist(&self, tools: &HashMap<String, ScriptTool>) -> Result<()> {
+        config_versions::write(&self.path, serde_json::to_string_pretty(tools)?).await?;
+        Ok(())
+    }
+
+    pub async fn list(&self) -> Result<Vec<ScriptTool>> {
+        let mut cache = self.tools.lock().await;
+        let mut tools: Vec<ScriptTool> = self.loaded(&mut cache).await?.values().cloned().collect();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(tools)
+    }
+
+    pub async fn list_tools(&self) -> Vec<MCPTool> {
+        self.list()
+            .await
+            .unwrap_or_default()
+            .iter()
+            .map(ScriptTool::to_tool)
+            .collect()
+    }
+
+    /// 스크립트 도구를 만들거나 같은 이름의 도구를 교체합니다
+    pub async fn save(&self, tool: ScriptTool) -> Result<()> {
+        if tool.name.is_empty()
+            || !tool
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(anyhow::anyhow!(
+                "Tool names may only contain letters, digits, '_' and '-'"
+            ));
+        }
+        if !tool.source.contains("handler") {
+            return Err(anyhow::anyhow!(
+                "Script must define a handler(args) function"
+            ));
+        }
+        if tool.timeout_secs == 0 || tool.timeout_secs > MAX_TIMEOUT_SECS {
+            return Err(anyhow::anyhow!(
+                "timeout_secs must be between 1 and {}",
+                MAX_TIMEOUT_SECS
+            ));
+        }
+        if tool.memory_mb == 0 || tool.memory_mb > MAX_MEMORY_MB {
+            return Err(anyhow::anyhow!(
+                "memory_mb must be between 1 and {}",
+                MAX_MEMORY_MB
+            ));
+        }
+        let mut cache = self.tools.lock().await;
+        let tools = self.loaded(&mut cache).await?;
+        tools.insert(tool.name.clone(), tool);
+        self.persist(tools).await
+    }
+
+    pub async fn delete(&self, name: &str) -> Result<()> {
+        let mut cache = self.tools.lock().await;
+        let tools = self.loaded(&mut cache).await?;
+        if tools.remove(name).is_none() {
+            return Err(anyhow::anyhow!("Script tool '{}' not found", name));
+        }
+        self.persist(tools).await
+    }
+
+    pub async fn call_tool(&self, name: &str, arguments: serde_json::Value) -> ToolCallResult {
+        let tool = {
+            let mut cache = self.tools.lock().await;
+            match self.loaded(&mut cache).await {
+                Ok(tools) => tools.get(name).cloned(),
+                Err(e) => return error_result(e.to_string()),
+            }
+        };
+        let Some(tool) = tool else {
+            return error_result(format!("Unknown script tool '{}'", name));
+        };
+        match run(&tool, &arguments).await {
+            Ok(output) => text_result(output),
+            Err(e) => error_result(e.to_string()),
+        }
+    }
+}
+
+async fn run(tool: &ScriptTool, arguments: &serde_json::Value) -> Result<String> {
+    let deno = integrity::resolve_executable("deno")
+        .ok_or_else(|| anyhow::anyhow!("Script tools require Deno on PATH"))?;
+
+    let script = std::env::temp_dir().join(format!(
+        "script-tool-{}-{}.ts",
+        tool.name,
+        uuid::Uuid::new_v4().simple()
+    ));
+    tokio::fs::write(&script, wrap_source(&tool.source)).await?;
+
+    let mut command = Command::new(deno);
+    command
+        .args([
+            "run",
+            "--quiet",
+            "--no-prompt",
+            "--no-remote",
+            "--no-npm",
+            "--no-config",
+        ])
+        .arg(format!(
+            "--v8-flags=--max-old-space-size={}",
+            tool.memory_mb
+        ));
+    if !tool.allow_read.is_empty() {
+        command.arg(format!("--allow-read={}", tool.allow_read.join(",")));
+    }
+    if !tool.allow_net.is_empty() {
+        command.arg(format!("--allow-net={}", tool.allow_net.join(",")));
+    }
+    let mut child = command
+        .arg(&script)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    // 입력을 읽지 않는 스크립트에 막히지 않도록 쓰기도 제한 시간에 포함합니다
+    let stdin = child.stdin.take();
+    let input = arguments.to_string();
+    let run = async move {
+        if let Some(mut stdin) = stdin {
+            stdin.write_all(input.as_bytes()).await?;
+        }
+        child.wait_with_output().await
+    };
+
+    let result =
+        tokio::time::timeout(tokio::time::Duration::from_secs(tool.timeout_secs), run).await;
+    let _ = tokio::fs::remove_file(&script).await;
+    let output =
+        result.map_err(|_| anyhow::anyhow!("Script timed out after {}s", tool.timeout_secs))??;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "Script failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    stdout
+        .rsplit_once(RESULT_MARKER)
+        .map(|(_, result)| result.trim().to_string())
+        .ok_or_else(|| anyhow::anyhow!("Script exited without returning a result"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script_tool(allow_net: &[&str]) -> ScriptTool {
+        ScriptTool {
+            name: "tool".to_string(),
+            description: String::new(),
+            input_schema: MCPToolInputSchema::default(),
+            source: "function handler() {}".to_string(),
+            allow_read: vec!["/tmp".to_string()],
+            allow_net: allow_net.iter().map(|host| host.to_string()).collect(),
+            timeout_secs: default_timeout_secs(),
+            memory_mb: default_memory_mb(),
+        }
+    }
+
+    #[test]
+    fn offline_scripts_are_read_only() {
+        let annotations = script_tool(&[]).to_tool().annotations.unwrap();
+        assert_eq!(annotations.read_only_hint, Some(true));
+        assert_eq!(annotations.destructive_hint, Some(false));
+        assert_eq!(annotations.open_world_hint, Some(false));
+    }
+
+    #[test]
+    fn network_access_is_not_read_only() {
+        let annotations = script_tool(&["api.example.com"])
+            .to_tool()
+            .annotations
+            .unwrap();
+        assert_eq!(annotations.read_only_hint, Some(false));
+        assert_eq!(annotations.destructive_hint, None);
+        assert_eq!(annotations.open_world_hint, Some(true));
+    }
+}
@@ -0,0 +1,127 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+
+use crate::config_versions;
+
+// 앱을 GUI에서 실행하면 셸 프로필의 PATH 설정이 빠지기 쉬운 흔한 설치 위치
+const WELL_KNOWN_DIRS: &[&str] = &[
+    "~/.local/bin",
+    "~/.asdf/shims",
+    "~/.volta/bin",
+    "~/.cargo/bin",
+    "~/.bun/bin",
+    "~/.deno/bin",
+    "/opt/homebrew/bin",
+    "/usr/local/bin",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PathSettings {
+    /// Directories searched after the app's own PATH, for every server and tool
+    /// lookup; a leading `~` is the home directory
+    #[serde(default)]
+    pub extra_path_entries: Vec<String>,
+}
+
+#[derive(Default)]
+struct SearchPath {
+    settings: PathSettings,
+    settings_path: Option<PathBuf>,
+}
+
+static SEARCH_PATH: OnceLock<RwLock<SearchPath>> = OnceLock::new();
+
+fn search_path() -> &'static RwLock<SearchPath> {
+    SEARCH_PATH.get_or_init(|| RwLock::new(SearchPath::default()))
+}
+
+pub fn settings() -> PathSettings {
+    search_path()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .settings
+        .clone()
+}
+
+/// Load the persisted settings, ignoring a missing file
+pub fn load_settings(path: &Path) -> Result<()> {
+    let settings = match std::fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => PathSettings::default(),
+        Err(e) => return Err(e.into()),
+    };
+    let mut state = search_path().write().unwrap_or_else(|e| e.into_inner());
+    state.settings = settings;
+    state.settings_path = Some(path.to_path_buf());
+    Ok(())
+}
+
+pub fn set_settings(mut settings: PathSettings) -> Result<PathSettings> {
+    settings.extra_path_entries = settings
+        .extra_path_entries
+        .into_iter()
+        .map(|entry| entry.trim().to_string())
+        .filter(|entry| !entry.is_empty())
+        .collect();
+    let path = {
+        let mut state = search_path().write().unwrap_or_else(|e| e.into_inner());
+        state.settings = settings.clone();
+        state.settings_path.clone()
+    };
+    if let Some(path) = path {
+        config_versions::write_blocking(&path, &serde_json::to_string_pretty(&settings)?)?;
+    }
+    Ok(settings)
+}
+
+fn expand(entry: &str) -> Option<PathBuf> {
+    match entry.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+            Some(dirs::home_dir()?.join(rest.trim_start_matches(['/', '\\'])))
+        }
+        _ => Some(PathBuf::from(entry)),
+    }
+}
+
+/// PATH for a child process: the server's own `path` entries first, then `base`
+/// (the app's PATH unless the config sets one), then the global extra entries.
+/// `None` when there is nothing to add.
+pub fn augmented(server_entries: &[String], base: Option<OsString>) -> Option<OsString> {
+    let extra = settings().extra_path_entries;
+    if server_entries.is_empty() && extra.is_empty() {
+        return None;
+    }
+    let base = base.or_else(|| std::env::var_os("PATH"));
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    let entries = server_entries
+        .iter()
+        .filter_map(|entry| expand(entry))
+        .chain(base.iter().flat_map(std::env::split_paths))
+        .chain(extra.iter().filter_map(|entry| expand(entry)));
+    for dir in entries {
+        if !dir.as_os_str().is_empty() && !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    std::env::join_paths(dirs).ok()
+}
+
+/// The app's PATH with the global extra entries, used to locate executables
+pub fn lookup_path() -> Option<OsString> {
+    augmented(&[], None).or_else(|| std::env::var_os("PATH"))
+}
+
+/// Common install locations that exist but are not searched yet
+pub fn suggestions() -> Vec<String> {
+    let searched: Vec<PathBuf> = lookup_path()
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+    WELL_KNOWN_DIRS
+        .iter()
+        .filter(|entry| expand(entry).is_some_and(|dir| dir.is_dir() && !searched.contains(&dir)))
+        .map(|entry| entry.to_string())
+        .collect()
+}
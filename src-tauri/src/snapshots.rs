@@ -0,0 +1,402 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+const MAX_SNAPSHOT_FILES: usize = 50_000;
+const MAX_SNAPSHOT_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+const MAX_SNAPSHOTS: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub id: String,
+    pub root: PathBuf,
+    pub created_at: String,
+    pub reason: String,
+    pub session_id: Option<String>,
+    pub files: usize,
+    pub bytes: u64,
+    /// Files hardlinked from the previous snapshot of the same directory instead of copied
+    pub shared: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SnapshotEntry {
+    path: PathBuf,
+    size: u64,
+    modified_ns: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    #[serde(flatten)]
+    snapshot: Snapshot,
+    entries: Vec<SnapshotEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreReport {
+    pub snapshot: Snapshot,
+    pub restored: usize,
+    pub removed: usize,
+    /// Snapshot of the directory taken right before restoring, so the restore can be reverted
+    pub safety_snapshot: String,
+}
+
+/// .gitignore를 따르되 숨김 파일(.env 등)은 포함하고 .git 디렉토리는 제외합니다
+fn walk(root: &Path) -> Vec<(PathBuf, SnapshotEntry)> {
+    let mut files = Vec::new();
+    let walker = ignore::WalkBuilder::new(root)
+        .hidden(false)
+        .require_git(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build();
+    for entry in walker {
+        let Ok(entry) = entry else { continue };
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let Ok(relative) = entry.path().strip_prefix(root) else {
+            continue;
+        };
+        let modified_ns = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or_default();
+        files.push((
+            entry.path().to_path_buf(),
+            SnapshotEntry {
+                path: relative.to_path_buf(),
+                size: metadata.len(),
+                modified_ns,
+            },
+        ));
+    }
+    files
+}
+
+/// 임시 파일로 복사한 뒤 교체하며, 지원되는 파일시스템에서는 copy-on-write로 복제합니다
+fn copy_into_place(source: &Path, target: &Path) -> Result<()> {
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file_name = target.file_name().unwrap_or_default();
+    let tmp_path = target.with_file_name(format!(".{}.snapshot-tmp", file_name.to_string_lossy()));
+    let _ = std::fs::remove_file(&tmp_path);
+    reflink_copy::reflink_or_copy(source, &tmp_path)?;
+    std::fs::rename(&tmp_path, target)?;
+    Ok(())
+}
+
+/// Whole-directory snapshots taken before risky agent runs, restorable in one step
+pub struct SnapshotStore {
+    dir: PathBuf,
+    lock: Mutex<()>,
+    // 이번 실행에서 이미 자동 스냅샷을 시도한 (세션, 루트) 조합
+    attempted: Mutex<HashSet<(String, PathBuf)>>,
+}
+
+impl SnapshotStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            lock: Mutex::new(()),
+            attempted: Mutex::new(HashSet::new()),
+        }
+    }
+
+    fn manifest_path(&self, id: &str) -> PathBuf {
+        self.dir.join(id).join("manifest.json")
+    }
+
+    fn files_dir(&self, id: &str) -> PathBuf {
+        self.dir.join(id).join("files")
+    }
+
+    fn load(&self, id: &str) -> Result<Manifest> {
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+            return Err(anyhow::anyhow!("Invalid snapshot id '{}'", id));
+        }
+        match std::fs::read_to_string(self.manifest_path(id)) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                Err(anyhow::anyhow!("Unknown snapshot '{}'", id))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 모든 스냅샷의 매니페스트를 최신순으로 읽습니다
+    fn manifests(&self) -> Result<Vec<Manifest>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut manifests = Vec::new();
+        for entry in entries.flatten() {
+            // 만들다 만 스냅샷은 매니페스트가 없으므로 건너뜁니다
+            if let Ok(content) = std::fs::read_to_string(entry.path().join("manifest.json")) {
+                if let Ok(manifest) = serde_json::from_str::<Manifest>(&content) {
+                    manifests.push(manifest);
+                }
+            }
+        }
+        manifests.sort_by(|a, b| b.snapshot.created_at.cmp(&a.snapshot.created_at));
+        Ok(manifests)
+    }
+
+    pub fn get(&self, id: &str) -> Result<Snapshot> {
+        Ok(self.load(id)?.snapshot)
+    }
+
+    /// Snapshots newest first, optionally only those of one directory
+    pub fn list(&self, root: Option<&Path>) -> Result<Vec<Snapshot>> {
+        Ok(self
+            .manifests()?
+            .into_iter()
+            .map(|manifest| manifest.snapshot)
+            .filter(|snapshot| root.is_none_or(|root| snapshot.root == root))
+            .collect())
+    }
+
+    /// Copy every non-ignored file under `root` into a new snapshot
+    pub fn create(&self, root: &Path, reason: &str, session_id: Option<&str>) -> Result<Snapshot> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let snapshot = self.create_locked(root, reason, session_id)?;
+        self.prune()?;
+        Ok(snapshot)
+    }
+
+    fn create_locked(
+        &self,
+        root: &Path,
+        reason: &str,
+        session_id: Option<&str>,
+    ) -> Result<Snapshot> {
+        if !root.is_dir() {
+            return Err(anyhow::anyhow!("{} is not a directory", root.display()));
+        }
+        let files = walk(root);
+        let bytes: u64 = files.iter().map(|(_, entry)| entry.size).sum();
+        if files.len() > MAX_SNAPSHOT_FILES || bytes > MAX_SNAPSHOT_BYTES {
+            return Err(anyhow::anyhow!(
+                "{} is too large to snapshot ({} files, {} MiB; limits are {} files and {} MiB)",
+                root.display(),
+                files.len(),
+                bytes / 1024 / 1024,
+                MAX_SNAPSHOT_FILES,
+                MAX_SNAPSHOT_BYTES / 1024 / 1024
+            ));
+        }
+
+        // 직전 스냅샷 이후 바뀌지 않은 파일은 하드링크로 공유합니다 (rsync --link-dest 방식)
+        let previous = self
+            .manifests()?
+            .into_iter()
+            .find(|manifest| manifest.snapshot.root == root);
+        let unchanged: HashMap<&Path, &SnapshotEntry> = previous
+            .iter()
+            .flat_map(|manifest| &manifest.entries)
+            .map(|entry| (entry.path.as_path(), entry))
+            .collect();
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let files_dir = self.files_dir(&id);
+        std::fs::create_dir_all(&files_dir)?;
+        let mut shared = 0;
+        let mut entries = Vec::with_capacity(files.len());
+        let copied: Result<()> = files.into_iter().try_for_each(|(source, entry)| {
+            let target = files_dir.join(&entry.path);
+            let linked = match (previous.as_ref(), unchanged.get(entry.path.as_path())) {
+                (Some(manifest), Some(old))
+                    if old.size == entry.size && old.modified_ns == entry.modified_ns =>
+                {
+                    let old_file = self.files_dir(&manifest.snapshot.id).join(&entry.path);
+                    target
+                        .parent()
+                        .map_or(Ok(()), std::fs::create_dir_all)
+                        .and_then(|_| std::fs::hard_link(&old_file, &target))
+                        .is_ok()
+                }
+                _ => false,
+            };
+            if linked {
+                shared += 1;
+            } else {
+                match copy_into_place(&source, &target) {
+                    Ok(()) => {}
+                    // 걷는 사이에 지워진 파일은 스냅샷에서 빠집니다
+                    Err(e)
+                        if e.downcast_ref::<std::io::Error>()
+                            .is_some_and(|e| e.kind() == ErrorKind::NotFound) =>
+                    {
+                        return Ok(())
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            entries.push(entry);
+            Ok(())
+        });
+        if let Err(e) = copied {
+            let _ = std::fs::remove_dir_all(self.dir.join(&id));
+            return Err(e);
+        }
+
+        let snapshot = Snapshot {
+            id: id.clone(),
+            root: root.to_path_buf(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            reason: reason.to_string(),
+            session_id: session_id.map(str::to_string),
+            files: entries.len(),
+            bytes: entries.iter().map(|entry| entry.size).sum(),
+            shared,
+        };
+        let manifest = Manifest {
+            snapshot: snapshot.clone(),
+            entries,
+        };
+        let manifest_path = self.manifest_path(&id);
+        let tmp_path = manifest_path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_string(&manifest)?)?;
+        std::fs::rename(&tmp_path, &manifest_path)?;
+        println!(
+            "📸 Snapshot {} of {}: {} files ({} shared with the previous snapshot)",
+            id,
+            root.display(),
+            snapshot.files,
+            shared
+        );
+        Ok(snapshot)
+    }
+
+    /// 가장 최근 스냅샷만 남기고 나머지는 지웁니다
+    fn prune(&self) -> Result<()> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        let keep: HashSet<String> = self
+            .manifests()?
+            .into_iter()
+            .take(MAX_SNAPSHOTS)
+            .map(|manifest| manifest.snapshot.id)
+            .collect();
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !keep.contains(&name) {
+                let _ = std::fs::remove_dir_all(entry.path());
+            }
+        }
+        Ok(())
+    }
+
+    /// Snapshot `root` once per agent session, before the session first changes it.
+    /// Returns `None` when this session already has (or already tried) one.
+    pub fn ensure_session_snapshot(
+        &self,
+        session_id: &str,
+        root: &Path,
+    ) -> Result<Option<Snapshot>> {
+        let key = (session_id.to_string(), root.to_path_buf());
+        if !self
+            .attempted
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key)
+        {
+            return Ok(None);
+        }
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let existing = self.manifests()?.into_iter().any(|manifest| {
+            manifest.snapshot.root == root
+                && manifest.snapshot.session_id.as_deref() == Some(session_id)
+        });
+        if existing {
+            return Ok(None);
+        }
+        let snapshot = self.create_locked(
+            root,
+            "Automatic, before the agent first changed this workspace",
+            Some(session_id),
+        )?;
+        self.prune()?;
+        Ok(Some(snapshot))
+    }
+
+    /// Put the directory back exactly as it was in the snapshot.
+    /// Files created since then are removed; ignored files are left alone.
+    pub fn restore(&self, id: &str) -> Result<RestoreReport> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let manifest = self.load(id)?;
+        let root = manifest.snapshot.root.clone();
+        std::fs::create_dir_all(&root)?;
+        let safety = self.create_locked(
+            &root,
+            &format!("Automatic, before restoring snapshot {}", id),
+            None,
+        )?;
+
+        let wanted: HashSet<&Path> = manifest
+            .entries
+            .iter()
+            .map(|entry| entry.path.as_path())
+            .collect();
+        let current: HashMap<PathBuf, SnapshotEntry> = self
+            .load(&safety.id)?
+            .entries
+            .into_iter()
+            .map(|entry| (entry.path.clone(), entry))
+            .collect();
+
+        let mut removed = 0;
+        for path in current
+            .keys()
+            .filter(|path| !wanted.contains(path.as_path()))
+        {
+            match std::fs::remove_file(root.join(path)) {
+                Ok(()) => removed += 1,
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let files_dir = self.files_dir(id);
+        let mut restored = 0;
+        for entry in &manifest.entries {
+            let unchanged = current
+                .get(&entry.path)
+                .is_some_and(|now| now.size == entry.size && now.modified_ns == entry.modified_ns);
+            if !unchanged {
+                copy_into_place(&files_dir.join(&entry.path), &root.join(&entry.path))?;
+                restored += 1;
+            }
+        }
+        println!(
+            "Restored snapshot {} into {} ({} files restored, {} removed)",
+            id,
+            root.display(),
+            restored,
+            removed
+        );
+        // 복원 대상이 지워지지 않도록 정리는 복원을 마친 뒤에 합니다
+        self.prune()?;
+        Ok(RestoreReport {
+            snapshot: manifest.snapshot,
+            restored,
+            removed,
+            safety_snapshot: safety.id,
+        })
+    }
+}
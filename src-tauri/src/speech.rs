@@ -0,0 +1,236 @@
+use anyhow::Result;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::{mpsc, Notify};
+
+use crate::events;
+use crate::integrity;
+
+struct Utterance {
+    id: u64,
+    /// `stop` 호출 시점의 세대; 이전 세대의 항목은 건너뜁니다
+    generation: u64,
+    text: String,
+    voice: Option<String>,
+}
+
+/// Reads queued text aloud one utterance at a time
+///
+/// Uses piper when the voice names a `.onnx` model (in `<app-data>/voices` or
+/// an absolute path), otherwise the platform synthesizer.
+pub struct SpeechQueue {
+    voices_dir: PathBuf,
+    sender: OnceLock<mpsc::UnboundedSender<Utterance>>,
+    next_id: AtomicU64,
+    generation: AtomicU64,
+    cancel: Notify,
+}
+
+impl SpeechQueue {
+    pub fn new(voices_dir: PathBuf) -> Self {
+        Self {
+            voices_dir,
+            sender: OnceLock::new(),
+            next_id: AtomicU64::new(1),
+            generation: AtomicU64::new(0),
+            cancel: Notify::new(),
+        }
+    }
+
+    /// Queue text for playback; returns the utterance id used in `speech-*` events
+    pub fn speak(&'static self, text: String, voice: Option<String>) -> Result<u64> {
+        if text.trim().is_empty() {
+            return Err(anyhow::anyhow!("Nothing to speak"));
+        }
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let utterance = Utterance {
+            id,
+            generation: self.generation.load(Ordering::SeqCst),
+            text,
+            voice,
+        };
+        self.sender
+            .get_or_init(|| {
+                let (sender, receiver) = mpsc::unbounded_channel();
+                tokio::spawn(self.worker(receiver));
+                sender
+            })
+            .send(utterance)
+            .map_err(|_| anyhow::anyhow!("Speech worker has stopped"))?;
+        Ok(id)
+    }
+
+    /// 현재 재생을 멈추고 대기 중인 항목을 모두 버립니다
+    pub fn stop(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.cancel.notify_waiters();
+    }
+
+    async fn worker(&'static self, mut receiver: mpsc::UnboundedReceiver<Utterance>) {
+        while let Some(utterance) = receiver.recv().await {
+            if utterance.generation != self.generation.load(Ordering::SeqCst) {
+                continue;
+            }
+            events::emit("speech-started", json!({ "id": utterance.id }));
+            let result = self.play(&utterance).await;
+            let error = result.err().map(|e| e.to_string());
+            if let Some(error) = &error {
+                log::warn!("Speech playback failed: {}", error);
+            }
+            events::emit(
+                "speech-finished",
+                json!({ "id": utterance.id, "error": error }),
+            );
+        }
+    }
+
+    fn piper_model(&self, voice: Option<&str>) -> Option<PathBuf> {
+        let voice = voice.filter(|v| v.ends_with(".onnx"))?;
+        let path = Path::new(voice);
+        Some(if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.voices_dir.join(voice)
+        })
+    }
+
+    async fn play(&self, utterance: &Utterance) -> Result<()> {
+        let Some(model) = self.piper_model(utterance.voice.as_deref()) else {
+            let command = platform_command(utterance.voice.as_deref())?;
+            return self
+                .run(command, Some(&utterance.text), utterance.generation)
+                .await;
+        };
+
+        let piper = integrity::resolve_executable("piper")
+            .ok_or_else(|| anyhow::anyhow!("piper is not installed"))?;
+        let wav = std::env::temp_dir().join(format!("speech-{}.wav", uuid::Uuid::new_v4()));
+        let mut synthesize = Command::new(piper);
+        synthesize
+            .arg("--model")
+            .arg(&model)
+            .arg("--output_file")
+            .arg(&wav);
+        let result = match self
+            .run(synthesize, Some(&utterance.text), utterance.generation)
+            .await
+        {
+            Ok(()) => {
+                self.run(player_command(&wav)?, None, utterance.generation)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        let _ = tokio::fs::remove_file(&wav).await;
+        result
+    }
+
+    /// 프로세스를 실행하고 끝나거나 `stop`이 호출될 때까지 기다립니다
+    async fn run(&self, mut command: Command, input: Option<&str>, generation: u64) -> Result<()> {
+        // 알림을 먼저 등록해야 확인과 실행 사이의 stop을 놓치지 않습니다
+        let cancelled = self.cancel.notified();
+        tokio::pin!(cancelled);
+        cancelled.as_mut().enable();
+        if generation != self.generation.load(Ordering::SeqCst) {
+            return Err(anyhow::anyhow!("Stopped"));
+        }
+
+        let mut child = command
+            .stdin(if input.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+        if let (Some(mut stdin), Some(input)) = (child.stdin.take(), input) {
+            stdin.write_all(input.as_bytes()).await?;
+        }
+
+        tokio::select! {
+            output = child.wait_with_output() => {
+                let output = output?;
+                if !output.status.success() {
+                    return Err(anyhow::anyhow!(
+                        "{}",
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    ));
+                }
+                Ok(())
+            }
+            // future가 drop되면서 kill_on_drop으로 프로세스가 종료됩니다
+            _ = cancelled => Err(anyhow::anyhow!("Stopped")),
+        }
+    }
+}
+
+fn platform_command(voice: Option<&str>) -> Result<Command> {
+    if cfg!(target_os = "macos") {
+        let mut command = Command::new("say");
+        if let Some(voice) = voice {
+            command.args(["-v", voice]);
+        }
+        command.args(["-f", "-"]);
+        Ok(command)
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("powershell");
+        command.args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "Add-Type -AssemblyName System.Speech; \
+             $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+             if ($env:SPEECH_VOICE) { $s.SelectVoice($env:SPEECH_VOICE) }; \
+             $s.Speak([Console]::In.ReadToEnd())",
+        ]);
+        if let Some(voice) = voice {
+            command.env("SPEECH_VOICE", voice);
+        }
+        Ok(command)
+    } else {
+        let espeak = ["espeak-ng", "espeak"]
+            .iter()
+            .find_map(|name| integrity::resolve_executable(name))
+            .ok_or_else(|| anyhow::anyhow!("No speech synthesizer found (install espeak-ng)"))?;
+        let mut command = Command::new(espeak);
+        if let Some(voice) = voice {
+            command.args(["-v", voice]);
+        }
+        command.arg("--stdin");
+        Ok(command)
+    }
+}
+
+fn player_command(wav: &Path) -> Result<Command> {
+    if cfg!(target_os = "macos") {
+        let mut command = Command::new("afplay");
+        command.arg(wav);
+        Ok(command)
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("powershell");
+        command
+            .args([
+                "-NoProfile",
+                "-NonInteractive",
+                "-Command",
+                "(New-Object Media.SoundPlayer $env:SPEECH_WAV).PlaySync()",
+            ])
+            .env("SPEECH_WAV", wav);
+        Ok(command)
+    } else {
+        let player = ["paplay", "aplay", "pw-play"]
+            .iter()
+            .find_map(|name| integrity::resolve_executable(name))
+            .ok_or_else(|| anyhow::anyhow!("No audio player found (paplay/aplay/pw-play)"))?;
+        let mut command = Command::new(player);
+        command.arg(wav);
+        Ok(command)
+    }
+}
@@ -0,0 +1,179 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::time::Duration;
+use sysinfo::{Disks, ProcessRefreshKind, ProcessesToUpdate, System};
+
+use crate::api::ask_approval;
+
+const DEFAULT_PROCESS_LIMIT: usize = 20;
+const MAX_PROCESS_LIMIT: usize = 100;
+
+// CPU 사용률은 두 번 측정한 차이로 계산되므로 최소 간격만큼 기다립니다
+fn cpu_sample_interval() -> Duration {
+    sysinfo::MINIMUM_CPU_UPDATE_INTERVAL.max(Duration::from_millis(200))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskUsage {
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+    pub removable: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemOverview {
+    pub os: Option<String>,
+    pub kernel: Option<String>,
+    pub arch: String,
+    pub host_name: Option<String>,
+    pub uptime_secs: u64,
+    pub cpu_brand: Option<String>,
+    pub cpu_count: usize,
+    pub physical_cores: Option<usize>,
+    pub cpu_usage_percent: f32,
+    /// 1, 5 and 15 minute load averages; zero on Windows
+    pub load_average: [f64; 3],
+    pub total_memory_bytes: u64,
+    pub used_memory_bytes: u64,
+    pub total_swap_bytes: u64,
+    pub used_swap_bytes: u64,
+    pub disks: Vec<DiskUsage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub status: String,
+    pub run_time_secs: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProcessSort {
+    Cpu,
+    Memory,
+}
+
+impl ProcessSort {
+    pub fn parse(value: Option<&str>) -> Result<Self> {
+        match value.unwrap_or("cpu") {
+            "cpu" => Ok(Self::Cpu),
+            "memory" => Ok(Self::Memory),
+            other => Err(anyhow::anyhow!("Unknown sort '{}' (cpu or memory)", other)),
+        }
+    }
+}
+
+/// OS, CPU, memory and disk usage after the user approves
+pub async fn overview() -> Result<SystemOverview> {
+    ask_approval(
+        "system-info",
+        "The assistant wants to read your system information (OS, CPU, memory and disks). Allow?"
+            .to_string(),
+    )
+    .await
+    .map_err(|e| anyhow::anyhow!(e))?;
+
+    Ok(tokio::task::spawn_blocking(|| {
+        let mut system = System::new();
+        system.refresh_cpu_all();
+        std::thread::sleep(cpu_sample_interval());
+        system.refresh_cpu_all();
+        system.refresh_memory();
+
+        let load = System::load_average();
+        let disks = Disks::new_with_refreshed_list()
+            .iter()
+            .map(|disk| DiskUsage {
+                name: disk.name().to_string_lossy().to_string(),
+                mount_point: disk.mount_point().display().to_string(),
+                file_system: disk.file_system().to_string_lossy().to_string(),
+                total_bytes: disk.total_space(),
+                available_bytes: disk.available_space(),
+                removable: disk.is_removable(),
+            })
+            .collect();
+
+        SystemOverview {
+            os: System::long_os_version(),
+            kernel: System::kernel_version(),
+            arch: System::cpu_arch(),
+            host_name: System::host_name(),
+            uptime_secs: System::uptime(),
+            cpu_brand: system
+                .cpus()
+                .first()
+                .map(|cpu| cpu.brand().trim().to_string()),
+            cpu_count: system.cpus().len(),
+            physical_cores: System::physical_core_count(),
+            cpu_usage_percent: system.global_cpu_usage(),
+            load_average: [load.one, load.five, load.fifteen],
+            total_memory_bytes: system.total_memory(),
+            used_memory_bytes: system.used_memory(),
+            total_swap_bytes: system.total_swap(),
+            used_swap_bytes: system.used_swap(),
+            disks,
+        }
+    })
+    .await?)
+}
+
+/// Top processes by CPU or memory, optionally filtered by name, after the user approves.
+/// Command lines are left out because they often carry tokens or file paths.
+pub async fn processes(
+    filter: Option<String>,
+    sort: ProcessSort,
+    limit: Option<usize>,
+) -> Result<Vec<ProcessInfo>> {
+    ask_approval(
+        "system-info",
+        "The assistant wants to list the processes running on your computer. Allow?".to_string(),
+    )
+    .await
+    .map_err(|e| anyhow::anyhow!(e))?;
+
+    let limit = limit
+        .unwrap_or(DEFAULT_PROCESS_LIMIT)
+        .clamp(1, MAX_PROCESS_LIMIT);
+    let filter = filter.map(|f| f.to_lowercase());
+
+    Ok(tokio::task::spawn_blocking(move || {
+        let mut system = System::new();
+        let refresh = ProcessRefreshKind::nothing().with_cpu().with_memory();
+        system.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
+        std::thread::sleep(cpu_sample_interval());
+        system.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
+
+        let mut processes: Vec<ProcessInfo> = system
+            .processes()
+            .values()
+            // 스레드는 부모 프로세스와 같은 이름으로 중복 표시되므로 제외합니다
+            .filter(|process| process.thread_kind().is_none())
+            .map(|process| ProcessInfo {
+                pid: process.pid().as_u32(),
+                name: process.name().to_string_lossy().to_string(),
+                cpu_percent: process.cpu_usage(),
+                memory_bytes: process.memory(),
+                status: process.status().to_string(),
+                run_time_secs: process.run_time(),
+            })
+            .filter(|process| {
+                filter
+                    .as_ref()
+                    .is_none_or(|f| process.name.to_lowercase().contains(f))
+            })
+            .collect();
+        match sort {
+            ProcessSort::Cpu => processes.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent)),
+            ProcessSort::Memory => processes.sort_by_key(|p| std::cmp::Reverse(p.memory_bytes)),
+        }
+        processes.truncate(limit);
+        processes
+    })
+    .await?)
+}
@@ -0,0 +1,275 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config_versions;
+
+const FLUSH_INTERVAL_SECS: u64 = 5;
+// 수집기가 꺼져 있을 때 메모리가 계속 늘어나지 않도록 제한합니다
+const MAX_PENDING_SPANS: usize = 5000;
+
+/// Optional OTLP/HTTP export; off by default
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    pub enabled: bool,
+    /// Base URL of an OTLP/HTTP collector, e.g. a local Jaeger or Grafana Alloy
+    pub endpoint: String,
+    pub service_name: String,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://localhost:4318".to_string(),
+            service_name: "tauri-mcp-agent".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpanKind {
+    Internal,
+    Client,
+}
+
+#[derive(Debug, Clone)]
+pub struct Span {
+    pub name: String,
+    pub kind: SpanKind,
+    pub start: SystemTime,
+    pub end: SystemTime,
+    pub attributes: Vec<(String, String)>,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+struct TelemetryState {
+    config: TelemetryConfig,
+    config_path: Option<PathBuf>,
+}
+
+static STATE: OnceLock<RwLock<TelemetryState>> = OnceLock::new();
+static PENDING: Mutex<Vec<Span>> = Mutex::new(Vec::new());
+// (metric name, server name) -> cumulative count
+static COUNTERS: OnceLock<Mutex<HashMap<(String, String), u64>>> = OnceLock::new();
+static STARTED_AT: OnceLock<SystemTime> = OnceLock::new();
+
+fn state() -> &'static RwLock<TelemetryState> {
+    STATE.get_or_init(|| RwLock::new(TelemetryState::default()))
+}
+
+fn counters() -> &'static Mutex<HashMap<(String, String), u64>> {
+    COUNTERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub fn is_enabled() -> bool {
+    state()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .config
+        .enabled
+}
+
+pub fn config() -> TelemetryConfig {
+    state()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .config
+        .clone()
+}
+
+/// Load the persisted config, ignoring a missing file
+pub fn load_config(path: &Path) -> Result<()> {
+    let config = match std::fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => TelemetryConfig::default(),
+        Err(e) => return Err(e.into()),
+    };
+    let mut state = state().write().unwrap_or_else(|e| e.into_inner());
+    state.config = config;
+    state.config_path = Some(path.to_path_buf());
+    Ok(())
+}
+
+pub fn set_config(config: TelemetryConfig) -> Result<()> {
+    let path = {
+        let mut state = state().write().unwrap_or_else(|e| e.into_inner());
+        state.config = config.clone();
+        state.config_path.clone()
+    };
+    if !config.enabled {
+        PENDING.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+    if let Some(path) = path {
+        config_versions::write_blocking(&path, &serde_json::to_string_pretty(&config)?)?;
+    }
+    Ok(())
+}
+
+/// Queue a finished span for export; a no-op while telemetry is disabled
+pub fn record_span(span: Span) {
+    if !is_enabled() {
+        return;
+    }
+    let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+    if pending.len() < MAX_PENDING_SPANS {
+        pending.push(span);
+    }
+}
+
+pub fn increment(metric: &str, server_name: &str) {
+    if !is_enabled() {
+        return;
+    }
+    *counters()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry((metric.to_string(), server_name.to_string()))
+        .or_default() += 1;
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn random_hex(bytes: usize) -> String {
+    let mut hex = uuid::Uuid::new_v4().simple().to_string();
+    hex.push_str(&uuid::Uuid::new_v4().simple().to_string());
+    hex.truncate(bytes * 2);
+    hex
+}
+
+fn attributes(pairs: &[(String, String)]) -> Vec<serde_json::Value> {
+    pairs
+        .iter()
+        .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+        .collect()
+}
+
+fn resource(config: &TelemetryConfig) -> serde_json::Value {
+    json!({
+        "attributes": attributes(&[
+            ("service.name".to_string(), config.service_name.clone()),
+            ("service.version".to_string(), env!("CARGO_PKG_VERSION").to_string()),
+        ])
+    })
+}
+
+fn traces_payload(config: &TelemetryConfig, spans: &[Span]) -> serde_json::Value {
+    let spans: Vec<serde_json::Value> = spans
+        .iter()
+        .map(|span| {
+            let status = match &span.error {
+                Some(message) => json!({ "code": 2, "message": message }),
+                None => json!({ "code": 1 }),
+            };
+            json!({
+                "traceId": random_hex(16),
+                "spanId": random_hex(8),
+                "name": span.name,
+                "kind": match span.kind {
+                    SpanKind::Internal => 1,
+                    SpanKind::Client => 3,
+                },
+                "startTimeUnixNano": unix_nanos(span.start),
+                "endTimeUnixNano": unix_nanos(span.end),
+                "attributes": attributes(&span.attributes),
+                "status": status,
+            })
+        })
+        .collect();
+    json!({
+        "resourceSpans": [{
+            "resource": resource(config),
+            "scopeSpans": [{ "scope": { "name": "tauri-mcp-agent" }, "spans": spans }]
+        }]
+    })
+}
+
+fn metrics_payload(config: &TelemetryConfig) -> Option<serde_json::Value> {
+    let counters = counters().lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if counters.is_empty() {
+        return None;
+    }
+    let start = unix_nanos(*STARTED_AT.get_or_init(SystemTime::now));
+    let now = unix_nanos(SystemTime::now());
+
+    let mut by_metric: HashMap<String, Vec<serde_json::Value>> = HashMap::new();
+    for ((metric, server_name), count) in counters {
+        by_metric.entry(metric).or_default().push(json!({
+            "attributes": attributes(&[("mcp.server".to_string(), server_name)]),
+            "startTimeUnixNano": start,
+            "timeUnixNano": now,
+            "asInt": count.to_string(),
+        }));
+    }
+    let metrics: Vec<serde_json::Value> = by_metric
+        .into_iter()
+        .map(|(name, data_points)| {
+            json!({
+                "name": name,
+                // 누적(cumulative), 단조 증가 카운터
+                "sum": {
+                    "dataPoints": data_points,
+                    "aggregationTemporality": 2,
+                    "isMonotonic": true
+                }
+            })
+        })
+        .collect();
+    Some(json!({
+        "resourceMetrics": [{
+            "resource": resource(config),
+            "scopeMetrics": [{ "scope": { "name": "tauri-mcp-agent" }, "metrics": metrics }]
+        }]
+    }))
+}
+
+async fn post(client: &reqwest::Client, url: String, payload: serde_json::Value) -> Result<()> {
+    let response = client.post(&url).json(&payload).send().await?;
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!("{} returned {}", url, response.status()));
+    }
+    Ok(())
+}
+
+/// 대기 중인 span과 카운터를 주기적으로 OTLP 수집기로 보냅니다
+pub async fn run_exporter() {
+    STARTED_AT.get_or_init(SystemTime::now);
+    let client = reqwest::Client::new();
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(FLUSH_INTERVAL_SECS)).await;
+        if !is_enabled() {
+            continue;
+        }
+        let config = config();
+        let endpoint = config.endpoint.trim_end_matches('/').to_string();
+
+        let spans = std::mem::take(&mut *PENDING.lock().unwrap_or_else(|e| e.into_inner()));
+        if !spans.is_empty() {
+            if let Err(e) = post(
+                &client,
+                format!("{}/v1/traces", endpoint),
+                traces_payload(&config, &spans),
+            )
+            .await
+            {
+                eprintln!("❌ Failed to export {} spans: {}", spans.len(), e);
+            }
+        }
+        if let Some(payload) = metrics_payload(&config) {
+            if let Err(e) = post(&client, format!("{}/v1/metrics", endpoint), payload).await {
+                eprintln!("❌ Failed to export metrics: {}", e);
+            }
+        }
+    }
+}
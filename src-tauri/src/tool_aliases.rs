@@ -0,0 +1,177 @@
+use anyhow::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tokio::sync::RwLock;
+
+use crate::config_versions;
+use crate::mcp::MCPTool;
+
+const DELIMITER: &str = "__";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolAliasSettings {
+    /// Server name → tool name → the name the model sees instead of `server__tool`
+    #[serde(default)]
+    pub aliases: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl ToolAliasSettings {
+    fn alias(&self, server_name: &str, tool_name: &str) -> Option<&str> {
+        self.aliases
+            .get(server_name)
+            .and_then(|tools| tools.get(tool_name))
+            .map(String::as_str)
+    }
+
+    /// Reject aliases that are not valid function names or that are used twice.
+    /// Aliases may not contain `__`, so they never collide with a prefixed name.
+    pub fn validate(&self) -> Result<()> {
+        static RE: OnceLock<Regex> = OnceLock::new();
+        let re = RE.get_or_init(|| Regex::new(r"^[A-Za-z0-9_-]{1,64}$").unwrap());
+        let mut seen: HashMap<&str, (&str, &str)> = HashMap::new();
+        for (server_name, tools) in &self.aliases {
+            for (tool_name, alias) in tools {
+                if !re.is_match(alias) || alias.contains(DELIMITER) {
+                    anyhow::bail!(
+                        "Invalid alias '{}' for {}{}{}: use up to 64 letters, digits, '_' or '-' without '{}'",
+                        alias,
+                        server_name,
+                        DELIMITER,
+                        tool_name,
+                        DELIMITER
+                    );
+                }
+                if let Some((other_server, other_tool)) =
+                    seen.insert(alias, (server_name, tool_name))
+                {
+                    anyhow::bail!(
+                        "Alias '{}' is used by both {}{}{} and {}{}{}",
+                        alias,
+                        other_server,
+                        DELIMITER,
+                        other_tool,
+                        server_name,
+                        DELIMITER,
+                        tool_name
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The server and tool behind a name the model called
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedToolName {
+    pub server_name: String,
+    pub tool_name: String,
+    pub alias: Option<String>,
+}
+
+/// Per-server renames applied to the tools the model sees, and their reverse mapping
+pub struct ToolAliases {
+    settings_path: PathBuf,
+    settings: RwLock<Option<ToolAliasSettings>>,
+}
+
+impl ToolAliases {
+    pub fn new(settings_path: PathBuf) -> Self {
+        Self {
+            settings_path,
+            settings: RwLock::new(None),
+        }
+    }
+
+    /// Forget the cached copy so the next read picks up the file on disk
+    pub async fn reload(&self) {
+        *self.settings.write().await = None;
+    }
+
+    pub async fn settings(&self) -> ToolAliasSettings {
+        if let Some(settings) = self.settings.read().await.as_ref() {
+            return settings.clone();
+        }
+        let settings: ToolAliasSettings = tokio::fs::read_to_string(&self.settings_path)
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        *self.settings.write().await = Some(settings.clone());
+        settings
+    }
+
+    pub async fn save_settings(&self, settings: ToolAliasSettings) -> Result<()> {
+        settings.validate()?;
+        config_versions::write(
+            &self.settings_path,
+            serde_json::to_string_pretty(&settings)?,
+        )
+        .await?;
+        *self.settings.write().await = Some(settings);
+        Ok(())
+    }
+
+    /// Rename `server__tool` tools that have an alias.
+    /// An alias that would clash with another exposed name is skipped and both tools keep
+    /// their prefixed names, so the model never sees two tools with the same name.
+    pub async fn apply(&self, tools: &mut [MCPTool]) {
+        let settings = self.settings().await;
+        if settings.aliases.is_empty() {
+            return;
+        }
+
+        let mut wanted: HashMap<String, Vec<usize>> = HashMap::new();
+        for (index, tool) in tools.iter().enumerate() {
+            if let Some(alias) = tool
+                .name
+                .split_once(DELIMITER)
+                .and_then(|(server_name, tool_name)| settings.alias(server_name, tool_name))
+            {
+                wanted.entry(alias.to_string()).or_default().push(index);
+            }
+        }
+        let taken: HashSet<String> = tools.iter().map(|tool| tool.name.clone()).collect();
+        for (alias, indices) in wanted {
+            // 설정 파일을 직접 고친 경우에도 이름이 겹치지 않도록 다시 확인합니다
+            if indices.len() > 1 || taken.contains(&alias) {
+                let names: Vec<&str> = indices.iter().map(|&i| tools[i].name.as_str()).collect();
+                eprintln!(
+                    "⚠️ Tool alias '{}' collides ({}), keeping prefixed names",
+                    alias,
+                    names.join(", ")
+                );
+                continue;
+            }
+            tools[indices[0]].name = alias;
+        }
+    }
+
+    /// Map a name from the model back to its server and tool: an alias, or `server__tool`
+    pub async fn resolve(&self, name: &str) -> Option<ResolvedToolName> {
+        let settings = self.settings().await;
+        let aliased = settings.aliases.iter().find_map(|(server_name, tools)| {
+            tools
+                .iter()
+                .find(|(_, alias)| alias.as_str() == name)
+                .map(|(tool_name, _)| (server_name, tool_name))
+        });
+        if let Some((server_name, tool_name)) = aliased {
+            return Some(ResolvedToolName {
+                server_name: server_name.clone(),
+                tool_name: tool_name.clone(),
+                alias: Some(name.to_string()),
+            });
+        }
+        name.split_once(DELIMITER)
+            .filter(|(server_name, tool_name)| !server_name.is_empty() && !tool_name.is_empty())
+            .map(|(server_name, tool_name)| ResolvedToolName {
+                server_name: server_name.to_string(),
+                tool_name: tool_name.to_string(),
+                alias: None,
+            })
+    }
+}
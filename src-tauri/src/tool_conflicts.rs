@@ -0,0 +1,148 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use tokio::sync::RwLock;
+
+use crate::config_versions;
+use crate::mcp::MCPTool;
+
+/// What to do when several servers export a tool with the same name
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CollisionStrategy {
+    /// Expose every copy under its `server__tool` name
+    #[default]
+    Prefix,
+    /// Expose only the copy from the server listed first in `server_priority`
+    PreferPriorityServer,
+    /// Expose none of the copies
+    HideDuplicates,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolConflictSettings {
+    #[serde(default)]
+    pub strategy: CollisionStrategy,
+    /// Servers in order of preference; unlisted servers follow in listing order
+    #[serde(default)]
+    pub server_priority: Vec<String>,
+}
+
+impl ToolConflictSettings {
+    fn rank(&self, server_name: &str) -> usize {
+        self.server_priority
+            .iter()
+            .position(|s| s == server_name)
+            .unwrap_or(self.server_priority.len())
+    }
+}
+
+/// A tool name exported by more than one server
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolConflict {
+    pub tool_name: String,
+    /// Servers exporting the tool, most preferred first
+    pub servers: Vec<String>,
+    /// Servers whose copy the model sees under the current strategy
+    pub exposed: Vec<String>,
+}
+
+/// Group prefixed tools by their bare name and decide which copies stay visible
+fn find(settings: &ToolConflictSettings, tools: &[MCPTool]) -> Vec<ToolConflict> {
+    let mut by_name: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for tool in tools {
+        if let Some((server_name, tool_name)) = tool.name.split_once("__") {
+            let servers = by_name.entry(tool_name).or_default();
+            if !servers.contains(&server_name) {
+                servers.push(server_name);
+            }
+        }
+    }
+    by_name
+        .into_iter()
+        .filter(|(_, servers)| servers.len() > 1)
+        .map(|(tool_name, mut servers)| {
+            // 정렬이 안정적이므로 우선순위가 같은 서버는 목록 순서를 유지합니다
+            servers.sort_by_key(|server_name| settings.rank(server_name));
+            let exposed = match settings.strategy {
+                CollisionStrategy::Prefix => servers.clone(),
+                CollisionStrategy::PreferPriorityServer => servers[..1].to_vec(),
+                CollisionStrategy::HideDuplicates => Vec::new(),
+            };
+            ToolConflict {
+                tool_name: tool_name.to_string(),
+                servers: servers.into_iter().map(str::to_string).collect(),
+                exposed: exposed.into_iter().map(str::to_string).collect(),
+            }
+        })
+        .collect()
+}
+
+/// Applies the collision strategy to the tools the model sees
+pub struct ConflictResolver {
+    settings_path: PathBuf,
+    settings: RwLock<Option<ToolConflictSettings>>,
+}
+
+impl ConflictResolver {
+    pub fn new(settings_path: PathBuf) -> Self {
+        Self {
+            settings_path,
+            settings: RwLock::new(None),
+        }
+    }
+
+    /// Forget the cached copy so the next read picks up the file on disk
+    pub async fn reload(&self) {
+        *self.settings.write().await = None;
+    }
+
+    pub async fn settings(&self) -> ToolConflictSettings {
+        if let Some(settings) = self.settings.read().await.as_ref() {
+            return settings.clone();
+        }
+        let settings: ToolConflictSettings = tokio::fs::read_to_string(&self.settings_path)
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        *self.settings.write().await = Some(settings.clone());
+        settings
+    }
+
+    pub async fn save_settings(&self, settings: ToolConflictSettings) -> Result<()> {
+        config_versions::write(
+            &self.settings_path,
+            serde_json::to_string_pretty(&settings)?,
+        )
+        .await?;
+        *self.settings.write().await = Some(settings);
+        Ok(())
+    }
+
+    /// Overlapping tool names among `server__tool` prefixed tools
+    pub async fn conflicts(&self, tools: &[MCPTool]) -> Vec<ToolConflict> {
+        find(&self.settings().await, tools)
+    }
+
+    /// Drop the copies of duplicated tools that the strategy hides
+    pub async fn apply(&self, tools: &mut Vec<MCPTool>) {
+        let settings = self.settings().await;
+        if settings.strategy == CollisionStrategy::Prefix {
+            return;
+        }
+        let hidden: HashSet<String> = find(&settings, tools)
+            .into_iter()
+            .flat_map(|conflict| {
+                conflict
+                    .servers
+                    .into_iter()
+                    .filter(|server_name| !conflict.exposed.contains(server_name))
+                    .map(|server_name| format!("{}__{}", server_name, conflict.tool_name))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        tools.retain(|tool| !hidden.contains(&tool.name));
+    }
+}
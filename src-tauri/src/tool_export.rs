@@ -0,0 +1,108 @@
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::json;
+use std::path::Path;
+
+use crate::mcp::MCPTool;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Chat Completions `tools` array
+    Openai,
+    /// Messages API `tools` array
+    Anthropic,
+    /// `tools` entry with `functionDeclarations`
+    Gemini,
+}
+
+fn parameters(tool: &MCPTool) -> serde_json::Value {
+    json!({
+        "type": "object",
+        "properties": tool.input_schema.properties,
+        "required": tool.input_schema.required.clone().unwrap_or_default(),
+    })
+}
+
+/// Gemini accepts only a subset of JSON schema; mirrors the frontend conversion
+fn gemini_property(schema: &serde_json::Value) -> serde_json::Value {
+    let mut converted = match schema.get("type").and_then(|t| t.as_str()) {
+        Some("number") | Some("integer") => json!({ "type": "NUMBER" }),
+        Some("boolean") => json!({ "type": "BOOLEAN" }),
+        Some("array") => json!({
+            "type": "ARRAY",
+            "items": schema
+                .get("items")
+                .map(gemini_property)
+                .unwrap_or_else(|| json!({ "type": "STRING" })),
+        }),
+        Some("object") => json!({ "type": "OBJECT" }),
+        _ => json!({ "type": "STRING" }),
+    };
+    if let Some(description) = schema.get("description").filter(|d| d.is_string()) {
+        converted["description"] = description.clone();
+    }
+    converted
+}
+
+fn gemini_parameters(tool: &MCPTool) -> serde_json::Value {
+    let properties: serde_json::Map<String, serde_json::Value> = tool
+        .input_schema
+        .properties
+        .iter()
+        .map(|(name, schema)| (name.clone(), gemini_property(schema)))
+        .collect();
+    json!({
+        "type": "OBJECT",
+        "properties": properties,
+        "required": tool.input_schema.required.clone().unwrap_or_default(),
+    })
+}
+
+/// 도구 목록을 제공자별 함수 호출 형식으로 변환합니다
+pub fn convert(tools: &[MCPTool], format: ExportFormat) -> serde_json::Value {
+    match format {
+        ExportFormat::Openai => tools
+            .iter()
+            .map(|tool| {
+                json!({
+                    "type": "function",
+                    "function": {
+                        "name": tool.name,
+                        "description": tool.description,
+                        "parameters": parameters(tool),
+                    }
+                })
+            })
+            .collect(),
+        ExportFormat::Anthropic => tools
+            .iter()
+            .map(|tool| {
+                json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                    "input_schema": parameters(tool),
+                })
+            })
+            .collect(),
+        ExportFormat::Gemini => json!([{
+            "functionDeclarations": tools
+                .iter()
+                .map(|tool| json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                    "parameters": gemini_parameters(tool),
+                }))
+                .collect::<Vec<_>>()
+        }]),
+    }
+}
+
+/// Write the converted definitions as pretty JSON; returns the number of tools
+pub async fn export(tools: &[MCPTool], format: ExportFormat, path: &Path) -> Result<usize> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(path, serde_json::to_string_pretty(&convert(tools, format))?).await?;
+    Ok(tools.len())
+}
@@ -0,0 +1,106 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tokio::sync::RwLock;
+
+use crate::config_versions;
+use crate::mcp::MCPTool;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolPrioritySettings {
+    /// Server name → priority; higher comes first, unlisted servers are 0
+    #[serde(default)]
+    pub servers: BTreeMap<String, i32>,
+    /// Server name → tool name → priority, overriding the server's
+    #[serde(default)]
+    pub tools: BTreeMap<String, BTreeMap<String, i32>>,
+    /// Keep only this many tools, dropping the lowest priorities
+    #[serde(default)]
+    pub max_tools: Option<usize>,
+}
+
+impl ToolPrioritySettings {
+    fn priority(&self, name: &str) -> i32 {
+        let Some((server_name, tool_name)) = name.split_once("__") else {
+            return 0;
+        };
+        self.tools
+            .get(server_name)
+            .and_then(|tools| tools.get(tool_name))
+            .or_else(|| self.servers.get(server_name))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+/// Orders the tools the model sees so preferred servers and tools come first
+pub struct ToolPrioritizer {
+    settings_path: PathBuf,
+    settings: RwLock<Option<ToolPrioritySettings>>,
+}
+
+impl ToolPrioritizer {
+    pub fn new(settings_path: PathBuf) -> Self {
+        Self {
+            settings_path,
+            settings: RwLock::new(None),
+        }
+    }
+
+    /// Forget the cached copy so the next read picks up the file on disk
+    pub async fn reload(&self) {
+        *self.settings.write().await = None;
+    }
+
+    pub async fn settings(&self) -> ToolPrioritySettings {
+        if let Some(settings) = self.settings.read().await.as_ref() {
+            return settings.clone();
+        }
+        let settings: ToolPrioritySettings = tokio::fs::read_to_string(&self.settings_path)
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        *self.settings.write().await = Some(settings.clone());
+        settings
+    }
+
+    pub async fn save_settings(&self, settings: ToolPrioritySettings) -> Result<()> {
+        if settings.max_tools == Some(0) {
+            return Err(anyhow::anyhow!("max_tools must be greater than 0"));
+        }
+        config_versions::write(
+            &self.settings_path,
+            serde_json::to_string_pretty(&settings)?,
+        )
+        .await?;
+        *self.settings.write().await = Some(settings);
+        Ok(())
+    }
+
+    /// Sort `server__tool` tools by priority, highest first, and trim to `max_tools`.
+    /// The sort is stable, so tools of equal priority keep their listing order.
+    pub async fn apply(&self, tools: &mut Vec<MCPTool>) {
+        let settings = self.settings().await;
+        if settings.servers.is_empty() && settings.tools.is_empty() && settings.max_tools.is_none()
+        {
+            return;
+        }
+        tools.sort_by_cached_key(|tool| std::cmp::Reverse(settings.priority(&tool.name)));
+        if let Some(max_tools) = settings.max_tools {
+            if tools.len() > max_tools {
+                let dropped: Vec<&str> = tools[max_tools..]
+                    .iter()
+                    .map(|tool| tool.name.as_str())
+                    .collect();
+                println!(
+                    "Trimmed {} low-priority tools: {}",
+                    dropped.len(),
+                    dropped.join(", ")
+                );
+                tools.truncate(max_tools);
+            }
+        }
+    }
+}
@@ -0,0 +1,432 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::OnceLock;
+
+use crate::mcp::MCPTool;
+
+const JSON_TYPES: [&str; 7] = [
+    "object", "array", "string", "number", "integer", "boolean", "null",
+];
+
+/// Keywords OpenAI rejects in strict mode
+const OPENAI_STRICT_UNSUPPORTED: [&str; 17] = [
+    "allOf",
+    "oneOf",
+    "not",
+    "if",
+    "then",
+    "else",
+    "dependentRequired",
+    "dependentSchemas",
+    "patternProperties",
+    "unevaluatedProperties",
+    "propertyNames",
+    "minProperties",
+    "maxProperties",
+    "unevaluatedItems",
+    "contains",
+    "minContains",
+    "maxContains",
+];
+const OPENAI_STRICT_MAX_DEPTH: usize = 10;
+const OPENAI_STRICT_MAX_PROPERTIES: usize = 5000;
+
+/// Rules a tool definition is checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ValidationTarget {
+    /// JSON Schema problems every provider trips over; always checked
+    Generic,
+    Openai,
+    /// OpenAI with `strict: true` structured outputs
+    OpenaiStrict,
+    Anthropic,
+    /// Gemini after the app's conversion to its schema subset
+    Gemini,
+}
+
+impl ValidationTarget {
+    pub const ALL: [Self; 5] = [
+        Self::Generic,
+        Self::Openai,
+        Self::OpenaiStrict,
+        Self::Anthropic,
+        Self::Gemini,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The provider rejects the tool, or the whole request
+    Error,
+    /// The tool is accepted but the model sees less than the server declared
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub target: ValidationTarget,
+    pub severity: Severity,
+    /// JSON pointer into the input schema; empty for the tool itself
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolReport {
+    pub tool_name: String,
+    pub findings: Vec<Finding>,
+}
+
+impl ToolReport {
+    /// No errors for `target` or for the generic rules
+    pub fn passes(&self, target: ValidationTarget) -> bool {
+        !self.findings.iter().any(|finding| {
+            finding.severity == Severity::Error
+                && (finding.target == target || finding.target == ValidationTarget::Generic)
+        })
+    }
+}
+
+/// Event payload for tools re-validated after a server listed them
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationNotice {
+    pub server_name: String,
+    pub reports: Vec<ToolReport>,
+}
+
+fn pointer(path: &str, key: &str) -> String {
+    format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Direct subschemas of `schema` with their JSON pointers
+fn children<'a>(schema: &'a Value, path: &str) -> Vec<(String, &'a Value)> {
+    let mut children = Vec::new();
+    let Some(object) = schema.as_object() else {
+        return children;
+    };
+    for keyword in ["properties", "$defs", "definitions", "patternProperties"] {
+        if let Some(map) = object.get(keyword).and_then(|v| v.as_object()) {
+            let base = pointer(path, keyword);
+            children.extend(map.iter().map(|(key, child)| (pointer(&base, key), child)));
+        }
+    }
+    for keyword in ["anyOf", "oneOf", "allOf", "prefixItems"] {
+        if let Some(items) = object.get(keyword).and_then(|v| v.as_array()) {
+            let base = pointer(path, keyword);
+            children.extend(
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, child)| (pointer(&base, &index.to_string()), child)),
+            );
+        }
+    }
+    for keyword in ["items", "additionalProperties", "not"] {
+        if let Some(child) = object.get(keyword).filter(|v| v.is_object()) {
+            children.push((pointer(path, keyword), child));
+        }
+    }
+    children
+}
+
+fn types(schema: &Value) -> Vec<&str> {
+    match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(|t| t.as_str()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn is_object_schema(schema: &Value) -> bool {
+    types(schema).contains(&"object") || schema.get("properties").is_some()
+}
+
+struct Checker<'a> {
+    root: &'a Value,
+    target: ValidationTarget,
+    findings: Vec<Finding>,
+}
+
+impl<'a> Checker<'a> {
+    fn new(root: &'a Value, target: ValidationTarget) -> Self {
+        Self {
+            root,
+            target,
+            findings: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, severity: Severity, path: &str, message: String) {
+        self.findings.push(Finding {
+            target: self.target,
+            severity,
+            path: path.to_string(),
+            message,
+        });
+    }
+
+    fn error(&mut self, path: &str, message: impl Into<String>) {
+        self.push(Severity::Error, path, message.into());
+    }
+
+    fn warn(&mut self, path: &str, message: impl Into<String>) {
+        self.push(Severity::Warning, path, message.into());
+    }
+
+    /// Call `check` on the root schema and every subschema below it, with their depth
+    fn walk(&mut self, check: &mut dyn FnMut(&mut Self, &'a Value, &str, usize)) {
+        let mut stack = vec![(self.root, String::new(), 0)];
+        while let Some((schema, path, depth)) = stack.pop() {
+            check(self, schema, &path, depth);
+            for (child_path, child) in children(schema, &path).into_iter().rev() {
+                stack.push((child, child_path, depth + 1));
+            }
+        }
+    }
+
+    fn name(&mut self, tool_name: &str, re: &Regex, rule: &str) {
+        if !re.is_match(tool_name) {
+            self.error("", format!("Tool name '{}' must be {}", tool_name, rule));
+        }
+    }
+
+    fn generic(&mut self, tool: &MCPTool) {
+        if tool.name.is_empty() {
+            self.error("", "Tool name is empty");
+        }
+        if tool.description.trim().is_empty() {
+            self.warn(
+                "",
+                "Tool has no description; models choose tools by their description",
+            );
+        }
+        if tool.input_schema.schema_type != "object" {
+            self.error(
+                "/type",
+                format!(
+                    "Input schema type is '{}', expected 'object'",
+                    tool.input_schema.schema_type
+                ),
+            );
+        }
+        self.walk(&mut |checker, schema, path, _| {
+            let Some(object) = schema.as_object() else {
+                if !schema.is_boolean() {
+                    checker.error(path, "Schema must be an object");
+                }
+                return;
+            };
+            match object.get("type") {
+                None => {}
+                Some(Value::String(t)) if JSON_TYPES.contains(&t.as_str()) => {}
+                Some(Value::Array(ts))
+                    if ts
+                        .iter()
+                        .all(|t| t.as_str().is_some_and(|t| JSON_TYPES.contains(&t))) => {}
+                Some(other) => {
+                    checker.error(&pointer(path, "type"), format!("Unknown type {}", other))
+                }
+            }
+            if let Some(required) = object.get("required").and_then(|r| r.as_array()) {
+                let properties = object.get("properties").and_then(|p| p.as_object());
+                for field in required.iter().filter_map(|f| f.as_str()) {
+                    if !properties.is_some_and(|p| p.contains_key(field)) {
+                        checker.error(
+                            &pointer(path, "required"),
+                            format!(
+                                "Field '{}' is required but not defined in properties",
+                                field
+                            ),
+                        );
+                    }
+                }
+            }
+            if let Some(values) = object.get("enum") {
+                if values.as_array().is_none_or(|v| v.is_empty()) {
+                    checker.error(&pointer(path, "enum"), "enum must be a non-empty array");
+                }
+            }
+            if let Some(reference) = object.get("$ref").and_then(|r| r.as_str()) {
+                match reference.strip_prefix('#') {
+                    Some(target) if checker.root.pointer(target).is_some() => {}
+                    Some(_) => checker.error(
+                        &pointer(path, "$ref"),
+                        format!("$ref '{}' does not resolve inside the schema", reference),
+                    ),
+                    None => checker.warn(
+                        &pointer(path, "$ref"),
+                        format!(
+                            "External $ref '{}' is not resolved for the model",
+                            reference
+                        ),
+                    ),
+                }
+            }
+        });
+    }
+
+    fn no_root_combinators(&mut self, keywords: &[&str]) {
+        for keyword in keywords {
+            if self.root.get(*keyword).is_some() {
+                self.error(
+                    &pointer("", keyword),
+                    format!(
+                        "'{}' is not allowed at the top level of the input schema",
+                        keyword
+                    ),
+                );
+            }
+        }
+    }
+
+    fn openai(&mut self, tool: &MCPTool) {
+        static RE: OnceLock<Regex> = OnceLock::new();
+        let re = RE.get_or_init(|| Regex::new(r"^[a-zA-Z0-9_-]{1,64}$").unwrap());
+        self.name(&tool.name, re, "1-64 letters, digits, '_' or '-'");
+        self.no_root_combinators(&["anyOf", "oneOf", "allOf", "enum", "not"]);
+        self.walk(&mut |checker, schema, path, _| {
+            if types(schema).contains(&"array") && schema.get("items").is_none() {
+                checker.error(path, "Array schema is missing 'items'");
+            }
+        });
+    }
+
+    fn openai_strict(&mut self, tool: &MCPTool) {
+        self.openai(tool);
+        let mut properties = 0;
+        self.walk(&mut |checker, schema, path, depth| {
+            let Some(object) = schema.as_object() else {
+                return;
+            };
+            if depth > OPENAI_STRICT_MAX_DEPTH {
+                checker.error(
+                    path,
+                    format!("Nested deeper than {} levels", OPENAI_STRICT_MAX_DEPTH),
+                );
+            }
+            for keyword in OPENAI_STRICT_UNSUPPORTED {
+                if object.contains_key(keyword) {
+                    checker.error(
+                        &pointer(path, keyword),
+                        format!("'{}' is not supported in strict mode", keyword),
+                    );
+                }
+            }
+            if !is_object_schema(schema) {
+                return;
+            }
+            if object.get("additionalProperties") != Some(&Value::Bool(false)) {
+                checker.error(path, "Objects must set \"additionalProperties\": false");
+            }
+            let defined = object.get("properties").and_then(|p| p.as_object());
+            properties += defined.map_or(0, |p| p.len());
+            let required: Vec<&str> = object
+                .get("required")
+                .and_then(|r| r.as_array())
+                .map(|r| r.iter().filter_map(|f| f.as_str()).collect())
+                .unwrap_or_default();
+            for field in defined.into_iter().flat_map(|p| p.keys()) {
+                if !required.contains(&field.as_str()) {
+                    checker.error(
+                        &pointer(&pointer(path, "properties"), field),
+                        format!(
+                            "Optional field '{}' must be required; allow null in its type instead",
+                            field
+                        ),
+                    );
+                }
+            }
+        });
+        if properties > OPENAI_STRICT_MAX_PROPERTIES {
+            self.error(
+                "",
+                format!(
+                    "{} properties exceed the strict mode limit of {}",
+                    properties, OPENAI_STRICT_MAX_PROPERTIES
+                ),
+            );
+        }
+    }
+
+    fn anthropic(&mut self, tool: &MCPTool) {
+        static RE: OnceLock<Regex> = OnceLock::new();
+        let re = RE.get_or_init(|| Regex::new(r"^[a-zA-Z0-9_-]{1,64}$").unwrap());
+        self.name(&tool.name, re, "1-64 letters, digits, '_' or '-'");
+        self.no_root_combinators(&["anyOf", "oneOf", "allOf"]);
+    }
+
+    /// Gemini only sees what the conversion in `tool_export` and the frontend keeps:
+    /// the type and description of each property, recursing into array items
+    fn gemini(&mut self, tool: &MCPTool) {
+        static RE: OnceLock<Regex> = OnceLock::new();
+        let re = RE.get_or_init(|| Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_.-]{0,63}$").unwrap());
+        self.name(
+            &tool.name,
+            re,
+            "up to 64 letters, digits, '_', '.' or '-' starting with a letter or '_'",
+        );
+        for (name, schema) in &tool.input_schema.properties {
+            let path = pointer("/properties", name);
+            self.gemini_property(schema, &path);
+        }
+    }
+
+    fn gemini_property(&mut self, schema: &Value, path: &str) {
+        match schema.get("type").and_then(|t| t.as_str()) {
+            Some("string") | Some("number") | Some("boolean") => {}
+            Some("integer") => self.warn(path, "Sent as NUMBER; the model may pass fractions"),
+            Some("array") => match schema.get("items") {
+                Some(items) => self.gemini_property(items, &pointer(path, "items")),
+                None => self.warn(path, "Array without 'items' is sent as an array of strings"),
+            },
+            Some("object") => self.error(
+                path,
+                "Nested object is sent without its properties, which Gemini rejects",
+            ),
+            _ => self.warn(path, "Untyped or multi-typed property is sent as STRING"),
+        }
+        let dropped: Vec<&str> = schema
+            .as_object()
+            .into_iter()
+            .flat_map(|object| object.keys())
+            .map(String::as_str)
+            .filter(|key| {
+                // 중첩 객체의 속성은 위의 오류로 이미 보고합니다
+                !matches!(
+                    *key,
+                    "type" | "description" | "items" | "title" | "properties" | "required"
+                )
+            })
+            .collect();
+        if !dropped.is_empty() {
+            self.warn(path, format!("Dropped for Gemini: {}", dropped.join(", ")));
+        }
+    }
+}
+
+/// Check a tool against the generic rules and each of `targets`
+pub fn validate(tool: &MCPTool, targets: &[ValidationTarget]) -> ToolReport {
+    let root = serde_json::to_value(&tool.input_schema).unwrap_or_default();
+    let mut findings = Vec::new();
+    let mut generic = Checker::new(&root, ValidationTarget::Generic);
+    generic.generic(tool);
+    findings.extend(generic.findings);
+    for &target in targets {
+        let mut checker = Checker::new(&root, target);
+        match target {
+            ValidationTarget::Generic => continue,
+            ValidationTarget::Openai => checker.openai(tool),
+            ValidationTarget::OpenaiStrict => checker.openai_strict(tool),
+            ValidationTarget::Anthropic => checker.anthropic(tool),
+            ValidationTarget::Gemini => checker.gemini(tool),
+        }
+        findings.extend(checker.findings);
+    }
+    ToolReport {
+        tool_name: tool.name.clone(),
+        findings,
+    }
+}
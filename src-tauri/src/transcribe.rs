@@ -0,0 +1,210 @@
+use anyhow::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::OnceLock;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::Command;
+
+use crate::events;
+use crate::integrity;
+
+// whisper.cpp 빌드/패키지마다 실행 파일 이름이 다릅니다
+const FILE_BINARIES: &[&str] = &["whisper-cli", "whisper-cpp"];
+const STREAM_BINARIES: &[&str] = &["whisper-stream", "whisper-cpp-stream"];
+const DEFAULT_MODEL: &str = "ggml-base.bin";
+const MAX_RECORDING_SECS: u64 = 600;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AudioSource {
+    /// WAV (16 kHz) or any format the installed whisper.cpp build decodes
+    File { path: String },
+    /// Record from the default microphone for a fixed duration
+    Microphone { duration_secs: u64 },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptSegment {
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Transcript {
+    pub id: String,
+    pub text: String,
+    pub segments: Vec<TranscriptSegment>,
+}
+
+/// Models are looked up in `<app-data>/models` unless an absolute path is given
+pub fn model_path(data_dir: &Path, model: Option<&str>) -> PathBuf {
+    let model = model.unwrap_or(DEFAULT_MODEL);
+    let path = Path::new(model);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        data_dir.join("models").join(model)
+    }
+}
+
+fn resolve_binary(candidates: &[&str]) -> Result<PathBuf> {
+    candidates
+        .iter()
+        .find_map(|name| integrity::resolve_executable(name))
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "whisper.cpp is not installed ({} not found on PATH)",
+                candidates.join(" / ")
+            )
+        })
+}
+
+fn timestamp_line() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^\[([^\]]+?)\s+-->\s+([^\]]+?)\]\s*(.*)$").unwrap())
+}
+
+fn ansi_escape() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\x1b\[[0-9;]*[A-Za-z]").unwrap())
+}
+
+/// 출력 한 줄을 세그먼트로 변환합니다 (진행 표시나 빈 줄은 무시)
+fn parse_line(line: &str) -> Option<TranscriptSegment> {
+    // whisper-stream은 같은 줄을 \r 로 덮어쓰므로 마지막 부분만 사용합니다
+    let line = ansi_escape().replace_all(line, "");
+    let line = line.rsplit('\r').next().unwrap_or_default().trim();
+    if line.is_empty() || line.starts_with("###") || line.starts_with("[Start speaking]") {
+        return None;
+    }
+    let segment = match timestamp_line().captures(line) {
+        Some(caps) => TranscriptSegment {
+            start: Some(caps[1].to_string()),
+            end: Some(caps[2].to_string()),
+            text: caps[3].trim().to_string(),
+        },
+        None => TranscriptSegment {
+            start: None,
+            end: None,
+            text: line.to_string(),
+        },
+    };
+    // 무음 구간은 [BLANK_AUDIO] 같은 표식으로 출력됩니다
+    if segment.text.is_empty() || (segment.text.starts_with('[') && segment.text.ends_with(']')) {
+        return None;
+    }
+    Some(segment)
+}
+
+/// Run whisper.cpp locally, emitting each segment as a `transcription-partial` event
+///
+/// Audio is never uploaded; it only goes to the local whisper.cpp process.
+/// `id` tags the emitted events so callers can match them to this request.
+pub async fn transcribe(
+    id: String,
+    data_dir: &Path,
+    source: AudioSource,
+    model: Option<&str>,
+    language: Option<&str>,
+) -> Result<Transcript> {
+    let model = model_path(data_dir, model);
+    if !model.is_file() {
+        return Err(anyhow::anyhow!(
+            "Whisper model not found at {} (download a ggml model there first)",
+            model.display()
+        ));
+    }
+    let language = language.unwrap_or("auto");
+
+    let (mut command, duration) = match &source {
+        AudioSource::File { path } => {
+            if !Path::new(path).is_file() {
+                return Err(anyhow::anyhow!("Audio file not found: {}", path));
+            }
+            let mut command = Command::new(resolve_binary(FILE_BINARIES)?);
+            command.arg("-f").arg(path);
+            (command, None)
+        }
+        AudioSource::Microphone { duration_secs } => {
+            if *duration_secs == 0 || *duration_secs > MAX_RECORDING_SECS {
+                return Err(anyhow::anyhow!(
+                    "duration_secs must be between 1 and {}",
+                    MAX_RECORDING_SECS
+                ));
+            }
+            let mut command = Command::new(resolve_binary(STREAM_BINARIES)?);
+            // step 0 = VAD 모드: 발화가 끝날 때마다 한 번씩 출력합니다
+            command.args(["--step", "0", "--length", "30000"]);
+            (command, Some(*duration_secs))
+        }
+    };
+    let mut child = command
+        .arg("-m")
+        .arg(&model)
+        .args(["-l", language])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    // 모델 로딩 로그가 많아 stderr 파이프가 차지 않도록 따로 읽습니다
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    let stderr_task = tokio::spawn(async move {
+        let mut buf = String::new();
+        let _ = stderr.read_to_string(&mut buf).await;
+        buf
+    });
+    let mut lines = BufReader::new(child.stdout.take().expect("stdout is piped")).lines();
+    let mut segments = Vec::new();
+    let deadline = async {
+        match duration {
+            Some(secs) => tokio::time::sleep(tokio::time::Duration::from_secs(secs)).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::pin!(deadline);
+
+    loop {
+        tokio::select! {
+            line = lines.next_line() => match line? {
+                Some(line) => {
+                    if let Some(segment) = parse_line(&line) {
+                        events::emit(
+                            "transcription-partial",
+                            json!({ "id": id, "text": segment.text, "start": segment.start, "end": segment.end }),
+                        );
+                        segments.push(segment);
+                    }
+                }
+                None => break,
+            },
+            _ = &mut deadline => {
+                // 녹음 시간이 끝나면 프로세스를 종료하고 남은 출력을 읽습니다
+                let _ = child.start_kill();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    segments.extend(parse_line(&line));
+                }
+                break;
+            }
+        }
+    }
+
+    let status = child.wait().await?;
+    let stderr = stderr_task.await.unwrap_or_default();
+    if duration.is_none() && !status.success() {
+        return Err(anyhow::anyhow!("Transcription failed: {}", stderr.trim()));
+    }
+
+    let text = segments
+        .iter()
+        .map(|segment| segment.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    events::emit("transcription-finished", json!({ "id": id, "text": text }));
+    Ok(Transcript { id, text, segments })
+}
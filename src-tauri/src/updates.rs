@@ -0,0 +1,221 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use tauri::AppHandle;
+use tauri_plugin_updater::{Update, UpdaterExt};
+use tokio::sync::{Mutex, RwLock};
+
+use crate::config_versions;
+use crate::events;
+use crate::power;
+
+// 백그라운드 루프가 예약된 확인 시각을 살피는 주기
+const CHECK_INTERVAL_SECS: u64 = 600;
+/// Sent with every check so the update server can stage a rollout itself
+const ROLLOUT_HEADER: &str = "X-Rollout-Bucket";
+
+// 릴리스 서명 키가 설정되기 전에는 확인해도 설치할 수 없으므로 기본값은 꺼 둡니다
+fn default_auto_check() -> bool {
+    false
+}
+
+fn default_interval_hours() -> u64 {
+    24
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateSettings {
+    /// Check on a schedule and announce new versions with an `update-available` event
+    #[serde(default = "default_auto_check")]
+    pub auto_check: bool,
+    #[serde(default = "default_interval_hours")]
+    pub interval_hours: u64,
+    /// Random id of this installation; its bucket decides when a staged release reaches it
+    #[serde(default)]
+    pub rollout_id: String,
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self {
+            auto_check: default_auto_check(),
+            interval_hours: default_interval_hours(),
+            rollout_id: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub date: Option<String>,
+    /// Release notes
+    pub body: Option<String>,
+}
+
+/// False while `plugins.updater.pubkey` is empty, where no download could be verified.
+/// Release builds inject the key; without it the updater plugin is not registered.
+pub fn signing_configured(app: &AppHandle) -> bool {
+    app.config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|updater| updater.get("pubkey"))
+        .and_then(|pubkey| pubkey.as_str())
+        .is_some_and(|pubkey| !pubkey.trim().is_empty())
+}
+
+impl From<&Update> for UpdateInfo {
+    fn from(update: &Update) -> Self {
+        Self {
+            version: update.version.clone(),
+            current_version: update.current_version.clone(),
+            date: update.date.map(|date| date.to_string()),
+            body: update.body.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct UpdateProgress {
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+/// Bucket in 0..100 for a rollout id; stable across runs
+fn rollout_bucket(rollout_id: &str) -> u64 {
+    let digest = Sha256::digest(rollout_id.as_bytes());
+    u64::from_be_bytes(digest[..8].try_into().unwrap_or_default()) % 100
+}
+
+/// Percentage of installations a release is offered to, from its optional `rollout` field
+fn rollout_percentage(update: &Update) -> u64 {
+    update
+        .raw_json
+        .get("rollout")
+        .and_then(|v| v.as_u64())
+        .map_or(100, |percentage| percentage.min(100))
+}
+
+/// Signed updates through the Tauri updater, with staged rollouts
+pub struct UpdateService {
+    settings_path: PathBuf,
+    settings: RwLock<Option<UpdateSettings>>,
+    // 확인된 업데이트를 설치할 때까지 보관합니다
+    pending: Mutex<Option<Update>>,
+}
+
+impl UpdateService {
+    pub fn new(settings_path: PathBuf) -> Self {
+        Self {
+            settings_path,
+            settings: RwLock::new(None),
+            pending: Mutex::new(None),
+        }
+    }
+
+    /// Forget the cached copy so the next read picks up the file on disk
+    pub async fn reload(&self) {
+        *self.settings.write().await = None;
+    }
+
+    pub async fn settings(&self) -> UpdateSettings {
+        if let Some(settings) = self.settings.read().await.as_ref() {
+            return settings.clone();
+        }
+        let mut settings: UpdateSettings = tokio::fs::read_to_string(&self.settings_path)
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        if settings.rollout_id.is_empty() {
+            settings.rollout_id = uuid::Uuid::new_v4().simple().to_string();
+            if let Err(e) = self.write(&settings).await {
+                eprintln!("❌ Failed to save update settings: {}", e);
+            }
+        }
+        *self.settings.write().await = Some(settings.clone());
+        settings
+    }
+
+    async fn write(&self, settings: &UpdateSettings) -> Result<()> {
+        config_versions::write(&self.settings_path, serde_json::to_string_pretty(settings)?)
+            .await?;
+        Ok(())
+    }
+
+    /// Save the schedule; the rollout id is kept
+    pub async fn save_settings(&self, mut settings: UpdateSettings) -> Result<UpdateSettings> {
+        if settings.interval_hours == 0 {
+            return Err(anyhow::anyhow!("interval_hours must be greater than 0"));
+        }
+        settings.rollout_id = self.settings().await.rollout_id;
+        self.write(&settings).await?;
+        *self.settings.write().await = Some(settings.clone());
+        Ok(settings)
+    }
+
+    /// Ask the update endpoints for a newer version. A release staged to fewer
+    /// installations than this one's bucket is treated as not available yet.
+    pub async fn check(&self, app: &AppHandle) -> Result<Option<UpdateInfo>> {
+        if !signing_configured(app) {
+            return Err(anyhow::anyhow!(
+                "Updates are not configured: the updater public key is empty"
+            ));
+        }
+        let bucket = rollout_bucket(&self.settings().await.rollout_id);
+        let update = app
+            .updater_builder()
+            .header(ROLLOUT_HEADER, bucket.to_string())?
+            .build()?
+            .check()
+            .await?
+            .filter(|update| bucket < rollout_percentage(update));
+        let info = update.as_ref().map(UpdateInfo::from);
+        *self.pending.lock().await = update;
+        Ok(info)
+    }
+
+    /// Download the update found by the last check, verify its signature and install it.
+    /// Progress is reported with `update-progress` events; the app restarts afterwards.
+    pub async fn install(&self, app: &AppHandle) -> Result<()> {
+        let Some(update) = self.pending.lock().await.take() else {
+            return Err(anyhow::anyhow!(
+                "No update to install; check for updates first"
+            ));
+        };
+        let mut downloaded = 0u64;
+        update
+            .download_and_install(
+                |chunk, total| {
+                    downloaded += chunk as u64;
+                    events::emit("update-progress", UpdateProgress { downloaded, total });
+                },
+                || println!("Downloaded update {}", update.version),
+            )
+            .await?;
+        app.restart();
+    }
+
+    /// Check whenever the last check is older than the interval, for as long as the app runs
+    pub async fn run_background(&self, app: AppHandle) {
+        let mut last_check: Option<std::time::Instant> = None;
+        loop {
+            let settings = self.settings().await;
+            let due = last_check.is_none_or(|checked| {
+                checked.elapsed().as_secs() >= settings.interval_hours * 3600
+            });
+            if settings.auto_check && due && !power::paused() && signing_configured(&app) {
+                last_check = Some(std::time::Instant::now());
+                match self.check(&app).await {
+                    Ok(Some(info)) => events::emit("update-available", info),
+                    Ok(None) => {}
+                    Err(e) => eprintln!("❌ Update check failed: {}", e),
+                }
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use anyhow::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::config_versions;
+use crate::redact;
+
+const DEFAULT_RESULTS: usize = 5;
+const MAX_RESULTS: usize = 20;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SearchEngine {
+    /// Self-hosted instance with the JSON output format enabled
+    Searxng {
+        base_url: String,
+    },
+    Brave {
+        api_key: String,
+    },
+    Tavily {
+        api_key: String,
+    },
+}
+
+impl SearchEngine {
+    fn name(&self) -> &'static str {
+        match self {
+            SearchEngine::Searxng { .. } => "searxng",
+            SearchEngine::Brave { .. } => "brave",
+            SearchEngine::Tavily { .. } => "tavily",
+        }
+    }
+
+    fn secret(&self) -> Option<&String> {
+        match self {
+            SearchEngine::Searxng { .. } => None,
+            SearchEngine::Brave { api_key } | SearchEngine::Tavily { api_key } => Some(api_key),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WebSearchSettings {
+    /// Tried in order; the next engine is used when one fails
+    #[serde(default)]
+    pub engines: Vec<SearchEngine>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResult {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+}
+
+pub struct WebSearch {
+    settings_path: PathBuf,
+    client: reqwest::Client,
+}
+
+fn html_tag() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"<[^>]*>").unwrap())
+}
+
+/// 엔진마다 다른 필드 이름을 title/url/snippet으로 정규화합니다
+fn normalize(items: Option<&serde_json::Value>, snippet_field: &str) -> Vec<SearchResult> {
+    let field = |item: &serde_json::Value, name: &str| {
+        item.get(name)
+            .and_then(|v| v.as_str())
+            .map(|text| html_tag().replace_all(text, "").trim().to_string())
+            .unwrap_or_default()
+    };
+    items
+        .and_then(|items| items.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .map(|item| SearchResult {
+                    title: field(item, "title"),
+                    url: field(item, "url"),
+                    snippet: field(item, snippet_field),
+                })
+                .filter(|result| !result.url.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl WebSearch {
+    pub fn new(settings_path: PathBuf) -> Self {
+        Self {
+            settings_path,
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    pub async fn settings(&self) -> WebSearchSettings {
+        let settings: WebSearchSettings = tokio::fs::read_to_string(&self.settings_path)
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        redact::register_secrets(settings.engines.iter().filter_map(SearchEngine::secret));
+        settings
+    }
+
+    pub async fn save_settings(&self, settings: &WebSearchSettings) -> Result<()> {
+        config_versions::write(&self.settings_path, serde_json::to_string_pretty(settings)?)
+            .await?;
+        redact::register_secrets(settings.engines.iter().filter_map(SearchEngine::secret));
+        Ok(())
+    }
+
+    async fn query(
+        &self,
+        engine: &SearchEngine,
+        query: &str,
+        k: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let results = match engine {
+            SearchEngine::Searxng { base_url } => {
+                let body: serde_json::Value = self
+                    .client
+                    .get(format!("{}/search", base_url.trim_end_matches('/')))
+                    .query(&[("q", query), ("format", "json")])
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+                normalize(body.get("results"), "content")
+            }
+            SearchEngine::Brave { api_key } => {
+                let body: serde_json::Value = self
+                    .client
+                    .get("https://api.search.brave.com/res/v1/web/search")
+                    .query(&[("q", query), ("count", &k.to_string())])
+                    .header("X-Subscription-Token", api_key)
+                    .header("Accept", "application/json")
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+                normalize(body.pointer("/web/results"), "description")
+            }
+            SearchEngine::Tavily { api_key } => {
+                let body: serde_json::Value = self
+                    .client
+                    .post("https://api.tavily.com/search")
+                    .bearer_auth(api_key)
+                    .json(&json!({ "query": query, "max_results": k }))
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+                normalize(body.get("results"), "content")
+            }
+        };
+        Ok(results.into_iter().take(k).collect())
+    }
+
+    /// Search with the first configured engine that answers
+    pub async fn search(&self, query: &str, k: Option<usize>) -> Result<Vec<SearchResult>> {
+        let k = k.unwrap_or(DEFAULT_RESULTS).clamp(1, MAX_RESULTS);
+        let settings = self.settings().await;
+        if settings.engines.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No search engine is configured (add SearXNG, Brave or Tavily in settings)"
+            ));
+        }
+        let mut errors = Vec::new();
+        for engine in &settings.engines {
+            match self.query(engine, query, k).await {
+                Ok(results) => return Ok(results),
+                Err(e) => errors.push(format!(
+                    "{}: {}",
+                    engine.name(),
+                    redact::redact(&e.to_string())
+                )),
+            }
+        }
+        Err(anyhow::anyhow!(
+            "Every search engine failed ({})",
+            errors.join("; ")
+        ))
+    }
+}
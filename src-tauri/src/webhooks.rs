@@ -0,0 +1,198 @@
+use anyhow::Result;
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::config_versions;
+
+/// A saved webhook that starts a run of one agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: String,
+    pub agent_id: String,
+    /// Shared secret for the `X-Hub-Signature-256` HMAC
+    pub secret: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookRegistration {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    pub url: String,
+}
+
+fn webhooks_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("webhooks.json")
+}
+
+pub fn url(port: u16, id: &str) -> String {
+    format!("http://127.0.0.1:{}/webhooks/{}", port, id)
+}
+
+pub fn load(data_dir: &Path) -> Vec<Webhook> {
+    std::fs::read_to_string(webhooks_path(data_dir))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save(data_dir: &Path, webhooks: &[Webhook]) -> Result<()> {
+    config_versions::write_blocking(
+        &webhooks_path(data_dir),
+        &serde_json::to_string_pretty(webhooks)?,
+    )?;
+    Ok(())
+}
+
+pub fn register(data_dir: &Path, agent_id: &str) -> Result<Webhook> {
+    let webhook = Webhook {
+        id: uuid::Uuid::new_v4().simple().to_string(),
+        agent_id: agent_id.to_string(),
+        secret: format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        ),
+        created_at: chrono::Local::now().to_rfc3339(),
+    };
+    let mut webhooks = load(data_dir);
+    webhooks.push(webhook.clone());
+    save(data_dir, &webhooks)?;
+    Ok(webhook)
+}
+
+pub fn remove(data_dir: &Path, id: &str) -> Result<()> {
+    let mut webhooks = load(data_dir);
+    let before = webhooks.len();
+    webhooks.retain(|webhook| webhook.id != id);
+    if webhooks.len() == before {
+        return Err(anyhow::anyhow!("Webhook '{}' not found", id));
+    }
+    save(data_dir, &webhooks)
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    // from_str_radix는 '+' 부호도 받아들이므로 먼저 확인합니다
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Check a GitHub-style `sha256=<hex>` signature of the raw request body
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(tag) = signature
+        .trim()
+        .strip_prefix("sha256=")
+        .and_then(decode_hex)
+    else {
+        return false;
+    };
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    // ring은 상수 시간으로 비교합니다
+    hmac::verify(&key, body, &tag).is_ok()
+}
+
+/// Compare secrets without returning early at the first differing byte
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    // 타이밍 공격을 피하기 위해 끝까지 비교합니다
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+        hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), message)
+            .as_ref()
+            .to_vec()
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn matches_rfc_4231_vectors() {
+        // Test Case 1
+        assert_eq!(
+            hex(&hmac_sha256(&[0x0b; 20], b"Hi There")),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+        );
+        // Test Case 2
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Test Case 6: a key longer than the block size is hashed first
+        assert_eq!(
+            hex(&hmac_sha256(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn compares_in_constant_time() {
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(!constant_time_eq(b"token", b"tokem"));
+        assert!(!constant_time_eq(b"token", b"token2"));
+        assert!(!constant_time_eq(b"", b"token"));
+        assert!(constant_time_eq(b"", b""));
+    }
+
+    #[test]
+    fn accepts_valid_signatures() {
+        let body = br#"{"action":"opened"}"#;
+        let digest = hex(&hmac_sha256(b"secret", body));
+        assert!(verify_signature(
+            "secret",
+            body,
+            &format!("sha256={}", digest)
+        ));
+        // 대문자 16진수와 앞뒤 공백도 받아들입니다
+        let upper = format!(" sha256={} ", digest.to_uppercase());
+        assert!(verify_signature("secret", body, &upper));
+    }
+
+    #[test]
+    fn rejects_invalid_signatures() {
+        let body = br#"{"action":"opened"}"#;
+        let digest = hex(&hmac_sha256(b"secret", body));
+        let signature = format!("sha256={}", digest);
+
+        assert!(!verify_signature("other", body, &signature));
+        assert!(!verify_signature("secret", b"{}", &signature));
+        // 접두사가 없거나 다른 알고리즘
+        assert!(!verify_signature("secret", body, &digest));
+        assert!(!verify_signature(
+            "secret",
+            body,
+            &format!("sha1={}", digest)
+        ));
+        // 16진수가 아니거나 길이가 다른 서명
+        assert!(!verify_signature(
+            "secret",
+            body,
+            &format!("sha256={}", "zz".repeat(32))
+        ));
+        assert!(!verify_signature(
+            "secret",
+            body,
+            &format!("sha256={}", &digest[..62])
+        ));
+        assert!(!verify_signature("secret", body, "sha256="));
+        assert!(!verify_signature(
+            "secret",
+            body,
+            &format!("sha256=+{}", &digest[1..])
+        ));
+    }
+}
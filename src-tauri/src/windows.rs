@@ -0,0 +1,120 @@
+use anyhow::Result;
+use serde::Serialize;
+#[cfg(desktop)]
+use tauri::menu::{Menu, MenuItem};
+#[cfg(desktop)]
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager};
+#[cfg(desktop)]
+use tauri::{WebviewUrl, WebviewWindowBuilder};
+
+use crate::events;
+
+/// Labels of extra chat windows; the capability file grants them the main window's
+/// permissions
+#[cfg(desktop)]
+const CHAT_WINDOW_PREFIX: &str = "chat-";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatWindow {
+    pub label: String,
+    pub title: String,
+    pub focused: bool,
+}
+
+/// Open another chat window. Every window talks to the same servers and stores, so
+/// sessions can run side by side; `session_id` is loaded on start.
+#[cfg(desktop)]
+pub fn open_chat(app: &AppHandle, session_id: Option<&str>) -> Result<String> {
+    let label = format!(
+        "{}{}",
+        CHAT_WINDOW_PREFIX,
+        &uuid::Uuid::new_v4().simple().to_string()[..8]
+    );
+    let path = match session_id {
+        Some(id) => format!("index.html?session={}", urlencode(id)),
+        None => "index.html".to_string(),
+    };
+    WebviewWindowBuilder::new(app, &label, WebviewUrl::App(path.into()))
+        .title("SynapticFlow")
+        .inner_size(800.0, 600.0)
+        .build()?;
+    Ok(label)
+}
+
+/// Mobile apps have a single window
+#[cfg(mobile)]
+pub fn open_chat(_app: &AppHandle, _session_id: Option<&str>) -> Result<String> {
+    Err(anyhow::anyhow!(
+        "Additional chat windows are not supported on this platform"
+    ))
+}
+
+/// Open windows, main window included
+pub fn list(app: &AppHandle) -> Vec<ChatWindow> {
+    let mut windows: Vec<ChatWindow> = app
+        .webview_windows()
+        .into_iter()
+        .map(|(label, window)| ChatWindow {
+            title: window.title().unwrap_or_default(),
+            focused: window.is_focused().unwrap_or(false),
+            label,
+        })
+        .collect();
+    windows.sort_by(|a, b| a.label.cmp(&b.label));
+    windows
+}
+
+/// Bring the window requests go to (focused, else main) to the front, restoring it
+/// from the tray or the taskbar; returns its label
+#[cfg_attr(mobile, allow(unused_variables))]
+pub fn show(app: &AppHandle) -> Option<String> {
+    let label = events::request_window()?;
+    // 모바일 창은 항상 화면에 떠 있습니다
+    #[cfg(desktop)]
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    Some(label)
+}
+
+/// Tray icon that keeps the app reachable while it runs hidden in background mode
+#[cfg(desktop)]
+pub fn create_tray(app: &AppHandle) -> Result<()> {
+    let show_item = MenuItem::with_id(app, "show", "Show SynapticFlow", true, None::<&str>)?;
+    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(app, &[&show_item, &quit_item])?;
+    let mut tray = TrayIconBuilder::new()
+        .tooltip("SynapticFlow")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            "show" => {
+                show(app);
+            }
+            "quit" => app.exit(0),
+            _ => {}
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+    Ok(())
+}
+
+#[cfg(desktop)]
+fn urlencode(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
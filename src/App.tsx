@@ -1,4 +1,4 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import ChatContainer from "./components/ChatContainer";
 import SettingsModal from "./components/SettingsModal";
 import { ChatContextProvider } from "./context/ChatContext";
@@ -13,6 +13,18 @@ import Sidebar from "./components/Sidebar";
 import Group from "./components/Group"; // New import
 import History from "./components/History"; // New import
 import GroupCreationModal from "./components/GroupCreationModal"; // New import
+import McpImportModal from "./components/McpImportModal";
+import { useApiBridge } from "./hooks/use-api-bridge";
+import { dbUtils } from "./lib/db";
+import { getLogger } from "./lib/logger";
+
+const logger = getLogger("App");
+
+// Mounted inside the providers so API chat requests use the current settings
+function ApiBridge() {
+  useApiBridge();
+  return null;
+}
 
 type CurrentView = "chat" | "group" | "history";
 
@@ -23,6 +35,22 @@ function App() {
   const [isGroupCreationModalOpen, setIsGroupCreationModalOpen] =
     useState(false); // New state
 
+  // Attachments saved inline by older versions move to the backend store
+  useEffect(() => {
+    dbUtils
+      .storePendingAttachments()
+      .then((remaining) => {
+        if (remaining > 0) {
+          logger.warn(
+            `${remaining} attachments are still waiting to be stored`,
+          );
+        }
+      })
+      .catch((error) =>
+        logger.error("Failed to store pending attachments:", error),
+      );
+  }, []);
+
   const renderMainContent = () => {
     switch (currentView) {
       case "chat":
@@ -45,6 +73,7 @@ function App() {
               <LocalToolProvider>
                 <WeatherTool />
                 <ChatContextProvider>
+                  <ApiBridge />
                   {/* Sidebar */}
                   <Sidebar
                     isCollapsed={isSidebarCollapsed}
@@ -80,6 +109,7 @@ function App() {
                     isOpen={isGroupCreationModalOpen}
                     onClose={() => setIsGroupCreationModalOpen(false)}
                   />
+                  <McpImportModal />
                 </ChatContextProvider>
               </LocalToolProvider>
             </MCPServerProvider>
@@ -1,11 +1,12 @@
 "use client";
 
-import { useCallback, useState } from "react";
+import { useCallback, useEffect, useState } from "react";
 import {
   DEFAULT_MCP_CONFIG,
   useAssistantContext,
 } from "../context/AssistantContext";
 import { useLocalTools } from "../context/LocalToolContext";
+import { useChatContext } from "../hooks/use-chat";
 import { useMCPServer } from "../hooks/use-mcp-server";
 import { getLogger } from "../lib/logger";
 import { Assistant } from "../types/chat";
@@ -26,7 +27,11 @@ export default function AssistantManager({ onClose }: AssistantManagerProps) {
     setCurrentAssistant,
     getNewAssistantTemplate,
   } = useAssistantContext();
-  const { status, isConnecting: isCheckingStatus } = useMCPServer();
+  const {
+    status,
+    unavailable,
+    isConnecting: isCheckingStatus,
+  } = useMCPServer();
   const { getAvailableServices, getToolsByService } = useLocalTools();
 
   const [editingAssistant, setEditingAssistant] =
@@ -34,6 +39,29 @@ export default function AssistantManager({ onClose }: AssistantManagerProps) {
   const [isCreating, setIsCreating] = useState(false);
   const [mcpConfigText, setMcpConfigText] = useState("");
   const [isDeleting, setIsDeleting] = useState<string | null>(null);
+  const { launchedServerConfig, clearLaunchedServerConfig } = useChatContext();
+
+  // Servers from a tauriagent://install-server link are added to the current
+  // assistant's config; nothing is saved until the user reviews it
+  useEffect(() => {
+    if (!launchedServerConfig || !currentAssistant) return;
+    setEditingAssistant(currentAssistant);
+    setIsCreating(false);
+    setMcpConfigText(
+      JSON.stringify(
+        {
+          ...currentAssistant.mcpConfig,
+          mcpServers: {
+            ...currentAssistant.mcpConfig?.mcpServers,
+            ...launchedServerConfig.mcpServers,
+          },
+        },
+        null,
+        2,
+      ),
+    );
+    clearLaunchedServerConfig();
+  }, [launchedServerConfig, currentAssistant, clearLaunchedServerConfig]);
 
   const handleCreateNew = () => {
     setIsCreating(true);
@@ -192,8 +220,9 @@ export default function AssistantManager({ onClose }: AssistantManagerProps) {
 
                   {currentAssistant?.id === assistant.id && (
                     <div className="flex flex-wrap gap-1 mb-2">
-                      {Object.keys(assistant.mcpConfig?.mcpServers || {}).map(
-                        (serverName) => (
+                      {Object.keys(assistant.mcpConfig?.mcpServers || {})
+                        .filter((serverName) => !unavailable[serverName])
+                        .map((serverName) => (
                           <div
                             key={serverName}
                             className="flex items-center gap-1 text-xs px-1 py-0.5 rounded bg-gray-800"
@@ -210,10 +239,18 @@ export default function AssistantManager({ onClose }: AssistantManagerProps) {
                             />
                             <span className="text-gray-300">{serverName}</span>
                           </div>
-                        ),
-                      )}
+                        ))}
                     </div>
                   )}
+                  {currentAssistant?.id === assistant.id &&
+                    Object.entries(unavailable).map(([serverName, reason]) => (
+                      <div
+                        key={serverName}
+                        className="text-xs text-yellow-400 mb-2"
+                      >
+                        {serverName}: {reason}
+                      </div>
+                    ))}
 
                   <div className="flex flex-wrap gap-2">
                     <Button
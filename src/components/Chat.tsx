@@ -4,7 +4,8 @@ import { useAssistantContext } from "../context/AssistantContext";
 import { useChatContext } from "../hooks/use-chat";
 import { useMCPServer } from "../hooks/use-mcp-server";
 import { useLocalTools } from "../context/LocalToolContext";
-import { StreamableMessage } from "../types/chat";
+import { MessageAttachment, StreamableMessage } from "../types/chat";
+import { tauriMCPClient } from "../lib/tauri-mcp-client";
 import { getLogger } from "../lib/logger";
 import AssistantManager from "./AssistantManager";
 import { FileAttachment, Input } from "./ui";
@@ -113,11 +114,31 @@ export default function Chat({
       });
     }
 
+    // The prompt carries the text; the message keeps a reference to the stored file
+    const attachments: MessageAttachment[] = [];
+    for (const file of attachedFiles) {
+      try {
+        const info = await tauriMCPClient.storeAttachment(
+          new TextEncoder().encode(file.content),
+          "text/plain",
+        );
+        attachments.push({
+          name: file.name,
+          hash: info.hash,
+          size: info.size,
+          mimeType: info.mime_type,
+        });
+      } catch (error) {
+        logger.error(`Error storing attachment ${file.name}:`, { error });
+      }
+    }
+
     const userMessage: StreamableMessage = {
       id: createId(),
       content: messageContent,
       role: "user",
       sessionId: currentSession?.id || "", // Add sessionId
+      attachments: attachments.length > 0 ? attachments : undefined,
     };
 
     setInput("");
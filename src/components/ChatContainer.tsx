@@ -1,4 +1,4 @@
-import React, { useState } from "react";
+import React, { useEffect, useState } from "react";
 import { useChatContext } from "../hooks/use-chat";
 import Chat from "./Chat";
 import StartSingleChatView from "./StartSingleChatView";
@@ -9,7 +9,14 @@ interface ChatContainerProps {
 
 export default function ChatContainer({ children }: ChatContainerProps) {
   const [showAssistantManager, setShowAssistantManager] = useState(false);
-  const { currentSession } = useChatContext();
+  const { currentSession, launchedServerConfig } = useChatContext();
+
+  // A tauriagent://install-server link opens the assistant editor prefilled
+  useEffect(() => {
+    if (launchedServerConfig) {
+      setShowAssistantManager(true);
+    }
+  }, [launchedServerConfig]);
 
   if (!currentSession) {
     return (
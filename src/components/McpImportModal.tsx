@@ -0,0 +1,123 @@
+import { useState } from "react";
+import { useAssistantContext } from "../context/AssistantContext";
+import { useChatContext } from "../hooks/use-chat";
+import { getLogger } from "../lib/logger";
+import { Badge, Button, Modal } from "./ui";
+
+const logger = getLogger("McpImportModal");
+
+const CHANGE_VARIANTS = {
+  added: "success",
+  changed: "warning",
+  unchanged: "default",
+} as const;
+
+// Shown when a .mcp.json file is opened with the app; the servers are merged into
+// the current assistant only after the user confirms
+export default function McpImportModal() {
+  const { currentAssistant, saveAssistant } = useAssistantContext();
+  const { mcpImportPreview: preview, clearMcpImportPreview } =
+    useChatContext();
+  const [isImporting, setIsImporting] = useState(false);
+
+  if (!preview) {
+    return null;
+  }
+
+  const pending = preview.servers.filter((s) => s.change !== "unchanged");
+
+  const handleImport = async () => {
+    if (!currentAssistant) return;
+    setIsImporting(true);
+    try {
+      const mcpConfig = {
+        ...currentAssistant.mcpConfig,
+        mcpServers: {
+          ...currentAssistant.mcpConfig?.mcpServers,
+          ...preview.config.mcpServers,
+        },
+      };
+      await saveAssistant(currentAssistant, JSON.stringify(mcpConfig, null, 2));
+      clearMcpImportPreview();
+    } catch (error) {
+      logger.error("Failed to import servers:", error);
+    } finally {
+      setIsImporting(false);
+    }
+  };
+
+  return (
+    <Modal
+      isOpen
+      onClose={clearMcpImportPreview}
+      title="Import MCP Servers"
+      size="lg"
+    >
+      <div className="p-4 space-y-4 text-gray-300">
+        <p className="text-sm text-gray-400 break-all">{preview.path}</p>
+        <p className="text-sm">
+          Servers are added to{" "}
+          <span className="text-green-300">{currentAssistant?.name}</span>.
+        </p>
+
+        <div className="border border-gray-700 rounded-md p-3 space-y-2 max-h-80 overflow-y-auto terminal-scrollbar">
+          {preview.servers.length === 0 ? (
+            <p className="text-gray-500">The file has no servers.</p>
+          ) : (
+            preview.servers.map((server) => (
+              <div key={server.name} className="space-y-1">
+                <div className="flex items-center gap-2">
+                  <Badge variant={CHANGE_VARIANTS[server.change]} size="sm">
+                    {server.change}
+                  </Badge>
+                  <span className="font-medium text-green-300">
+                    {server.name}
+                  </span>
+                </div>
+                <pre className="text-xs text-gray-400 whitespace-pre-wrap">
+                  {JSON.stringify(server.config, null, 2)}
+                </pre>
+              </div>
+            ))
+          )}
+        </div>
+
+        {preview.diagnostics.length > 0 && (
+          <ul className="text-sm space-y-1">
+            {preview.diagnostics.map((d) => (
+              <li
+                key={`${d.pointer}-${d.message}`}
+                className={
+                  d.severity === "error" ? "text-red-400" : "text-yellow-400"
+                }
+              >
+                {d.path || "(root)"}: {d.message}
+                {d.suggestion && ` (${d.suggestion})`}
+              </li>
+            ))}
+          </ul>
+        )}
+
+        <div className="flex justify-end gap-2">
+          <Button variant="ghost" onClick={clearMcpImportPreview}>
+            Cancel
+          </Button>
+          <Button
+            variant="primary"
+            onClick={handleImport}
+            disabled={
+              !preview.importable ||
+              pending.length === 0 ||
+              !currentAssistant ||
+              isImporting
+            }
+          >
+            {isImporting
+              ? "Importing..."
+              : `Import ${pending.length} server${pending.length === 1 ? "" : "s"}`}
+          </Button>
+        </div>
+      </div>
+    </Modal>
+  );
+}
@@ -1,5 +1,6 @@
 import React from "react";
 import { LoadingSpinner } from "./ui";
+import { MessageAttachment } from "../types/chat";
 
 interface MessageWithAttachments {
   id: string;
@@ -7,7 +8,7 @@ interface MessageWithAttachments {
   role: "user" | "assistant" | "system" | "tool";
   thinking?: string;
   isStreaming?: boolean;
-  attachments?: { name: string; content: string }[];
+  attachments?: MessageAttachment[];
   tool_calls?: {
     id: string;
     type: "function";
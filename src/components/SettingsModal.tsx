@@ -7,11 +7,35 @@ import {
   TabsTrigger,
   TabsContent,
   CompactModelPicker,
+  Dropdown,
 } from "./ui";
-import { AIServiceProvider } from "../lib/ai-service";
+import {
+  AIServiceProvider,
+  AZURE_DEFAULT_API_VERSION,
+  ProviderSettings,
+} from "../lib/ai-service";
 import { useSettings } from "../hooks/use-settings";
 import { ChangeEvent, useCallback, useState } from "react";
 
+// "a, b" 형식의 목록과 "model=deployment, ..." 형식의 매핑을 다룹니다
+const parseList = (text: string) =>
+  text
+    .split(",")
+    .map((item) => item.trim())
+    .filter(Boolean);
+
+const parseDeployments = (text: string) =>
+  Object.fromEntries(
+    parseList(text)
+      .map((entry) => entry.split("=").map((part) => part.trim()))
+      .filter(([model, deployment]) => model && deployment),
+  );
+
+const formatDeployments = (deployments?: Record<string, string>) =>
+  Object.entries(deployments || {})
+    .map(([model, deployment]) => `${model}=${deployment}`)
+    .join(", ");
+
 interface SettingsModalProps {
   isOpen: boolean;
   onClose: () => void;
@@ -19,7 +43,7 @@ interface SettingsModalProps {
 
 export default function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
   const {
-    value: { apiKeys, windowSize },
+    value: { apiKeys, providerSettings, windowSize },
     update,
   } = useSettings();
   const [activeTab, setActiveTab] = useState("api-key");
@@ -31,6 +55,23 @@ export default function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
     [update],
   );
 
+  const handleProviderSettingsUpdate = useCallback(
+    (serviceProvider: AIServiceProvider, patch: ProviderSettings) => {
+      update({
+        providerSettings: {
+          [serviceProvider]: {
+            ...providerSettings[serviceProvider],
+            ...patch,
+          },
+        },
+      });
+    },
+    [update, providerSettings],
+  );
+
+  const azure = providerSettings[AIServiceProvider.Azure] || {};
+  const openRouter = providerSettings[AIServiceProvider.OpenRouter] || {};
+
   const handleWindowSizeUpdate = useCallback(
     (size: number) => {
       update({ windowSize: size });
@@ -72,6 +113,83 @@ export default function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                     value={apiKeys[serviceProvider] || ""}
                     onChange={(e) => handleApiKeyUpdate(e, serviceProvider)}
                   />
+                  {serviceProvider === AIServiceProvider.Azure && (
+                    <div className="space-y-2 mt-2 pl-2">
+                      <Input
+                        placeholder="https://<resource>.openai.azure.com"
+                        value={azure.endpoint || ""}
+                        onChange={(e) =>
+                          handleProviderSettingsUpdate(serviceProvider, {
+                            endpoint: e.target.value,
+                          })
+                        }
+                      />
+                      <Input
+                        placeholder={`API version (default ${AZURE_DEFAULT_API_VERSION})`}
+                        value={azure.apiVersion || ""}
+                        onChange={(e) =>
+                          handleProviderSettingsUpdate(serviceProvider, {
+                            apiVersion: e.target.value,
+                          })
+                        }
+                      />
+                      <Input
+                        key={formatDeployments(azure.deployments)}
+                        placeholder="Deployments, e.g. gpt-4.1=my-gpt41"
+                        defaultValue={formatDeployments(azure.deployments)}
+                        onBlur={(e) =>
+                          handleProviderSettingsUpdate(serviceProvider, {
+                            deployments: parseDeployments(e.target.value),
+                          })
+                        }
+                      />
+                      <Dropdown
+                        options={[
+                          { label: "API key", value: "api-key" },
+                          {
+                            label: "Microsoft Entra ID token",
+                            value: "aad",
+                          },
+                        ]}
+                        value={azure.authMode || "api-key"}
+                        placeholder="Authentication"
+                        onChange={(authMode: "api-key" | "aad") =>
+                          handleProviderSettingsUpdate(serviceProvider, {
+                            authMode,
+                          })
+                        }
+                      />
+                    </div>
+                  )}
+                  {serviceProvider === AIServiceProvider.OpenRouter && (
+                    <div className="space-y-2 mt-2 pl-2">
+                      <Input
+                        key={(openRouter.providerOrder || []).join(", ")}
+                        placeholder="Provider order, e.g. anthropic, openai"
+                        defaultValue={(openRouter.providerOrder || []).join(
+                          ", ",
+                        )}
+                        onBlur={(e) =>
+                          handleProviderSettingsUpdate(serviceProvider, {
+                            providerOrder: parseList(e.target.value),
+                          })
+                        }
+                      />
+                      <label className="flex items-center text-sm text-gray-400">
+                        <input
+                          type="checkbox"
+                          className="mr-2"
+                          checked={openRouter.allowFallbacks ?? true}
+                          onChange={(e) =>
+                            handleProviderSettingsUpdate(serviceProvider, {
+                              allowFallbacks: e.target.checked,
+                            })
+                          }
+                        />
+                        Allow fallback to other providers
+                      </label>
+                    </div>
+                  )}
                 </div>
               ))}
             </div>
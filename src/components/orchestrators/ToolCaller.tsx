@@ -23,10 +23,12 @@ export const ToolCaller: React.FC = () => {
         const toolResults: StreamableMessage[] = [];
         for (const toolCall of lastMessage.tool_calls!) {
           const toolName = toolCall.function.name;
-          const callFunction = isLocalTool(toolName)
-            ? callLocalTool
-            : callMcpTool;
-          const result = await callFunction(toolCall);
+          const result = isLocalTool(toolName)
+            ? await callLocalTool(toolCall)
+            : await callMcpTool(toolCall, {
+                session_id: currentSession?.id,
+                agent_id: currentSession?.assistants[0]?.id,
+              });
           toolResults.push({
             id: createId(),
             role: "tool",
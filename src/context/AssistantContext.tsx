@@ -156,6 +156,18 @@ export const AssistantContextProvider = ({
 
       try {
         const finalConfig = { mcpServers: mcpConfig.mcpServers || {} };
+        // Servers added here (typed, imported or from a link) start with a clean
+        // environment; servers saved before keep whatever policy they had
+        const previousServers =
+          assistants?.find((a) => a.id === editingAssistant.id)?.mcpConfig
+            ?.mcpServers ?? {};
+        for (const [name, server] of Object.entries<any>(
+          finalConfig.mcpServers,
+        )) {
+          if (!(name in previousServers) && server && !server.env_policy) {
+            server.env_policy = "clean";
+          }
+        }
 
         // 기존 Assistant 편집 시 id를 유지, 새 Assistant일 때만 id 생성
 
@@ -192,7 +204,7 @@ export const AssistantContextProvider = ({
         return undefined;
       }
     },
-    [currentAssistant, loadAssistants],
+    [assistants, currentAssistant, loadAssistants],
   );
 
   const [{}, deleteAssistant] = useAsyncFn(
@@ -11,6 +11,8 @@ import { StreamableMessage, Session, Assistant } from "../types/chat";
 import { useAssistantContext } from "./AssistantContext";
 import { createId } from "@paralleldrive/cuid2";
 import { dbService, dbUtils } from "../lib/db"; // Import dbService and dbUtils
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import { McpImportPreview, tauriMCPClient } from "../lib/tauri-mcp-client";
 
 export interface ChatContextType {
   messages: StreamableMessage[];
@@ -40,6 +42,12 @@ export interface ChatContextType {
   loadSession: (sessionId: string) => Promise<void>;
   clearCurrentSession: () => void;
   deleteSession: (sessionId: string) => Promise<void>;
+  // Server config from a tauriagent://install-server link, waiting for the user to review
+  launchedServerConfig: Assistant["mcpConfig"] | null;
+  clearLaunchedServerConfig: () => void;
+  // An opened .mcp.json file, waiting for the user to confirm the import
+  mcpImportPreview: McpImportPreview | null;
+  clearMcpImportPreview: () => void;
 }
 
 export const ChatContext = createContext<ChatContextType | undefined>(
@@ -56,13 +64,18 @@ export const ChatContextProvider: React.FC<ChatProviderProps> = ({
   const [messages, setMessages] = useState<StreamableMessage[]>([]);
   const [currentSession, setCurrentSession] = useState<Session | null>(null);
   const messagesRef = useRef(messages);
+  // Prompt from `tauri-agent chat`, sent once its new session is current
+  const pendingPromptRef = useRef<string | null>(null);
+  const [launchedServerConfig, setLaunchedServerConfig] = useState<
+    Assistant["mcpConfig"] | null
+  >(null);
   const {
     error,
     isLoading,
     response,
     submit: triggerAIService,
   } = useAIService();
-  const { currentAssistant } = useAssistantContext();
+  const { currentAssistant, assistants } = useAssistantContext();
   const { connectServers } = useMCPServer();
 
   // Message validation function
@@ -299,6 +312,16 @@ export const ChatContextProvider: React.FC<ChatProviderProps> = ({
     }
   }, []);
 
+  // Extra chat windows are opened on a session with ?session=<id>
+  useEffect(() => {
+    const sessionId = new URLSearchParams(window.location.search).get(
+      "session",
+    );
+    if (sessionId) {
+      loadSession(sessionId).catch(() => {});
+    }
+  }, [loadSession]);
+
   const getMessages = useCallback(() => {
     return messagesRef.current;
   }, []);
@@ -374,6 +397,88 @@ export const ChatContextProvider: React.FC<ChatProviderProps> = ({
     [triggerAIService, currentSession, validateMessage],
   );
 
+  const clearLaunchedServerConfig = useCallback(() => {
+    setLaunchedServerConfig(null);
+  }, []);
+
+  const [mcpImportPreview, setMcpImportPreview] =
+    useState<McpImportPreview | null>(null);
+  const clearMcpImportPreview = useCallback(() => {
+    setMcpImportPreview(null);
+  }, []);
+
+  // Launches with arguments, including ones forwarded from a second launch
+  useEffect(() => {
+    if (!currentAssistant) {
+      return;
+    }
+    const handleLaunchRequests = async () => {
+      const requests = await tauriMCPClient.takeLaunchRequests();
+      for (const request of requests) {
+        if (request.kind === "chat") {
+          pendingPromptRef.current = request.prompt;
+          await startNewSession([currentAssistant], "single");
+        } else if (request.kind === "run-agent") {
+          const assistant = assistants?.find(
+            (a) => a.id === request.agent || a.name === request.agent,
+          );
+          if (!assistant) {
+            console.error(`Agent '${request.agent}' not found`);
+            continue;
+          }
+          // Links can come from any web page, so the prompt is shown before it runs
+          if (
+            !window.confirm(
+              `Run "${assistant.name}" with this prompt?\n\n${request.prompt}`,
+            )
+          ) {
+            continue;
+          }
+          pendingPromptRef.current = request.prompt;
+          await startNewSession([assistant], "single");
+        } else if (request.kind === "install-server") {
+          setLaunchedServerConfig(request.config);
+        } else if (request.path.endsWith(".mcp.json")) {
+          setMcpImportPreview(
+            await tauriMCPClient.previewMcpImport(
+              request.path,
+              currentAssistant.mcpConfig,
+            ),
+          );
+        } else {
+          console.info("Opened with file:", request.path);
+        }
+      }
+    };
+    handleLaunchRequests().catch((error) =>
+      console.error("Failed to handle launch requests:", error),
+    );
+    const unlisten = getCurrentWindow().listen("launch-request", () => {
+      handleLaunchRequests().catch((error) =>
+        console.error("Failed to handle launch requests:", error),
+      );
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [currentAssistant, assistants, startNewSession]);
+
+  useEffect(() => {
+    const prompt = pendingPromptRef.current;
+    if (!prompt || !currentSession) {
+      return;
+    }
+    pendingPromptRef.current = null;
+    submit([
+      {
+        id: createId(),
+        sessionId: currentSession.id,
+        role: "user",
+        content: prompt,
+      },
+    ]).catch(() => {});
+  }, [currentSession, submit]);
+
   return (
     <ChatContext.Provider
       value={{
@@ -392,6 +497,10 @@ export const ChatContextProvider: React.FC<ChatProviderProps> = ({
         loadSession,
         clearCurrentSession,
         deleteSession,
+        launchedServerConfig,
+        clearLaunchedServerConfig,
+        mcpImportPreview,
+        clearMcpImportPreview,
       }}
     >
       {children}
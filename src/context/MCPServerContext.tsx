@@ -9,7 +9,11 @@ import React, {
 } from "react";
 import { useAsyncFn } from "react-use";
 import { getLogger } from "../lib/logger";
-import { MCPTool, tauriMCPClient } from "../lib/tauri-mcp-client";
+import {
+  MCPTool,
+  tauriMCPClient,
+  ToolCallContext,
+} from "../lib/tauri-mcp-client";
 import { useAssistantContext } from "./AssistantContext";
 import { Assistant } from "../types/chat";
 
@@ -20,12 +24,17 @@ interface MCPServerContextType {
   getAvailableTools: () => MCPTool[];
   isConnecting: boolean;
   status: Record<string, boolean>;
+  // Servers left out because this platform cannot start their transport, with the reason
+  unavailable: Record<string, string>;
   connectServers: (assistant: Assistant) => Promise<void>;
-  executeToolCall: (toolCall: {
-    id: string;
-    type: "function";
-    function: { name: string; arguments: string };
-  }) => Promise<{ role: "tool"; content: string; tool_call_id: string }>;
+  executeToolCall: (
+    toolCall: {
+      id: string;
+      type: "function";
+      function: { name: string; arguments: string };
+    },
+    context?: ToolCallContext,
+  ) => Promise<{ role: "tool"; content: string; tool_call_id: string }>;
 }
 
 export const MCPServerContext = createContext<MCPServerContextType | undefined>(
@@ -37,15 +46,43 @@ export const MCPServerProvider: React.FC<{ children: ReactNode }> = ({
 }) => {
   const [availableTools, setAvailableTools] = useState<MCPTool[]>([]);
   const [serverStatus, setServerStatus] = useState<Record<string, boolean>>({});
+  const [unavailable, setUnavailable] = useState<Record<string, string>>({});
   const availableToolsRef = useRef(availableTools);
   const { currentAssistant } = useAssistantContext();
   const [{ loading: isConnecting }, connectServers] = useAsyncFn(
     async (assistant: Assistant) => {
       const serverStatus: Record<string, boolean> = {};
       try {
-        const configForTauri = {
-          mcpServers: assistant.mcpConfig.mcpServers || {},
-        };
+        // Mobile builds only reach remote servers; stdio and docker configs are skipped
+        const { transports } = await tauriMCPClient.getPlatformCapabilities();
+        const mcpServers: NonNullable<Assistant["mcpConfig"]["mcpServers"]> =
+          {};
+        const skipped: Record<string, string> = {};
+        for (const [name, config] of Object.entries(
+          assistant.mcpConfig.mcpServers || {},
+        )) {
+          // Without a transport, a url means a remote server (ws:// or wss:// for websocket)
+          const transport =
+            config?.runtime === "docker"
+              ? "docker"
+              : config?.transport ||
+                (config?.url
+                  ? /^wss?:\/\//.test(config.url)
+                    ? "websocket"
+                    : "http"
+                  : "stdio");
+          if (transports.includes(transport)) {
+            mcpServers[name] = config;
+          } else {
+            skipped[name] =
+              `The ${transport} transport is not available on this platform; only remote (http, websocket) servers can be used`;
+          }
+        }
+        setUnavailable(skipped);
+        if (Object.keys(skipped).length > 0) {
+          logger.warn("Skipping servers this platform cannot start:", skipped);
+        }
+        const configForTauri = { mcpServers };
 
         const servers = Object.keys(configForTauri.mcpServers);
 
@@ -84,23 +121,20 @@ export const MCPServerProvider: React.FC<{ children: ReactNode }> = ({
   );
 
   const executeToolCall = useCallback(
-    async (toolCall: {
-      id: string;
-      type: "function";
-      function: { name: string; arguments: string };
-    }): Promise<{ role: "tool"; content: string; tool_call_id: string }> => {
+    async (
+      toolCall: {
+        id: string;
+        type: "function";
+        function: { name: string; arguments: string };
+      },
+      context?: ToolCallContext,
+    ): Promise<{ role: "tool"; content: string; tool_call_id: string }> => {
       logger.debug(`Executing tool call:`, { toolCall });
       const aiProvidedToolName = toolCall.function.name;
-      let serverName: string | undefined;
-      let toolName: string | undefined;
-
-      // Use '__' as delimiter for URL and JSON safety
-      const delimiter = "__";
-      const parts = aiProvidedToolName.split(delimiter);
-      if (parts.length >= 2) {
-        serverName = parts[0];
-        toolName = parts.slice(1).join(delimiter);
-      }
+      // Aliased tools have no server prefix, so the backend maps the name back
+      const resolved = await tauriMCPClient.resolveToolName(aiProvidedToolName);
+      const serverName = resolved?.server_name;
+      const toolName = resolved?.tool_name;
 
       if (!serverName || !toolName) {
         logger.error(
@@ -133,6 +167,7 @@ export const MCPServerProvider: React.FC<{ children: ReactNode }> = ({
           serverName,
           toolName,
           toolArguments,
+          context,
         );
         logger.debug(`Tool execution result for ${toolCall.function.name}:`, {
           result,
@@ -177,6 +212,7 @@ export const MCPServerProvider: React.FC<{ children: ReactNode }> = ({
       isConnecting,
       getAvailableTools,
       status: serverStatus,
+      unavailable,
       connectServers,
       executeToolCall,
     }),
@@ -184,6 +220,7 @@ export const MCPServerProvider: React.FC<{ children: ReactNode }> = ({
       availableTools,
       isConnecting,
       serverStatus,
+      unavailable,
       getAvailableTools,
       connectServers,
       executeToolCall,
@@ -6,7 +6,7 @@ import React, {
   useMemo,
 } from "react";
 import { useAsyncFn } from "react-use";
-import { AIServiceProvider } from "../lib/ai-service";
+import { AIServiceProvider, ProviderSettings } from "../lib/ai-service";
 import { dbService } from "../lib/db";
 import { llmConfigManager } from "../lib/llm-config-manager";
 import { getLogger } from "../lib/logger";
@@ -20,6 +20,7 @@ interface ModelChoice {
 
 export interface Settings {
   apiKeys: Record<AIServiceProvider, string>;
+  providerSettings: Partial<Record<AIServiceProvider, ProviderSettings>>;
   preferredModel: ModelChoice;
   windowSize: number;
 }
@@ -28,6 +29,7 @@ const DEFAULT_MODEL = llmConfigManager.recommendModel({});
 
 export const DEFAULT_SETTING: Settings = {
   apiKeys: {} as Record<AIServiceProvider, string>,
+  providerSettings: {},
   preferredModel: {
     provider: (DEFAULT_MODEL?.providerId || "openai") as AIServiceProvider,
     model: DEFAULT_MODEL?.modelId || "",
@@ -49,14 +51,23 @@ export const SettingsContext = createContext<SettingsContextType | undefined>(
 export function SettingsProvider({ children }: { children: React.ReactNode }) {
   const [{ value, loading, error }, load] = useAsyncFn(async () => {
     try {
-      const [apiKeysObject, preferredModelObject, windowSizeObject] = await Promise.all([
+      const [
+        apiKeysObject,
+        providerSettingsObject,
+        preferredModelObject,
+        windowSizeObject,
+      ] = await Promise.all([
         dbService.objects.read("apiKeys"),
+        dbService.objects.read("providerSettings"),
         dbService.objects.read("preferredModel"),
         dbService.objects.read("windowSize"),
       ]);
       const settings: Settings = {
         ...DEFAULT_SETTING,
         ...(apiKeysObject ? { apiKeys: apiKeysObject.value } : {}),
+        ...(providerSettingsObject
+          ? { providerSettings: providerSettingsObject.value }
+          : {}),
         ...(preferredModelObject ? { preferredModel: preferredModelObject.value } : {}),
         ...(windowSizeObject != null ? { windowSize: windowSizeObject.value } : {}),
       };
@@ -79,6 +90,16 @@ export function SettingsProvider({ children }: { children: React.ReactNode }) {
           const newApiKeys = { ...(value?.apiKeys || {}), ...settings.apiKeys };
           await dbService.objects.upsert({ key: "apiKeys", value: newApiKeys });
         }
+        if (settings.providerSettings) {
+          const newProviderSettings = {
+            ...(value?.providerSettings || {}),
+            ...settings.providerSettings,
+          };
+          await dbService.objects.upsert({
+            key: "providerSettings",
+            value: newProviderSettings,
+          });
+        }
         if (settings.preferredModel) {
           await dbService.objects.upsert({
             key: "preferredModel",
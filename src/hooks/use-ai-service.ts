@@ -7,16 +7,38 @@ import { useSettings } from "./use-settings";
 import { useMCPServer } from "./use-mcp-server";
 import { useLocalTools } from "../context/LocalToolContext";
 import { useAssistantContext } from "../context/AssistantContext";
+import { tauriMCPClient } from "../lib/tauri-mcp-client";
+import { invoke } from "@tauri-apps/api/core";
+import { emit } from "@tauri-apps/api/event";
 
 const logger = getLogger("useAIService");
 
 const DEFAULT_SYSTEM_PROMPT = "You are a helpful assistant.";
 
+// Append the instructions of connected MCP servers that are toggled on
+const buildSystemPrompt = async (basePrompt: string): Promise<string> => {
+  try {
+    const sections = (await tauriMCPClient.getServerInstructions())
+      .filter((entry) => entry.enabled)
+      .map(
+        (entry) =>
+          `## Instructions from MCP server "${entry.server_name}"\n${entry.instructions}`,
+      );
+    return sections.length > 0
+      ? [basePrompt, ...sections].join("\n\n")
+      : basePrompt;
+  } catch (err) {
+    logger.warn("Failed to load MCP server instructions:", err);
+    return basePrompt;
+  }
+};
+
 export const useAIService = (config?: AIServiceConfig) => {
   const {
     value: {
       preferredModel: { model, provider },
       apiKeys,
+      providerSettings,
     },
   } = useSettings();
   const [response, setResponse] = useState<StreamableMessage | null>(null);
@@ -24,12 +46,17 @@ export const useAIService = (config?: AIServiceConfig) => {
   const [error, setError] = useState<Error | null>(null);
   const serviceInstance = useMemo(
     () =>
-      AIServiceFactory.getService(provider, apiKeys[provider], {
-        defaultModel: model,
-        maxRetries: 3,
-        maxTokens: 4096,
-      }),
-    [provider, apiKeys, model]
+      AIServiceFactory.getService(
+        provider,
+        apiKeys[provider],
+        {
+          defaultModel: model,
+          maxRetries: 3,
+          maxTokens: 4096,
+        },
+        providerSettings[provider],
+      ),
+    [provider, apiKeys, providerSettings, model]
   );
   const { getCurrentAssistant } = useAssistantContext();
 
@@ -53,11 +80,16 @@ export const useAIService = (config?: AIServiceConfig) => {
       let thinking = "";
       let toolCalls: any[] = [];
       let finalMessage: StreamableMessage | null = null;
+      const startMs = Date.now();
+      let spanError: string | null = null;
 
       try {
+        const systemPrompt = await buildSystemPrompt(
+          getCurrentAssistant()?.systemPrompt || DEFAULT_SYSTEM_PROMPT,
+        );
         const stream = serviceInstance.streamChat(messages, {
           modelName: model,
-          systemPrompt: getCurrentAssistant()?.systemPrompt || DEFAULT_SYSTEM_PROMPT,
+          systemPrompt,
           availableTools,
           config: config,
         });
@@ -93,6 +125,15 @@ export const useAIService = (config?: AIServiceConfig) => {
           if (parsedChunk.content) {
             fullContent += parsedChunk.content;
           }
+          if (parsedChunk.content || parsedChunk.thinking) {
+            // Mirrored to external WebSocket clients by the backend
+            emit("agent-token", {
+              responseId: currentResponseId,
+              sessionId: messages[0]?.sessionId || "",
+              content: parsedChunk.content ?? "",
+              thinking: parsedChunk.thinking ?? "",
+            }).catch(() => {});
+          }
 
           finalMessage = {
             id: currentResponseId,
@@ -121,6 +162,7 @@ export const useAIService = (config?: AIServiceConfig) => {
         return finalMessage!;
       } catch (err) {
         logger.error("Error in useAIService stream:", err);
+        spanError = (err as Error)?.message ?? String(err);
         setError(err as Error);
         setResponse((prev) => {
           if (prev) {
@@ -131,6 +173,14 @@ export const useAIService = (config?: AIServiceConfig) => {
         throw err;
       } finally {
         setIsLoading(false);
+        // Reported to the backend telemetry exporter; ignored when it is disabled
+        invoke("record_llm_span", {
+          provider,
+          model,
+          startMs,
+          endMs: Date.now(),
+          error: spanError,
+        }).catch(() => {});
       }
     },
     [
@@ -0,0 +1,169 @@
+import { createId } from "@paralleldrive/cuid2";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import { useEffect, useRef } from "react";
+import { dbService, dbUtils } from "../lib/db";
+import { getLogger } from "../lib/logger";
+import { tauriMCPClient } from "../lib/tauri-mcp-client";
+import { StreamableMessage } from "../types/chat";
+import { useAIService } from "./use-ai-service";
+
+const logger = getLogger("useApiBridge");
+
+interface ApiRequest {
+  id: string;
+  kind:
+    | "chat"
+    | "sessions"
+    | "webhook"
+    | "agents"
+    | "agent-run"
+    | "approval"
+    | "backup-export"
+    | "backup-restore"
+    | "sync-export"
+    | "sync-import"
+    | "history-import"
+    | "retention";
+  payload: any;
+}
+
+// Answers backend requests that need the webview (sessions, agent loop, approvals,
+// backups, device sync, history imports and retention)
+export const useApiBridge = () => {
+  const { submit } = useAIService();
+  // Keep the listener stable while always using the latest submit
+  const submitRef = useRef(submit);
+  submitRef.current = submit;
+
+  useEffect(() => {
+    // One turn of a saved assistant, used by webhooks and A2A tasks
+    const runAgent = async (
+      agentId: string,
+      prompt: string,
+      sessionId: string = createId(),
+    ): Promise<StreamableMessage> => {
+      const assistant = await dbService.assistants.read(agentId);
+      if (!assistant) {
+        throw new Error(`Agent '${agentId}' not found`);
+      }
+      return await submitRef.current([
+        {
+          id: createId(),
+          sessionId,
+          role: "system",
+          content: assistant.systemPrompt,
+        },
+        { id: createId(), sessionId, role: "user", content: prompt },
+      ]);
+    };
+
+    const handle = async ({ id, kind, payload }: ApiRequest) => {
+      try {
+        if (kind === "sessions") {
+          const page = await dbService.sessions.getPage(
+            payload?.page ?? 1,
+            payload?.page_size ?? 20,
+          );
+          await tauriMCPClient.resolveApiRequest(id, page);
+        } else if (kind === "chat") {
+          const sessionId: string = payload?.session_id ?? createId();
+          const messages: StreamableMessage[] = (payload?.messages ?? []).map(
+            (message: { role: StreamableMessage["role"]; content: string }) => ({
+              id: createId(),
+              sessionId,
+              role: message.role,
+              content: message.content,
+            }),
+          );
+          const reply = await submitRef.current(messages);
+          await tauriMCPClient.resolveApiRequest(id, reply);
+        } else if (kind === "webhook") {
+          const eventLabel = payload.event_type
+            ? ` (${payload.event_type})`
+            : "";
+          const reply = await runAgent(
+            payload.agent_id,
+            `A webhook event was received${eventLabel}:\n\n\`\`\`json\n${JSON.stringify(payload.payload, null, 2)}\n\`\`\``,
+          );
+          await tauriMCPClient.resolveApiRequest(id, reply);
+        } else if (kind === "agents") {
+          const { items } = await dbService.assistants.getPage(1, -1);
+          await tauriMCPClient.resolveApiRequest(
+            id,
+            items.map((assistant) => ({ id: assistant.id, name: assistant.name })),
+          );
+        } else if (kind === "agent-run") {
+          const reply = await runAgent(
+            payload.agent_id,
+            payload.prompt,
+            payload.context_id,
+          );
+          await tauriMCPClient.resolveApiRequest(id, reply);
+        } else if (kind === "approval") {
+          // Backend actions such as screen capture need an explicit yes
+          await tauriMCPClient.resolveApiRequest(
+            id,
+            window.confirm(payload.message),
+          );
+        } else if (kind === "backup-export" || kind === "sync-export") {
+          await tauriMCPClient.resolveApiRequest(
+            id,
+            await dbUtils.exportDatabase(),
+          );
+        } else if (kind === "backup-restore") {
+          await dbUtils.importDatabase(payload);
+          await tauriMCPClient.resolveApiRequest(id, true);
+        } else if (kind === "sync-import") {
+          // Synced sessions carry all their messages; deletions came from another device
+          await dbUtils.importDatabase({
+            sessions: payload.sessions,
+            messages: payload.messages,
+          });
+          for (const sessionId of payload.deleted ?? []) {
+            await dbService.sessions.delete(sessionId);
+          }
+          await tauriMCPClient.resolveApiRequest(id, true);
+        } else if (kind === "history-import") {
+          // Sessions parsed from a ChatGPT or Claude export
+          await dbUtils.importDatabase({
+            sessions: payload.sessions,
+            messages: payload.messages,
+          });
+          await tauriMCPClient.resolveApiRequest(id, true);
+        } else if (kind === "retention") {
+          // Session age rule of the retention policy; answers with the count removed
+          const cutoff = new Date(
+            Date.now() - payload.max_session_age_days * 24 * 60 * 60 * 1000,
+          );
+          await tauriMCPClient.resolveApiRequest(
+            id,
+            await dbUtils.deleteSessionsOlderThan(cutoff),
+          );
+        } else {
+          await tauriMCPClient.resolveApiRequest(
+            id,
+            undefined,
+            `Unsupported request kind '${kind}'`,
+          );
+        }
+      } catch (err) {
+        logger.error("Failed to answer API request:", err);
+        await tauriMCPClient
+          .resolveApiRequest(id, undefined, String(err))
+          .catch(() => {});
+      }
+    };
+
+    // Only requests sent to this window (or broadcast); a global listener would
+    // answer requests routed to other chat windows too
+    const unlisten = getCurrentWindow().listen<ApiRequest>(
+      "api-request",
+      (event) => {
+        handle(event.payload);
+      },
+    );
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+};
@@ -1,6 +1,6 @@
 import { createId } from "@paralleldrive/cuid2";
 import Groq from "groq-sdk";
-import OpenAI from "openai";
+import OpenAI, { AzureOpenAI } from "openai";
 import Anthropic from "@anthropic-ai/sdk";
 import { FunctionDeclaration, GoogleGenAI, Content, Type } from "@google/genai";
 import { ChatCompletionTool as GroqChatCompletionTool } from "groq-sdk/resources/chat/completions.mjs";
@@ -30,9 +30,30 @@ export enum AIServiceProvider {
   OpenAI = "openai",
   Anthropic = "anthropic",
   Gemini = "gemini",
+  OpenRouter = "openrouter",
+  Azure = "azure",
   Empty = "empty",
 }
 
+/** Provider-specific connection settings beyond the API key */
+export interface ProviderSettings {
+  /** Azure OpenAI resource endpoint, e.g. https://my-resource.openai.azure.com */
+  endpoint?: string;
+  /** Azure OpenAI REST api-version */
+  apiVersion?: string;
+  /** With "aad" the API key field holds a Microsoft Entra ID access token */
+  authMode?: "api-key" | "aad";
+  /** Model id to Azure deployment name; unmapped models use the model id */
+  deployments?: Record<string, string>;
+  /** OpenRouter upstream providers to try in order, e.g. ["anthropic", "openai"] */
+  providerOrder?: string[];
+  /** Whether OpenRouter may fall back to providers outside `providerOrder` */
+  allowFallbacks?: boolean;
+}
+
+export const AZURE_DEFAULT_API_VERSION = "2024-10-21";
+const OPENROUTER_BASE_URL = "https://openrouter.ai/api/v1";
+
 import { StreamableMessage } from "../types/chat";
 
 export class AIServiceError extends Error {
@@ -221,6 +242,8 @@ function convertMCPToolToProviderFormat(
 
   switch (provider) {
     case AIServiceProvider.OpenAI:
+    case AIServiceProvider.OpenRouter:
+    case AIServiceProvider.Azure:
       return {
         type: "function",
         function: {
@@ -549,14 +572,16 @@ export class GroqService extends BaseAIService {
 }
 
 export class OpenAIService extends BaseAIService {
-  private openai: OpenAI;
+  protected openai: OpenAI;
 
-  constructor(apiKey: string, config?: AIServiceConfig) {
+  constructor(apiKey: string, config?: AIServiceConfig, client?: OpenAI) {
     super(apiKey, config);
-    this.openai = new OpenAI({
-      apiKey: this.apiKey,
-      dangerouslyAllowBrowser: true,
-    });
+    this.openai =
+      client ??
+      new OpenAI({
+        apiKey: this.apiKey,
+        dangerouslyAllowBrowser: true,
+      });
   }
 
   getProvider(): AIServiceProvider {
@@ -579,7 +604,7 @@ export class OpenAIService extends BaseAIService {
       logger.info(" tool calls: ", {
         tools: convertMCPToolsToProviderTools(
           options?.availableTools,
-          AIServiceProvider.OpenAI,
+          this.getProvider(),
         ),
       });
     }
@@ -593,14 +618,17 @@ export class OpenAIService extends BaseAIService {
 
       const completion = await this.withRetry(() =>
         this.openai.chat.completions.create({
-          model: options.modelName || config.defaultModel || "gpt-4-turbo",
+          ...this.extraBody(),
+          model: this.resolveModel(
+            options.modelName || config.defaultModel || "gpt-4-turbo",
+          ),
           messages: openaiMessages,
           max_completion_tokens: config.maxTokens,
           stream: true,
           tools: options.availableTools
             ? (convertMCPToolsToProviderTools(
                 options.availableTools,
-                AIServiceProvider.OpenAI,
+                this.getProvider(),
               ) as OpenAIChatCompletionTool[])
             : undefined,
           tool_choice: options.availableTools ? "auto" : undefined,
@@ -620,16 +648,26 @@ export class OpenAIService extends BaseAIService {
       }
     } catch (error) {
       throw new AIServiceError(
-        `OpenAI streaming failed: ${
+        `${this.getProvider()} streaming failed: ${
           error instanceof Error ? error.message : "Unknown error"
         }`,
-        AIServiceProvider.OpenAI,
+        this.getProvider(),
         undefined,
         error instanceof Error ? error : undefined,
       );
     }
   }
 
+  /** Model name sent in the request */
+  protected resolveModel(modelName: string): string {
+    return modelName;
+  }
+
+  /** Provider-specific fields merged into the request body */
+  protected extraBody(): Record<string, unknown> {
+    return {};
+  }
+
   private convertToOpenAIMessages(
     messages: StreamableMessage[],
     systemPrompt?: string,
@@ -676,6 +714,84 @@ export class OpenAIService extends BaseAIService {
   }
 }
 
+export class OpenRouterService extends OpenAIService {
+  constructor(
+    apiKey: string,
+    config?: AIServiceConfig,
+    private settings: ProviderSettings = {},
+  ) {
+    super(
+      apiKey,
+      config,
+      new OpenAI({
+        apiKey,
+        baseURL: OPENROUTER_BASE_URL,
+        // OpenRouter 앱 순위에 표시되는 정보입니다
+        defaultHeaders: {
+          "HTTP-Referer": "https://github.com/fritzprix/tauri-agent",
+          "X-Title": "TauriAgent",
+        },
+        dangerouslyAllowBrowser: true,
+      }),
+    );
+  }
+
+  getProvider(): AIServiceProvider {
+    return AIServiceProvider.OpenRouter;
+  }
+
+  protected extraBody(): Record<string, unknown> {
+    const { providerOrder, allowFallbacks } = this.settings;
+    if (!providerOrder?.length && allowFallbacks === undefined) {
+      return {};
+    }
+    return {
+      provider: {
+        ...(providerOrder?.length ? { order: providerOrder } : {}),
+        ...(allowFallbacks !== undefined
+          ? { allow_fallbacks: allowFallbacks }
+          : {}),
+      },
+    };
+  }
+}
+
+export class AzureOpenAIService extends OpenAIService {
+  constructor(
+    apiKey: string,
+    config?: AIServiceConfig,
+    private settings: ProviderSettings = {},
+  ) {
+    if (!settings.endpoint) {
+      throw new AIServiceError(
+        "Azure OpenAI requires a resource endpoint",
+        AIServiceProvider.Azure,
+      );
+    }
+    super(
+      apiKey,
+      config,
+      new AzureOpenAI({
+        endpoint: settings.endpoint,
+        apiVersion: settings.apiVersion || AZURE_DEFAULT_API_VERSION,
+        ...(settings.authMode === "aad"
+          ? { azureADTokenProvider: async () => apiKey }
+          : { apiKey }),
+        dangerouslyAllowBrowser: true,
+      }),
+    );
+  }
+
+  getProvider(): AIServiceProvider {
+    return AIServiceProvider.Azure;
+  }
+
+  // Azure는 모델 대신 배포 이름으로 요청을 보냅니다
+  protected resolveModel(modelName: string): string {
+    return this.settings.deployments?.[modelName] || modelName;
+  }
+}
+
 export class AnthropicService extends BaseAIService {
   private anthropic: Anthropic;
 
@@ -1032,8 +1148,10 @@ export class AIServiceFactory {
     provider: AIServiceProvider,
     apiKey: string,
     config?: AIServiceConfig,
+    settings?: ProviderSettings,
   ): IAIService {
-    const instanceKey = `${provider}:${apiKey}`;
+    const settingsKey = JSON.stringify(settings ?? {});
+    const instanceKey = `${provider}:${apiKey}:${settingsKey}`;
     const now = Date.now();
 
     // Clean up expired instances
@@ -1059,6 +1177,12 @@ export class AIServiceFactory {
         case AIServiceProvider.OpenAI:
           service = new OpenAIService(apiKey, config);
           break;
+        case AIServiceProvider.OpenRouter:
+          service = new OpenRouterService(apiKey, config, settings);
+          break;
+        case AIServiceProvider.Azure:
+          service = new AzureOpenAIService(apiKey, config, settings);
+          break;
         case AIServiceProvider.Anthropic:
           service = new AnthropicService(apiKey, config);
           break;
@@ -1,5 +1,10 @@
+import { createId } from "@paralleldrive/cuid2";
 import Dexie, { Table } from "dexie";
 import { Assistant, Session, StreamableMessage } from "../types/chat";
+import { getLogger } from "./logger";
+import { tauriMCPClient } from "./tauri-mcp-client";
+
+const logger = getLogger("db");
 
 // --- TYPE DEFINITIONS ---
 export interface DatabaseObject {
@@ -18,6 +23,33 @@ export interface Page<T> {
   hasPreviousPage: boolean;
 }
 
+export interface SessionQuery {
+  text?: string; // Matches name, description and assistant names
+  tags?: string[]; // Sessions must carry every tag
+  folder?: string; // "" selects sessions outside any folder
+  includeSubfolders?: boolean;
+}
+
+export interface SessionTreeNode {
+  session: Session;
+  children: SessionTreeNode[]; // Oldest fork first
+}
+
+// Inline attachment content saved before v6, waiting to move to the backend store
+export interface PendingAttachment {
+  id: string; // "<messageId>:<attachment index>"
+  messageId: string;
+  index: number;
+  content: string;
+}
+
+export interface DatabaseExport {
+  assistants: Assistant[];
+  objects: DatabaseObject[];
+  sessions: Session[];
+  messages: StreamableMessage[];
+}
+
 export interface CRUD<T> {
   upsert: (object: T) => Promise<void>;
   upsertMany: (objects: T[]) => Promise<void>;
@@ -47,6 +79,7 @@ class LocalDatabase extends Dexie {
   objects!: Table<DatabaseObject, string>;
   sessions!: Table<Session, string>;
   messages!: Table<StreamableMessage, string>;
+  pendingAttachments!: Table<PendingAttachment, string>;
 
   constructor() {
     super("MCPAgentDB");
@@ -98,6 +131,53 @@ class LocalDatabase extends Dexie {
           "Upgrading database to version 3 - adding sessions and messages tables",
         );
       });
+
+    // Version 4: Index session tags and folders
+    this.version(4).stores({
+      sessions: "&id, createdAt, updatedAt, *tags, folder",
+    });
+
+    // Version 5: Index forked sessions by the session they branched from
+    this.version(5).stores({
+      sessions: "&id, createdAt, updatedAt, *tags, folder, parentId",
+    });
+
+    // Version 6: Messages keep attachment hashes; contents move to the backend store.
+    // The store is only reachable outside the upgrade transaction, so inline contents
+    // are staged here and moved by dbUtils.storePendingAttachments
+    this.version(6)
+      .stores({
+        pendingAttachments: "&id, messageId",
+      })
+      .upgrade(async (tx) => {
+        const pending: PendingAttachment[] = [];
+        await tx
+          .table("messages")
+          .toCollection()
+          .modify((message) => {
+            if (!message.attachments) return;
+            message.attachments = message.attachments.map(
+              (
+                attachment: { name: string; content?: string },
+                index: number,
+              ) => {
+                if (typeof attachment.content !== "string") return attachment;
+                pending.push({
+                  id: `${message.id}:${index}`,
+                  messageId: message.id,
+                  index,
+                  content: attachment.content,
+                });
+                return {
+                  name: attachment.name,
+                  size: new TextEncoder().encode(attachment.content).length,
+                  mimeType: "text/plain",
+                };
+              },
+            );
+          });
+        await tx.table("pendingAttachments").bulkAdd(pending);
+      });
   }
 }
 
@@ -247,12 +327,22 @@ export const dbService: DatabaseService = {
       const db = LocalDatabase.getInstance();
       // Use a transaction to ensure atomicity.
       // If deleting messages fails, the session won't be deleted either.
-      await db.transaction("rw", db.sessions, db.messages, async () => {
-        // Delete all messages associated with this session first
-        await db.messages.where("sessionId").equals(id).delete();
-        // Then delete the session itself
-        await db.sessions.delete(id);
-      });
+      const hashes = await db.transaction(
+        "rw",
+        db.sessions,
+        db.messages,
+        async () => {
+          const messages = db.messages.where("sessionId").equals(id);
+          const hashes = attachmentHashes(await messages.toArray());
+          await reparentForks(db, [id]);
+          // Delete all messages associated with this session first
+          await messages.delete();
+          // Then delete the session itself
+          await db.sessions.delete(id);
+          return hashes;
+        },
+      );
+      await releaseAttachments(hashes);
     },
     getPage: async (page: number, pageSize: number): Promise<Page<Session>> => {
       const db = LocalDatabase.getInstance();
@@ -331,7 +421,65 @@ export const dbService: DatabaseService = {
   },
 };
 
+const attachmentHashes = (messages: StreamableMessage[]): string[] =>
+  messages.flatMap((message) =>
+    (message.attachments ?? []).flatMap((attachment) =>
+      attachment.hash ? [attachment.hash] : [],
+    ),
+  );
+
+// Drops the references deleted messages held, once their transaction has committed
+const releaseAttachments = async (hashes: string[]): Promise<void> => {
+  for (const hash of hashes) {
+    try {
+      await tauriMCPClient.releaseAttachment(hash);
+    } catch (error) {
+      logger.warn(`Failed to release attachment ${hash}:`, error);
+    }
+  }
+};
+
+// Forks of deleted sessions move up to the nearest ancestor that is kept; the message
+// they branched from is gone with their parent. Runs inside the deleting transaction
+const reparentForks = async (
+  db: LocalDatabase,
+  deletedIds: string[],
+): Promise<void> => {
+  const deleted = new Set(deletedIds);
+  const parents = new Map<string, string | undefined>();
+  for (const session of await db.sessions.bulkGet(deletedIds)) {
+    if (session) parents.set(session.id, session.parentId);
+  }
+  const keptAncestor = (id: string | undefined): string | undefined => {
+    const seen = new Set<string>();
+    while (id && deleted.has(id) && !seen.has(id)) {
+      seen.add(id);
+      id = parents.get(id);
+    }
+    return id && !deleted.has(id) ? id : undefined;
+  };
+  const forks = await db.sessions.where("parentId").anyOf(deletedIds).toArray();
+  for (const fork of forks) {
+    if (deleted.has(fork.id)) continue;
+    await db.sessions.update(fork.id, {
+      parentId: keptAncestor(fork.parentId),
+      forkedFromMessageId: undefined,
+    });
+  }
+};
+
 // Expanded utility functions
+// Exported rows pass through JSON, which turns Date fields into ISO strings
+const reviveDates = <T>(row: T): T => {
+  const revived = { ...row } as Record<string, unknown>;
+  for (const field of ["createdAt", "updatedAt"]) {
+    if (typeof revived[field] === "string") {
+      revived[field] = new Date(revived[field] as string);
+    }
+  }
+  return revived as T;
+};
+
 export const dbUtils = {
   // --- Assistants ---
   getAllAssistants: async (): Promise<Assistant[]> => {
@@ -371,12 +519,225 @@ export const dbUtils = {
       .toArray();
   },
   clearAllSessions: async (): Promise<void> => {
-    await LocalDatabase.getInstance().sessions.clear();
-    await LocalDatabase.getInstance().messages.clear(); // Also clear all messages
+    const db = LocalDatabase.getInstance();
+    const hashes = attachmentHashes(await db.messages.toArray());
+    await db.sessions.clear();
+    await db.messages.clear(); // Also clear all messages
+    await releaseAttachments(hashes);
+  },
+  // Moves attachment contents staged by the v6 upgrade into the backend store;
+  // returns how many are still waiting
+  storePendingAttachments: async (): Promise<number> => {
+    const db = LocalDatabase.getInstance();
+    const pending = await db.pendingAttachments.toArray();
+    let remaining = 0;
+    for (const item of pending) {
+      try {
+        const info = await tauriMCPClient.storeAttachment(
+          new TextEncoder().encode(item.content),
+          "text/plain",
+        );
+        const attached = await db.transaction(
+          "rw",
+          db.messages,
+          db.pendingAttachments,
+          async () => {
+            await db.pendingAttachments.delete(item.id);
+            const message = await db.messages.get(item.messageId);
+            const attachment = message?.attachments?.[item.index];
+            if (!message || !attachment) return false;
+            attachment.hash = info.hash;
+            await db.messages.put(message);
+            return true;
+          },
+        );
+        // 메시지가 그사이 지워졌다면 방금 만든 참조를 돌려줍니다
+        if (!attached) await releaseAttachments([info.hash]);
+      } catch (error) {
+        logger.warn(`Failed to store attachment ${item.id}:`, error);
+        remaining += 1;
+      }
+    }
+    return remaining;
+  },
+  // Deletes sessions last updated before the cutoff, with their messages; forks of
+  // deleted sessions are kept and attached to the nearest remaining ancestor
+  deleteSessionsOlderThan: async (cutoff: Date): Promise<number> => {
+    const db = LocalDatabase.getInstance();
+    const { removed, hashes } = await db.transaction(
+      "rw",
+      db.sessions,
+      db.messages,
+      async () => {
+        const ids = (await db.sessions
+          .where("updatedAt")
+          .below(cutoff)
+          .primaryKeys()) as string[];
+        const messages = db.messages.where("sessionId").anyOf(ids);
+        const hashes = attachmentHashes(await messages.toArray());
+        await reparentForks(db, ids);
+        await messages.delete();
+        await db.sessions.bulkDelete(ids);
+        return { removed: ids.length, hashes };
+      },
+    );
+    await releaseAttachments(hashes);
+    return removed;
   },
   bulkUpsertSessions: async (sessions: Session[]): Promise<void> => {
     await dbService.sessions.upsertMany(sessions);
   },
+  // Organizing a session does not touch updatedAt, so lists keep their order
+  tagSession: async (
+    sessionId: string,
+    tags: string[],
+    mode: "add" | "remove" | "set" = "add",
+  ): Promise<string[]> => {
+    const db = LocalDatabase.getInstance();
+    return db.transaction("rw", db.sessions, async () => {
+      const session = await db.sessions.get(sessionId);
+      if (!session) throw new Error(`Session ${sessionId} not found`);
+      const cleaned = tags.map((tag) => tag.trim()).filter(Boolean);
+      const current = session.tags ?? [];
+      const next =
+        mode === "set"
+          ? cleaned
+          : mode === "add"
+            ? [...current, ...cleaned]
+            : current.filter((tag) => !cleaned.includes(tag));
+      const unique = [...new Set(next)];
+      await db.sessions.update(sessionId, { tags: unique });
+      return unique;
+    });
+  },
+  moveSession: async (
+    sessionId: string,
+    folder: string | null,
+  ): Promise<void> => {
+    const path = (folder ?? "")
+      .split("/")
+      .map((part) => part.trim())
+      .filter(Boolean)
+      .join("/");
+    const updated = await LocalDatabase.getInstance().sessions.update(
+      sessionId,
+      { folder: path || undefined },
+    );
+    if (!updated) throw new Error(`Session ${sessionId} not found`);
+  },
+  listSessions: async (query: SessionQuery = {}): Promise<Session[]> => {
+    const db = LocalDatabase.getInstance();
+    const [firstTag, ...otherTags] = query.tags ?? [];
+    // 태그 인덱스로 후보를 줄인 뒤 나머지 조건은 메모리에서 확인합니다
+    const candidates = firstTag
+      ? await db.sessions.where("tags").equals(firstTag).toArray()
+      : await db.sessions.toArray();
+    const text = query.text?.trim().toLowerCase();
+    const folder = query.folder;
+    return candidates
+      .filter((session) =>
+        otherTags.every((tag) => session.tags?.includes(tag)),
+      )
+      .filter((session) => {
+        if (folder === undefined) return true;
+        const current = session.folder ?? "";
+        if (current === folder) return true;
+        return (
+          !!query.includeSubfolders &&
+          (folder === "" || current.startsWith(`${folder}/`))
+        );
+      })
+      .filter(
+        (session) =>
+          !text ||
+          [
+            session.name,
+            session.description,
+            ...session.assistants.map((assistant) => assistant.name),
+          ].some((field) => field?.toLowerCase().includes(text)),
+      )
+      .sort(
+        (a, b) =>
+          new Date(b.updatedAt).getTime() - new Date(a.updatedAt).getTime(),
+      );
+  },
+  // Copies history up to and including fromMessageId into a new child session
+  forkSession: async (
+    sessionId: string,
+    fromMessageId: string,
+  ): Promise<Session> => {
+    const db = LocalDatabase.getInstance();
+    const { forked, hashes } = await db.transaction(
+      "rw",
+      db.sessions,
+      db.messages,
+      async () => {
+      const session = await db.sessions.get(sessionId);
+      if (!session) throw new Error(`Session ${sessionId} not found`);
+      const history = await db.messages
+        .where("sessionId")
+        .equals(sessionId)
+        .sortBy("createdAt");
+      const end = history.findIndex((message) => message.id === fromMessageId);
+      if (end === -1) {
+        throw new Error(
+          `Message ${fromMessageId} not found in session ${sessionId}`,
+        );
+      }
+
+      const now = new Date();
+      const fork: Session = {
+        ...session,
+        id: createId(),
+        name: session.name ? `${session.name} (fork)` : undefined,
+        parentId: sessionId,
+        forkedFromMessageId: fromMessageId,
+        createdAt: now,
+        updatedAt: now,
+      };
+      await db.sessions.add(fork);
+      // 원래 시각을 유지해야 복사한 메시지의 순서가 그대로 남습니다
+      const copied = history.slice(0, end + 1);
+      await db.messages.bulkAdd(
+        copied.map((message) => ({
+          ...message,
+          id: createId(),
+          sessionId: fork.id,
+          isStreaming: false,
+        })),
+      );
+      return { forked: fork, hashes: attachmentHashes(copied) };
+      },
+    );
+    // 복사한 메시지도 첨부 파일을 참조하므로 참조 횟수를 올립니다
+    for (const hash of hashes) {
+      await tauriMCPClient.retainAttachment(hash);
+    }
+    return forked;
+  },
+  getSessionTree: async (rootId: string): Promise<SessionTreeNode> => {
+    const db = LocalDatabase.getInstance();
+    const root = await db.sessions.get(rootId);
+    if (!root) throw new Error(`Session ${rootId} not found`);
+    const seen = new Set<string>([rootId]);
+    const build = async (session: Session): Promise<SessionTreeNode> => {
+      const children = (
+        await db.sessions
+          .where("parentId")
+          .equals(session.id)
+          .sortBy("createdAt")
+      ).filter((child) => !seen.has(child.id));
+      children.forEach((child) => seen.add(child.id));
+      return { session, children: await Promise.all(children.map(build)) };
+    };
+    return build(root);
+  },
+  listSessionFolders: async (): Promise<string[]> => {
+    const folders = await LocalDatabase.getInstance()
+      .sessions.orderBy("folder")
+      .uniqueKeys();
+    return folders.map(String);
+  },
 
   // --- Messages ---
   getAllMessages: async (): Promise<StreamableMessage[]> => {
@@ -402,4 +763,28 @@ export const dbUtils = {
   bulkUpsertMessages: async (messages: StreamableMessage[]): Promise<void> => {
     await dbService.messages.upsertMany(messages);
   },
+  // Whole database as plain rows, used by app data backups
+  exportDatabase: async (): Promise<DatabaseExport> => {
+    const db = LocalDatabase.getInstance();
+    return {
+      assistants: await db.assistants.toArray(),
+      objects: await db.objects.toArray(),
+      sessions: await db.sessions.toArray(),
+      messages: await db.messages.toArray(),
+    };
+  },
+  // Merges an export; rows with the same key are replaced, others are kept
+  importDatabase: async (data: Partial<DatabaseExport>): Promise<void> => {
+    const db = LocalDatabase.getInstance();
+    await db.transaction(
+      "rw",
+      [db.assistants, db.objects, db.sessions, db.messages],
+      async () => {
+        await db.assistants.bulkPut((data.assistants ?? []).map(reviveDates));
+        await db.objects.bulkPut((data.objects ?? []).map(reviveDates));
+        await db.sessions.bulkPut((data.sessions ?? []).map(reviveDates));
+        await db.messages.bulkPut((data.messages ?? []).map(reviveDates));
+      },
+    );
+  },
 };
@@ -108,6 +108,7 @@ export class LLMConfigManager {
       anthropic: "anthropic",
       groq: "groq",
       google: "google-genai",
+      azure: "azure_openai",
     };
 
     const langchainProvider = providerMap[providerId];
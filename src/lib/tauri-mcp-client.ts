@@ -1,15 +1,36 @@
 import { invoke } from "@tauri-apps/api/core";
+import { getCurrentWindow } from "@tauri-apps/api/window";
 
 export interface MCPServerConfig {
   name: string;
   command?: string;
   args?: string[];
   env?: Record<string, string>;
+  // Launcher preset expanded to command/args at spawn time
+  runtime?: "npx" | "uvx" | "docker" | "deno" | "wsl";
+  // Package (or image, for docker) started by runtime
+  package?: string;
+  // WSL distribution for the wsl runtime; the default one when unset
+  distro?: string;
+  // Directories searched before the app's PATH for this server; "~" is home
+  path?: string[];
+  // How much of the app's environment the server gets; inherit-all when unset
+  env_policy?: "inherit-all" | "inherit-list" | "clean";
   transport: "stdio" | "http" | "websocket";
+  // Tool stack (e.g. "coding") started and stopped together
+  group?: string;
   url?: string;
   port?: number;
 }
 
+export interface MCPToolAnnotations {
+  title?: string;
+  read_only_hint?: boolean;
+  destructive_hint?: boolean;
+  idempotent_hint?: boolean;
+  open_world_hint?: boolean;
+}
+
 export interface MCPTool {
   name: string;
   description: string;
@@ -18,6 +39,7 @@ export interface MCPTool {
     properties: Record<string, unknown>;
     required?: string[];
   };
+  annotations?: MCPToolAnnotations;
 }
 
 export interface ToolCallResult {
@@ -26,7 +48,788 @@ export interface ToolCallResult {
   error?: string;
 }
 
+export interface ServerInstructions {
+  server_name: string;
+  instructions: string;
+  enabled: boolean;
+}
+
+export interface TimelineEvent {
+  id: number;
+  timestamp: string;
+  kind: string;
+  server_name?: string;
+  detail: unknown;
+}
+
+export interface TimelinePage {
+  events: TimelineEvent[];
+  cursor: number;
+  truncated: boolean;
+}
+
+export interface ApiSettings {
+  enabled: boolean;
+  port: number;
+  token: string;
+}
+
+export interface AutostartSettings {
+  // Launch the app when the user logs in
+  enabled: boolean;
+  // Login launches start hidden in the tray and warm up servers flagged "autostart"
+  background: boolean;
+}
+
+export interface UpdateSettings {
+  // Check on a schedule and announce new versions with an "update-available" event
+  auto_check: boolean;
+  interval_hours: number;
+  // Random id of this installation for staged rollouts; kept when saving
+  rollout_id: string;
+}
+
+export interface UpdateInfo {
+  version: string;
+  current_version: string;
+  date: string | null;
+  // Release notes
+  body: string | null;
+}
+
+// Payload of "update-progress" events while an update downloads
+export interface UpdateProgress {
+  downloaded: number;
+  total: number | null;
+}
+
+export interface PowerPolicy {
+  mode: "always-run" | "pause-on-battery" | "pause-on-low-battery";
+  // Battery level at or below which "pause-on-low-battery" pauses, while unplugged
+  low_battery_percent: number;
+}
+
+// Also the payload of "power-status" events, sent when background work pauses or resumes
+export interface PowerStatus {
+  policy: PowerPolicy;
+  state: {
+    on_battery: boolean;
+    // Null on machines without a battery
+    battery_percent: number | null;
+    low_power_mode: boolean;
+  };
+  // Scheduled backups, syncs, refreshes and cleanups are on hold
+  paused: boolean;
+}
+
+export interface PathSettings {
+  // Directories searched after the app's PATH for every server, e.g. "~/.local/bin"
+  extra_path_entries: string[];
+}
+
+export type OpenApiAuth =
+  | { type: "none" }
+  | { type: "bearer"; token: string }
+  | { type: "header"; name: string; value: string }
+  | { type: "query"; name: string; value: string }
+  | { type: "basic"; username: string; password: string };
+
+export interface OpenApiConfig {
+  name: string;
+  spec: string;
+  base_url?: string;
+  auth?: OpenApiAuth;
+  headers?: Record<string, string>;
+}
+
+export interface ScriptTool {
+  name: string;
+  description: string;
+  input_schema: MCPTool["input_schema"];
+  // Must define `handler(args)`; the return value becomes the tool result
+  source: string;
+  allow_read?: string[];
+  allow_net?: string[];
+  timeout_secs?: number;
+  memory_mb?: number;
+}
+
+export type AudioSource =
+  | { type: "file"; path: string }
+  | { type: "microphone"; duration_secs: number };
+
+export interface TranscriptSegment {
+  start: string | null;
+  end: string | null;
+  text: string;
+}
+
+export interface Transcript {
+  id: string;
+  text: string;
+  segments: TranscriptSegment[];
+}
+
+export interface BrowserSettings {
+  // Hosts (and their subdomains) the browser tools may visit
+  allowed_domains: string[];
+  show_window: boolean;
+}
+
+export type SearchEngine =
+  | { type: "searxng"; base_url: string }
+  | { type: "brave"; api_key: string }
+  | { type: "tavily"; api_key: string };
+
+export interface WebSearchSettings {
+  // Tried in order; later engines are fallbacks
+  engines: SearchEngine[];
+}
+
+export interface PostProcessRule {
+  // Server or tool name, "*" matches any
+  server: string;
+  tool: string;
+  html_to_markdown: boolean;
+  collapse_whitespace: boolean;
+  // Matches are replaced with [REDACTED]
+  redact_patterns: string[];
+  // Approximate budget, 4 characters per token
+  max_tokens?: number;
+}
+
+export interface PostProcessSettings {
+  // The first matching rule is applied
+  rules: PostProcessRule[];
+}
+
+export interface ResultCacheSettings {
+  enabled: boolean;
+  ttl_secs: number;
+  // Servers whose results are never cached
+  disabled_servers: string[];
+}
+
+export interface ResultCacheStats {
+  entries: number;
+  hits: number;
+  misses: number;
+  // Calls that joined an identical call already in flight
+  deduped: number;
+  in_flight: number;
+}
+
+export interface ToolAliasSettings {
+  // Server name -> tool name -> name shown to the model instead of "server__tool"
+  aliases: Record<string, Record<string, string>>;
+}
+
+export interface ResolvedToolName {
+  server_name: string;
+  tool_name: string;
+  // Set when the model called the tool by its alias
+  alias?: string;
+}
+
+export interface ToolConflictSettings {
+  // "prefix" keeps every copy, the others hide some copies of a duplicated tool
+  strategy: "prefix" | "prefer-priority-server" | "hide-duplicates";
+  // Most preferred first; unlisted servers follow
+  server_priority: string[];
+}
+
+export interface ToolPrioritySettings {
+  // Server name -> priority; higher comes first, unlisted servers are 0
+  servers: Record<string, number>;
+  // Server name -> tool name -> priority, overriding the server's
+  tools: Record<string, Record<string, number>>;
+  // Keep only this many tools, dropping the lowest priorities
+  max_tools: number | null;
+}
+
+export interface ToolConflict {
+  tool_name: string;
+  // Most preferred first
+  servers: string[];
+  // Servers whose copy the model sees
+  exposed: string[];
+}
+
+export type ValidationTarget =
+  | "generic"
+  | "openai"
+  | "openai-strict"
+  | "anthropic"
+  | "gemini";
+
+export interface ValidationFinding {
+  // "generic" findings apply to every provider
+  target: ValidationTarget;
+  // "error": rejected by the provider, "warning": the model sees less than declared
+  severity: "error" | "warning";
+  // JSON pointer into the input schema, empty for the tool itself
+  path: string;
+  message: string;
+}
+
+export interface ToolValidationReport {
+  tool_name: string;
+  findings: ValidationFinding[];
+}
+
+export type StarterServer = "filesystem" | "fetch" | "memory";
+
+export interface RuntimeInfo {
+  name: string;
+  // null when not on PATH
+  path: string | null;
+}
+
+export interface StarterSuggestion {
+  server: StarterServer;
+  description: string;
+  // null when no suitable runtime is installed
+  config: MCPServerConfig | null;
+  // What to install when config is null
+  missing: string | null;
+}
+
+export interface SetupSuggestions {
+  first_run: boolean;
+  runtimes: RuntimeInfo[];
+  suggestions: StarterSuggestion[];
+}
+
+export interface SetupSelection {
+  server: StarterServer;
+  // Folder for the filesystem server; defaults to the first workspace root or home
+  root?: string;
+}
+
+export interface SetupServerResult {
+  server: StarterServer;
+  config: MCPServerConfig | null;
+  started: boolean;
+  self_test: { tool_name: string; success: boolean; error: string | null } | null;
+  error: string | null;
+}
+
+export interface SetupReport {
+  completed_at: string;
+  runtimes: RuntimeInfo[];
+  servers: SetupServerResult[];
+}
+
+export interface ClaudeExportReport {
+  exported: string[];
+  // Server name -> why Claude Desktop cannot run it
+  skipped: Record<string, string>;
+}
+
+export interface ConfigVersion {
+  // "<unix millis>-<file name>"
+  id: string;
+  file: string;
+  created_at: string;
+  size: number;
+}
+
+export interface ConfigRollbackReport {
+  file: string;
+  // Backup of the replaced content, so the rollback can be undone too
+  backup_id: string | null;
+  // false when the setting takes effect after a restart
+  reloaded: boolean;
+}
+
+export interface ConfigSyncSettings {
+  enabled: boolean;
+  // Without a remote, changes are only committed locally
+  remote: string | null;
+  branch: string;
+  // Push after every committed change
+  auto_push: boolean;
+}
+
+export interface ConfigSyncStatus {
+  settings: ConfigSyncSettings;
+  initialized: boolean;
+  // Short commit ID and summary
+  head: string | null;
+  // Config files tracked in git; the others stay on this machine
+  synced_files: string[];
+}
+
+export interface ConfigSyncReport {
+  committed: string | null;
+  // Config files changed by the pull
+  pulled: string[];
+  pushed: boolean;
+}
+
+export type SyncBackend =
+  | {
+      // S3-compatible bucket (AWS, MinIO, R2, ...), addressed path-style
+      type: "s3";
+      endpoint: string;
+      bucket: string;
+      region: string;
+      access_key_id: string;
+      secret_access_key: string;
+      prefix?: string;
+    }
+  | { type: "webdav"; url: string; username: string; password: string };
+
+export interface DeviceSyncSettings {
+  enabled: boolean;
+  backend: SyncBackend | null;
+  // Sync in the background this often; only on demand when unset
+  interval_secs: number | null;
+}
+
+export interface DeviceSyncStatus {
+  settings: DeviceSyncSettings;
+  // A passphrase has been set on this device
+  has_key: boolean;
+  device_id: string | null;
+  last_sync: string | null;
+  records: number;
+}
+
+export interface SyncConflict {
+  // "config/<file>" or "session/<id>"
+  record: string;
+  resolution: "merged" | "kept-local" | "took-remote";
+}
+
+export interface DeviceSyncReport {
+  uploaded: number;
+  downloaded: number;
+  conflicts: SyncConflict[];
+  // Config files changed by the sync
+  configs: string[];
+  // Sessions were included; they need the app window
+  sessions: boolean;
+}
+
+export interface BackupSettings {
+  // Create backups on a schedule; manual backups work either way
+  enabled: boolean;
+  directory: string | null;
+  interval_hours: number;
+  // Archives to keep; older ones are deleted after each backup
+  keep: number;
+}
+
+export interface BackupInfo {
+  id: string;
+  path: string;
+  created_at: string;
+  size_bytes: number;
+}
+
+export interface BackupRestoreReport {
+  id: string;
+  // Config files written back; the replaced versions can be rolled back
+  restored: string[];
+  // Sessions and messages were merged back into the session database
+  session_db: boolean;
+  // Restored files that only apply after a restart
+  restart_required: string[];
+}
+
+export interface RemoteConfigSource {
+  url: string;
+  refresh_interval_secs: number;
+}
+
+// Servers fetched from one source; read-only, they change only when the source does
+export interface ManagedSection {
+  url: string;
+  fetched_at: string | null;
+  // Unix seconds of the last attempt
+  checked_at: number;
+  servers: MCPServerConfig[];
+  // Servers the last refresh dropped from the source
+  removed: string[];
+  // Error of the last attempt; the previous servers are kept
+  error: string | null;
+}
+
+export interface RemoteConfigState {
+  sources: RemoteConfigSource[];
+  sections: ManagedSection[];
+}
+
+export interface ServerDrift {
+  server_name: string;
+  source: string;
+  kind: "not-running" | "modified" | "removed";
+  // Top-level config fields that differ, for "modified"
+  fields: string[];
+}
+
+export interface ConfigDiagnostic {
+  severity: "error" | "warning";
+  // e.g. "mcpServers.github.args[1]"
+  path: string;
+  // JSON pointer to the same location, e.g. "/mcpServers/github/args/1"
+  pointer: string;
+  expected: string | null;
+  found: string | null;
+  message: string;
+  suggestion: string | null;
+}
+
+export interface ServerChange {
+  name: string;
+  change: "added" | "changed" | "unchanged";
+  config: Record<string, unknown>;
+  // The entry this one would replace
+  previous: Record<string, unknown> | null;
+}
+
+export interface McpImportPreview {
+  path: string;
+  servers: ServerChange[];
+  diagnostics: ConfigDiagnostic[];
+  // False while any diagnostic is an error
+  importable: boolean;
+  // The file's servers as an mcpServers document, ready to merge
+  config: { mcpServers: Record<string, any> };
+}
+
+export type HistoryFormat = "chatgpt" | "claude";
+
+export interface HistoryImportReport {
+  sessions: number;
+  messages: number;
+  // Conversations without any text message
+  skipped: number;
+}
+
+export interface PlatformCapabilities {
+  // Android or iOS, where servers cannot be spawned locally
+  mobile: boolean;
+  // Transports this build can start, e.g. ["http", "websocket"] on mobile
+  transports: string[];
+}
+
+export interface StartupReport {
+  // Servers grouped by start order; each stage starts concurrently
+  stages: string[][];
+  started: string[];
+  failed: Record<string, string>;
+  // Servers not started because a dependency failed
+  skipped: Record<string, string>;
+}
+
+export interface GroupStopReport {
+  stopped: string[];
+  failed: Record<string, string>;
+}
+
+export type AnalyticsRange = "day" | "week" | "month" | "all";
+
+export interface ToolUsage {
+  server_name: string;
+  tool_name: string;
+  calls: number;
+  // Between 0 and 1
+  success_rate: number;
+  avg_latency_ms: number;
+  // Distinct chat sessions that called the tool
+  sessions: number;
+}
+
+export interface UnusedServer {
+  server_name: string;
+  // Tools it adds to every prompt
+  tool_count: number;
+  // Memory of its process tree, when it runs locally
+  memory_bytes: number | null;
+  message: string;
+}
+
+export interface ToolAnalytics {
+  range: AnalyticsRange;
+  // First day with data inside the range
+  since: string | null;
+  // Most called first
+  tools: ToolUsage[];
+  // Candidates to disable to shrink prompts and memory use
+  unused_servers: UnusedServer[];
+}
+
+export interface CodeIndexStats {
+  files: number;
+  updated: number;
+  removed: number;
+  // The workspace had more source files than the index accepts
+  truncated: boolean;
+}
+
+export interface ToolCallContext {
+  session_id?: string;
+  // Assistant, webhook agent or scheduled task that issued the call
+  agent_id?: string;
+  // Window label; approvals for the call are asked there. Filled in by callTool
+  window?: string;
+}
+
+export interface ChatWindow {
+  label: string;
+  title: string;
+  focused: boolean;
+}
+
+// From launching the app with arguments or a tauriagent:// link, e.g.
+// `tauri-agent chat "question"` or a file open
+export type LaunchRequest =
+  | { kind: "chat"; prompt: string }
+  | { kind: "open-file"; path: string }
+  // Assistant name or id
+  | { kind: "run-agent"; agent: string; prompt: string }
+  // An `mcpServers` config to prefill the add-server form with
+  | {
+      kind: "install-server";
+      config: {
+        mcpServers: Record<
+          string,
+          { command: string; args?: string[]; env?: Record<string, string> }
+        >;
+      };
+    };
+
+export interface FsOperation {
+  id: string;
+  session_id?: string;
+  tool: string;
+  kind: "create" | "write" | "delete";
+  path: string;
+  timestamp: string;
+  // False when the previous content was too large to keep
+  undoable: boolean;
+  undone: boolean;
+}
+
+export interface SandboxViolation {
+  timestamp: string;
+  path: string;
+  access: "read" | "write";
+  reason: string;
+  // Directory the user could approve to allow this access
+  suggested_root?: string;
+}
+
+export interface WorkspaceSnapshot {
+  id: string;
+  root: string;
+  created_at: string;
+  reason: string;
+  session_id?: string;
+  files: number;
+  bytes: number;
+  // Files hardlinked from the previous snapshot of the same directory
+  shared: number;
+}
+
+export interface SnapshotRestoreReport {
+  snapshot: WorkspaceSnapshot;
+  restored: number;
+  removed: number;
+  // Snapshot taken right before restoring, so the restore can be reverted
+  safety_snapshot: string;
+}
+
+// Relays one Slack or Discord channel to a saved assistant
+export interface ChatBridgeSettings {
+  enabled: boolean;
+  platform: "slack" | "discord";
+  bot_token: string;
+  app_token?: string; // Slack app-level token for Socket Mode
+  channel_id: string;
+  agent_id: string;
+}
+
+export interface AttachmentInfo {
+  hash: string;
+  size: number;
+  mime_type?: string;
+  ref_count: number;
+  created_at: number;
+}
+
+export interface WebhookRegistration {
+  id: string;
+  agent_id: string;
+  secret: string;
+  created_at: string;
+  url: string;
+}
+
 export class TauriMCPClient {
+  // Newest first; pass a file name such as "postprocess.json" to filter
+  async listConfigVersions(file?: string): Promise<ConfigVersion[]> {
+    return await invoke("list_config_versions", { file });
+  }
+
+  async rollbackConfig(versionId: string): Promise<ConfigRollbackReport> {
+    return await invoke("rollback_config", { versionId });
+  }
+
+  async getConfigSyncStatus(): Promise<ConfigSyncStatus> {
+    return await invoke("get_config_sync_status");
+  }
+
+  // Enabling initializes the repository and syncs once with the remote
+  async setConfigSyncSettings(
+    settings: ConfigSyncSettings,
+  ): Promise<ConfigSyncReport> {
+    return await invoke("set_config_sync_settings", { settings });
+  }
+
+  async syncConfigNow(): Promise<ConfigSyncReport> {
+    return await invoke("sync_config_now");
+  }
+
+  // All windows share the running servers and stores; returns the new window's label
+  async openChatWindow(sessionId?: string): Promise<string> {
+    return await invoke("open_chat_window", { sessionId });
+  }
+
+  async listChatWindows(): Promise<ChatWindow[]> {
+    return await invoke("list_chat_windows");
+  }
+
+  // Each request is returned once; a "launch-request" event announces new ones
+  async takeLaunchRequests(): Promise<LaunchRequest[]> {
+    return await invoke("take_launch_requests");
+  }
+
+  async getDeviceSyncStatus(): Promise<DeviceSyncStatus> {
+    return await invoke("get_device_sync_status");
+  }
+
+  // The passphrase must match the one used by devices already syncing; it is never stored
+  async setDeviceSyncSettings(
+    settings: DeviceSyncSettings,
+    passphrase?: string,
+  ): Promise<DeviceSyncReport> {
+    return await invoke("set_device_sync_settings", { settings, passphrase });
+  }
+
+  async syncDevicesNow(): Promise<DeviceSyncReport> {
+    return await invoke("sync_devices_now");
+  }
+
+  async getBackupSettings(): Promise<BackupSettings> {
+    return await invoke("get_backup_settings");
+  }
+
+  async setBackupSettings(settings: BackupSettings): Promise<void> {
+    return await invoke("set_backup_settings", { settings });
+  }
+
+  // Sessions are only included while the app window is open
+  async createBackupNow(): Promise<BackupInfo> {
+    return await invoke("create_backup_now");
+  }
+
+  async listBackups(): Promise<BackupInfo[]> {
+    return await invoke("list_backups");
+  }
+
+  // Restored sessions are merged; existing ones are kept
+  async restoreBackup(id: string): Promise<BackupRestoreReport> {
+    return await invoke("restore_backup", { id });
+  }
+
+  // Fetched now and every refreshInterval seconds (at least 60)
+  async addRemoteConfigSource(
+    url: string,
+    refreshInterval: number,
+  ): Promise<ManagedSection> {
+    return await invoke("add_remote_config_source", { url, refreshInterval });
+  }
+
+  async removeRemoteConfigSource(url: string): Promise<void> {
+    return await invoke("remove_remote_config_source", { url });
+  }
+
+  async listRemoteConfigSources(): Promise<RemoteConfigState> {
+    return await invoke("list_remote_config_sources");
+  }
+
+  async refreshRemoteConfigSource(url: string): Promise<ManagedSection> {
+    return await invoke("refresh_remote_config_source", { url });
+  }
+
+  async getManagedServers(): Promise<MCPServerConfig[]> {
+    return await invoke("get_managed_servers");
+  }
+
+  async getManagedConfigDrift(): Promise<ServerDrift[]> {
+    return await invoke("get_managed_config_drift");
+  }
+
+  // Accepts {"mcpServers": {...}} or {"servers": [...]}
+  async getPlatformCapabilities(): Promise<PlatformCapabilities> {
+    return await invoke("get_platform_capabilities");
+  }
+
+  async validateServerConfig(config: unknown): Promise<ConfigDiagnostic[]> {
+    return await invoke("validate_server_config", { config });
+  }
+
+  // Parses and validates a .mcp.json file and compares it with `current`, an
+  // assistant's mcpConfig; nothing is written
+  async previewMcpImport(
+    path: string,
+    current: unknown,
+  ): Promise<McpImportPreview> {
+    return await invoke("preview_mcp_import", { path, current });
+  }
+
+  // Path is the export ZIP or its conversations.json; re-importing updates in place
+  async importHistory(
+    path: string,
+    format: HistoryFormat,
+  ): Promise<HistoryImportReport> {
+    return await invoke("import_history", { path, format });
+  }
+
+  async getServerConfigSchema(): Promise<Record<string, unknown>> {
+    return await invoke("get_server_config_schema");
+  }
+
+  async startGroup(name: string): Promise<StartupReport> {
+    return await invoke("start_group", { name });
+  }
+
+  async stopGroup(name: string): Promise<GroupStopReport> {
+    return await invoke("stop_group", { name });
+  }
+
+  // Group name -> servers in it
+  async getServerGroups(): Promise<Record<string, string[]>> {
+    return await invoke("get_server_groups");
+  }
+
+  // Env values are written as ${NAME} placeholders, never the real secrets
+  async exportConfigClaudeFormat(path: string): Promise<ClaudeExportReport> {
+    return await invoke("export_config_claude_format", { path });
+  }
+
+  async getFirstRunSuggestions(): Promise<SetupSuggestions> {
+    return await invoke("get_first_run_suggestions");
+  }
+
+  // Starts and self-tests the servers; save the returned configs to keep them
+  async runFirstTimeSetup(selections: SetupSelection[]): Promise<SetupReport> {
+    return await invoke("run_first_time_setup", { selections });
+  }
+
   async startServer(config: MCPServerConfig): Promise<string> {
     return await invoke("start_mcp_server", { config });
   }
@@ -35,15 +838,22 @@ export class TauriMCPClient {
     return await invoke("stop_mcp_server", { serverName });
   }
 
+  // sessionId attributes builtin file changes to a chat in the undo journal
   async callTool(
     serverName: string,
     toolName: string,
     arguments_: Record<string, unknown>,
+    // Chat session and agent the call is attributed to
+    context?: ToolCallContext,
+    // Skip cached results of read-only tools and fetch a fresh one
+    bypassCache?: boolean,
   ): Promise<ToolCallResult> {
     return await invoke("call_mcp_tool", {
       serverName,
       toolName,
       arguments: arguments_,
+      context: { ...context, window: context?.window ?? getCurrentWindow().label },
+      bypassCache,
     });
   }
 
@@ -71,6 +881,330 @@ export class TauriMCPClient {
   async checkAllServersStatus(): Promise<Record<string, boolean>> {
     return await invoke("check_all_servers_status");
   }
+
+  async getServerInstructions(): Promise<ServerInstructions[]> {
+    return await invoke("get_server_instructions");
+  }
+
+  async setServerInstructionsEnabled(
+    serverName: string,
+    enabled: boolean,
+  ): Promise<void> {
+    return await invoke("set_server_instructions_enabled", {
+      serverName,
+      enabled,
+    });
+  }
+
+  async getEventsSince(cursor: number, limit?: number): Promise<TimelinePage> {
+    return await invoke("get_events_since", { cursor, limit });
+  }
+
+  async recordTimelineEvent(
+    kind: string,
+    detail: unknown,
+    serverName?: string,
+  ): Promise<void> {
+    return await invoke("record_timeline_event", { kind, serverName, detail });
+  }
+
+  async getApiSettings(): Promise<ApiSettings> {
+    return await invoke("get_api_settings");
+  }
+
+  async setApiSettings(enabled: boolean, port: number): Promise<ApiSettings> {
+    return await invoke("set_api_settings", { enabled, port });
+  }
+
+  async regenerateApiToken(): Promise<ApiSettings> {
+    return await invoke("regenerate_api_token");
+  }
+
+  async getChatBridgeSettings(): Promise<ChatBridgeSettings> {
+    return await invoke("get_chat_bridge_settings");
+  }
+
+  // Saving reconnects the bridge with the new settings
+  async setChatBridgeSettings(settings: ChatBridgeSettings): Promise<void> {
+    return await invoke("set_chat_bridge_settings", { settings });
+  }
+
+  async getAutostartSettings(): Promise<AutostartSettings> {
+    return await invoke("get_autostart_settings");
+  }
+
+  async setAutostartSettings(
+    settings: AutostartSettings,
+  ): Promise<AutostartSettings> {
+    return await invoke("set_autostart_settings", { settings });
+  }
+
+  // False for builds without an updater public key; hide update controls then
+  async isUpdaterConfigured(): Promise<boolean> {
+    return await invoke("is_updater_configured");
+  }
+
+  async getUpdateSettings(): Promise<UpdateSettings> {
+    return await invoke("get_update_settings");
+  }
+
+  async setUpdateSettings(settings: UpdateSettings): Promise<UpdateSettings> {
+    return await invoke("set_update_settings", { settings });
+  }
+
+  // Null when this installation is up to date or not yet in a staged rollout
+  async checkForUpdates(): Promise<UpdateInfo | null> {
+    return await invoke("check_for_updates");
+  }
+
+  // Installs the update found by the last check; the app restarts when done
+  async installUpdate(): Promise<void> {
+    return await invoke("install_update");
+  }
+
+  async getPowerStatus(): Promise<PowerStatus> {
+    return await invoke("get_power_status");
+  }
+
+  async setPowerPolicy(policy: PowerPolicy): Promise<PowerStatus> {
+    return await invoke("set_power_policy", { policy });
+  }
+
+  async getPathSettings(): Promise<PathSettings> {
+    return await invoke("get_path_settings");
+  }
+
+  async setPathSettings(settings: PathSettings): Promise<PathSettings> {
+    return await invoke("set_path_settings", { settings });
+  }
+
+  // Existing install locations such as asdf shims or volta that are not on PATH yet
+  async suggestPathEntries(): Promise<string[]> {
+    return await invoke("suggest_path_entries");
+  }
+
+  async listOpenApiServers(): Promise<OpenApiConfig[]> {
+    return await invoke("list_openapi_servers");
+  }
+
+  async registerOpenApiServer(config: OpenApiConfig): Promise<MCPTool[]> {
+    return await invoke("register_openapi_server", { config });
+  }
+
+  async removeOpenApiServer(name: string): Promise<void> {
+    return await invoke("remove_openapi_server", { name });
+  }
+
+  async listScriptTools(): Promise<ScriptTool[]> {
+    return await invoke("list_script_tools");
+  }
+
+  async createScriptTool(tool: ScriptTool): Promise<void> {
+    return await invoke("create_script_tool", { tool });
+  }
+
+  async deleteScriptTool(name: string): Promise<void> {
+    return await invoke("delete_script_tool", { name });
+  }
+
+  // Partial results are emitted as "transcription-partial" events tagged with requestId
+  async transcribeAudio(
+    source: AudioSource,
+    options: { model?: string; language?: string; requestId?: string } = {},
+  ): Promise<Transcript> {
+    return await invoke("transcribe_audio", {
+      source,
+      model: options.model,
+      language: options.language,
+      requestId: options.requestId,
+    });
+  }
+
+  async getBrowserSettings(): Promise<BrowserSettings> {
+    return await invoke("get_browser_settings");
+  }
+
+  async setBrowserSettings(settings: BrowserSettings): Promise<void> {
+    return await invoke("set_browser_settings", { settings });
+  }
+
+  async getWebSearchSettings(): Promise<WebSearchSettings> {
+    return await invoke("get_web_search_settings");
+  }
+
+  async setWebSearchSettings(settings: WebSearchSettings): Promise<void> {
+    return await invoke("set_web_search_settings", { settings });
+  }
+
+  async getPostProcessSettings(): Promise<PostProcessSettings> {
+    return await invoke("get_postprocess_settings");
+  }
+
+  async setPostProcessSettings(settings: PostProcessSettings): Promise<void> {
+    return await invoke("set_postprocess_settings", { settings });
+  }
+
+  async getResultCacheSettings(): Promise<ResultCacheSettings> {
+    return await invoke("get_result_cache_settings");
+  }
+
+  async setResultCacheSettings(settings: ResultCacheSettings): Promise<void> {
+    return await invoke("set_result_cache_settings", { settings });
+  }
+
+  async getResultCacheStats(): Promise<ResultCacheStats> {
+    return await invoke("get_result_cache_stats");
+  }
+
+  async clearResultCache(): Promise<void> {
+    return await invoke("clear_result_cache");
+  }
+
+  async getToolAliasSettings(): Promise<ToolAliasSettings> {
+    return await invoke("get_tool_alias_settings");
+  }
+
+  // Rejects aliases that are used twice or contain "__"
+  async setToolAliasSettings(settings: ToolAliasSettings): Promise<void> {
+    return await invoke("set_tool_alias_settings", { settings });
+  }
+
+  // Accepts an alias or a "server__tool" name
+  async resolveToolName(name: string): Promise<ResolvedToolName | null> {
+    return await invoke("resolve_tool_name", { name });
+  }
+
+  async getToolConflictSettings(): Promise<ToolConflictSettings> {
+    return await invoke("get_tool_conflict_settings");
+  }
+
+  async setToolConflictSettings(settings: ToolConflictSettings): Promise<void> {
+    return await invoke("set_tool_conflict_settings", { settings });
+  }
+
+  // Defaults to the last week
+  async getToolAnalytics(range?: AnalyticsRange): Promise<ToolAnalytics> {
+    return await invoke("get_tool_analytics", { range });
+  }
+
+  async getToolPrioritySettings(): Promise<ToolPrioritySettings> {
+    return await invoke("get_tool_priority_settings");
+  }
+
+  async setToolPrioritySettings(settings: ToolPrioritySettings): Promise<void> {
+    return await invoke("set_tool_priority_settings", { settings });
+  }
+
+  // Tool names exported by more than one connected server
+  async getToolConflicts(): Promise<ToolConflict[]> {
+    return await invoke("get_tool_conflicts");
+  }
+
+  // Also pushed as "mcp-tool-validation" events whenever a server lists its tools
+  async validateAllTools(
+    targets?: ValidationTarget[],
+  ): Promise<ToolValidationReport[]> {
+    return await invoke("validate_all_tools", { targets });
+  }
+
+  // A server's tools without those the target provider would reject
+  async getValidatedTools(
+    serverName: string,
+    target: ValidationTarget,
+  ): Promise<MCPTool[]> {
+    return await invoke("get_validated_tools", { serverName, target });
+  }
+
+  // Hooks wrapping every tool call, outermost first
+  async listToolHooks(): Promise<string[]> {
+    return await invoke("list_tool_hooks");
+  }
+
+  // Playback progress arrives as "speech-started" / "speech-finished" events
+  async speakText(text: string, voice?: string): Promise<number> {
+    return await invoke("speak_text", { text, voice });
+  }
+
+  async stopSpeaking(): Promise<void> {
+    return await invoke("stop_speaking");
+  }
+
+  async indexCodeWorkspace(root: string): Promise<CodeIndexStats> {
+    return await invoke("index_code_workspace", { root });
+  }
+
+  // Newest first; omit sessionId to list changes from every chat
+  async listFsOperations(sessionId?: string): Promise<FsOperation[]> {
+    return await invoke("list_fs_operations", { sessionId });
+  }
+
+  async undoFsOperation(opId: string): Promise<FsOperation> {
+    return await invoke("undo_fs_operation", { opId });
+  }
+
+  async getSandboxViolations(limit?: number): Promise<SandboxViolation[]> {
+    return await invoke("get_sandbox_violations", { limit });
+  }
+
+  async createWorkspaceSnapshot(dir: string): Promise<WorkspaceSnapshot> {
+    return await invoke("create_workspace_snapshot", { dir });
+  }
+
+  async listWorkspaceSnapshots(dir?: string): Promise<WorkspaceSnapshot[]> {
+    return await invoke("list_workspace_snapshots", { dir });
+  }
+
+  async restoreSnapshot(id: string): Promise<SnapshotRestoreReport> {
+    return await invoke("restore_snapshot", { id });
+  }
+
+  async exportToolDefinitions(
+    format: "openai" | "anthropic" | "gemini",
+    path: string,
+  ): Promise<number> {
+    return await invoke("export_tool_definitions", { format, path });
+  }
+
+  // Identical content is stored once; each call adds a reference
+  async storeAttachment(
+    bytes: Uint8Array,
+    mimeType?: string,
+  ): Promise<AttachmentInfo> {
+    let binary = "";
+    for (let i = 0; i < bytes.length; i += 0x8000) {
+      binary += String.fromCharCode(...bytes.subarray(i, i + 0x8000));
+    }
+    return await invoke("store_attachment", { data: btoa(binary), mimeType });
+  }
+
+  async retainAttachment(hash: string): Promise<AttachmentInfo> {
+    return await invoke("retain_attachment", { hash });
+  }
+
+  // The blob is deleted once its last reference is released
+  async releaseAttachment(hash: string): Promise<void> {
+    return await invoke("release_attachment", { hash });
+  }
+
+  async registerWebhook(agentId: string): Promise<WebhookRegistration> {
+    return await invoke("register_webhook", { agentId });
+  }
+
+  async listWebhooks(): Promise<WebhookRegistration[]> {
+    return await invoke("list_webhooks");
+  }
+
+  async removeWebhook(id: string): Promise<void> {
+    return await invoke("remove_webhook", { id });
+  }
+
+  async resolveApiRequest(
+    id: string,
+    result?: unknown,
+    error?: string,
+  ): Promise<void> {
+    return await invoke("resolve_api_request", { id, result, error });
+  }
 }
 
 export const tauriMCPClient = new TauriMCPClient();
@@ -1,5 +1,13 @@
 import { MCPTool } from "../lib/tauri-mcp-client";
 
+// Attachment contents live in the backend store, addressed by their sha256
+export interface MessageAttachment {
+  name: string;
+  hash?: string; // Unset until an attachment saved before v6 reaches the store
+  size: number;
+  mimeType?: string;
+}
+
 export interface Message {
   id: string;
   sessionId: string; // Added sessionId
@@ -10,7 +18,7 @@ export interface Message {
   isStreaming?: boolean;
   thinking?: string;
   assistantId?: string; // Optional, used for tracking in multi-agent scenarios
-  attachments?: { name: string; content: string }[];
+  attachments?: MessageAttachment[];
   tool_use?: { id: string; name: string; input: Record<string, unknown> };
   function_call?: { name: string; arguments: Record<string, unknown> };
   createdAt?: Date; // Added
@@ -39,6 +47,12 @@ export interface Assistant {
         command: string;
         args?: string[];
         env?: Record<string, string>;
+        // Missing on servers created before it existed, which inherit everything
+        env_policy?: "inherit-all" | "inherit-list" | "clean";
+        // "stdio" unless set; mobile builds only start "http" and "websocket"
+        transport?: string;
+        url?: string;
+        runtime?: string;
       }
     >;
   };
@@ -58,6 +72,10 @@ export interface Session {
   assistants: Assistant[];
   name?: string; // Group 세션의 경우 그룹명
   description?: string; // Group 세션의 경우 설명
+  tags?: string[]; // 필터링용 태그
+  folder?: string; // "/"로 구분한 폴더 경로 (예: "work/clients")
+  parentId?: string; // 분기한 원본 세션
+  forkedFromMessageId?: string; // 원본 세션에서 분기한 마지막 메시지
   createdAt: Date;
   updatedAt: Date;
 }